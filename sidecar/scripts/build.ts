@@ -1,7 +1,7 @@
 import { build } from 'esbuild';
 import { execFile } from 'node:child_process';
 import { promisify } from 'node:util';
-import { copyFile, rename, mkdir, unlink } from 'node:fs/promises';
+import { copyFile, readFile, rename, mkdir, unlink } from 'node:fs/promises';
 import { join, dirname } from 'node:path';
 import { fileURLToPath } from 'node:url';
 
@@ -26,6 +26,8 @@ function getTargetTriple(): string {
 async function main() {
   console.log('Building sidecar...');
 
+  const packageJson = JSON.parse(await readFile(join(projectRoot, 'package.json'), 'utf8'));
+
   // Bundle with esbuild - use CJS format for pkg compatibility
   await build({
     entryPoints: [join(projectRoot, 'src', 'index.ts')],
@@ -34,6 +36,10 @@ async function main() {
     target: 'node22',
     format: 'cjs',
     outfile: join(projectRoot, 'dist', 'index.cjs'),
+    // Reported to Trove with each generation.
+    define: {
+      'process.env.TROVE_SIDECAR_VERSION': JSON.stringify(packageJson.version),
+    },
   });
 
   console.log('Bundled with esbuild');
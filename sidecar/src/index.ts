@@ -2,8 +2,11 @@
 
 import { query } from "@anthropic-ai/claude-agent-sdk";
 import { execFileSync } from "node:child_process";
-import { accessSync, constants, statSync } from "node:fs";
+import { randomUUID } from "node:crypto";
+import { accessSync, constants, readFileSync, statSync } from "node:fs";
 import { delimiter, join, resolve, relative, sep, isAbsolute } from "node:path";
+import { createInterface } from "node:readline";
+import { progressLine } from "./protocol.js";
 
 const SYSTEM_PROMPT = `You are an expert web developer. Your task is to generate a single, self-contained HTML file that implements the user's request.
 
@@ -32,66 +35,127 @@ Example usage:
   await TroveStorage.set('todos', todos);
 
 DO NOT use localStorage or sessionStorage - they won't persist. Use TroveStorage instead.
+When the app is open in another window and that copy changes stored data, a 'trove-storage-change' event fires on window (event.detail.key, or null after clear); reload the affected state from TroveStorage.
+
+If the app talks to a service whose address or key the user should be able to change (e.g. an API base URL), read it with the TroveConfig API instead of hard-coding it:
+- await TroveConfig.get(key) - Returns the configured string, or null if unset
+- await TroveConfig.getAll() - Returns all config values as an object
+Config is read-only for the app; the user sets values in Trove. Fall back to a sensible default when a value is unset.
+
+To show the app's own title, icon or theme color, read them at startup instead of hard-coding them, since the user can rename the app:
+- await TroveApp.getMetadata() - Returns { id, name, emoji, background_color, created_at, language }
+- await TroveApp.rename(name) - Renames the app, e.g. after the user names the project it tracks; returns the new name. The user is asked to allow this the first time.
+- await TroveApp.setBadge(count) - Shows a count on the Trove dock/taskbar icon (e.g. unread items); null or 0 removes it
+- await TroveApp.setProgress(percent) - Shows a 0-100 progress bar on the dock/taskbar icon (e.g. a timer's remaining time); null removes it
+Badges and progress are cleared automatically when the app closes.
+- await TroveApp.playSound(id, volume) - Plays a built-in sound: 'ding', 'chime', 'success', 'alert' or 'tick'; volume 0-1 (default 1). Use this for timer and notification sounds instead of <audio> or the Web Audio API, which the webview blocks without a click.
+
+If the app counts down to something (pomodoro, tea, cooking timers), use the TroveTimers API instead of relying only on setTimeout, since JS timers stop when the app closes:
+- await TroveTimers.start(id, seconds, label) - Starts or restarts a timer that keeps running in Trove; the user gets a notification showing label when it ends
+- await TroveTimers.list() - Returns running timers as [{ id, label, duration_secs, started_at, ends_at }]; use ends_at to restore countdown displays on load
+- await TroveTimers.cancel(id) - Stops a timer
+When a timer ends while the app is open, a 'trove-timer-finished' event fires on window (event.detail.id, event.detail.label).
+
+If the app offers keyboard shortcuts with modifiers, register them with the TroveShortcuts API instead of listening for those keys yourself:
+- await TroveShortcuts.register(id, accelerator, handler) - e.g. register('save', 'CmdOrCtrl+S', save); accelerators combine CmdOrCtrl, Alt and Shift with a letter, digit, F1-F12 or a named key such as ArrowUp or Enter. Rejects if the combination is reserved or taken.
+- await TroveShortcuts.unregister(id)
+Shortcuts need CmdOrCtrl or Alt (except F-keys); handle plain keys with a normal keydown listener.
+
+If the app needs to read the user's local text files (e.g. a markdown viewer), use the TroveFiles API:
+- await TroveFiles.requestFolder() - Asks the user to pick a folder; returns its path, or null if cancelled
+- await TroveFiles.read(path) - Returns the text of a file inside a granted folder
+Only files inside folders the user granted can be read; there is no write access.
+- await TroveFiles.download(url, suggestedName) - Downloads a remote file to a location the user picks; returns the saved path
+The user is asked to allow each new download domain, and downloads are size-limited.
+
+THEMING:
+Trove keeps a data-prefers-trove-theme attribute ("light" or "dark") on <html> in sync with the system appearance.
+Style dark mode with :root[data-prefers-trove-theme="dark"] selectors rather than polling or reading matchMedia yourself.
+A 'trove-theme-change' event fires on window (event.detail.theme) when it changes.
 
 Start your response directly with <!DOCTYPE html> and end with </html>.`;
 
-const TROVE_STORAGE_SCRIPT = `
-<script>
-(function() {
-  var TIMEOUT_MS = 5000;
-  var pendingRequests = new Map();
-  var requestIdCounter = 0;
+type ClaudeTextBlock = {
+  type: "text";
+  text: string;
+};
 
-  window.addEventListener('message', function(event) {
-    var data = event.data;
-    if (data?.type !== 'trove-storage-response') return;
+/** Injected by the build; `tsx` dev runs report "dev". */
+const SIDECAR_VERSION = process.env.TROVE_SIDECAR_VERSION ?? "dev";
 
-    var request = pendingRequests.get(data.requestId);
-    if (!request) return;
+/**
+ * Output protocol version Trove asks for through TROVE_SIDECAR_PROTOCOL:
+ * one JSON object per line, announced with a `hello` message. Without it,
+ * output uses the older `PROGRESS:`/`HTML_START`/`ERROR:` line markers.
+ */
+const SIDECAR_PROTOCOL = 1;
+const jsonLines = process.env.TROVE_SIDECAR_PROTOCOL === String(SIDECAR_PROTOCOL);
+/** Largest `html_chunk` message, in UTF-16 code units. */
+const HTML_CHUNK_LENGTH = 16 * 1024;
+
+type SidecarMessage =
+  | { type: "hello"; protocol: number }
+  | { type: "progress"; message: string }
+  | { type: "question"; id: string; question: string }
+  | { type: "tool_info"; info: { sidecar_version: string; model: string } }
+  | { type: "html_start" }
+  | { type: "html_chunk"; data: string }
+  | { type: "html_end" }
+  | { type: "error"; message: string };
+
+function send(message: SidecarMessage): void {
+  console.log(JSON.stringify(message));
+}
 
-    clearTimeout(request.timeoutId);
-    pendingRequests.delete(data.requestId);
-    if (data.success) {
-      request.resolve(data.result);
-    } else {
-      request.reject(new Error(data.error));
-    }
-  });
+function reportProgress(message: string): void {
+  console.log(progressLine(message, jsonLines));
+}
 
-  function sendRequest(action, key, value) {
-    return new Promise(function(resolve, reject) {
-      var requestId = ++requestIdCounter;
-      var timeoutId = setTimeout(function() {
-        pendingRequests.delete(requestId);
-        reject(new Error('TroveStorage: operation timed out'));
-      }, TIMEOUT_MS);
-
-      pendingRequests.set(requestId, { resolve: resolve, reject: reject, timeoutId: timeoutId });
-      window.parent.postMessage(
-        { type: 'trove-storage', requestId: requestId, action: action, key: key, value: value },
-        '*'
-      );
-    });
+/**
+ * Writes the finished HTML. JSON-lines output sends it as string chunks, so
+ * nothing in the content can be mistaken for protocol; chunks never split a
+ * surrogate pair.
+ */
+function sendHtml(html: string, markerNonce: string | null): void {
+  if (!jsonLines) {
+    // The nonce fences the HTML so content lines can't be mistaken for markers.
+    const markerSuffix = markerNonce ? `:${markerNonce}` : "";
+    console.log(`HTML_START${markerSuffix}`);
+    console.log(html);
+    console.log(`HTML_END${markerSuffix}`);
+    return;
   }
-
-  window.TroveStorage = {
-    get: function(key) { return sendRequest('get', key); },
-    set: function(key, value) { return sendRequest('set', key, value); },
-    delete: function(key) { return sendRequest('delete', key); },
-    clear: function() { return sendRequest('clear'); },
-    getAll: function() { return sendRequest('getAll'); }
-  };
-})();
-</script>
-`;
-
-type ClaudeTextBlock = {
-  type: "text";
-  text: string;
-};
+  send({ type: "html_start" });
+  let offset = 0;
+  while (offset < html.length) {
+    let end = Math.min(offset + HTML_CHUNK_LENGTH, html.length);
+    const last = html.charCodeAt(end - 1);
+    if (end < html.length && last >= 0xd800 && last <= 0xdbff) {
+      end -= 1;
+    }
+    send({ type: "html_chunk", data: html.slice(offset, end) });
+    offset = end;
+  }
+  send({ type: "html_end" });
+}
 
 let activeQuery: { close(): void } | null = null;
 let activeAbortController: AbortController | null = null;
+/** The question currently waiting on an answer from Trove. */
+let pendingAnswer: { id: string; resolve: (answer: string) => void } | null = null;
+
+/** Control messages Trove writes to stdin, one JSON object per line. */
+type ControlMessage =
+  | { type: "pause" }
+  | { type: "resume" }
+  | { type: "finish_early" }
+  | { type: "answer"; id: string; text: string };
+
+type ControlHandlers = {
+  onPause: () => void;
+  onResume: () => void;
+  onFinishEarly: () => void;
+};
 
 /**
  * Narrow unknown values to record objects.
@@ -129,6 +193,15 @@ function isTextBlock(value: unknown): value is ClaudeTextBlock {
   );
 }
 
+/**
+ * Extract the model name from the SDK's init system message, if present.
+ */
+function getInitModel(value: unknown): string | null {
+  if (!isRecord(value) || value.type !== "system" || value.subtype !== "init") return null;
+  const model = value.model;
+  return typeof model === "string" ? model : null;
+}
+
 /**
  * Extract the result text from a result message, if present.
  */
@@ -149,23 +222,12 @@ function exitWithError(message: string): never {
   if (activeQuery) {
     activeQuery.close();
   }
-  console.log(`ERROR:${normalized}`);
-  process.exit(1);
-}
-
-/**
- * Injects the TroveStorage API script into the HTML head.
- */
-function injectTroveStorage(html: string): string {
-  const headCloseIndex = html.toLowerCase().indexOf('</head>');
-  if (headCloseIndex === -1) {
-    return html;
+  if (jsonLines) {
+    send({ type: "error", message: normalized });
+  } else {
+    console.log(`ERROR:${normalized}`);
   }
-  return (
-    html.slice(0, headCloseIndex) +
-    TROVE_STORAGE_SCRIPT +
-    html.slice(headCloseIndex)
-  );
+  process.exit(1);
 }
 
 /**
@@ -199,6 +261,118 @@ function validateHtml(html: string): string | null {
   return null;
 }
 
+/**
+ * Closes whatever a cut-off document left open (a script or style block,
+ * the head, body and html) so it passes validation.
+ */
+function closeTruncatedHtml(html: string): string {
+  let closed = html;
+  for (const tag of ["script", "style"]) {
+    const lower = closed.toLowerCase();
+    if (lower.lastIndexOf(`<${tag}`) > lower.lastIndexOf(`</${tag}>`)) {
+      closed += `\n</${tag}>`;
+    }
+  }
+  const lower = closed.toLowerCase();
+  if (!lower.includes("</head>")) {
+    closed += "\n</head>";
+  }
+  if (!lower.includes("<body")) {
+    closed += "\n<body>";
+  }
+  if (!lower.includes("</body>")) {
+    closed += "\n</body>";
+  }
+  if (!lower.includes("</html>")) {
+    closed += "\n</html>";
+  }
+  return closed;
+}
+
+/**
+ * Parses one stdin control line, ignoring anything malformed.
+ */
+function parseControlMessage(line: string): ControlMessage | null {
+  let value: unknown;
+  try {
+    value = JSON.parse(line);
+  } catch {
+    return null;
+  }
+  switch (getType(value)) {
+    case "pause":
+      return { type: "pause" };
+    case "resume":
+      return { type: "resume" };
+    case "finish_early":
+      return { type: "finish_early" };
+    case "answer": {
+      const id = isRecord(value) ? value.id : null;
+      const text = isRecord(value) ? value.text : null;
+      return typeof id === "string" && typeof text === "string"
+        ? { type: "answer", id, text }
+        : null;
+    }
+    default:
+      return null;
+  }
+}
+
+/**
+ * Listens on stdin for control messages from Trove. Answers go to the
+ * pending `askQuestion`; the rest to `handlers`, looked up per message so
+ * they can be swapped as the run progresses. Returns a function that stops
+ * listening so stdin doesn't keep the process alive.
+ */
+function listenForControlMessages(handlers: ControlHandlers): () => void {
+  const lines = createInterface({ input: process.stdin });
+  lines.on("line", (line) => {
+    const message = parseControlMessage(line.trim());
+    switch (message?.type) {
+      case "pause":
+        handlers.onPause();
+        break;
+      case "resume":
+        handlers.onResume();
+        break;
+      case "finish_early":
+        handlers.onFinishEarly();
+        break;
+      case "answer":
+        if (pendingAnswer?.id === message.id) {
+          const { resolve: resolveAnswer } = pendingAnswer;
+          pendingAnswer = null;
+          resolveAnswer(message.text);
+        }
+        break;
+    }
+  });
+  return () => {
+    lines.close();
+    process.stdin.destroy();
+  };
+}
+
+/**
+ * Asks the user a clarifying question through Trove and waits for the
+ * answer. Only one question can be outstanding at a time.
+ */
+function askQuestion(question: string): Promise<string> {
+  if (pendingAnswer) {
+    return Promise.reject(new Error("A question is already waiting for an answer"));
+  }
+  const id = randomUUID();
+  return new Promise((resolveAnswer) => {
+    pendingAnswer = { id, resolve: resolveAnswer };
+    if (jsonLines) {
+      send({ type: "question", id, question });
+    } else {
+      console.log(`QUESTION:${JSON.stringify({ id, question })}`);
+    }
+    reportProgress("Waiting for your answer...");
+  });
+}
+
 /**
  * Checks whether a path is executable.
  */
@@ -289,7 +463,194 @@ function findClaudeExecutable(): string | undefined {
   return findClaudeViaLoginShell();
 }
 
+/**
+ * Parses a --creativity value, which must be a number between 0 and 1.
+ */
+function parseCreativity(value: string): number {
+  const parsed = Number.parseFloat(value);
+  if (!Number.isFinite(parsed) || parsed < 0 || parsed > 1) {
+    exitWithError("--creativity must be a number between 0 and 1");
+  }
+  return parsed;
+}
+
+/**
+ * The agent SDK has no sampling temperature option, so creativity is
+ * expressed as design guidance in the prompt instead.
+ */
+function creativityGuidance(creativity: number): string {
+  if (creativity < 0.34) {
+    return "Design approach: keep it conventional and predictable. Use standard layouts, familiar controls, and minimal visual flourish.";
+  }
+  if (creativity > 0.66) {
+    return "Design approach: be inventive. Make bold, distinctive visual and interaction choices, as long as the app stays easy to use.";
+  }
+  return "Design approach: balance polish with familiarity. Add a few thoughtful touches without surprising the user.";
+}
+
+/**
+ * Parses a --language value, a BCP 47 tag such as "en" or "pt-BR".
+ */
+function parseLanguage(value: string): string {
+  const trimmed = value.trim();
+  if (!/^[A-Za-z0-9]{1,8}(-[A-Za-z0-9]{1,8})*$/.test(trimmed)) {
+    exitWithError("--language must be a BCP 47 language tag");
+  }
+  return trimmed;
+}
+
+const CLASSIFY_SYSTEM_PROMPT = `You categorize small single-file web apps from their name and description. Reply with ONLY a JSON object such as {"category": "Productivity", "tags": ["todo", "tasks"]}: one short category and one to five short lowercase tags. No prose and no code fences.`;
+
+const CLARIFY_SYSTEM_PROMPT = `You decide whether a request for a small single-file web app is too vague to build well. Reply with ONLY a JSON object: {"question": null} when a reasonable app can be built as described, or {"question": "..."} with the one short question whose answer would most change what gets built. No prose and no code fences.`;
+
+const NAME_SYSTEM_PROMPT = `You name small single-file web apps from a description of what they should do. Reply with ONLY a JSON object such as {"suggestions": [{"name": "Water Log", "emoji": "💧"}, {"name": "Sip", "emoji": "🥤"}]}: three to five distinct suggestions, each a short title-case name of at most four words and one fitting emoji. No prose and no code fences.`;
+
+/**
+ * Runs a single tool-less model turn and returns the first JSON object in the
+ * reply. `label` names the task in errors.
+ */
+async function querySingleTurnJson(
+  systemPrompt: string,
+  prompt: string,
+  model: string,
+  label: string
+): Promise<unknown> {
+  const claudePath = findClaudeExecutable();
+  if (!claudePath) {
+    exitWithError(
+      "Claude Code CLI not found. Install it or set CLAUDE_CODE_PATH to the executable path."
+    );
+  }
+
+  const abortController = new AbortController();
+  activeAbortController = abortController;
+  const queryHandle = query({
+    prompt,
+    options: {
+      model,
+      systemPrompt,
+      maxTurns: 1,
+      allowedTools: [],
+      pathToClaudeCodeExecutable: claudePath,
+      abortController,
+    },
+  });
+  activeQuery = queryHandle;
+
+  let text = "";
+  try {
+    for await (const message of queryHandle) {
+      const messageType = getType(message);
+      if (messageType === "assistant") {
+        for (const block of getMessageContent(message) ?? []) {
+          if (isTextBlock(block)) {
+            text += block.text;
+          }
+        }
+      } else if (messageType === "result") {
+        const result = getResultText(message);
+        if (result && !text) {
+          text = result;
+        }
+      }
+    }
+  } finally {
+    activeQuery = null;
+    activeAbortController = null;
+  }
+
+  const start = text.indexOf("{");
+  const end = text.lastIndexOf("}");
+  if (start === -1 || end <= start) {
+    throw new Error(`${label} returned no JSON`);
+  }
+  try {
+    return JSON.parse(text.slice(start, end + 1));
+  } catch {
+    throw new Error(`${label} returned invalid JSON`);
+  }
+}
+
+/**
+ * Classify mode: suggests a category and tags for an app and prints them as a
+ * single `SUGGESTION:<json>` line. Trove validates the JSON itself.
+ */
+async function classifyApp(name: string, prompt: string, modelOverride: string | null) {
+  const suggestion = await querySingleTurnJson(
+    CLASSIFY_SYSTEM_PROMPT,
+    `App name: ${name}\n\nDescription:\n${prompt}`,
+    modelOverride ?? process.env.TROVE_CLASSIFY_MODEL ?? "haiku",
+    "Classifier"
+  );
+  console.log(`SUGGESTION:${JSON.stringify(suggestion)}`);
+}
+
+/**
+ * Name mode: suggests a few names with emoji for a prompt and prints them as
+ * a single `NAME:<json>` line. Trove validates the JSON itself.
+ */
+async function suggestName(prompt: string, modelOverride: string | null) {
+  const suggestion = await querySingleTurnJson(
+    NAME_SYSTEM_PROMPT,
+    `Description:\n${prompt}`,
+    modelOverride ?? process.env.TROVE_CLASSIFY_MODEL ?? "haiku",
+    "Namer"
+  );
+  console.log(`NAME:${JSON.stringify(suggestion)}`);
+}
+
+/**
+ * Asks a quick model whether the prompt needs a clarifying question first.
+ * Any failure just means generating without one.
+ */
+async function findClarifyingQuestion(
+  name: string,
+  prompt: string,
+  modelOverride: string | null
+): Promise<string | null> {
+  try {
+    const reply = await querySingleTurnJson(
+      CLARIFY_SYSTEM_PROMPT,
+      `App name: ${name}\n\nDescription:\n${prompt}`,
+      modelOverride ?? process.env.TROVE_CLASSIFY_MODEL ?? "haiku",
+      "Clarifier"
+    );
+    const question = isRecord(reply) ? reply.question : null;
+    return typeof question === "string" && question.trim() ? question.trim() : null;
+  } catch (error) {
+    console.error("Clarifying check failed:", error);
+    return null;
+  }
+}
+
+const ACCESSIBILITY_GUIDANCE = `Accessibility requirements:
+- Use semantic HTML (header, main, nav, button, label) and give every interactive element an accessible name via visible text, <label>, or aria-label.
+- Everything must be operable by keyboard: logical tab order, visible focus styles, Enter/Space on custom controls, Escape to close dialogs.
+- Announce dynamic updates with aria-live regions and keep ARIA states (aria-expanded, aria-pressed, aria-selected) in sync.
+- Meet WCAG AA contrast, don't rely on color alone, and honour prefers-contrast: more, forced-colors, and prefers-reduced-motion.`;
+
+type AppKind = "widget" | "fullscreen";
+
+const APP_KIND_GUIDANCE: Record<AppKind, string> = {
+  widget: `Layout: this is a widget shown in a small frameless window of about 360x280 pixels.
+- Show one glanceable thing; skip headers, navigation, and footers.
+- Fit the content without scrolling and keep controls compact.`,
+  fullscreen: `Layout: this app runs fullscreen.
+- Fill the whole viewport (100vw x 100vh) with no page scrolling and scale content to the screen size.
+- Don't rely on browser chrome; offer an in-app way to leave any focused or paused state.`,
+};
+
+function parseAppKind(value: string): AppKind {
+  if (value !== "widget" && value !== "fullscreen") {
+    exitWithError("--app-kind must be widget or fullscreen");
+  }
+  return value as AppKind;
+}
+
 async function main() {
+  if (jsonLines) {
+    send({ type: "hello", protocol: SIDECAR_PROTOCOL });
+  }
   if (process.platform !== "darwin") {
     exitWithError("Trove sidecar currently supports macOS only");
   }
@@ -297,9 +658,18 @@ async function main() {
   const args = process.argv.slice(2);
   let existingHtmlPath: string | null = null;
   let appsDirPath: string | null = null;
+  let contextFilePath: string | null = null;
   let modelOverride: string | null = null;
   let maxTurnsOverride: number | null = null;
   let timeoutOverride: number | null = null;
+  let creativity: number | null = null;
+  let language: string | null = null;
+  let accessibilityMode = false;
+  let appKind: AppKind | null = null;
+  let markerNonce: string | null = null;
+  let classifyMode = false;
+  let nameMode = false;
+  let clarifyMode = false;
   const filteredArgs: string[] = [];
 
   for (let i = 0; i < args.length; i += 1) {
@@ -330,6 +700,19 @@ async function main() {
       appsDirPath = arg.slice("--apps-dir=".length);
       continue;
     }
+    if (arg === "--context-file") {
+      const next = args[i + 1];
+      if (!next) {
+        exitWithError("Missing path after --context-file");
+      }
+      contextFilePath = next;
+      i += 1;
+      continue;
+    }
+    if (arg.startsWith("--context-file=")) {
+      contextFilePath = arg.slice("--context-file=".length);
+      continue;
+    }
     if (arg === "--model") {
       const next = args[i + 1];
       if (!next) {
@@ -385,6 +768,66 @@ async function main() {
       timeoutOverride = parsed;
       continue;
     }
+    if (arg === "--creativity") {
+      const next = args[i + 1];
+      if (!next) {
+        exitWithError("Missing value after --creativity");
+      }
+      creativity = parseCreativity(next);
+      i += 1;
+      continue;
+    }
+    if (arg.startsWith("--creativity=")) {
+      creativity = parseCreativity(arg.slice("--creativity=".length));
+      continue;
+    }
+    if (arg === "--accessibility") {
+      accessibilityMode = true;
+      continue;
+    }
+    if (arg === "--app-kind") {
+      const next = args[i + 1];
+      if (!next) {
+        exitWithError("Missing value after --app-kind");
+      }
+      appKind = parseAppKind(next);
+      i += 1;
+      continue;
+    }
+    if (arg === "--classify") {
+      classifyMode = true;
+      continue;
+    }
+    if (arg === "--clarify") {
+      clarifyMode = true;
+      continue;
+    }
+    if (arg === "--suggest-name") {
+      nameMode = true;
+      continue;
+    }
+    if (arg === "--marker-nonce") {
+      const next = args[i + 1];
+      if (!next) {
+        exitWithError("Missing value after --marker-nonce");
+      }
+      markerNonce = next;
+      i += 1;
+      continue;
+    }
+    if (arg === "--language") {
+      const next = args[i + 1];
+      if (!next) {
+        exitWithError("Missing value after --language");
+      }
+      language = parseLanguage(next);
+      i += 1;
+      continue;
+    }
+    if (arg.startsWith("--language=")) {
+      language = parseLanguage(arg.slice("--language=".length));
+      continue;
+    }
     filteredArgs.push(arg);
   }
 
@@ -392,19 +835,40 @@ async function main() {
     exitWithError("--apps-dir is required when using --edit");
   }
 
+  if (nameMode) {
+    if (filteredArgs.length < 1) {
+      exitWithError("Usage: trove-sidecar [--model <name>] --suggest-name <prompt>");
+    }
+    try {
+      await suggestName(filteredArgs.join(" "), modelOverride);
+    } catch (error) {
+      exitWithError(error instanceof Error ? error.message : String(error));
+    }
+    return;
+  }
+
   if (filteredArgs.length < 2) {
     exitWithError(
-      "Usage: trove-sidecar [--edit <html-path> --apps-dir <dir>] [--model <name>] [--max-turns <n>] [--timeout-ms <ms>] <name> <prompt>"
+      "Usage: trove-sidecar [--edit <html-path> --apps-dir <dir>] [--context-file <path>] [--model <name>] [--max-turns <n>] [--timeout-ms <ms>] [--creativity <0-1>] [--language <tag>] [--accessibility] [--app-kind widget|fullscreen] [--marker-nonce <nonce>] [--clarify] [--classify] <name> <prompt>"
     );
   }
 
   const [name, ...promptParts] = filteredArgs;
   const prompt = promptParts.join(" ");
 
-  console.log("PROGRESS:Initializing AI agent...");
+  if (classifyMode) {
+    try {
+      await classifyApp(name, prompt, modelOverride);
+    } catch (error) {
+      exitWithError(error instanceof Error ? error.message : String(error));
+    }
+    return;
+  }
+
+  reportProgress("Initializing AI agent...");
 
   try {
-    console.log(`PROGRESS:Generating "${name}"...`);
+    reportProgress(`Generating "${name}"...`);
 
     const editMode = Boolean(existingHtmlPath);
     let resolvedHtmlPath: string | null = null;
@@ -432,12 +896,56 @@ async function main() {
       ? `Update the existing app "${name}" based on the current HTML file at "${resolvedHtmlPath}". Use the Read tool to inspect the existing file before making changes. Apply the new requirements below while preserving working parts unless they conflict.`
       : `Create a web app called "${name}" with the following functionality:`;
 
-    const userPrompt = `${baseInstruction}\n\n${prompt}\n\nRemember: Output ONLY the complete HTML file, starting with <!DOCTYPE html> and ending with </html>.`;
+    let referenceSection = "";
+    if (contextFilePath) {
+      let context: string;
+      try {
+        context = readFileSync(contextFilePath, "utf8").trim();
+      } catch {
+        exitWithError("Failed to read reference context file");
+      }
+      if (context) {
+        referenceSection = `\n\nReference material provided by the user (data, specs, or docs the app should be built around):\n<reference>\n${context}\n</reference>`;
+      }
+    }
+
+    const creativitySection = creativity === null ? "" : `\n\n${creativityGuidance(creativity)}`;
+
+    const languageSection =
+      language === null
+        ? ""
+        : `\n\nWrite all user-facing text (labels, placeholders, messages, sample data) in the language with BCP 47 tag "${language}", and set <html lang="${language}">. Keep code identifiers in English.`;
+
+    const accessibilitySection = accessibilityMode ? `\n\n${ACCESSIBILITY_GUIDANCE}` : "";
+    const appKindSection = appKind ? `\n\n${APP_KIND_GUIDANCE[appKind]}` : "";
+
+    // Handlers are filled in once the query starts; until then only
+    // answers to a clarifying question matter.
+    const controls: ControlHandlers = {
+      onPause: () => {},
+      onResume: () => {},
+      onFinishEarly: () => {},
+    };
+    const stopListening = listenForControlMessages(controls);
+
+    let clarificationSection = "";
+    if (clarifyMode && !editMode) {
+      reportProgress("Checking whether anything needs clarifying...");
+      const question = await findClarifyingQuestion(name, prompt, modelOverride);
+      if (question) {
+        const answer = await askQuestion(question);
+        clarificationSection = `\n\nClarification from the user:\nQ: ${question}\nA: ${answer}`;
+        reportProgress(`Generating "${name}"...`);
+      }
+    }
+
+    const userPrompt = `${baseInstruction}\n\n${prompt}${clarificationSection}${referenceSection}${creativitySection}${languageSection}${accessibilitySection}${appKindSection}\n\nRemember: Output ONLY the complete HTML file, starting with <!DOCTYPE html> and ending with </html>.`;
 
     let htmlContent = "";
     let started = false;
+    let reportedModel: string | null = null;
 
-    console.log("PROGRESS:AI is generating your app...");
+    reportProgress("AI is generating your app...");
 
     const claudePath = findClaudeExecutable();
     if (!claudePath) {
@@ -483,14 +991,61 @@ async function main() {
     });
     activeQuery = queryHandle;
 
-    const timeoutId = setTimeout(() => {
+    const onTimeout = () => {
       abortController.abort();
       queryHandle.close();
-    }, resolvedTimeoutMs);
+    };
+    let deadline = Date.now() + resolvedTimeoutMs;
+    let timeoutId = setTimeout(onTimeout, resolvedTimeoutMs);
+    let remainingMs = resolvedTimeoutMs;
+
+    // Pausing stops reading the agent's output and the timeout clock; the
+    // agent may run ahead until its output buffer fills.
+    // Asserted so the loop's checks aren't narrowed away; handlers set it.
+    let pause = null as { wait: Promise<void>; release: () => void } | null;
+    const releasePause = () => {
+      pause?.release();
+      pause = null;
+    };
+
+    let finishRequested = false;
+    Object.assign(controls, {
+      onPause: () => {
+        if (pause || finishRequested) return;
+        clearTimeout(timeoutId);
+        remainingMs = Math.max(0, deadline - Date.now());
+        let release = () => {};
+        const wait = new Promise<void>((resolveWait) => {
+          release = resolveWait;
+        });
+        pause = { wait, release };
+        reportProgress("Paused");
+      },
+      onResume: () => {
+        if (!pause) return;
+        deadline = Date.now() + remainingMs;
+        timeoutId = setTimeout(onTimeout, remainingMs);
+        releasePause();
+        reportProgress("Resuming...");
+      },
+      onFinishEarly: () => {
+        if (finishRequested) return;
+        finishRequested = true;
+        releasePause();
+        reportProgress("Wrapping up with what's been generated...");
+        abortController.abort();
+        queryHandle.close();
+      },
+    });
 
     try {
       for await (const message of queryHandle) {
+        if (pause) {
+          await pause.wait;
+        }
+        if (finishRequested) break;
         const messageType = getType(message);
+        reportedModel = reportedModel ?? getInitModel(message);
         if (messageType === "assistant") {
           const content = getMessageContent(message);
           if (content) {
@@ -499,7 +1054,11 @@ async function main() {
                 htmlContent += block.text;
                 if (!started && htmlContent.includes("<!DOCTYPE")) {
                   started = true;
-                  console.log("PROGRESS:Receiving HTML content...");
+                  reportProgress("Receiving HTML content...");
+                } else if (started) {
+                  // Trove throttles these, so report every block.
+                  const kb = Math.round(Buffer.byteLength(htmlContent, "utf8") / 1024);
+                  reportProgress(`Receiving HTML content... (${kb} KB)`);
                 }
               }
             }
@@ -512,18 +1071,36 @@ async function main() {
         }
       }
     } catch (error) {
-      if (error instanceof Error && error.name === "AbortError") {
-        exitWithError(
-          `Generation timed out after ${Math.round(resolvedTimeoutMs / 1000)}s`
-        );
+      // Stopping the query on request surfaces as an error; keep the output.
+      if (!finishRequested) {
+        if (error instanceof Error && error.name === "AbortError") {
+          exitWithError(
+            `Generation timed out after ${Math.round(resolvedTimeoutMs / 1000)}s`
+          );
+        }
+        throw error;
       }
-      throw error;
     } finally {
       clearTimeout(timeoutId);
+      stopListening();
       activeQuery = null;
       activeAbortController = null;
     }
 
+    if (finishRequested && !started) {
+      exitWithError("Generation stopped before any HTML was received");
+    }
+
+    const toolInfo = {
+      sidecar_version: SIDECAR_VERSION,
+      model: reportedModel ?? resolvedModel,
+    };
+    if (jsonLines) {
+      send({ type: "tool_info", info: toolInfo });
+    } else {
+      console.log(`TOOL_INFO:${JSON.stringify(toolInfo)}`);
+    }
+
     htmlContent = htmlContent.trim();
 
     if (!htmlContent.startsWith("<!DOCTYPE") && !htmlContent.startsWith("<html")) {
@@ -536,6 +1113,10 @@ async function main() {
       }
     }
 
+    if (finishRequested) {
+      htmlContent = closeTruncatedHtml(htmlContent);
+    }
+
     const htmlEndIndex = htmlContent.lastIndexOf("</html>");
     if (htmlEndIndex !== -1) {
       htmlContent = htmlContent.substring(0, htmlEndIndex + 7);
@@ -550,14 +1131,12 @@ async function main() {
       exitWithError(validationError);
     }
 
-    // Inject TroveStorage API for data persistence
-    htmlContent = injectTroveStorage(htmlContent);
+    // The runtime bridge (TroveStorage and friends) is injected by Trove
+    // when it stores the HTML, so it can be upgraded in place later.
 
-    console.log("PROGRESS:Finalizing...");
-    console.log("HTML_START");
-    console.log(htmlContent);
-    console.log("HTML_END");
-    console.log("PROGRESS:Done!");
+    reportProgress("Finalizing...");
+    sendHtml(htmlContent, markerNonce);
+    reportProgress("Done!");
 
   } catch (error) {
     const message = error instanceof Error ? error.message : String(error);
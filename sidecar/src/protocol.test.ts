@@ -0,0 +1,17 @@
+import assert from "node:assert/strict";
+import { test } from "node:test";
+import { progressLine } from "./protocol.js";
+
+test("marker mode reports progress as a PROGRESS: line", () => {
+  assert.equal(
+    progressLine("Initializing AI agent...", false),
+    "PROGRESS:Initializing AI agent..."
+  );
+});
+
+test("JSON-lines mode reports progress as a progress message", () => {
+  assert.deepEqual(JSON.parse(progressLine("Generating...", true)), {
+    type: "progress",
+    message: "Generating...",
+  });
+});
@@ -0,0 +1,10 @@
+/**
+ * The stdout line reporting `message` as progress: a `progress` message
+ * under the JSON-lines protocol, else a `PROGRESS:` marker line.
+ */
+export function progressLine(message: string, jsonLines: boolean): string {
+  if (jsonLines) {
+    return JSON.stringify({ type: "progress", message });
+  }
+  return `PROGRESS:${message}`;
+}
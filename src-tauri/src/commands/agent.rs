@@ -1,31 +1,46 @@
-use crate::commands::apps::{get_app_html_path, get_app_internal, get_apps_dir_path, save_app};
-use crate::models::{validate_name_prompt, AppMetadata};
+use crate::commands::apps::{
+    get_active_profile_data_dir, get_app_html_path, get_app_internal, get_apps_dir_path,
+    load_app_context, save_app, save_app_context,
+};
+use crate::commands::app_errors::{delete_app_errors, load_app_errors};
+use crate::commands::disk::ensure_disk_space;
+use crate::commands::edits::{apply_edit, hold_pending_edit};
+use crate::commands::generations::{cancel_generations, GenerationJob, GenerationManager};
+use crate::commands::history::{record_generation_attempt, record_unsaved_attempt};
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::providers::run_http_provider;
+use crate::commands::settings::load_settings;
+use crate::commands::tags::spawn_tag_suggestion;
+use crate::commands::timeline::record_generation_timeline;
+use crate::commands::versions::record_version;
+use crate::html::{inject_runtime, lint_bridge_usage, suspicious_edit_reason};
+use crate::models::{
+    build_fix_prompt, validate_context, validate_creativity, validate_language,
+    validate_name_prompt, AppKind, AppMetadata, ChangeKind, GeneratedWith, GenerationAttempt,
+    GenerationKind, GenerationTimeline, ModelProvider, PendingEdit, Settings, TimelineEvent,
+};
+use crate::sandbox::{
+    kill_process_tree, rlimit_args, sample_process_tree, sandbox_exec_args, scrub_env,
+    RLIMIT_SHELL_PATH, SANDBOX_EXEC_PATH,
+};
+use crate::utils::write_atomic;
 use chrono::Utc;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Mutex, OnceLock};
-use tauri::{AppHandle, Emitter, Manager, Window};
-use tauri_plugin_shell::process::CommandChild;
+use std::path::{Path, PathBuf};
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Manager, State, Window};
+use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
-use tokio::time::{timeout, Duration};
+use tokio::time::{timeout, Duration, Instant};
+use uuid::Uuid;
 
-static GENERATION_CANCELLED: AtomicBool = AtomicBool::new(false);
-static GENERATION_ACTIVE: AtomicBool = AtomicBool::new(false);
-static ACTIVE_CHILD: OnceLock<Mutex<Option<CommandChild>>> = OnceLock::new();
-const MAX_HTML_BYTES: usize = 10 * 1024 * 1024;
-
-fn child_store() -> &'static Mutex<Option<CommandChild>> {
-    ACTIVE_CHILD.get_or_init(|| Mutex::new(None))
-}
-
-fn kill_active_child() {
-    if let Ok(mut guard) = child_store().lock() {
-        if let Some(child) = guard.take() {
-            let _ = child.kill();
-        }
-    }
-}
+pub(crate) const MAX_HTML_BYTES: usize = 10 * 1024 * 1024;
+const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// Partial HTML from failed generations, under the profile data dir.
+const FAILED_GENERATIONS_DIR: &str = "failed";
+const MAX_FAILED_GENERATIONS: usize = 20;
+/// Longest answer accepted for a clarifying question from the sidecar.
+const QUESTION_ANSWER_MAX_LENGTH: usize = 2000;
 
 fn validate_sidecar_executable(path: &PathBuf) -> Result<(), String> {
     let metadata =
@@ -44,91 +59,428 @@ fn validate_sidecar_executable(path: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-struct ChildCleanup;
+struct ChildCleanup<'a>(&'a GenerationJob);
 
-impl Drop for ChildCleanup {
+impl Drop for ChildCleanup<'_> {
     fn drop(&mut self) {
-        if let Ok(mut guard) = child_store().lock() {
-            guard.take();
-        }
+        self.0.manager().clear_child(self.0.id);
     }
 }
 
-struct GenerationGuard;
+/// Scratch directory for one sidecar run. It is the sidecar's working
+/// directory and holds the reference context file; removed once the
+/// generation finishes.
+pub(crate) struct GenerationWorkspace(PathBuf);
+
+impl GenerationWorkspace {
+    pub(crate) fn create(context: Option<&str>) -> Result<Self, String> {
+        let dir = std::env::temp_dir().join(format!("trove-generation-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create generation workspace: {}", e))?;
+        let workspace = Self(dir);
+        if let Some(context) = context {
+            write_atomic(&workspace.context_path(), context)?;
+        }
+        Ok(workspace)
+    }
 
-impl GenerationGuard {
-    fn acquire() -> Result<Self, String> {
-        GENERATION_ACTIVE
-            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
-            .map_err(|_| "Another generation is already running".to_string())?;
-        Ok(Self)
+    pub(crate) fn context_path(&self) -> PathBuf {
+        self.0.join("context.txt")
     }
 }
 
-impl Drop for GenerationGuard {
+impl Drop for GenerationWorkspace {
     fn drop(&mut self) {
-        GENERATION_ACTIVE.store(false, Ordering::SeqCst);
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn normalize_context(context: Option<String>) -> Result<Option<String>, String> {
+    let Some(context) = context else {
+        return Ok(None);
+    };
+    let trimmed = context.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    validate_context(trimmed)?;
+    Ok(Some(trimmed.to_string()))
+}
+
+fn normalize_language(language: Option<String>) -> Result<Option<String>, String> {
+    let Some(language) = language else {
+        return Ok(None);
+    };
+    let trimmed = language.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    validate_language(trimmed)?;
+    Ok(Some(trimmed.to_string()))
+}
+
+/// Progress updates within the same stage are delivered at most this often.
+const PROGRESS_MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The stage a progress message belongs to: the message without a trailing
+/// parenthesized detail, e.g. "Receiving HTML content... (12 KB)".
+fn progress_stage(message: &str) -> &str {
+    match message.rfind(" (") {
+        Some(idx) if message.ends_with(')') => &message[..idx],
+        _ => message,
     }
 }
 
+/// Coalesces sidecar progress so a chatty sidecar can't flood the webview.
+/// Stage changes go out immediately; updates within a stage are throttled
+/// to the latest one per `PROGRESS_MIN_INTERVAL`.
+#[derive(Default)]
+pub(crate) struct ProgressThrottle {
+    last_stage: Option<String>,
+    last_sent: Option<Instant>,
+    pending: Option<String>,
+    ready: Vec<String>,
+}
+
+impl ProgressThrottle {
+    pub(crate) fn offer(&mut self, message: &str, now: Instant) {
+        let stage_changed = self.last_stage.as_deref() != Some(progress_stage(message));
+        if stage_changed || self.is_due(now) {
+            self.send(message.to_string(), now);
+        } else {
+            self.pending = Some(message.to_string());
+        }
+    }
+
+    /// Releases a coalesced update once its interval has passed, or
+    /// unconditionally when `force` is set at the end of a run.
+    pub(crate) fn flush(&mut self, now: Instant, force: bool) {
+        if self.pending.is_some() && (force || self.is_due(now)) {
+            if let Some(message) = self.pending.take() {
+                self.send(message, now);
+            }
+        }
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.last_sent
+            .is_none_or(|sent| now.duration_since(sent) >= PROGRESS_MIN_INTERVAL)
+    }
+
+    fn send(&mut self, message: String, now: Instant) {
+        self.last_stage = Some(progress_stage(&message).to_string());
+        self.last_sent = Some(now);
+        self.pending = None;
+        self.ready.push(message);
+    }
+
+    fn take_ready(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.ready)
+    }
+}
+
+/// Protocol version asked for through `SIDECAR_PROTOCOL_ENV`.
+const SIDECAR_PROTOCOL_VERSION: u32 = 1;
+const SIDECAR_PROTOCOL_ENV: &str = "TROVE_SIDECAR_PROTOCOL";
+
+/// How the sidecar frames its output. Sidecars that understand
+/// `SIDECAR_PROTOCOL_ENV` open with a `hello` message and switch to JSON
+/// lines; older ones never send it and keep the line-prefix markers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SidecarProtocol {
+    Markers,
+    JsonLines,
+}
+
+/// One line of JSON-lines output. HTML arrives as string chunks, so its
+/// content can never be mistaken for protocol.
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SidecarMessage {
+    Hello { protocol: u32 },
+    Progress { message: String },
+    Question(GenerationQuestion),
+    ToolInfo { info: serde_json::Value },
+    HtmlStart,
+    HtmlChunk { data: String },
+    HtmlEnd,
+    Error { message: String },
+}
+
+/// Tracks whether stdout is inside the HTML block. With the marker protocol
+/// the start and end lines carry a per-run nonce, so generated content that
+/// happens to contain a bare `HTML_END` line can't end collection early.
+struct HtmlCollector {
+    protocol: SidecarProtocol,
+    start_marker: String,
+    end_marker: String,
+    collecting: bool,
+    /// Percentages of `MAX_HTML_BYTES` still to warn about, ascending.
+    warning_percents: Vec<u8>,
+    crossed_percents: Vec<u8>,
+    /// Clarifying questions from the sidecar not yet sent to the window.
+    questions: Vec<GenerationQuestion>,
+}
+
+impl HtmlCollector {
+    fn new(nonce: &str) -> Self {
+        Self {
+            protocol: SidecarProtocol::Markers,
+            start_marker: format!("HTML_START:{}", nonce),
+            end_marker: format!("HTML_END:{}", nonce),
+            collecting: false,
+            warning_percents: Vec::new(),
+            crossed_percents: Vec::new(),
+            questions: Vec::new(),
+        }
+    }
+
+    fn with_size_warnings(mut self, percents: &[u8]) -> Self {
+        self.warning_percents = percents.to_vec();
+        self.warning_percents.sort_unstable();
+        self.warning_percents.dedup();
+        self
+    }
+
+    /// Notes each warning threshold the collected HTML has now passed.
+    fn check_size(&mut self, html_bytes: usize) {
+        while let Some(&percent) = self.warning_percents.first() {
+            if html_bytes * 100 < MAX_HTML_BYTES * usize::from(percent) {
+                break;
+            }
+            self.warning_percents.remove(0);
+            self.crossed_percents.push(percent);
+        }
+    }
+
+    fn take_crossed_percents(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.crossed_percents)
+    }
+
+    fn take_questions(&mut self) -> Vec<GenerationQuestion> {
+        std::mem::take(&mut self.questions)
+    }
+}
+
+/// Longest question id accepted from the sidecar.
+const QUESTION_ID_MAX_LENGTH: usize = 64;
+
+impl GenerationQuestion {
+    fn parse(raw: &str) -> Option<Self> {
+        serde_json::from_str::<Self>(raw).ok()?.validated()
+    }
+
+    /// Rejects questions with an unusable id or no text.
+    fn validated(self) -> Option<Self> {
+        let id_valid = !self.id.is_empty()
+            && self.id.len() <= QUESTION_ID_MAX_LENGTH
+            && self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        let question = self.question.trim();
+        if !id_valid || question.is_empty() {
+            return None;
+        }
+        Some(Self {
+            question: question.to_string(),
+            id: self.id,
+        })
+    }
+}
+
+/// Control messages written to the sidecar's stdin, one JSON object per
+/// line, so a running generation can be steered.
+#[derive(serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SidecarControl<'a> {
+    Pause,
+    Resume,
+    FinishEarly,
+    Answer { id: &'a str, text: &'a str },
+}
+
+fn send_sidecar_control(
+    manager: &GenerationManager,
+    job_id: Uuid,
+    control: &SidecarControl<'_>,
+) -> Result<(), String> {
+    let mut line = serde_json::to_vec(control)
+        .map_err(|e| format!("Failed to encode sidecar message: {}", e))?;
+    line.push(b'\n');
+    manager.with_child(job_id, |child| {
+        child
+            .write(&line)
+            .map_err(|e| format!("Failed to message the sidecar: {}", e))
+    })
+}
+
 fn process_sidecar_output_line(
     raw_line: &str,
     html_content: &mut String,
-    collecting_html: &mut bool,
+    collector: &mut HtmlCollector,
     error_occurred: &mut Option<String>,
+    timeline: &mut GenerationTimeline,
+    progress: &mut ProgressThrottle,
 ) -> Result<(), String> {
     let line = raw_line.trim();
+    if collector.protocol == SidecarProtocol::JsonLines {
+        return process_sidecar_message(
+            line,
+            html_content,
+            collector,
+            error_occurred,
+            timeline,
+            progress,
+        );
+    }
+
+    // Inside the block every line is content except the exact end marker.
+    if collector.collecting {
+        if line == collector.end_marker {
+            timeline.mark(TimelineEvent::HtmlEnd);
+            collector.collecting = false;
+            return Ok(());
+        }
+        let extra = if html_content.is_empty() { 0 } else { 1 };
+        if html_content.len() + raw_line.len() + extra > MAX_HTML_BYTES {
+            return Err("Generated HTML exceeded size limit".to_string());
+        }
+        if !html_content.is_empty() {
+            html_content.push('\n');
+        }
+        html_content.push_str(raw_line);
+        collector.check_size(html_content.len());
+        return Ok(());
+    }
 
-    if line.starts_with("PROGRESS:") {
+    if line.starts_with('{') {
+        if let Ok(SidecarMessage::Hello { protocol }) = serde_json::from_str(line) {
+            if protocol == SIDECAR_PROTOCOL_VERSION {
+                collector.protocol = SidecarProtocol::JsonLines;
+            } else {
+                eprintln!("Ignoring unsupported sidecar protocol {}", protocol);
+            }
+            return Ok(());
+        }
+    }
+    if let Some(message) = line.strip_prefix("PROGRESS:") {
+        timeline.mark(TimelineEvent::FirstProgress);
+        progress.offer(message.trim(), Instant::now());
         return Ok(());
     }
-    if line == "HTML_START" {
-        *collecting_html = true;
+    if let Some(raw) = line.strip_prefix("QUESTION:") {
+        match GenerationQuestion::parse(raw) {
+            Some(question) => collector.questions.push(question),
+            None => eprintln!("Ignoring malformed sidecar question: {}", raw),
+        }
         return Ok(());
     }
-    if line == "HTML_END" {
-        *collecting_html = false;
+    if let Some(raw) = line.strip_prefix("TOOL_INFO:") {
+        timeline.generated_with = GeneratedWith::parse(raw);
+        return Ok(());
+    }
+    if line == collector.start_marker {
+        timeline.mark(TimelineEvent::HtmlStart);
+        collector.collecting = true;
         return Ok(());
     }
     if let Some(msg) = line.strip_prefix("ERROR:") {
         if error_occurred.is_none() {
             *error_occurred = Some(msg.to_string());
         }
-        return Ok(());
     }
 
-    if *collecting_html {
-        let extra = if html_content.is_empty() { 0 } else { 1 };
-        if html_content.len() + raw_line.len() + extra > MAX_HTML_BYTES {
-            return Err("Generated HTML exceeded size limit".to_string());
+    Ok(())
+}
+
+fn process_sidecar_message(
+    line: &str,
+    html_content: &mut String,
+    collector: &mut HtmlCollector,
+    error_occurred: &mut Option<String>,
+    timeline: &mut GenerationTimeline,
+    progress: &mut ProgressThrottle,
+) -> Result<(), String> {
+    if line.is_empty() {
+        return Ok(());
+    }
+    let message: SidecarMessage = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(err) => {
+            eprintln!("Ignoring malformed sidecar message: {}", err);
+            return Ok(());
         }
-        if !html_content.is_empty() {
-            html_content.push('\n');
+    };
+    match message {
+        SidecarMessage::Hello { .. } => {}
+        SidecarMessage::Progress { message } => {
+            timeline.mark(TimelineEvent::FirstProgress);
+            progress.offer(message.trim(), Instant::now());
+        }
+        SidecarMessage::Question(question) => match question.validated() {
+            Some(question) => collector.questions.push(question),
+            None => eprintln!("Ignoring malformed sidecar question"),
+        },
+        SidecarMessage::ToolInfo { info } => {
+            timeline.generated_with = GeneratedWith::parse(&info.to_string());
+        }
+        SidecarMessage::HtmlStart => {
+            timeline.mark(TimelineEvent::HtmlStart);
+            collector.collecting = true;
+        }
+        SidecarMessage::HtmlChunk { data } => {
+            if !collector.collecting {
+                eprintln!("Ignoring sidecar HTML sent outside html_start/html_end");
+                return Ok(());
+            }
+            if html_content.len() + data.len() > MAX_HTML_BYTES {
+                return Err("Generated HTML exceeded size limit".to_string());
+            }
+            html_content.push_str(&data);
+            collector.check_size(html_content.len());
+        }
+        SidecarMessage::HtmlEnd => {
+            timeline.mark(TimelineEvent::HtmlEnd);
+            collector.collecting = false;
+        }
+        SidecarMessage::Error { message } => {
+            if error_occurred.is_none() {
+                *error_occurred = Some(message);
+            }
         }
-        html_content.push_str(raw_line);
     }
-
     Ok(())
 }
 
+/// Decodes one complete stdout line, dropping a trailing `\r`. Only truly
+/// invalid bytes are replaced, never a character split by chunking.
+fn decode_stdout_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Feeds raw stdout bytes through the line parser. Bytes are buffered until
+/// a full line arrives so multi-byte UTF-8 split across chunks decodes intact.
 fn process_sidecar_stdout_chunk(
     chunk: &[u8],
-    stdout_buffer: &mut String,
+    stdout_buffer: &mut Vec<u8>,
     html_content: &mut String,
-    collecting_html: &mut bool,
+    collector: &mut HtmlCollector,
     error_occurred: &mut Option<String>,
+    timeline: &mut GenerationTimeline,
+    progress: &mut ProgressThrottle,
 ) -> Result<(), String> {
-    let chunk = String::from_utf8_lossy(chunk);
-    stdout_buffer.push_str(&chunk);
-
-    while let Some(newline_idx) = stdout_buffer.find('\n') {
-        let mut line = stdout_buffer[..newline_idx].to_string();
-        if line.ends_with('\r') {
-            line.pop();
-        }
+    stdout_buffer.extend_from_slice(chunk);
 
-        process_sidecar_output_line(&line, html_content, collecting_html, error_occurred)?;
-        stdout_buffer.drain(..=newline_idx);
+    while let Some(newline_idx) = stdout_buffer.iter().position(|&byte| byte == b'\n') {
+        let line_bytes: Vec<u8> = stdout_buffer.drain(..=newline_idx).collect();
+        let line = decode_stdout_line(&line_bytes[..newline_idx]);
+        process_sidecar_output_line(
+            &line,
+            html_content,
+            collector,
+            error_occurred,
+            timeline,
+            progress,
+        )?;
     }
 
     Ok(())
@@ -139,60 +491,272 @@ pub struct GenerationComplete {
     pub app: AppMetadata,
 }
 
+#[derive(Clone, serde::Serialize)]
+pub struct GenerationProgress {
+    pub message: String,
+}
+
+pub(crate) fn emit_progress(
+    window: &Window,
+    job: &GenerationJob,
+    progress: &mut ProgressThrottle,
+) {
+    for message in progress.take_ready() {
+        job.emit(window, "generation-progress", GenerationProgress { message });
+    }
+}
+
 #[derive(Clone, serde::Serialize)]
 pub struct GenerationError {
     pub message: String,
+    /// Where the HTML produced before the failure was saved, if any.
+    pub partial_html_path: Option<String>,
 }
 
-async fn run_sidecar(
+/// The HTML being collected passed a soft size threshold. Generation goes
+/// on; only `MAX_HTML_BYTES` itself fails the run.
+#[derive(Clone, serde::Serialize)]
+pub struct GenerationWarning {
+    pub message: String,
+    pub percent: u8,
+    pub html_bytes: u64,
+    pub limit_bytes: u64,
+}
+
+/// The sidecar paused to ask the user something; generation resumes once
+/// `answer_generation_question` is called with this id.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct GenerationQuestion {
+    pub id: String,
+    pub question: String,
+}
+
+fn emit_questions(window: &Window, job: &GenerationJob, collector: &mut HtmlCollector) {
+    for question in collector.take_questions() {
+        job.manager()
+            .set_pending_question(job.id, Some(question.id.clone()));
+        job.emit(window, "generation-question", question);
+    }
+}
+
+fn emit_size_warnings(
+    window: &Window,
+    job: &GenerationJob,
+    collector: &mut HtmlCollector,
+    html_bytes: usize,
+) {
+    for percent in collector.take_crossed_percents() {
+        let message = format!(
+            "The app's HTML has passed {}% of the {} MB size limit",
+            percent,
+            MAX_HTML_BYTES / (1024 * 1024)
+        );
+        job.emit(
+            window,
+            "generation-warning",
+            GenerationWarning {
+                message,
+                percent,
+                html_bytes: html_bytes as u64,
+                limit_bytes: MAX_HTML_BYTES as u64,
+            },
+        );
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct GenerationSuspicious {
+    pub app_id: String,
+    pub reason: String,
+}
+
+/// What one sidecar run should produce.
+pub(crate) struct SidecarRequest<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) prompt: &'a str,
+    pub(crate) edit_path: Option<PathBuf>,
+    pub(crate) creativity: Option<f32>,
+    pub(crate) language: Option<&'a str>,
+    pub(crate) accessibility_mode: bool,
+    pub(crate) app_kind: AppKind,
+    /// Backend to generate with; the settings default when unset.
+    pub(crate) provider: Option<ModelProvider>,
+}
+
+/// Runs one generation and reports a failure as its only
+/// `generation-error`, after salvaging whatever HTML it had produced.
+pub(crate) async fn run_sidecar(
     app_handle: &AppHandle,
     window: &Window,
-    name: &str,
-    prompt: &str,
-    edit_path: Option<PathBuf>,
+    job: &GenerationJob,
+    request: SidecarRequest<'_>,
+    workspace: &GenerationWorkspace,
+    timeline: &mut GenerationTimeline,
 ) -> Result<String, String> {
-    let _generation_guard = GenerationGuard::acquire()?;
+    let mut output = String::new();
+    let result =
+        generate_html(app_handle, window, job, request, workspace, timeline, &mut output).await;
+    result.map_err(|err| salvage_partial_html(app_handle, window, job, &output, err))
+}
 
+async fn generate_html(
+    app_handle: &AppHandle,
+    window: &Window,
+    job: &GenerationJob,
+    request: SidecarRequest<'_>,
+    workspace: &GenerationWorkspace,
+    timeline: &mut GenerationTimeline,
+    html_content: &mut String,
+) -> Result<String, String> {
+    let settings = load_settings(app_handle)?;
+    let provider = request.provider.unwrap_or(settings.provider);
+    if provider != ModelProvider::ClaudeCode {
+        return run_http_provider(
+            window,
+            job,
+            provider,
+            &settings,
+            &request,
+            workspace,
+            timeline,
+            html_content,
+        )
+        .await;
+    }
     let shell = app_handle.shell();
     let sidecar_path = resolve_sidecar_path(app_handle, "trove-sidecar")?;
-    let sidecar = shell
-        .sidecar(sidecar_path)
-        .map_err(|e| format!("Failed to create sidecar: {}", e))?;
 
     let mut args: Vec<String> = Vec::new();
-    if let Some(path) = edit_path {
+    if settings.ask_clarifying_questions && request.edit_path.is_none() {
+        args.push("--clarify".to_string());
+    }
+    if let Some(path) = request.edit_path {
         let apps_dir = get_apps_dir_path(app_handle)?;
         args.push("--apps-dir".to_string());
         args.push(apps_dir.to_string_lossy().to_string());
         args.push("--edit".to_string());
         args.push(path.to_string_lossy().to_string());
     }
-    args.push(name.to_string());
-    args.push(prompt.to_string());
+    let context_path = workspace.context_path();
+    if context_path.exists() {
+        args.push("--context-file".to_string());
+        args.push(context_path.to_string_lossy().to_string());
+    }
+    if let Some(creativity) = request.creativity {
+        args.push("--creativity".to_string());
+        args.push(format!("{:.2}", creativity));
+    }
+    if let Some(language) = request.language {
+        args.push("--language".to_string());
+        args.push(language.to_string());
+    }
+    if request.accessibility_mode {
+        args.push("--accessibility".to_string());
+    }
+    if let Some(app_kind) = request.app_kind.sidecar_flag() {
+        args.push("--app-kind".to_string());
+        args.push(app_kind.to_string());
+    }
+    let marker_nonce = Uuid::new_v4().simple().to_string();
+    args.push("--marker-nonce".to_string());
+    args.push(marker_nonce.clone());
+    args.push(request.name.to_string());
+    args.push(request.prompt.to_string());
+
+    // Wrappers exec into the next program, so the spawned pid ends up being
+    // the sidecar itself: rlimit shell -> sandbox-exec -> sidecar.
+    let mut program: Option<String> = None;
+    // sandbox-exec only exists on macOS; elsewhere the setting is a no-op.
+    if settings.sandbox_sidecar && cfg!(target_os = "macos") {
+        let home = dirs::home_dir();
+        let mut wrapped = sandbox_exec_args(&workspace.0, home.as_deref(), &sidecar_path);
+        wrapped.append(&mut args);
+        args = wrapped;
+        program = Some(SANDBOX_EXEC_PATH.to_string());
+    }
+    if let Some(cpu_seconds) = settings.sidecar_max_cpu_seconds.filter(|_| cfg!(unix)) {
+        let inner = program.unwrap_or_else(|| sidecar_path.to_string_lossy().to_string());
+        args = rlimit_args(cpu_seconds, &inner, args);
+        program = Some(RLIMIT_SHELL_PATH.to_string());
+    }
+    let sidecar = match program {
+        Some(program) => shell.command(program),
+        None => shell
+            .sidecar(&sidecar_path)
+            .map_err(|e| format!("Failed to create sidecar: {}", e))?,
+    };
 
     let (mut rx, child) = sidecar
         .args(args)
+        .env_clear()
+        .envs(scrub_env(std::env::vars()))
+        .env(SIDECAR_PROTOCOL_ENV, SIDECAR_PROTOCOL_VERSION.to_string())
+        .current_dir(&workspace.0)
         .spawn()
         .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+    timeline.mark(TimelineEvent::Spawned);
+    let pid = child.pid();
+
+    job.manager().set_child(job.id, child)?;
+    let _child_guard = ChildCleanup(job);
+
+    let mut collector =
+        HtmlCollector::new(&marker_nonce).with_size_warnings(&settings.html_size_warning_percents);
+    collect_sidecar_html(
+        window,
+        job,
+        &mut rx,
+        pid,
+        &settings,
+        &mut collector,
+        html_content,
+        timeline,
+    )
+    .await
+}
 
-    if let Ok(mut guard) = child_store().lock() {
-        *guard = Some(child);
-    } else {
-        return Err("Failed to track sidecar process".to_string());
-    }
-    let _child_guard = ChildCleanup;
-
-    let mut html_content = String::new();
-    let mut collecting_html = false;
+#[allow(clippy::too_many_arguments)]
+async fn collect_sidecar_html(
+    window: &Window,
+    job: &GenerationJob,
+    rx: &mut Receiver<CommandEvent>,
+    pid: u32,
+    settings: &Settings,
+    collector: &mut HtmlCollector,
+    html_content: &mut String,
+    timeline: &mut GenerationTimeline,
+) -> Result<String, String> {
     let mut error_occurred: Option<String> = None;
-    let mut stdout_buffer = String::new();
+    let mut stdout_buffer: Vec<u8> = Vec::new();
+    let mut progress = ProgressThrottle::default();
+    let mut last_memory_check = Instant::now();
 
     loop {
-        if GENERATION_CANCELLED.load(Ordering::SeqCst) {
-            kill_active_child();
+        if job.is_cancelled() {
+            job.manager().kill_child(job.id);
             return Err("Generation cancelled".to_string());
         }
 
+        if let Some(limit_mb) = settings.sidecar_max_memory_mb {
+            if last_memory_check.elapsed() >= MEMORY_CHECK_INTERVAL {
+                last_memory_check = Instant::now();
+                if let Some((pids, rss_kb)) = sample_process_tree(pid).await {
+                    let used_mb = rss_kb / 1024;
+                    if used_mb > limit_mb {
+                        kill_process_tree(&pids);
+                        job.manager().kill_child(job.id);
+                        return Err(format!(
+                            "Generation stopped: the sidecar used {} MB, over the {} MB limit",
+                            used_mb, limit_mb
+                        ));
+                    }
+                }
+            }
+        }
+
+        progress.flush(Instant::now(), false);
+        emit_progress(window, job, &mut progress);
+
         let event = match timeout(Duration::from_millis(200), rx.recv()).await {
             Ok(event) => event,
             Err(_) => continue,
@@ -200,29 +764,34 @@ async fn run_sidecar(
 
         let Some(event) = event else { break };
 
-        use tauri_plugin_shell::process::CommandEvent;
         match event {
             CommandEvent::Stdout(chunk) => {
                 if let Err(err) = process_sidecar_stdout_chunk(
                     &chunk,
                     &mut stdout_buffer,
-                    &mut html_content,
-                    &mut collecting_html,
+                    html_content,
+                    collector,
                     &mut error_occurred,
+                    timeline,
+                    &mut progress,
                 ) {
-                    kill_active_child();
+                    job.manager().kill_child(job.id);
                     return Err(err);
                 }
+                emit_progress(window, job, &mut progress);
+                emit_size_warnings(window, job, collector, html_content.len());
+                emit_questions(window, job, collector);
             }
             CommandEvent::Stderr(line) => {
                 let line = String::from_utf8_lossy(&line);
                 eprintln!("Sidecar stderr: {}", line);
             }
             CommandEvent::Error(err) => {
-                kill_active_child();
+                job.manager().kill_child(job.id);
                 return Err(format!("Sidecar error: {}", err));
             }
             CommandEvent::Terminated(status) => {
+                timeline.mark(TimelineEvent::Terminated);
                 if let Some(code) = status.code {
                     if code != 0 {
                         if let Some(err) = error_occurred.take() {
@@ -238,20 +807,21 @@ async fn run_sidecar(
     }
 
     if !stdout_buffer.is_empty() {
-        let trailing = stdout_buffer.trim_end_matches('\r').to_string();
-        if let Err(err) = process_sidecar_output_line(
+        let trailing = decode_stdout_line(&stdout_buffer);
+        process_sidecar_output_line(
             &trailing,
-            &mut html_content,
-            &mut collecting_html,
+            html_content,
+            collector,
             &mut error_occurred,
-        ) {
-            return Err(err);
-        }
+            timeline,
+            &mut progress,
+        )?;
         stdout_buffer.clear();
     }
+    progress.flush(Instant::now(), true);
+    emit_progress(window, job, &mut progress);
 
     if let Some(err) = error_occurred.take() {
-        let _ = window.emit("generation-error", GenerationError { message: err.clone() });
         return Err(err);
     }
 
@@ -266,7 +836,74 @@ async fn run_sidecar(
     Ok(final_html)
 }
 
-fn resolve_sidecar_path(app_handle: &AppHandle, name: &str) -> Result<PathBuf, String> {
+/// Keeps the HTML a failed generation had produced so far in
+/// `failed/<job-id>.html` and points the error at it, so the output can be
+/// inspected or rescued by hand and matched to its job, then emits the
+/// job's `generation-error`.
+fn salvage_partial_html(
+    app_handle: &AppHandle,
+    window: &Window,
+    job: &GenerationJob,
+    html_content: &str,
+    err: String,
+) -> String {
+    let path = if html_content.trim().is_empty() {
+        None
+    } else {
+        match save_partial_html(app_handle, job.id, html_content) {
+            Ok(path) => Some(path.to_string_lossy().to_string()),
+            Err(save_err) => {
+                eprintln!("Failed to save partial HTML: {}", save_err);
+                None
+            }
+        }
+    };
+    let message = match &path {
+        Some(path) => format!("{} (partial output saved to {})", err, path),
+        None => err,
+    };
+    job.emit(
+        window,
+        "generation-error",
+        GenerationError {
+            message: message.clone(),
+            partial_html_path: path,
+        },
+    );
+    message
+}
+
+fn save_partial_html(
+    app_handle: &AppHandle,
+    job_id: Uuid,
+    html_content: &str,
+) -> Result<PathBuf, String> {
+    let dir = get_active_profile_data_dir(app_handle)?.join(FAILED_GENERATIONS_DIR);
+    let path = dir.join(format!("{}.html", job_id));
+    write_atomic(&path, html_content)?;
+    prune_failed_generations(&dir);
+    Ok(path)
+}
+
+/// Drops all but the newest `MAX_FAILED_GENERATIONS` salvaged files.
+fn prune_failed_generations(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    for (_, path) in files.into_iter().skip(MAX_FAILED_GENERATIONS) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub(crate) fn resolve_sidecar_path(app_handle: &AppHandle, name: &str) -> Result<PathBuf, String> {
     let exe_path = std::env::current_exe()
         .map_err(|e| format!("Failed to resolve current executable: {}", e))?;
     let exe_dir = exe_path
@@ -318,6 +955,7 @@ fn resolve_sidecar_path(app_handle: &AppHandle, name: &str) -> Result<PathBuf, S
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn generate_app(
     app_handle: AppHandle,
     window: Window,
@@ -325,20 +963,81 @@ pub async fn generate_app(
     prompt: String,
     emoji: String,
     background_color: String,
+    context: Option<String>,
+    creativity: Option<f32>,
+    language: Option<String>,
+    accessibility_mode: Option<bool>,
+    app_kind: Option<AppKind>,
+    provider: Option<ModelProvider>,
+    job_id: Option<String>,
 ) -> Result<AppMetadata, String> {
-    GENERATION_CANCELLED.store(false, Ordering::SeqCst);
-
     let trimmed_name = name.trim().to_string();
     let trimmed_prompt = prompt.trim().to_string();
-    validate_name_prompt(&trimmed_name, &trimmed_prompt)?;
-
-    let app = AppMetadata::new(trimmed_name, trimmed_prompt, emoji, background_color);
-    let final_html =
-        run_sidecar(&app_handle, &window, &app.name, &app.prompt, None).await?;
-
-    save_app(&app_handle, &app, &final_html)?;
+    let settings = load_settings(&app_handle)?;
+    validate_name_prompt(&trimmed_name, &trimmed_prompt, settings.prompt_max_length)?;
+    let context = normalize_context(context)?;
+    creativity.map(validate_creativity).transpose()?;
+    let language = normalize_language(language)?.or(settings.default_language);
+    let job = GenerationJob::start(&app_handle, &window, job_id, None, &trimmed_name).await?;
+    ensure_disk_space(&app_handle, MAX_HTML_BYTES as u64)?;
+    let workspace = GenerationWorkspace::create(context.as_deref())?;
+
+    let mut app = AppMetadata::new(trimmed_name, trimmed_prompt, emoji, background_color);
+    app.creativity = creativity;
+    app.language = language;
+    app.accessibility_mode = accessibility_mode.unwrap_or(false);
+    app.app_kind = app_kind.unwrap_or_default();
+    let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+    let saved = async {
+        let request = SidecarRequest {
+            name: &app.name,
+            prompt: &app.prompt,
+            edit_path: None,
+            creativity,
+            language: app.language.as_deref(),
+            accessibility_mode: app.accessibility_mode,
+            app_kind: app.app_kind,
+            provider,
+        };
+        let html = run_sidecar(
+            &app_handle,
+            &window,
+            &job,
+            request,
+            &workspace,
+            &mut timeline,
+        )
+        .await?;
+        app.generated_with = timeline.generated_with.clone();
+        let final_html = lint_generated_html(&mut app, html);
+        save_app(&app_handle, &mut app, &final_html)?;
+        Ok::<_, String>(final_html)
+    }
+    .await;
+    let final_html = match saved {
+        Ok(final_html) => final_html,
+        Err(err) => {
+            let attempt = GenerationAttempt::from_timeline(&timeline, &app.prompt, Some(&err));
+            record_unsaved_attempt(&app_handle, attempt);
+            return Err(err);
+        }
+    };
+    // A failed generation leaves no app to attach the timeline to, so only
+    // successful runs are recorded here.
+    let attempt = GenerationAttempt::from_timeline(&timeline, &app.prompt, None);
+    record_generation_attempt(&app_handle, app.id, attempt);
+    record_generation_timeline(&app_handle, app.id, timeline);
+    if let Some(context) = &context {
+        save_app_context(&app_handle, app.id, context)?;
+    }
+    record_version(&app_handle, &app, ChangeKind::Created, &final_html)?;
+    if settings.suggest_tags {
+        spawn_tag_suggestion(app_handle.clone(), &app);
+    }
 
-    let _ = window.emit(
+    job.complete();
+    job.emit(
+        &window,
         "generation-complete",
         GenerationComplete { app: app.clone() },
     );
@@ -347,131 +1046,469 @@ pub async fn generate_app(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn edit_app(
     app_handle: AppHandle,
     window: Window,
+    unlocked: State<'_, UnlockedApps>,
     id: String,
     name: String,
     prompt: String,
     emoji: String,
     background_color: String,
+    context: Option<String>,
+    creativity: Option<f32>,
+    accessibility_mode: Option<bool>,
+    provider: Option<ModelProvider>,
+    job_id: Option<String>,
 ) -> Result<AppMetadata, String> {
-    GENERATION_CANCELLED.store(false, Ordering::SeqCst);
-
     let trimmed_name = name.trim().to_string();
     let trimmed_prompt = prompt.trim().to_string();
-    validate_name_prompt(&trimmed_name, &trimmed_prompt)?;
-
-    let mut app = get_app_internal(&app_handle, &id)?;
+    let settings = load_settings(&app_handle)?;
+    validate_name_prompt(&trimmed_name, &trimmed_prompt, settings.prompt_max_length)?;
+    let new_context = normalize_context(context)?;
+    creativity.map(validate_creativity).transpose()?;
+    ensure_disk_space(&app_handle, MAX_HTML_BYTES as u64)?;
+
+    let original_app = get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, original_app.id)?;
+    let job = GenerationJob::start(
+        &app_handle,
+        &window,
+        job_id,
+        Some(original_app.id),
+        &trimmed_name,
+    )
+    .await?;
+    let mut app = original_app.clone();
     app.name = trimmed_name;
     app.prompt = trimmed_prompt;
     app.emoji = emoji;
     app.background_color = background_color;
-    app.updated_at = Utc::now();
+    // Like context, creativity sticks with the app across edits.
+    if creativity.is_some() {
+        app.creativity = creativity;
+    }
+    if let Some(accessibility_mode) = accessibility_mode {
+        app.accessibility_mode = accessibility_mode;
+    }
+
+    let instruction = app.prompt.clone();
+    run_edit(
+        &app_handle,
+        &window,
+        &job,
+        &original_app,
+        app,
+        &instruction,
+        new_context,
+        provider,
+    )
+    .await
+}
+
+/// Injects the current runtime into freshly generated HTML, applies the
+/// bridge lint's safe rewrites, and records what the lint found on the app.
+pub(crate) fn lint_generated_html(app: &mut AppMetadata, html: String) -> String {
+    let (html, warnings) = lint_bridge_usage(&inject_runtime(&html));
+    app.lint_warnings = warnings;
+    html
+}
 
+/// Runs an edit generation of `original_app` into `app` and applies it, or
+/// holds it back when it looks suspicious. `instruction` is what the sidecar
+/// is asked to change, which is usually the app's updated prompt. Nothing
+/// about the app is persisted until the generation has succeeded, so the
+/// index keeps showing `original_app` for the whole run.
+#[allow(clippy::too_many_arguments)]
+async fn run_edit(
+    app_handle: &AppHandle,
+    window: &Window,
+    job: &GenerationJob,
+    original_app: &AppMetadata,
+    mut app: AppMetadata,
+    instruction: &str,
+    new_context: Option<String>,
+    provider: Option<ModelProvider>,
+) -> Result<AppMetadata, String> {
+    let settings = load_settings(app_handle)?;
     let uuid = app.id;
-    let existing_html_path = get_app_html_path(&app_handle, uuid)?;
+    let existing_html_path = get_app_html_path(app_handle, uuid)?;
     if !existing_html_path.exists() {
         return Err("App HTML file not found".to_string());
     }
-    let final_html =
-        run_sidecar(&app_handle, &window, &app.name, &app.prompt, Some(existing_html_path))
-            .await?;
+    // Edits reuse the stored reference context unless a new one is supplied.
+    let context = match new_context {
+        Some(context) => Some(context),
+        None => load_app_context(app_handle, uuid)?,
+    };
+    let workspace = GenerationWorkspace::create(context.as_deref())?;
+    let mut timeline = GenerationTimeline::new(GenerationKind::Edit);
+    let request = SidecarRequest {
+        name: &app.name,
+        prompt: instruction,
+        edit_path: Some(existing_html_path.clone()),
+        creativity: app.creativity,
+        language: app.language.as_deref(),
+        accessibility_mode: app.accessibility_mode,
+        app_kind: app.app_kind,
+        provider,
+    };
+    let result = run_sidecar(app_handle, window, job, request, &workspace, &mut timeline).await;
+    timeline.error = result.as_ref().err().cloned();
+    app.generated_with = timeline.generated_with.clone();
+    let outcome = result.and_then(|html| {
+        let final_html = lint_generated_html(&mut app, html);
+        app.updated_at = Utc::now();
+        if settings.guard_suspicious_edits {
+            hold_if_suspicious(app_handle, window, job, &app, &final_html, context.as_deref())?;
+        }
+        apply_edit(app_handle, original_app, &mut app, &final_html, context.as_deref())
+    });
+    // Recorded once the edit is applied or refused, so a held or unsaved
+    // edit doesn't show up as a success.
+    let error = outcome.as_ref().err().map(String::as_str);
+    let attempt = GenerationAttempt::from_timeline(&timeline, instruction, error);
+    record_generation_attempt(app_handle, uuid, attempt);
+    record_generation_timeline(app_handle, uuid, timeline);
+    outcome?;
+
+    job.complete();
+    job.emit(window, "generation-complete", GenerationComplete { app: app.clone() });
 
-    save_app(&app_handle, &app, &final_html)?;
+    Ok(app)
+}
 
-    let _ = window.emit("generation-complete", GenerationComplete { app: app.clone() });
+/// Holds an edit back for review when it differs suspiciously from the
+/// app's current HTML, failing with the reason.
+fn hold_if_suspicious(
+    app_handle: &AppHandle,
+    window: &Window,
+    job: &GenerationJob,
+    app: &AppMetadata,
+    final_html: &str,
+    context: Option<&str>,
+) -> Result<(), String> {
+    let previous_html = fs::read_to_string(get_app_html_path(app_handle, app.id)?)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+    let Some(reason) = suspicious_edit_reason(&previous_html, final_html) else {
+        return Ok(());
+    };
+    let pending = PendingEdit {
+        app: app.clone(),
+        reason: reason.clone(),
+        context: context.map(str::to_string),
+        created_at: Utc::now(),
+    };
+    hold_pending_edit(app_handle, &pending, final_html)?;
+    job.emit(
+        window,
+        "generation-suspicious",
+        GenerationSuspicious {
+            app_id: app.id.to_string(),
+            reason: reason.clone(),
+        },
+    );
+    Err(format!(
+        "Suspicious output: {}. The original app was kept; use force_apply_edit to apply it anyway",
+        reason
+    ))
+}
+
+/// Edits an app to fix the runtime errors its bridge has reported, keeping
+/// its prompt and settings. Errors are cleared once the fix is applied.
+/// Locked apps must be unlocked first, as for any edit.
+#[tauri::command]
+pub async fn auto_fix_app(
+    app_handle: AppHandle,
+    window: Window,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+    job_id: Option<String>,
+) -> Result<AppMetadata, String> {
+    let original_app = get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, original_app.id)?;
+    let errors = load_app_errors(&app_handle, original_app.id)?.errors;
+    if errors.is_empty() {
+        return Err("App has no recorded runtime errors".to_string());
+    }
+    let job = GenerationJob::start(
+        &app_handle,
+        &window,
+        job_id,
+        Some(original_app.id),
+        &original_app.name,
+    )
+    .await?;
+    ensure_disk_space(&app_handle, MAX_HTML_BYTES as u64)?;
+
+    let instruction = build_fix_prompt(&original_app.prompt, &errors);
+    let app = run_edit(
+        &app_handle,
+        &window,
+        &job,
+        &original_app,
+        original_app.clone(),
+        &instruction,
+        None,
+        None,
+    )
+    .await?;
+    delete_app_errors(&app_handle, app.id)?;
 
     Ok(app)
 }
 
+/// Cancels the generation with `job_id`, or every running generation when
+/// none is given. A running job reports the cancellation through its own
+/// `generation-error`, with any partial output it salvaged; a queued one
+/// fails its command.
 #[tauri::command]
-pub fn cancel_generation(window: Window) -> Result<(), String> {
-    GENERATION_CANCELLED.store(true, Ordering::SeqCst);
-    if let Ok(mut guard) = child_store().lock() {
-        if let Some(child) = guard.take() {
-            let _ = child.kill();
-        }
+pub fn cancel_generation(
+    manager: State<'_, GenerationManager>,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    cancel_generations(&manager, job_id.as_deref())?;
+    Ok(())
+}
+
+/// Unlike `cancel_generation`, keeps the run going: the sidecar stops the
+/// agent, closes off the HTML it has and hands it back to be saved as usual.
+/// Without a `job_id`, applies to the only running generation; the same goes
+/// for pausing and resuming.
+#[tauri::command]
+pub fn finish_generation_early(
+    manager: State<'_, GenerationManager>,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    let job_id = manager.resolve(job_id.as_deref())?;
+    send_sidecar_control(&manager, job_id, &SidecarControl::FinishEarly)
+}
+
+#[tauri::command]
+pub fn pause_generation(
+    manager: State<'_, GenerationManager>,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    let job_id = manager.resolve(job_id.as_deref())?;
+    send_sidecar_control(&manager, job_id, &SidecarControl::Pause)
+}
+
+#[tauri::command]
+pub fn resume_generation(
+    manager: State<'_, GenerationManager>,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    let job_id = manager.resolve(job_id.as_deref())?;
+    send_sidecar_control(&manager, job_id, &SidecarControl::Resume)
+}
+
+/// Answers the clarifying question from a `generation-question` event. The
+/// question id is enough to find the generation that asked it.
+#[tauri::command]
+pub fn answer_generation_question(
+    manager: State<'_, GenerationManager>,
+    id: String,
+    answer: String,
+) -> Result<(), String> {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err("Answer cannot be empty".to_string());
     }
-    let _ = window.emit(
-        "generation-error",
-        GenerationError {
-            message: "Generation cancelled".to_string(),
+    if answer.chars().count() > QUESTION_ANSWER_MAX_LENGTH {
+        return Err(format!(
+            "Answer must be at most {} characters",
+            QUESTION_ANSWER_MAX_LENGTH
+        ));
+    }
+    let job_id = manager
+        .job_for_question(&id)
+        .ok_or_else(|| "That question is no longer waiting for an answer".to_string())?;
+    send_sidecar_control(
+        &manager,
+        job_id,
+        &SidecarControl::Answer {
+            id: &id,
+            text: answer,
         },
-    );
+    )?;
+    manager.set_pending_question(job_id, None);
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        process_sidecar_output_line, process_sidecar_stdout_chunk, MAX_HTML_BYTES,
+        process_sidecar_output_line, process_sidecar_stdout_chunk, progress_stage, HtmlCollector,
+        ProgressThrottle, SidecarControl, MAX_HTML_BYTES, PROGRESS_MIN_INTERVAL,
     };
+    use crate::models::{GenerationKind, GenerationTimeline, TimelineEvent};
+    use tokio::time::Instant;
 
     #[test]
     fn parses_html_markers_when_chunk_contains_multiple_lines() {
-        let mut stdout_buffer = String::new();
+        let mut stdout_buffer = Vec::new();
         let mut html_content = String::new();
-        let mut collecting_html = false;
+        let mut collector = HtmlCollector::new("n0");
         let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
 
         process_sidecar_stdout_chunk(
-            b"PROGRESS:Generating...\nHTML_START\n<!DOCTYPE html>\n<html></html>\nHTML_END\n",
+            b"PROGRESS:Generating...\nHTML_START:n0\n<!DOCTYPE html>\n<html></html>\nHTML_END:n0\n",
             &mut stdout_buffer,
             &mut html_content,
-            &mut collecting_html,
+            &mut collector,
             &mut error,
+            &mut timeline,
+            &mut ProgressThrottle::default(),
         )
         .expect("chunk should parse");
 
         assert!(stdout_buffer.is_empty());
         assert_eq!(html_content, "<!DOCTYPE html>\n<html></html>");
-        assert!(!collecting_html);
+        assert!(!collector.collecting);
         assert!(error.is_none());
+        let events: Vec<TimelineEvent> = timeline.marks.iter().map(|mark| mark.event).collect();
+        assert_eq!(
+            events,
+            [
+                TimelineEvent::FirstProgress,
+                TimelineEvent::HtmlStart,
+                TimelineEvent::HtmlEnd
+            ]
+        );
     }
 
     #[test]
     fn parses_marker_when_split_across_chunks() {
-        let mut stdout_buffer = String::new();
+        let mut stdout_buffer = Vec::new();
         let mut html_content = String::new();
-        let mut collecting_html = false;
+        let mut collector = HtmlCollector::new("n0");
         let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
 
         process_sidecar_stdout_chunk(
             b"HTML_STA",
             &mut stdout_buffer,
             &mut html_content,
-            &mut collecting_html,
+            &mut collector,
             &mut error,
+            &mut timeline,
+            &mut ProgressThrottle::default(),
         )
         .expect("first chunk should parse");
 
-        assert_eq!(stdout_buffer, "HTML_STA");
+        assert_eq!(stdout_buffer, b"HTML_STA");
 
         process_sidecar_stdout_chunk(
-            b"RT\n<body>\n",
+            b"RT:n0\n<body>\n",
             &mut stdout_buffer,
             &mut html_content,
-            &mut collecting_html,
+            &mut collector,
             &mut error,
+            &mut timeline,
+            &mut ProgressThrottle::default(),
         )
         .expect("second chunk should parse");
 
-        assert!(collecting_html);
+        assert!(collector.collecting);
         assert_eq!(html_content, "<body>");
     }
 
+    #[test]
+    fn keeps_bare_marker_lines_inside_fenced_html() {
+        let mut stdout_buffer = Vec::new();
+        let mut html_content = String::new();
+        let mut collector = HtmlCollector::new("n0");
+        let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+
+        process_sidecar_stdout_chunk(
+            b"HTML_START:n0\n<pre>\nHTML_END\nERROR:not real\n</pre>\nHTML_END:n0\n",
+            &mut stdout_buffer,
+            &mut html_content,
+            &mut collector,
+            &mut error,
+            &mut timeline,
+            &mut ProgressThrottle::default(),
+        )
+        .expect("chunk should parse");
+
+        assert!(!collector.collecting);
+        assert!(error.is_none());
+        assert_eq!(html_content, "<pre>\nHTML_END\nERROR:not real\n</pre>");
+    }
+
+    #[test]
+    fn switches_to_json_lines_after_hello() {
+        let mut stdout_buffer = Vec::new();
+        let mut html_content = String::new();
+        let mut collector = HtmlCollector::new("n0");
+        let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+        let mut progress = ProgressThrottle::default();
+
+        let output = concat!(
+            "{\"type\":\"hello\",\"protocol\":1}\n",
+            "PROGRESS:ignored once JSON lines are on\n",
+            "{\"type\":\"progress\",\"message\":\"Working\"}\n",
+            "{\"type\":\"tool_info\",\"info\":{\"model\":\"sonnet\"}}\n",
+            "{\"type\":\"html_start\"}\n",
+            "{\"type\":\"html_chunk\",\"data\":\"<pre>\\nHTML_END:n0\\nERROR:x\"}\n",
+            "{\"type\":\"html_chunk\",\"data\":\"</pre>\"}\n",
+            "{\"type\":\"html_end\"}\n",
+            "not json\n",
+        );
+        process_sidecar_stdout_chunk(
+            output.as_bytes(),
+            &mut stdout_buffer,
+            &mut html_content,
+            &mut collector,
+            &mut error,
+            &mut timeline,
+            &mut progress,
+        )
+        .expect("chunk should parse");
+
+        assert!(!collector.collecting);
+        assert!(error.is_none());
+        assert_eq!(html_content, "<pre>\nHTML_END:n0\nERROR:x</pre>");
+        assert_eq!(progress.take_ready(), vec!["Working".to_string()]);
+        assert_eq!(
+            timeline
+                .generated_with
+                .as_ref()
+                .and_then(|with| with.model.as_deref()),
+            Some("sonnet")
+        );
+
+        process_sidecar_stdout_chunk(
+            b"{\"type\":\"error\",\"message\":\"boom\"}\n",
+            &mut stdout_buffer,
+            &mut html_content,
+            &mut collector,
+            &mut error,
+            &mut timeline,
+            &mut progress,
+        )
+        .expect("chunk should parse");
+        assert_eq!(error.as_deref(), Some("boom"));
+    }
+
     #[test]
     fn stores_sidecar_error_line() {
         let mut html_content = String::new();
-        let mut collecting_html = false;
+        let mut collector = HtmlCollector::new("n0");
         let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
 
         process_sidecar_output_line(
             "ERROR:Claude Code CLI not found",
             &mut html_content,
-            &mut collecting_html,
+            &mut collector,
             &mut error,
+            &mut timeline,
+            &mut ProgressThrottle::default(),
         )
         .expect("error line should parse");
 
@@ -481,18 +1518,149 @@ mod tests {
     #[test]
     fn rejects_html_larger_than_limit() {
         let mut html_content = String::new();
-        let mut collecting_html = true;
+        let mut collector = HtmlCollector::new("n0");
+        collector.collecting = true;
         let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
         let oversized = "a".repeat(MAX_HTML_BYTES + 1);
 
         let err = process_sidecar_output_line(
             &oversized,
             &mut html_content,
-            &mut collecting_html,
+            &mut collector,
             &mut error,
+            &mut timeline,
+            &mut ProgressThrottle::default(),
         )
         .expect_err("oversized html should fail");
 
         assert_eq!(err, "Generated HTML exceeded size limit");
     }
+
+    #[test]
+    fn reports_each_soft_size_threshold_once() {
+        let mut html_content = String::new();
+        let mut collector = HtmlCollector::new("n0").with_size_warnings(&[95, 80, 80]);
+        collector.collecting = true;
+        let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+        let mut push = |bytes: usize, collector: &mut HtmlCollector| {
+            process_sidecar_output_line(
+                &"a".repeat(bytes),
+                &mut html_content,
+                collector,
+                &mut error,
+                &mut timeline,
+                &mut ProgressThrottle::default(),
+            )
+            .expect("html under the limit should be kept");
+        };
+
+        push(MAX_HTML_BYTES / 2, &mut collector);
+        assert!(collector.take_crossed_percents().is_empty());
+        push(MAX_HTML_BYTES * 2 / 5, &mut collector);
+        assert_eq!(collector.take_crossed_percents(), vec![80]);
+        push(MAX_HTML_BYTES / 50, &mut collector);
+        assert!(collector.take_crossed_percents().is_empty());
+        push(MAX_HTML_BYTES / 20, &mut collector);
+        assert_eq!(collector.take_crossed_percents(), vec![95]);
+    }
+
+    #[test]
+    fn parses_questions_and_encodes_control_messages() {
+        let mut html_content = String::new();
+        let mut collector = HtmlCollector::new("n0");
+        let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+        for line in [
+            r#"QUESTION:{"id":"q-1","question":" Dark mode? "}"#,
+            r#"QUESTION:{"id":"bad id","question":"Why?"}"#,
+            "QUESTION:oops",
+        ] {
+            process_sidecar_output_line(
+                line,
+                &mut html_content,
+                &mut collector,
+                &mut error,
+                &mut timeline,
+                &mut ProgressThrottle::default(),
+            )
+            .expect("questions should parse");
+        }
+        let questions = collector.take_questions();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].id, "q-1");
+        assert_eq!(questions[0].question, "Dark mode?");
+
+        let encode = |control| serde_json::to_string(&control).unwrap();
+        assert_eq!(encode(SidecarControl::FinishEarly), r#"{"type":"finish_early"}"#);
+        assert_eq!(
+            encode(SidecarControl::Answer {
+                id: "q-1",
+                text: "yes"
+            }),
+            r#"{"type":"answer","id":"q-1","text":"yes"}"#
+        );
+    }
+
+    #[test]
+    fn progress_stage_ignores_trailing_detail() {
+        assert_eq!(progress_stage("Receiving HTML content... (12 KB)"), "Receiving HTML content...");
+        assert_eq!(progress_stage("Generating \"Todo (v2)\"..."), "Generating \"Todo (v2)\"...");
+        assert_eq!(progress_stage("Finalizing..."), "Finalizing...");
+    }
+
+    #[test]
+    fn progress_throttle_coalesces_updates_but_not_stage_changes() {
+        let start = Instant::now();
+        let mut progress = ProgressThrottle::default();
+
+        progress.offer("Receiving HTML content... (1 KB)", start);
+        for kb in 2..50 {
+            progress.offer(&format!("Receiving HTML content... ({} KB)", kb), start);
+        }
+        assert_eq!(progress.take_ready(), vec!["Receiving HTML content... (1 KB)"]);
+
+        progress.flush(start, false);
+        assert!(progress.take_ready().is_empty());
+        progress.flush(start + PROGRESS_MIN_INTERVAL, false);
+        assert_eq!(progress.take_ready(), vec!["Receiving HTML content... (49 KB)"]);
+
+        progress.offer("Receiving HTML content... (50 KB)", start + PROGRESS_MIN_INTERVAL);
+        progress.offer("Finalizing...", start + PROGRESS_MIN_INTERVAL);
+        assert_eq!(progress.take_ready(), vec!["Finalizing..."]);
+        progress.flush(start + PROGRESS_MIN_INTERVAL, true);
+        assert!(progress.take_ready().is_empty());
+    }
+
+    #[test]
+    fn keeps_multibyte_characters_split_across_chunks() {
+        let mut stdout_buffer = Vec::new();
+        let mut html_content = String::new();
+        let mut collector = HtmlCollector::new("n0");
+        let mut error: Option<String> = None;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+        let mut progress = ProgressThrottle::default();
+
+        let output = "HTML_START:n0\r\n<p>héllo ✨</p>\r\nHTML_END:n0\n".as_bytes();
+        // Split inside both the two-byte "é" and the three-byte "✨".
+        let split_points = [17, 23, output.len()];
+        let mut start = 0;
+        for end in split_points {
+            process_sidecar_stdout_chunk(
+                &output[start..end],
+                &mut stdout_buffer,
+                &mut html_content,
+                &mut collector,
+                &mut error,
+                &mut timeline,
+                &mut progress,
+            )
+            .expect("chunk should parse");
+            start = end;
+        }
+
+        assert_eq!(html_content, "<p>héllo ✨</p>");
+        assert!(stdout_buffer.is_empty());
+    }
 }
@@ -0,0 +1,129 @@
+use crate::commands::apps::{get_app_file_path, get_app_internal};
+use crate::models::{AppErrorLog, AppErrorReport, AppRuntimeError};
+use crate::utils::write_atomic;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+pub(crate) fn get_errors_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "errors.json")
+}
+
+pub(crate) fn load_app_errors(app_handle: &AppHandle, id: Uuid) -> Result<AppErrorLog, String> {
+    let path = get_errors_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(AppErrorLog::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read app errors: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse app errors: {}", e))
+}
+
+fn save_app_errors(app_handle: &AppHandle, id: Uuid, log: &AppErrorLog) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(log)
+        .map_err(|e| format!("Failed to serialize app errors: {}", e))?;
+
+    write_atomic(&get_errors_path(app_handle, id)?, &content)
+}
+
+pub fn delete_app_errors(app_handle: &AppHandle, id: Uuid) -> Result<(), String> {
+    let path = get_errors_path(app_handle, id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete app errors: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Called by the host when an app's bridge catches an uncaught exception or
+/// unhandled rejection.
+#[tauri::command]
+pub fn report_app_error(
+    app_handle: AppHandle,
+    app_id: String,
+    error: AppErrorReport,
+) -> Result<(), String> {
+    if error.message.trim().is_empty() {
+        return Err("Error message is required".to_string());
+    }
+    let app = get_app_internal(&app_handle, &app_id)?;
+    let mut log = load_app_errors(&app_handle, app.id)?;
+    log.record(error);
+    save_app_errors(&app_handle, app.id, &log)
+}
+
+/// Returns an app's recent runtime errors, most recently seen last.
+#[tauri::command]
+pub fn get_app_errors(app_handle: AppHandle, id: String) -> Result<Vec<AppRuntimeError>, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    Ok(load_app_errors(&app_handle, app.id)?.errors)
+}
+
+#[tauri::command]
+pub fn clear_app_errors(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    delete_app_errors(&app_handle, app.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        build_fix_prompt, AppErrorLog, AppErrorReport, APP_ERRORS_MAX, FIX_PROMPT_MAX_ERRORS,
+    };
+
+    fn report(message: &str, line: u32) -> AppErrorReport {
+        AppErrorReport {
+            message: message.to_string(),
+            source: Some("index.html".to_string()),
+            line: Some(line),
+            column: Some(1),
+            stack: None,
+        }
+    }
+
+    #[test]
+    fn repeated_errors_are_counted_once_and_moved_last() {
+        let mut log = AppErrorLog::default();
+        log.record(report("TypeError: a is undefined", 10));
+        log.record(report("ReferenceError: b is not defined", 20));
+        log.record(report("TypeError: a is undefined", 10));
+
+        assert_eq!(log.errors.len(), 2);
+        assert_eq!(log.errors[1].message, "TypeError: a is undefined");
+        assert_eq!(log.errors[1].count, 2);
+        assert_eq!(log.errors[0].count, 1);
+    }
+
+    #[test]
+    fn keeps_only_the_newest_errors_and_truncates_messages() {
+        let mut log = AppErrorLog::default();
+        for line in 0..(APP_ERRORS_MAX as u32 + 5) {
+            log.record(report("Error", line));
+        }
+        assert_eq!(log.errors.len(), APP_ERRORS_MAX);
+        assert_eq!(log.errors[0].line, Some(5));
+
+        log.record(report(&"é".repeat(5000), 0));
+        let last = log.errors.last().unwrap();
+        assert!(last.message.len() <= 2048);
+        assert!(last.message.chars().all(|ch| ch == 'é'));
+    }
+
+    #[test]
+    fn fix_prompt_lists_recent_errors_newest_first() {
+        let mut log = AppErrorLog::default();
+        for line in 0..(FIX_PROMPT_MAX_ERRORS as u32 + 2) {
+            log.record(report(&format!("Error on {}", line), line));
+        }
+
+        let prompt = build_fix_prompt("  A todo list  ", &log.errors);
+        let newest = prompt.find("1. Error on 6").expect("newest error listed first");
+        let oldest_kept = prompt.find("5. Error on 2").expect("fifth most recent kept");
+        assert!(newest < oldest_kept);
+        assert!(!prompt.contains("Error on 1\n"));
+        assert!(prompt.contains("Location: line 6:1"));
+        assert!(prompt.ends_with("Original app description:\nA todo list"));
+    }
+}
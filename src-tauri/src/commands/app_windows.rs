@@ -0,0 +1,238 @@
+use crate::commands::audit::record_bridge_call;
+use crate::models::AppShortcut;
+use crate::utils::parse_uuid;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{AppHandle, Emitter, State, WebviewWindow, Window};
+use uuid::Uuid;
+
+/// A write to an app's storage, sent to the other windows showing the app.
+/// `key` is `None` when the whole store was cleared.
+#[derive(Clone, Serialize)]
+pub struct StorageChanged {
+    pub app_id: Uuid,
+    pub key: Option<String>,
+}
+
+/// Which webview windows are showing which app: its own app window, or the
+/// main window's panel. App-scoped events go only to these windows rather
+/// than to every open window.
+#[derive(Default)]
+pub struct AppWindows {
+    hosts: Mutex<HashMap<Uuid, HashSet<String>>>,
+    /// Windows whose app set a badge or progress bar, which is cleared
+    /// once the window closes or shows another app.
+    indicators: Mutex<HashSet<String>>,
+    /// Shortcuts registered by the app each window shows, dropped with the
+    /// window or when it shows another app.
+    shortcuts: Mutex<HashMap<String, Vec<AppShortcut>>>,
+}
+
+impl AppWindows {
+    /// Records that the window now shows `app_id`. A window shows one app
+    /// at a time, so it stops hosting whatever it showed before. Returns
+    /// whether the previous app left indicators to clear.
+    pub(crate) fn host(&self, app_id: Uuid, label: &str) -> bool {
+        if let Ok(mut hosts) = self.hosts.lock() {
+            release_label(&mut hosts, label);
+            hosts.entry(app_id).or_default().insert(label.to_string());
+        }
+        self.drop_window_state(label)
+    }
+
+    /// Forgets a closed window. Returns whether its app left indicators.
+    pub(crate) fn release_window(&self, label: &str) -> bool {
+        if let Ok(mut hosts) = self.hosts.lock() {
+            release_label(&mut hosts, label);
+        }
+        self.drop_window_state(label)
+    }
+
+    fn is_hosting(&self, app_id: Uuid, label: &str) -> bool {
+        self.hosts.lock().is_ok_and(|hosts| {
+            hosts
+                .get(&app_id)
+                .is_some_and(|labels| labels.contains(label))
+        })
+    }
+
+    fn mark_indicators(&self, label: &str) {
+        if let Ok(mut indicators) = self.indicators.lock() {
+            indicators.insert(label.to_string());
+        }
+    }
+
+    /// Forgets what the window's app set up on it. Returns whether that
+    /// included indicators, which the caller clears on the window.
+    fn drop_window_state(&self, label: &str) -> bool {
+        if let Ok(mut shortcuts) = self.shortcuts.lock() {
+            shortcuts.remove(label);
+        }
+        self.indicators
+            .lock()
+            .is_ok_and(|mut indicators| indicators.remove(label))
+    }
+
+    /// Runs `f` on the window's shortcuts if it is showing `app_id`.
+    pub(crate) fn with_shortcuts<T>(
+        &self,
+        app_id: Uuid,
+        label: &str,
+        f: impl FnOnce(&mut Vec<AppShortcut>) -> Result<T, String>,
+    ) -> Result<T, String> {
+        if !self.is_hosting(app_id, label) {
+            return Err("App is not shown in this window".to_string());
+        }
+        let mut shortcuts = self
+            .shortcuts
+            .lock()
+            .map_err(|_| "Shortcut registry is unavailable".to_string())?;
+        f(shortcuts.entry(label.to_string()).or_default())
+    }
+
+    pub(crate) fn labels_for(&self, app_id: Uuid) -> Vec<String> {
+        let Ok(hosts) = self.hosts.lock() else {
+            return Vec::new();
+        };
+        hosts
+            .get(&app_id)
+            .map(|labels| labels.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn release_label(hosts: &mut HashMap<Uuid, HashSet<String>>, label: &str) {
+    hosts.retain(|_, labels| {
+        labels.remove(label);
+        !labels.is_empty()
+    });
+}
+
+/// Emits an app-scoped event to the windows showing the app, skipping
+/// `except` (usually the window whose command caused it).
+pub(crate) fn emit_to_app_windows<S: Serialize + Clone>(
+    app_handle: &AppHandle,
+    windows: &AppWindows,
+    app_id: Uuid,
+    except: Option<&str>,
+    event: &str,
+    payload: S,
+) {
+    for label in windows.labels_for(app_id) {
+        if except == Some(label.as_str()) {
+            continue;
+        }
+        let _ = app_handle.emit_to(label.as_str(), event, payload.clone());
+    }
+}
+
+/// Removes the badge and progress bar an app set on its window. On macOS
+/// both live on the dock rather than the window, so they are cleared there
+/// even when the window that set them is already gone.
+pub(crate) fn clear_indicators(window: &WebviewWindow) {
+    let _ = window.set_badge_count(None);
+    let _ = window.set_progress_bar(ProgressBarState {
+        status: Some(ProgressBarStatus::None),
+        progress: None,
+    });
+}
+
+/// Checks that the calling window is showing the app, so an app can't
+/// decorate a window it isn't in.
+fn ensure_hosted(window: &Window, windows: &AppWindows, app_id: &str) -> Result<(), String> {
+    if !windows.is_hosting(parse_uuid(app_id)?, window.label()) {
+        return Err("App is not shown in this window".to_string());
+    }
+    Ok(())
+}
+
+/// Bridge command: shows a count on the dock or taskbar icon, e.g. unread
+/// items; `None` or 0 removes it.
+#[tauri::command]
+pub fn set_app_badge(
+    app_handle: AppHandle,
+    window: Window,
+    windows: State<'_, AppWindows>,
+    app_id: String,
+    count: Option<i64>,
+) -> Result<(), String> {
+    let result = ensure_hosted(&window, &windows, &app_id).and_then(|()| {
+        let count = count.filter(|count| *count > 0);
+        window
+            .set_badge_count(count)
+            .map_err(|e| format!("Failed to set badge: {}", e))?;
+        windows.mark_indicators(window.label());
+        Ok(())
+    });
+    record_bridge_call(&app_handle, &app_id, "badge", None, result.is_ok());
+    result
+}
+
+/// Bridge command: shows a progress bar on the dock or taskbar icon, e.g.
+/// a timer's remaining time, as a percentage; `None` removes it.
+#[tauri::command]
+pub fn set_app_progress(
+    app_handle: AppHandle,
+    window: Window,
+    windows: State<'_, AppWindows>,
+    app_id: String,
+    progress: Option<u64>,
+) -> Result<(), String> {
+    let result = ensure_hosted(&window, &windows, &app_id).and_then(|()| {
+        if progress.is_some_and(|progress| progress > 100) {
+            return Err("Progress must be between 0 and 100".to_string());
+        }
+        let status = match progress {
+            Some(_) => ProgressBarStatus::Normal,
+            None => ProgressBarStatus::None,
+        };
+        window
+            .set_progress_bar(ProgressBarState {
+                status: Some(status),
+                progress,
+            })
+            .map_err(|e| format!("Failed to set progress: {}", e))?;
+        windows.mark_indicators(window.label());
+        Ok(())
+    });
+    record_bridge_call(&app_handle, &app_id, "progress", None, result.is_ok());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppWindows;
+    use uuid::Uuid;
+
+    #[test]
+    fn windows_host_one_app_at_a_time() {
+        let windows = AppWindows::default();
+        let (notes, todo) = (Uuid::new_v4(), Uuid::new_v4());
+        windows.host(notes, "main");
+        windows.host(notes, "app-notes");
+        windows.host(todo, "main");
+
+        assert_eq!(windows.labels_for(notes), vec!["app-notes"]);
+        assert_eq!(windows.labels_for(todo), vec!["main"]);
+
+        windows.release_window("app-notes");
+        assert!(windows.labels_for(notes).is_empty());
+    }
+
+    #[test]
+    fn indicators_are_released_with_their_window() {
+        let windows = AppWindows::default();
+        let (timer, notes) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(!windows.host(timer, "main"));
+        assert!(windows.is_hosting(timer, "main"));
+        windows.mark_indicators("main");
+
+        assert!(windows.host(notes, "main"));
+        assert!(!windows.is_hosting(timer, "main"));
+        windows.mark_indicators("main");
+        assert!(windows.release_window("main"));
+        assert!(!windows.release_window("main"));
+    }
+}
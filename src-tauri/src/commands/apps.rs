@@ -1,8 +1,19 @@
-use crate::models::{AppMetadata, AppsIndex};
-use crate::utils::{parse_uuid, write_atomic};
+use crate::commands::confirm::{redeem_confirmation, ConfirmationTokens, DestructiveAction};
+use crate::commands::focus::ensure_not_focus_blocked;
+use crate::commands::html_cache::HtmlCache;
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::settings::load_settings;
+use crate::html::runtime_version;
+use crate::models::{
+    validate_name_prompt, validate_user_profile_name, AppAsset, AppMetadata, AppOrigin, AppsIndex,
+    ChangeKind, IndexChange, APP_NAME_MAX_LENGTH,
+};
+use crate::utils::{parse_uuid, sync_dir, write_atomic, write_synced, ContentHasher};
+use serde_json::Value;
 use std::fs;
-use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
 use uuid::Uuid;
 
 fn migrate_legacy_apps(apps_dir: &Path) -> Result<(), String> {
@@ -51,33 +62,153 @@ fn migrate_legacy_apps(apps_dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
-fn get_apps_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app_handle
+pub fn get_app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle
         .path()
         .app_data_dir()
-        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?;
-    let apps_dir = app_data_dir.join("apps");
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))
+}
+
+const PROFILES_DIR_NAME: &str = "profiles";
+pub(crate) const INDEX_FILE_NAME: &str = "apps.json";
+/// Where a save keeps the files it replaced, inside its staging directory.
+const PREVIOUS_HTML_FILE: &str = "previous.html";
+const PREVIOUS_ASSETS_DIR: &str = "previous-assets";
+/// The content hash a save is writing, so startup can tell whether an
+/// interrupted save reached the index.
+const STAGED_HASH_FILE: &str = "hash";
+/// Where a save moved conflicting HTML, so startup can put it back.
+const SET_ASIDE_FILE: &str = "set-aside";
+/// Where `save_app` moves HTML it finds in an app's place that doesn't
+/// match the app's index entry, e.g. after backups were restored by hand.
+const RECOVERED_DIR_NAME: &str = "recovered";
+/// The app's HTML inside its directory.
+pub(crate) const APP_HTML_FILE: &str = "index.html";
+/// Marks an apps directory whose flat `<uuid>.<name>` files have been moved
+/// into per-app directories.
+const APP_DIRS_MARKER: &str = ".app-dirs";
+/// Where saves stage their files, inside the apps directory.
+pub(crate) const STAGING_DIR_NAME: &str = ".staging";
+
+/// Where a user profile keeps its data; the main profile (`None`) uses the
+/// app data dir itself, so its layout predates profiles.
+pub(crate) fn get_profile_data_dir(
+    app_handle: &AppHandle,
+    profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    let data_dir = get_app_data_dir(app_handle)?;
+    match profile {
+        Some(name) => {
+            // The name becomes a path component, and settings.json could
+            // have been edited by hand.
+            validate_user_profile_name(name)?;
+            Ok(data_dir.join(PROFILES_DIR_NAME).join(name))
+        }
+        None => Ok(data_dir),
+    }
+}
+
+pub(crate) fn get_active_profile_data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let settings = load_settings(app_handle)?;
+    get_profile_data_dir(app_handle, settings.current_profile.as_deref())
+}
+
+pub(crate) fn get_profile_apps_dir(
+    app_handle: &AppHandle,
+    profile: Option<&str>,
+) -> Result<PathBuf, String> {
+    let apps_dir = get_profile_data_dir(app_handle, profile)?.join("apps");
 
     if !apps_dir.exists() {
         fs::create_dir_all(&apps_dir)
             .map_err(|e| format!("Failed to create apps directory: {}", e))?;
     }
 
-    migrate_legacy_apps(&apps_dir)?;
+    if profile.is_none() {
+        migrate_legacy_apps(&apps_dir)?;
+    }
+    migrate_flat_app_files(&apps_dir)?;
 
     Ok(apps_dir)
 }
 
-pub fn get_apps_dir_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    get_apps_dir(app_handle)
+/// The directory holding every file of one app, so deleting or moving an
+/// app is a single directory operation.
+pub(crate) fn get_app_dir_in(apps_dir: &Path, id: Uuid) -> PathBuf {
+    apps_dir.join(id.to_string())
+}
+
+/// One of an app's files, e.g. `<uuid>/data.json`.
+pub(crate) fn app_file_in(apps_dir: &Path, id: Uuid, name: &str) -> PathBuf {
+    get_app_dir_in(apps_dir, id).join(name)
+}
+
+/// `app_file_in` for the active profile's apps directory.
+pub(crate) fn get_app_file_path(
+    app_handle: &AppHandle,
+    id: Uuid,
+    name: &str,
+) -> Result<PathBuf, String> {
+    Ok(app_file_in(&get_apps_dir(app_handle)?, id, name))
 }
 
-fn get_index_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
-    Ok(get_apps_dir(app_handle)?.join("apps.json"))
+/// Where a file from the old flat layout belongs in its app's directory:
+/// `<uuid>.data.json` becomes `<uuid>/data.json`, and `<uuid>.html` becomes
+/// `<uuid>/index.html`.
+fn nested_app_file(file_name: &str) -> Option<(Uuid, String)> {
+    let (id, rest) = file_name.split_once('.')?;
+    let id = Uuid::parse_str(id).ok()?;
+    match rest {
+        "" => None,
+        "html" => Some((id, APP_HTML_FILE.to_string())),
+        _ => Some((id, rest.to_string())),
+    }
 }
 
-fn load_index(app_handle: &AppHandle) -> Result<AppsIndex, String> {
-    let index_path = get_index_path(app_handle)?;
+/// Moves files saved before per-app directories, when every app kept its
+/// files flat in the apps directory, into the app's own directory. Runs
+/// once per apps directory; files whose new path is already taken stay put.
+fn migrate_flat_app_files(apps_dir: &Path) -> Result<(), String> {
+    let marker = apps_dir.join(APP_DIRS_MARKER);
+    if marker.exists() {
+        return Ok(());
+    }
+    let entries =
+        fs::read_dir(apps_dir).map_err(|e| format!("Failed to read apps directory: {}", e))?;
+    for entry in entries.flatten() {
+        let Some((id, name)) = nested_app_file(&entry.file_name().to_string_lossy()) else {
+            continue;
+        };
+        let dest = app_file_in(apps_dir, id, &name);
+        if dest.exists() {
+            continue;
+        }
+        fs::create_dir_all(get_app_dir_in(apps_dir, id))
+            .map_err(|e| format!("Failed to create app directory: {}", e))?;
+        match fs::rename(entry.path(), &dest) {
+            // Another caller migrating at the same time got there first.
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Failed to move {} into its app directory: {}", name, e));
+            }
+            _ => {}
+        }
+    }
+    write_atomic(&marker, "")
+}
+
+/// The active profile's apps directory, which every per-app path is built
+/// on, so switching profiles switches the whole library.
+fn get_apps_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let settings = load_settings(app_handle)?;
+    get_profile_apps_dir(app_handle, settings.current_profile.as_deref())
+}
+
+pub fn get_apps_dir_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    get_apps_dir(app_handle)
+}
+
+pub(crate) fn load_index_in(apps_dir: &Path) -> Result<AppsIndex, String> {
+    let index_path = apps_dir.join(INDEX_FILE_NAME);
     if !index_path.exists() {
         return Ok(AppsIndex::default());
     }
@@ -89,22 +220,182 @@ fn load_index(app_handle: &AppHandle) -> Result<AppsIndex, String> {
         .map_err(|e| format!("Failed to parse apps index: {}", e))
 }
 
-fn save_index(app_handle: &AppHandle, index: &AppsIndex) -> Result<(), String> {
-    let index_path = get_index_path(app_handle)?;
+pub(crate) fn save_index_in(apps_dir: &Path, index: &AppsIndex) -> Result<(), String> {
     let content = serde_json::to_string_pretty(index)
         .map_err(|e| format!("Failed to serialize apps index: {}", e))?;
 
-    write_atomic(&index_path, &content)
+    write_atomic(&apps_dir.join(INDEX_FILE_NAME), &content)
+}
+
+pub(crate) fn load_index(app_handle: &AppHandle) -> Result<AppsIndex, String> {
+    load_index_in(&get_apps_dir(app_handle)?)
+}
+
+/// Sent as `app-added` and `app-updated` whenever the active profile's
+/// index changes, so every window can patch its list instead of reloading it.
+#[derive(Clone, serde::Serialize)]
+pub struct AppChanged {
+    pub app: AppMetadata,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct AppRemoved {
+    pub app_id: Uuid,
+}
+
+/// Sent as `app-html-recovered` when a save finds HTML its app didn't write
+/// and moves it to `path`.
+#[derive(Clone, serde::Serialize)]
+pub struct AppHtmlRecovered {
+    pub app_id: Uuid,
+    pub path: PathBuf,
+}
+
+/// Serializes changes to the index. Generations finish in parallel, and a
+/// writer working from a stale copy would drop the other's entry.
+#[derive(Default)]
+pub struct IndexLock(Mutex<()>);
+
+/// Loads the active profile's index, applies `change` and saves the result
+/// under `IndexLock`, announcing each entry that changed. Nothing is written
+/// if `change` fails or leaves the index as it was. `change` must not touch
+/// the index through any other path.
+pub(crate) fn update_index<T>(
+    app_handle: &AppHandle,
+    change: impl FnOnce(&mut AppsIndex) -> Result<T, String>,
+) -> Result<T, String> {
+    let lock = app_handle.state::<IndexLock>();
+    let _guard = lock.0.lock().unwrap_or_else(|e| e.into_inner());
+    let apps_dir = get_apps_dir(app_handle)?;
+    let previous = load_index_in(&apps_dir)?;
+    let mut index = previous.clone();
+    let value = change(&mut index)?;
+    let changes = index.changes_since(&previous);
+    if changes.is_empty() {
+        return Ok(value);
+    }
+    save_index_in(&apps_dir, &index)?;
+    for change in changes {
+        let _ = match change {
+            IndexChange::Added(app) => app_handle.emit("app-added", AppChanged { app }),
+            IndexChange::Updated(app) => app_handle.emit("app-updated", AppChanged { app }),
+            IndexChange::Removed(app_id) => app_handle.emit("app-removed", AppRemoved { app_id }),
+        };
+    }
+    Ok(value)
+}
+
+/// The directory holding an app's HTML, lock and config as the active
+/// profile sees it: its own apps directory, or the main profile's for apps
+/// shared through the gallery. Storage and other per-profile state always
+/// stay in the active profile.
+pub(crate) fn get_app_home_dir(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    let settings = load_settings(app_handle)?;
+    let apps_dir = get_profile_apps_dir(app_handle, settings.current_profile.as_deref())?;
+    let is_own = app_file_in(&apps_dir, id, APP_HTML_FILE).exists();
+    if settings.current_profile.is_none() || is_own || !settings.is_app_visible(id) {
+        return Ok(apps_dir);
+    }
+    get_profile_apps_dir(app_handle, None)
 }
 
 pub fn get_app_html_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
-    Ok(get_apps_dir(app_handle)?.join(format!("{}.html", id)))
+    Ok(app_file_in(&get_app_home_dir(app_handle, id)?, id, APP_HTML_FILE))
+}
+
+pub fn get_app_context_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "context.txt")
+}
+
+/// Loads the reference context stored alongside an app, if any.
+pub fn load_app_context(app_handle: &AppHandle, id: Uuid) -> Result<Option<String>, String> {
+    let path = get_app_context_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read app context: {}", e))
+}
+
+pub fn save_app_context(app_handle: &AppHandle, id: Uuid, context: &str) -> Result<(), String> {
+    let path = get_app_context_path(app_handle, id)?;
+    write_atomic(&path, context)
 }
 
 #[tauri::command]
 pub fn list_apps(app_handle: AppHandle) -> Result<Vec<AppMetadata>, String> {
+    let mut apps = load_index(&app_handle)?.apps;
+    apps.extend(load_gallery_apps(&app_handle)?);
+    Ok(apps.into_iter().filter(|app| !app.draft).collect())
+}
+
+/// Like `list_apps`, but each app's prompt is cut down to `prompt_preview`
+/// to keep the payload small for large libraries. `get_app` returns the
+/// full metadata.
+#[tauri::command]
+pub fn list_apps_light(app_handle: AppHandle) -> Result<Vec<Value>, String> {
+    list_apps(app_handle)?
+        .iter()
+        .map(AppMetadata::to_listing)
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_app(app_handle: AppHandle, id: String) -> Result<AppMetadata, String> {
+    get_visible_app(&app_handle, parse_uuid(&id)?)
+}
+
+/// Main profile apps shared into the active profile; none for the main
+/// profile itself.
+pub(crate) fn load_gallery_apps(app_handle: &AppHandle) -> Result<Vec<AppMetadata>, String> {
+    let settings = load_settings(app_handle)?;
+    if settings.current_profile.is_none() {
+        return Ok(Vec::new());
+    }
+    let gallery = load_index_in(&get_profile_apps_dir(app_handle, None)?)?;
+    Ok(gallery
+        .apps
+        .into_iter()
+        .filter(|app| settings.is_app_visible(app.id))
+        .collect())
+}
+
+/// Looks an app up in the active profile, falling back to the apps shared
+/// into it through the gallery. Only for reading: gallery apps belong to
+/// the main profile, so commands that change an app use `get_app_internal`.
+pub(crate) fn get_visible_app(app_handle: &AppHandle, id: Uuid) -> Result<AppMetadata, String> {
+    if let Some(app) = load_index(app_handle)?.get(id) {
+        return Ok(app.clone());
+    }
+    load_gallery_apps(app_handle)?
+        .into_iter()
+        .find(|app| app.id == id)
+        .ok_or_else(|| format!("App not found: {}", id))
+}
+
+#[tauri::command]
+pub fn list_drafts(app_handle: AppHandle) -> Result<Vec<AppMetadata>, String> {
     let index = load_index(&app_handle)?;
-    Ok(index.apps)
+    Ok(index.apps.into_iter().filter(|app| app.draft).collect())
+}
+
+/// Moves a draft into the main grid. Publishing doesn't trust the app: a
+/// quarantined import stays quarantined.
+#[tauri::command]
+pub fn publish_draft(app_handle: AppHandle, id: String) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        if !app.draft {
+            return Err("App is not a draft".to_string());
+        }
+        app.draft = false;
+        Ok(app.clone())
+    })
 }
 
 pub(crate) fn get_app_internal(app_handle: &AppHandle, id: &str) -> Result<AppMetadata, String> {
@@ -117,56 +408,406 @@ pub(crate) fn get_app_internal(app_handle: &AppHandle, id: &str) -> Result<AppMe
 }
 
 #[tauri::command]
-pub fn get_app_path(app_handle: AppHandle, id: String) -> Result<String, String> {
-    let uuid = parse_uuid(&id)?;
-    let path = get_app_html_path(&app_handle, uuid)?;
+pub fn get_app_path(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    html_cache: State<'_, HtmlCache>,
+    id: String,
+) -> Result<String, String> {
+    let path = get_openable_html_path(&app_handle, &unlocked, &id)?;
+    // The webview reads the file itself; reading it here as well warms the
+    // disk cache and keeps it ready for `get_app_html`.
+    html_cache.warm(&app_handle, path.clone());
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// The HTML path of an app the user may open right now: visible in the
+/// active profile, not blocked by focus mode, and unlocked.
+pub(crate) fn get_openable_html_path(
+    app_handle: &AppHandle,
+    unlocked: &UnlockedApps,
+    id: &str,
+) -> Result<PathBuf, String> {
+    let uuid = parse_uuid(id)?;
+    get_visible_app(app_handle, uuid)?;
+    ensure_not_focus_blocked(app_handle, uuid)?;
+    ensure_unlocked(app_handle, unlocked, uuid)?;
+    let path = get_app_html_path(app_handle, uuid)?;
     if !path.exists() {
         return Err(format!("App HTML file not found for id: {}", id));
     }
-    Ok(path.to_string_lossy().to_string())
+    Ok(path)
 }
 
+/// Second step of deleting an app; `confirm_token` comes from
+/// `request_delete_confirmation` and can only be used once.
 #[tauri::command]
-pub fn delete_app(app_handle: AppHandle, id: String) -> Result<(), String> {
-    let uuid = parse_uuid(&id)?;
+pub fn delete_app(
+    app_handle: AppHandle,
+    tokens: State<'_, ConfirmationTokens>,
+    id: String,
+    confirm_token: String,
+) -> Result<(), String> {
+    redeem_confirmation(&tokens, &confirm_token, DestructiveAction::DeleteApp, &id)?;
+    remove_app(&app_handle, &id)
+}
 
-    let mut index = load_index(&app_handle)?;
-    index
-        .remove(uuid)
-        .ok_or_else(|| format!("App not found: {}", id))?;
-    save_index(&app_handle, &index)?;
+/// Removes an app from the index along with every file belonging to it.
+pub(crate) fn remove_app(app_handle: &AppHandle, id: &str) -> Result<(), String> {
+    let uuid = parse_uuid(id)?;
 
-    let html_path = get_app_html_path(&app_handle, uuid)?;
+    let removed = update_index(app_handle, |index| {
+        index
+            .remove(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))
+    })?;
+    crate::commands::search::unindex_app(app_handle, uuid);
+    if removed.autostart {
+        crate::commands::autostart::sync_login_item(app_handle)?;
+    }
+
+    crate::commands::artifacts::remove_app_artifacts(app_handle, uuid)
+}
+
+pub fn get_app_assets_dir(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "assets")
+}
+
+fn get_staging_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_apps_dir(app_handle)?.join(STAGING_DIR_NAME))
+}
+
+/// Resolves an asset path, rejecting anything that could escape the
+/// assets directory.
+pub(crate) fn validate_asset_path(path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    let is_safe = !path.is_empty()
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !is_safe {
+        return Err(format!("Invalid asset path: {}", path));
+    }
+    Ok(relative.to_path_buf())
+}
+
+/// Writes the HTML and assets of an app into a fresh staging directory,
+/// fsyncing everything before it is moved into place, along with the hash
+/// they add up to.
+fn stage_app_files(
+    staging: &Path,
+    html_content: &str,
+    assets: &[AppAsset],
+    hash: &str,
+) -> Result<(), String> {
+    let staged_assets = staging.join("assets");
+    fs::create_dir_all(&staged_assets)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    write_synced(&staging.join(STAGED_HASH_FILE), hash.as_bytes())?;
+    write_synced(&staging.join("index.html"), html_content.as_bytes())?;
+    for asset in assets {
+        let dest = staged_assets.join(validate_asset_path(&asset.path)?);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create asset directory: {}", e))?;
+            write_synced(&dest, &asset.data)?;
+            sync_dir(parent);
+        }
+    }
+    sync_dir(&staged_assets);
+    sync_dir(staging);
+    Ok(())
+}
+
+/// Moves staged files over the live ones. The replaced HTML and assets are
+/// kept in the staging directory until the index is committed, so
+/// `restore_swapped_files` can put them back. A save without assets only
+/// replaces the HTML and leaves the live assets where they are.
+fn swap_staged_files(
+    html_path: &Path,
+    assets_dir: &Path,
+    staging: &Path,
+    has_assets: bool,
+) -> Result<(), String> {
+    let app_dir = html_path.parent().ok_or("App HTML path has no parent")?;
+    fs::create_dir_all(app_dir).map_err(|e| format!("Failed to create app directory: {}", e))?;
+
+    if has_assets {
+        if assets_dir.exists() {
+            fs::rename(assets_dir, staging.join(PREVIOUS_ASSETS_DIR))
+                .map_err(|e| format!("Failed to swap app assets: {}", e))?;
+        }
+        fs::rename(staging.join("assets"), assets_dir)
+            .map_err(|e| format!("Failed to swap app assets: {}", e))?;
+    }
+
+    // A hard link keeps the old HTML without a moment where the app has none.
     if html_path.exists() {
-        fs::remove_file(&html_path)
-            .map_err(|e| format!("Failed to delete app HTML: {}", e))?;
+        let previous = staging.join(PREVIOUS_HTML_FILE);
+        fs::hard_link(html_path, &previous)
+            .or_else(|_| fs::copy(html_path, &previous).map(|_| ()))
+            .map_err(|e| format!("Failed to back up app HTML: {}", e))?;
     }
+    fs::rename(staging.join("index.html"), html_path)
+        .map_err(|e| format!("Failed to finalize app HTML: {}", e))?;
+    sync_dir(app_dir);
+    Ok(())
+}
 
-    // Delete associated storage file
-    crate::commands::storage::delete_storage_file(&app_handle, &id)?;
+/// Undoes `swap_staged_files` after a failed or interrupted save, putting
+/// back any HTML set aside for it. Best effort: in-process this runs while
+/// another error is already being reported.
+fn restore_swapped_files(html_path: &Path, assets_dir: &Path, staging: &Path) {
+    let previous_html = staging.join(PREVIOUS_HTML_FILE);
+    if previous_html.exists() {
+        let _ = fs::rename(&previous_html, html_path);
+    } else if !staging.join("index.html").exists() {
+        let _ = fs::remove_file(html_path);
+    }
+    if !staging.join("assets").exists() {
+        let _ = fs::remove_dir_all(assets_dir);
+    }
+    let previous_assets = staging.join(PREVIOUS_ASSETS_DIR);
+    if previous_assets.exists() {
+        let _ = fs::rename(&previous_assets, assets_dir);
+    }
+    if let Ok(set_aside) = fs::read_to_string(staging.join(SET_ASIDE_FILE)) {
+        if !html_path.exists() {
+            let _ = fs::rename(set_aside, html_path);
+        }
+    }
+}
 
+/// Whether a save interrupted by a crash reached the index. The index is
+/// written last, after the HTML rename, so a save whose HTML is still
+/// staged never did; one whose hash the entry records did.
+fn interrupted_save_committed(
+    html_swapped: bool,
+    staged_hash: Option<&str>,
+    entry: Option<&AppMetadata>,
+) -> bool {
+    html_swapped
+        && staged_hash.is_some()
+        && entry.and_then(|entry| entry.content_hash.as_deref()) == staged_hash
+}
+
+/// Finishes saves a crash interrupted in one apps directory: ones that
+/// reached the index keep their new files, the rest get their old files
+/// back, so an app never starts with new assets beside old HTML.
+fn recover_interrupted_saves_in(apps_dir: &Path) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(apps_dir.join(STAGING_DIR_NAME)) else {
+        return Ok(());
+    };
+    let index = load_index_in(apps_dir)?;
+    for entry in entries.flatten() {
+        let staging = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = name.get(..36).and_then(|id| Uuid::parse_str(id).ok()) else {
+            continue;
+        };
+        let staged_hash = fs::read_to_string(staging.join(STAGED_HASH_FILE)).ok();
+        let html_swapped = !staging.join("index.html").exists();
+        if !interrupted_save_committed(html_swapped, staged_hash.as_deref(), index.get(id)) {
+            let html_path = app_file_in(apps_dir, id, APP_HTML_FILE);
+            restore_swapped_files(&html_path, &app_file_in(apps_dir, id, "assets"), &staging);
+        }
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to remove interrupted save: {}", e))?;
+    }
     Ok(())
 }
 
-pub fn save_app(
+/// Runs at startup, before anything can save, for every profile.
+pub fn recover_interrupted_saves(app_handle: &AppHandle) {
+    let profiles = match load_settings(app_handle) {
+        Ok(settings) => settings.profiles,
+        Err(err) => {
+            eprintln!("Failed to load profiles to recover saves: {}", err);
+            return;
+        }
+    };
+    let names = profiles.iter().map(|profile| Some(profile.name.as_str()));
+    for profile in std::iter::once(None).chain(names) {
+        let result = get_profile_data_dir(app_handle, profile)
+            .and_then(|dir| recover_interrupted_saves_in(&dir.join("apps")));
+        if let Err(err) = result {
+            eprintln!("Failed to recover interrupted saves: {}", err);
+        }
+    }
+}
+
+/// Whether the HTML file at an app's path is the one its index entry last
+/// saved. Entries from before content hashing are trusted, as are apps with
+/// assets, whose hash can't be rechecked from the HTML alone.
+fn html_matches_entry(entry: Option<&AppMetadata>, disk_html: &str, has_assets: bool) -> bool {
+    let Some(entry) = entry else {
+        return false;
+    };
+    match &entry.content_hash {
+        Some(hash) if !has_assets => *hash == hash_app_content(disk_html, &[]),
+        _ => true,
+    }
+}
+
+/// Moves HTML that doesn't belong to the app being saved out of its way
+/// instead of overwriting it, so whatever it held can still be recovered.
+/// Where it went is recorded in `staging` first, so an interrupted save
+/// can put it back.
+fn set_aside_foreign_html(
     app_handle: &AppHandle,
-    app: &AppMetadata,
-    html_content: &str,
-) -> Result<(), String> {
-    let mut index = load_index(app_handle)?;
+    id: Uuid,
+    html_path: &Path,
+    staging: &Path,
+) -> Result<PathBuf, String> {
+    let dir = get_apps_dir(app_handle)?.join(RECOVERED_DIR_NAME);
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create recovered files directory: {}", e))?;
+    let dest = dir.join(format!(
+        "{}-{}.html",
+        id,
+        chrono::Utc::now().format("%Y%m%dT%H%M%S%3f")
+    ));
+    write_synced(&staging.join(SET_ASIDE_FILE), dest.to_string_lossy().as_bytes())?;
+    fs::rename(html_path, &dest)
+        .map_err(|e| format!("Failed to set aside conflicting app HTML: {}", e))?;
+    Ok(dest)
+}
 
-    if let Some(existing) = index.get_mut(app.id) {
-        *existing = app.clone();
-    } else {
-        index.add(app.clone());
+/// Hashes everything `save_app_with_assets` writes to disk for an app.
+fn hash_app_content(html_content: &str, assets: &[AppAsset]) -> String {
+    let mut hasher = ContentHasher::new();
+    hasher.update(html_content.as_bytes());
+    for asset in assets {
+        hasher.update(&[0]);
+        hasher.update(asset.path.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&asset.data);
     }
+    hasher.finish()
+}
 
-    save_index(app_handle, &index)?;
+pub fn save_app(
+    app_handle: &AppHandle,
+    app: &mut AppMetadata,
+    html_content: &str,
+) -> Result<bool, String> {
+    save_app_with_assets(app_handle, app, html_content, &[])
+}
 
+/// Saves an app's HTML, assets, and index entry. Files are staged and
+/// fsynced first, then renamed into place, assets before HTML, and the index
+/// is only updated once they are live, so no file is ever half-written. A
+/// failed step puts the old files back. The replaced files stay in the
+/// staging directory until the index is written, so a save a crash cuts
+/// short is rolled forward or back by `recover_interrupted_saves` on the
+/// next launch.
+///
+/// If the content hash matches what is already on disk, the files are left
+/// untouched and `updated_at` keeps its stored value. Returns whether the
+/// content changed, so callers can skip recording a redundant version.
+///
+/// HTML already at the app's path that its index entry didn't write, such
+/// as a file from another app restored by hand, is moved to the recovered
+/// directory rather than overwritten, once the new files are staged, and
+/// reported as `app-html-recovered`. It is put back if the save fails.
+pub fn save_app_with_assets(
+    app_handle: &AppHandle,
+    app: &mut AppMetadata,
+    html_content: &str,
+    assets: &[AppAsset],
+) -> Result<bool, String> {
+    let hash = hash_app_content(html_content, assets);
+    app.runtime_version = runtime_version(html_content);
+    let index = load_index(app_handle)?;
     let html_path = get_app_html_path(app_handle, app.id)?;
-    write_atomic(&html_path, html_content)?;
+    let foreign = match fs::read(&html_path) {
+        Ok(disk_html) => {
+            let has_assets = get_app_assets_dir(app_handle, app.id)?.exists();
+            let disk_html = String::from_utf8_lossy(&disk_html);
+            !html_matches_entry(index.get(app.id), &disk_html, has_assets)
+        }
+        Err(_) => false,
+    };
+    let unchanged = match index.get(app.id) {
+        Some(existing) => {
+            existing.content_hash.as_deref() == Some(hash.as_str())
+                && html_path.exists()
+                && !foreign
+        }
+        None => false,
+    };
+
+    if unchanged {
+        let changed = update_index(app_handle, |index| {
+            let existing = index.get_mut(app.id).ok_or("App not found")?;
+            app.updated_at = existing.updated_at;
+            app.content_hash = Some(hash);
+            let changed =
+                serde_json::to_value(&*existing).ok() != serde_json::to_value(&*app).ok();
+            *existing = app.clone();
+            Ok(changed)
+        })?;
+        if changed {
+            crate::commands::search::index_app(app_handle, app, None);
+        }
+        return Ok(false);
+    }
 
-    Ok(())
+    let asset_bytes: usize = assets.iter().map(|asset| asset.data.len()).sum();
+    crate::commands::disk::ensure_disk_space(
+        app_handle,
+        (html_content.len() + asset_bytes) as u64,
+    )?;
+
+    let assets_dir = get_app_assets_dir(app_handle, app.id)?;
+    let staging = get_staging_dir(app_handle)?.join(format!("{}-{}", app.id, Uuid::new_v4()));
+    if let Err(err) = stage_app_files(&staging, html_content, assets, &hash) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(err);
+    }
+    // Only now, so a failed disk check or staging leaves the file in place.
+    let set_aside = if foreign {
+        match set_aside_foreign_html(app_handle, app.id, &html_path, &staging) {
+            Ok(dest) => Some(dest),
+            Err(err) => {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+    let mut saved = swap_staged_files(&html_path, &assets_dir, &staging, !assets.is_empty());
+    if saved.is_ok() {
+        let mut entry = app.clone();
+        entry.content_hash = Some(hash);
+        // Reloaded under the lock: another job may have saved its own app
+        // while these files were staged.
+        saved = update_index(app_handle, |index| {
+            if let Some(existing) = index.get_mut(entry.id) {
+                *existing = entry.clone();
+            } else {
+                index.add(entry.clone());
+            }
+            Ok(())
+        })
+        .map(|()| *app = entry);
+    }
+    if saved.is_err() {
+        restore_swapped_files(&html_path, &assets_dir, &staging);
+    }
+    let _ = fs::remove_dir_all(&staging);
+    saved?;
+    if let Some(path) = set_aside {
+        let recovered = AppHtmlRecovered {
+            app_id: app.id,
+            path,
+        };
+        let _ = app_handle.emit("app-html-recovered", recovered);
+    }
+    crate::commands::search::index_app(app_handle, app, Some(html_content));
+    Ok(true)
 }
 
 #[tauri::command]
@@ -178,19 +819,170 @@ pub fn update_app_metadata(
     background_color: String,
 ) -> Result<AppMetadata, String> {
     let uuid = parse_uuid(&id)?;
-    let mut index = load_index(&app_handle)?;
+    let updated_app = update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+
+        app.name = name;
+        app.emoji = emoji;
+        app.background_color = background_color;
+        app.updated_at = chrono::Utc::now();
+        Ok(app.clone())
+    })?;
+    crate::commands::search::index_app(&app_handle, &updated_app, None);
+
+    Ok(updated_app)
+}
+
+#[tauri::command]
+pub fn trust_app(app_handle: AppHandle, id: String) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.quarantined = false;
+        Ok(app.clone())
+    })
+}
+
+/// Rejects bridge calls that need trust (storage writes, network) for
+/// apps that are still quarantined after import.
+pub(crate) fn ensure_app_trusted(app_handle: &AppHandle, id: &str) -> Result<(), String> {
+    let app = get_visible_app(app_handle, parse_uuid(id)?)?;
+    if app.quarantined {
+        return Err("App is quarantined until you trust it".to_string());
+    }
+    Ok(())
+}
+
+/// Creates a copy of an app to take in a new direction, keeping a link to
+/// the parent in its origin. Storage data is not copied.
+#[tauri::command]
+pub fn remix_app(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+) -> Result<AppMetadata, String> {
+    let parent = get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, parent.id)?;
+    let html = fs::read_to_string(get_app_html_path(&app_handle, parent.id)?)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+
+    let name = remix_name(&parent.name);
+    let settings = load_settings(&app_handle)?;
+    validate_name_prompt(&name, &parent.prompt, settings.prompt_max_length)?;
+    let mut app = AppMetadata::new(
+        name,
+        parent.prompt.clone(),
+        parent.emoji.clone(),
+        parent.background_color.clone(),
+    );
+    app.origin = AppOrigin::Remix {
+        parent_id: parent.id,
+    };
+    app.quarantined = parent.quarantined;
+
+    save_app(&app_handle, &mut app, &html)?;
+    crate::commands::versions::record_version(&app_handle, &app, ChangeKind::Created, &html)?;
+
+    Ok(app)
+}
+
+/// The parent's name with " (remix)" appended, shortened to fit
+/// `APP_NAME_MAX_LENGTH` bytes, the limit `validate_name` checks.
+fn remix_name(parent_name: &str) -> String {
+    let suffix = " (remix)";
+    let mut end = parent_name.len().min(APP_NAME_MAX_LENGTH - suffix.len());
+    while !parent_name.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", parent_name[..end].trim_end(), suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        hash_app_content, html_matches_entry, interrupted_save_committed, nested_app_file,
+        remix_name, restore_swapped_files, stage_app_files, swap_staged_files,
+    };
+    use crate::models::{validate_name, AppMetadata};
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn rolls_interrupted_saves_forward_only_once_indexed() {
+        let mut entry = AppMetadata::new(
+            "Timer".to_string(),
+            "A timer".to_string(),
+            "⏱".to_string(),
+            "#ffffff".to_string(),
+        );
+        entry.content_hash = Some("new".to_string());
+
+        assert!(interrupted_save_committed(true, Some("new"), Some(&entry)));
+        assert!(!interrupted_save_committed(false, Some("new"), Some(&entry)));
+        assert!(!interrupted_save_committed(true, Some("newer"), Some(&entry)));
+        assert!(!interrupted_save_committed(true, None, Some(&entry)));
+        assert!(!interrupted_save_committed(true, Some("new"), None));
+    }
 
-    let app = index
-        .get_mut(uuid)
-        .ok_or_else(|| format!("App not found: {}", id))?;
+    #[test]
+    fn html_only_saves_keep_existing_assets() {
+        let root = std::env::temp_dir().join(format!("trove-save-{}", Uuid::new_v4()));
+        let html_path = root.join("app").join("index.html");
+        let assets_dir = root.join("app").join("assets");
+        let staging = root.join("staging");
+        fs::create_dir_all(&assets_dir).unwrap();
+        fs::write(&html_path, "<html>old</html>").unwrap();
+        fs::write(assets_dir.join("logo.png"), b"png").unwrap();
+
+        stage_app_files(&staging, "<html>new</html>", &[], "hash").unwrap();
+        swap_staged_files(&html_path, &assets_dir, &staging, false).unwrap();
+        assert_eq!(fs::read_to_string(&html_path).unwrap(), "<html>new</html>");
+        assert_eq!(fs::read(assets_dir.join("logo.png")).unwrap(), b"png");
+
+        restore_swapped_files(&html_path, &assets_dir, &staging);
+        assert_eq!(fs::read_to_string(&html_path).unwrap(), "<html>old</html>");
+        assert_eq!(fs::read(assets_dir.join("logo.png")).unwrap(), b"png");
+        let _ = fs::remove_dir_all(&root);
+    }
 
-    app.name = name;
-    app.emoji = emoji;
-    app.background_color = background_color;
-    app.updated_at = chrono::Utc::now();
+    #[test]
+    fn remix_names_fit_the_byte_limit() {
+        let name = remix_name(&"🎲".repeat(50));
+        assert!(name.ends_with(" (remix)"));
+        assert!(validate_name(&name).is_ok());
+        assert_eq!(remix_name("Dice"), "Dice (remix)");
+    }
 
-    let updated_app = app.clone();
-    save_index(&app_handle, &index)?;
+    #[test]
+    fn detects_html_written_for_another_entry() {
+        let mut app = AppMetadata::new(
+            "Timer".to_string(),
+            "A timer".to_string(),
+            "⏱".to_string(),
+            "#ffffff".to_string(),
+        );
+        assert!(!html_matches_entry(None, "<html></html>", false));
+        assert!(html_matches_entry(Some(&app), "<html></html>", false));
+
+        app.content_hash = Some(hash_app_content("<html>timer</html>", &[]));
+        assert!(html_matches_entry(Some(&app), "<html>timer</html>", false));
+        assert!(!html_matches_entry(Some(&app), "<html>notes</html>", false));
+        assert!(html_matches_entry(Some(&app), "<html>notes</html>", true));
+    }
 
-    Ok(updated_app)
+    #[test]
+    fn maps_flat_files_into_app_directories() {
+        let id = Uuid::new_v4();
+        let nested = |name: &str| nested_app_file(&format!("{}.{}", id, name));
+        assert_eq!(nested("html"), Some((id, "index.html".to_string())));
+        assert_eq!(nested("data.json"), Some((id, "data.json".to_string())));
+        assert_eq!(nested("versions"), Some((id, "versions".to_string())));
+        assert_eq!(nested_app_file(&format!("{}.", id)), None);
+        assert_eq!(nested_app_file(&id.to_string()), None);
+        assert_eq!(nested_app_file("apps.json"), None);
+    }
 }
@@ -0,0 +1,129 @@
+use crate::commands::app_errors::get_errors_path;
+use crate::commands::apps::{
+    get_app_assets_dir, get_app_context_path, get_app_dir_in, get_app_html_path, get_apps_dir_path,
+};
+use crate::commands::audit::get_audit_path;
+use crate::commands::config::get_app_config_path;
+use crate::commands::edits::get_pending_paths;
+use crate::commands::history::get_history_path;
+use crate::commands::lock::get_app_lock_path;
+use crate::commands::permissions::get_permissions_path;
+use crate::commands::storage::{
+    get_deleted_storage_path, get_msgpack_storage_path, get_storage_path,
+};
+use crate::commands::timeline::get_timeline_path;
+use crate::commands::versions::{get_changelog_path, get_restore_points_path, get_versions_dir};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArtifactKind {
+    Html,
+    Assets,
+    Context,
+    Storage,
+    DeletedStorage,
+    Config,
+    AuditLog,
+    Versions,
+    Changelog,
+    RestorePoints,
+    PendingEdit,
+    Permissions,
+    Timeline,
+    History,
+    RuntimeErrors,
+    Lock,
+}
+
+impl ArtifactKind {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ArtifactKind::Html => "app HTML",
+            ArtifactKind::Assets => "app assets",
+            ArtifactKind::Context => "app context",
+            ArtifactKind::Storage => "app storage",
+            ArtifactKind::DeletedStorage => "deleted storage",
+            ArtifactKind::Config => "app config",
+            ArtifactKind::AuditLog => "audit log",
+            ArtifactKind::Versions => "app versions",
+            ArtifactKind::Changelog => "changelog",
+            ArtifactKind::RestorePoints => "restore points",
+            ArtifactKind::PendingEdit => "pending edit",
+            ArtifactKind::Permissions => "permissions",
+            ArtifactKind::Timeline => "generation timeline",
+            ArtifactKind::History => "generation history",
+            ArtifactKind::RuntimeErrors => "app errors",
+            ArtifactKind::Lock => "app lock",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct AppArtifact {
+    pub(crate) kind: ArtifactKind,
+    pub(crate) path: PathBuf,
+}
+
+impl AppArtifact {
+    pub(crate) fn exists(&self) -> bool {
+        self.path.exists()
+    }
+}
+
+/// Every file or directory that can belong to an app, whether or not it
+/// exists yet. Anything that deletes, moves, or exports a whole app should
+/// go through this list, so new per-app files only need registering here
+/// to be cleaned up with the app.
+pub(crate) fn app_artifacts(app_handle: &AppHandle, id: Uuid) -> Result<Vec<AppArtifact>, String> {
+    let app_id = id.to_string();
+    let (pending_meta, pending_html) = get_pending_paths(app_handle, id)?;
+    let paths = [
+        (ArtifactKind::Html, get_app_html_path(app_handle, id)?),
+        (ArtifactKind::Assets, get_app_assets_dir(app_handle, id)?),
+        (ArtifactKind::Context, get_app_context_path(app_handle, id)?),
+        (ArtifactKind::Storage, get_storage_path(app_handle, &app_id)?),
+        (ArtifactKind::Storage, get_msgpack_storage_path(app_handle, &app_id)?),
+        (ArtifactKind::DeletedStorage, get_deleted_storage_path(app_handle, &app_id)?),
+        (ArtifactKind::Config, get_app_config_path(app_handle, id)?),
+        (ArtifactKind::AuditLog, get_audit_path(app_handle, &app_id)?),
+        (ArtifactKind::Versions, get_versions_dir(app_handle, id)?),
+        (ArtifactKind::Changelog, get_changelog_path(app_handle, id)?),
+        (ArtifactKind::RestorePoints, get_restore_points_path(app_handle, id)?),
+        (ArtifactKind::PendingEdit, pending_meta),
+        (ArtifactKind::PendingEdit, pending_html),
+        (ArtifactKind::Permissions, get_permissions_path(app_handle, &app_id)?),
+        (ArtifactKind::Timeline, get_timeline_path(app_handle, id)?),
+        (ArtifactKind::History, get_history_path(app_handle, id)?),
+        (ArtifactKind::RuntimeErrors, get_errors_path(app_handle, id)?),
+        (ArtifactKind::Lock, get_app_lock_path(app_handle, id)?),
+    ];
+
+    Ok(paths
+        .into_iter()
+        .map(|(kind, path)| AppArtifact { kind, path })
+        .collect())
+}
+
+/// Deletes whichever of an app's artifacts exist, then its directory.
+pub(crate) fn remove_app_artifacts(app_handle: &AppHandle, id: Uuid) -> Result<(), String> {
+    for artifact in app_artifacts(app_handle, id)? {
+        if !artifact.exists() {
+            continue;
+        }
+        let result = if artifact.path.is_dir() {
+            fs::remove_dir_all(&artifact.path)
+        } else {
+            fs::remove_file(&artifact.path)
+        };
+        result.map_err(|e| format!("Failed to delete {}: {}", artifact.kind.label(), e))?;
+    }
+    let app_dir = get_app_dir_in(&get_apps_dir_path(app_handle)?, id);
+    if app_dir.exists() {
+        fs::remove_dir_all(&app_dir)
+            .map_err(|e| format!("Failed to delete app directory: {}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use crate::commands::apps::get_app_file_path;
+use crate::models::{AuditEntry, AuditLog};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+/// Per-app locks around audit log load/append/save, so bridge calls an app
+/// makes in parallel can't overwrite each other's entries.
+#[derive(Default)]
+pub struct AuditLocks {
+    locks: Mutex<HashMap<Uuid, Arc<Mutex<()>>>>,
+}
+
+/// Runs `f` holding the app's audit log lock. Anything that rewrites the
+/// log goes through here.
+pub(crate) fn with_audit_lock<T>(
+    app_handle: &AppHandle,
+    app_id: &str,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let app_id = parse_uuid(app_id)?;
+    let state = app_handle.state::<AuditLocks>();
+    let lock = {
+        let mut locks = state.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.entry(app_id).or_default().clone()
+    };
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    f()
+}
+
+pub(crate) fn get_audit_path(app_handle: &AppHandle, app_id: &str) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, parse_uuid(app_id)?, "audit.json")
+}
+
+pub(crate) fn load_audit_log(app_handle: &AppHandle, app_id: &str) -> Result<AuditLog, String> {
+    let path = get_audit_path(app_handle, app_id)?;
+    if !path.exists() {
+        return Ok(AuditLog::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read audit log: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse audit log: {}", e))
+}
+
+pub(crate) fn save_audit_log(
+    app_handle: &AppHandle,
+    app_id: &str,
+    log: &AuditLog,
+) -> Result<(), String> {
+    let path = get_audit_path(app_handle, app_id)?;
+    let content = serde_json::to_string_pretty(log)
+        .map_err(|e| format!("Failed to serialize audit log: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Records a permission-gated bridge call, whether it was allowed or not.
+/// Audit failures are logged rather than surfaced so they never break the app.
+pub(crate) fn record_bridge_call(
+    app_handle: &AppHandle,
+    app_id: &str,
+    action: &str,
+    detail: Option<&str>,
+    allowed: bool,
+) {
+    let result = with_audit_lock(app_handle, app_id, || {
+        let mut log = load_audit_log(app_handle, app_id)?;
+        log.push(AuditEntry {
+            action: action.to_string(),
+            detail: detail.map(str::to_string),
+            allowed,
+            timestamp: Utc::now(),
+        });
+        save_audit_log(app_handle, app_id, &log)
+    });
+    if let Err(err) = result {
+        eprintln!("Failed to record audit entry for {}: {}", app_id, err);
+    }
+}
+
+#[tauri::command]
+pub fn get_app_audit_log(app_handle: AppHandle, id: String) -> Result<Vec<AuditEntry>, String> {
+    Ok(load_audit_log(&app_handle, &id)?.entries)
+}
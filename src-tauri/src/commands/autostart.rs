@@ -0,0 +1,134 @@
+use crate::commands::apps::{load_index, update_index};
+use crate::commands::focus::ensure_not_focus_blocked;
+use crate::commands::launch::open_app_window;
+use crate::models::AppMetadata;
+use crate::utils::parse_uuid;
+use tauri::AppHandle;
+
+#[cfg(target_os = "macos")]
+use crate::utils::write_atomic;
+#[cfg(target_os = "macos")]
+use std::fs;
+#[cfg(target_os = "macos")]
+use std::path::PathBuf;
+
+/// The LaunchAgent that starts Trove at login, named after the bundle
+/// identifier so it can't clash with other apps' agents.
+#[cfg(target_os = "macos")]
+fn get_login_item_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or_else(|| "Failed to resolve home directory".to_string())?;
+    Ok(home
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{}.plist", app_handle.config().identifier)))
+}
+
+#[cfg(target_os = "macos")]
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(target_os = "macos")]
+fn set_login_item(app_handle: &AppHandle, enabled: bool) -> Result<(), String> {
+    let path = get_login_item_path(app_handle)?;
+    if !enabled {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove login item: {}", e))?;
+        }
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to resolve Trove executable: {}", e))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create LaunchAgents directory: {}", e))?;
+    }
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        escape_xml(&app_handle.config().identifier),
+        escape_xml(&exe.to_string_lossy())
+    );
+    write_atomic(&path, &plist)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn set_login_item(_app_handle: &AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        return Err("Autostart is only supported on macOS".to_string());
+    }
+    Ok(())
+}
+
+/// Registers Trove as a login item while any app wants autostart, and
+/// removes it once none do.
+pub(crate) fn sync_login_item(app_handle: &AppHandle) -> Result<(), String> {
+    let index = load_index(app_handle)?;
+    set_login_item(app_handle, index.apps.iter().any(|app| app.autostart))
+}
+
+/// Opens the app in its own window whenever Trove starts.
+#[tauri::command]
+pub fn set_app_autostart(
+    app_handle: AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.autostart = enabled;
+        let updated_app = app.clone();
+
+        // Register the login item before saving so a failure leaves no app
+        // flagged without one.
+        set_login_item(&app_handle, index.apps.iter().any(|app| app.autostart))?;
+        Ok(updated_app)
+    })
+}
+
+/// Opens every autostart app's window. Called once at startup; also
+/// refreshes the login item in case Trove moved since it was written.
+pub fn launch_autostart_apps(app_handle: &AppHandle) {
+    let index = match load_index(app_handle) {
+        Ok(index) => index,
+        Err(err) => {
+            eprintln!("Failed to load apps for autostart: {}", err);
+            return;
+        }
+    };
+    if let Err(err) = sync_login_item(app_handle) {
+        eprintln!("Failed to update login item: {}", err);
+    }
+
+    // Apps blocked by focus mode would only show the blocked error.
+    for app in index.apps.iter().filter(|app| {
+        app.autostart
+            && !app.quarantined
+            && !app.draft
+            && ensure_not_focus_blocked(app_handle, app.id).is_ok()
+    }) {
+        if let Err(err) = open_app_window(app_handle, app) {
+            eprintln!("Failed to autostart {}: {}", app.id, err);
+        }
+    }
+}
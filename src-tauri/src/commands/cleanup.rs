@@ -0,0 +1,105 @@
+use crate::commands::apps::{load_index, remove_app};
+use crate::commands::audit::{get_audit_path, load_audit_log, save_audit_log, with_audit_lock};
+use crate::commands::settings::load_settings;
+use crate::commands::versions::{delete_snapshot, load_changelog, save_changelog};
+use crate::models::{AppMetadata, Settings};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct CleanupReport {
+    pub bytes_reclaimed: u64,
+    pub versions_pruned: usize,
+    pub log_entries_pruned: usize,
+    pub drafts_removed: usize,
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|meta| meta.len()).unwrap_or(0)
+}
+
+/// The moment `days` ago, or `None` when that is out of range, in which
+/// case nothing is old enough to prune.
+fn retention_cutoff(days: u32) -> Option<DateTime<Utc>> {
+    Utc::now().checked_sub_signed(ChronoDuration::days(i64::from(days)))
+}
+
+fn prune_versions(
+    app_handle: &AppHandle,
+    app: &AppMetadata,
+    settings: &Settings,
+    report: &mut CleanupReport,
+) -> Result<(), String> {
+    let mut changelog = load_changelog(app_handle, app.id)?;
+    let live = changelog.entries.iter().filter(|entry| !entry.pruned).count();
+    let mut excess = live.saturating_sub(settings.max_versions_per_app);
+    if excess == 0 {
+        return Ok(());
+    }
+
+    for entry in changelog.entries.iter_mut().filter(|entry| !entry.pruned) {
+        if excess == 0 {
+            break;
+        }
+        report.bytes_reclaimed += delete_snapshot(app_handle, app.id, entry.version_id)?;
+        entry.pruned = true;
+        report.versions_pruned += 1;
+        excess -= 1;
+    }
+    save_changelog(app_handle, app.id, &changelog)
+}
+
+fn prune_audit_log(
+    app_handle: &AppHandle,
+    app: &AppMetadata,
+    settings: &Settings,
+    report: &mut CleanupReport,
+) -> Result<(), String> {
+    let app_id = app.id.to_string();
+    with_audit_lock(app_handle, &app_id, || {
+        let mut log = load_audit_log(app_handle, &app_id)?;
+        let before = log.entries.len();
+        if let Some(cutoff) = retention_cutoff(settings.max_log_age_days) {
+            log.entries.retain(|entry| entry.timestamp >= cutoff);
+        }
+        log.trim_to_bytes(settings.max_log_size_kb.saturating_mul(1024));
+        if log.entries.len() == before {
+            return Ok(());
+        }
+
+        let path = get_audit_path(app_handle, &app_id)?;
+        let old_size = file_size(&path);
+        save_audit_log(app_handle, &app_id, &log)?;
+        report.bytes_reclaimed += old_size.saturating_sub(file_size(&path));
+        report.log_entries_pruned += before - log.entries.len();
+        Ok(())
+    })
+}
+
+/// Applies the retention settings to every app's versions and logs, and
+/// deletes stale generated drafts.
+pub fn run_cleanup(app_handle: &AppHandle) -> Result<CleanupReport, String> {
+    let settings = load_settings(app_handle)?;
+    let index = load_index(app_handle)?;
+    let mut report = CleanupReport::default();
+
+    let draft_cutoff = retention_cutoff(settings.max_draft_age_days);
+    for app in &index.apps {
+        if draft_cutoff.is_some_and(|cutoff| app.is_expired_draft(cutoff)) {
+            remove_app(app_handle, &app.id.to_string())?;
+            report.drafts_removed += 1;
+            continue;
+        }
+        prune_versions(app_handle, app, &settings, &mut report)?;
+        prune_audit_log(app_handle, app, &settings, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn run_cleanup_now(app_handle: AppHandle) -> Result<CleanupReport, String> {
+    run_cleanup(&app_handle)
+}
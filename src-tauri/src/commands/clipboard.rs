@@ -0,0 +1,123 @@
+use crate::commands::agent::generate_app;
+use crate::commands::naming::run_namer;
+use crate::commands::settings::load_settings;
+use crate::models::{default_background_color, default_emoji, AppMetadata, APP_NAME_MAX_LENGTH};
+use tauri::{AppHandle, Window};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Words of the prompt used as the name when the agent can't suggest one.
+const FALLBACK_NAME_WORDS: usize = 6;
+
+fn read_clipboard_text(app_handle: &AppHandle) -> Result<String, String> {
+    app_handle
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {}", e))
+}
+
+/// Turns clipboard text into a prompt: control characters other than line
+/// breaks and tabs are dropped, and text past `max_length` bytes is cut off
+/// rather than rejected, since pasted notes often run long.
+fn clipboard_prompt(text: &str, max_length: usize) -> Result<String, String> {
+    let cleaned: String = text
+        .chars()
+        .filter(|ch| !ch.is_control() || matches!(ch, '\n' | '\r' | '\t'))
+        .collect();
+    let mut prompt = cleaned.trim();
+    if prompt.is_empty() {
+        return Err("Clipboard has no text to use as a prompt".to_string());
+    }
+    if prompt.len() > max_length {
+        let mut end = max_length;
+        while !prompt.is_char_boundary(end) {
+            end -= 1;
+        }
+        prompt = prompt[..end].trim_end();
+    }
+    Ok(prompt.to_string())
+}
+
+/// The first few words of the prompt's first line, for when naming fails.
+fn fallback_name(prompt: &str) -> String {
+    let first_line = prompt.lines().find(|line| !line.trim().is_empty()).unwrap_or("");
+    let mut name = String::new();
+    for word in first_line.split_whitespace().take(FALLBACK_NAME_WORDS) {
+        if name.len() + word.len() + 1 > APP_NAME_MAX_LENGTH {
+            break;
+        }
+        if !name.is_empty() {
+            name.push(' ');
+        }
+        name.push_str(word);
+    }
+    let name = name.trim_end_matches(|ch: char| ch.is_ascii_punctuation());
+    if name.is_empty() {
+        "Clipboard App".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Generates an app straight from the text on the clipboard, with a name
+/// and emoji picked by the agent, so an idea goes to an app without filling
+/// in the form. Naming failures fall back to the prompt's first words.
+#[tauri::command]
+pub async fn generate_from_clipboard(
+    app_handle: AppHandle,
+    window: Window,
+    job_id: Option<String>,
+) -> Result<AppMetadata, String> {
+    let settings = load_settings(&app_handle)?;
+    let text = read_clipboard_text(&app_handle)?;
+    let prompt = clipboard_prompt(&text, settings.prompt_max_length)?;
+
+    let (name, emoji) = match run_namer(&app_handle, &prompt).await {
+        Ok(mut suggestions) => {
+            let suggestion = suggestions.remove(0);
+            (suggestion.name, suggestion.emoji)
+        }
+        Err(err) => {
+            eprintln!("Failed to suggest a name for clipboard app: {}", err);
+            (fallback_name(&prompt), default_emoji())
+        }
+    };
+
+    generate_app(
+        app_handle,
+        window,
+        name,
+        prompt,
+        emoji,
+        default_background_color(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        job_id,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clipboard_prompt, fallback_name};
+
+    #[test]
+    fn cleans_and_truncates_clipboard_prompts() {
+        assert_eq!(
+            clipboard_prompt("  a habit\u{0}\ttracker\n ", 100).unwrap(),
+            "a habit\ttracker"
+        );
+        assert_eq!(clipboard_prompt("café au lait", 4).unwrap(), "caf");
+        assert!(clipboard_prompt(" \n\u{7} ", 100).is_err());
+    }
+
+    #[test]
+    fn falls_back_to_the_prompts_first_words() {
+        let prompt = "\nTrack my daily water intake!\nRemind me every hour.";
+        assert_eq!(fallback_name(prompt), "Track my daily water intake");
+        assert_eq!(fallback_name("!!!"), "Clipboard App");
+    }
+}
@@ -0,0 +1,101 @@
+use crate::commands::apps::{app_file_in, get_app_home_dir, get_app_internal, get_visible_app};
+use crate::models::AppConfig;
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+/// What an app may learn about itself through `TroveApp.getMetadata()`,
+/// so it can show its own name and colors without hard-coding them.
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnMetadata {
+    pub id: Uuid,
+    pub name: String,
+    pub emoji: String,
+    pub background_color: String,
+    pub created_at: DateTime<Utc>,
+    pub language: Option<String>,
+}
+
+pub(crate) fn get_app_config_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    Ok(app_file_in(&get_app_home_dir(app_handle, id)?, id, "config.json"))
+}
+
+pub(crate) fn load_app_config(app_handle: &AppHandle, id: Uuid) -> Result<AppConfig, String> {
+    let path = get_app_config_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(AppConfig::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read app config: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse app config: {}", e))
+}
+
+pub(crate) fn save_app_config(
+    app_handle: &AppHandle,
+    id: Uuid,
+    config: &AppConfig,
+) -> Result<(), String> {
+    let path = get_app_config_path(app_handle, id)?;
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize app config: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+#[tauri::command]
+pub fn get_app_config(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<BTreeMap<String, String>, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&id)?)?;
+    Ok(load_app_config(&app_handle, app.id)?.values)
+}
+
+#[tauri::command]
+pub fn get_own_metadata(app_handle: AppHandle, app_id: String) -> Result<OwnMetadata, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&app_id)?)?;
+    Ok(OwnMetadata {
+        id: app.id,
+        name: app.name,
+        emoji: app.emoji,
+        background_color: app.background_color,
+        created_at: app.created_at,
+        language: app.language,
+    })
+}
+
+/// Sets one config value, which the app reads with `TroveConfig.get(key)`.
+#[tauri::command]
+pub fn set_app_config(
+    app_handle: AppHandle,
+    id: String,
+    key: String,
+    value: String,
+) -> Result<BTreeMap<String, String>, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    let mut config = load_app_config(&app_handle, app.id)?;
+    config.set(key, value)?;
+    save_app_config(&app_handle, app.id, &config)?;
+    Ok(config.values)
+}
+
+#[tauri::command]
+pub fn delete_app_config(
+    app_handle: AppHandle,
+    id: String,
+    key: String,
+) -> Result<BTreeMap<String, String>, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    let mut config = load_app_config(&app_handle, app.id)?;
+    if config.values.remove(&key).is_some() {
+        save_app_config(&app_handle, app.id, &config)?;
+    }
+    Ok(config.values)
+}
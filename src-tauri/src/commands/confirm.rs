@@ -0,0 +1,209 @@
+use crate::commands::apps::get_app_internal;
+use crate::commands::artifacts::{app_artifacts, ArtifactKind};
+use crate::commands::storage::load_storage;
+use crate::commands::versions::load_changelog;
+use crate::utils::{parse_uuid, path_size};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// How long a confirmation token stays valid after its summary was shown.
+const CONFIRMATION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Destructive commands that take a confirmation token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DestructiveAction {
+    DeleteApp,
+    StorageClear,
+}
+
+/// What a destructive action would remove, for the confirmation dialog,
+/// with the token that lets the action go ahead.
+#[derive(Clone, Serialize)]
+pub struct DeletionSummary {
+    pub token: String,
+    pub action: DestructiveAction,
+    pub app_id: Uuid,
+    pub app_name: String,
+    /// Saved versions that go with the app; zero for `clear_app_storage`.
+    pub versions: usize,
+    pub storage_keys: usize,
+    pub storage_bytes: u64,
+    /// Everything removed from disk, storage included.
+    pub total_bytes: u64,
+    pub expires_at: DateTime<Utc>,
+}
+
+struct PendingConfirmation {
+    action: DestructiveAction,
+    app_id: Uuid,
+    issued_at: Instant,
+}
+
+/// Tokens handed out by `request_delete_confirmation`. Each works once, for
+/// the action and app it was issued for.
+#[derive(Default)]
+pub struct ConfirmationTokens {
+    pending: Mutex<HashMap<String, PendingConfirmation>>,
+}
+
+impl ConfirmationTokens {
+    fn issue(&self, action: DestructiveAction, app_id: Uuid) -> String {
+        let token = Uuid::new_v4().to_string();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.retain(|_, confirmation| confirmation.issued_at.elapsed() < CONFIRMATION_TTL);
+            pending.insert(
+                token.clone(),
+                PendingConfirmation {
+                    action,
+                    app_id,
+                    issued_at: Instant::now(),
+                },
+            );
+        }
+        token
+    }
+
+    /// Uses up the token, failing unless it was issued for this action and
+    /// app within `ttl`.
+    fn redeem(
+        &self,
+        token: &str,
+        action: DestructiveAction,
+        app_id: Uuid,
+        ttl: Duration,
+    ) -> Result<(), String> {
+        let confirmation = self
+            .pending
+            .lock()
+            .map_err(|_| "Failed to check confirmation".to_string())?
+            .remove(token);
+        match confirmation {
+            Some(confirmation)
+                if confirmation.action == action
+                    && confirmation.app_id == app_id
+                    && confirmation.issued_at.elapsed() < ttl =>
+            {
+                Ok(())
+            }
+            _ => Err("Confirmation expired or invalid; please confirm again".to_string()),
+        }
+    }
+}
+
+pub(crate) fn redeem_confirmation(
+    tokens: &ConfirmationTokens,
+    token: &str,
+    action: DestructiveAction,
+    app_id: &str,
+) -> Result<(), String> {
+    tokens.redeem(token, action, parse_uuid(app_id)?, CONFIRMATION_TTL)
+}
+
+/// First step of a destructive action: reports what `action` would remove
+/// from the app and returns a one-time token for the second step.
+#[tauri::command]
+pub fn request_delete_confirmation(
+    app_handle: AppHandle,
+    tokens: State<'_, ConfirmationTokens>,
+    action: DestructiveAction,
+    app_id: String,
+) -> Result<DeletionSummary, String> {
+    let app = get_app_internal(&app_handle, &app_id)?;
+    let storage_keys = load_storage(&app_handle, &app_id)?.len();
+    let (mut storage_bytes, mut total_bytes) = (0, 0);
+    for artifact in app_artifacts(&app_handle, app.id)? {
+        let bytes = path_size(&artifact.path);
+        total_bytes += bytes;
+        if artifact.kind == ArtifactKind::Storage {
+            storage_bytes += bytes;
+        }
+    }
+    let versions = match action {
+        DestructiveAction::DeleteApp => load_changelog(&app_handle, app.id)?
+            .entries
+            .iter()
+            .filter(|entry| !entry.pruned)
+            .count(),
+        DestructiveAction::StorageClear => {
+            total_bytes = storage_bytes;
+            0
+        }
+    };
+
+    Ok(DeletionSummary {
+        token: tokens.issue(action, app.id),
+        action,
+        app_id: app.id,
+        app_name: app.name,
+        versions,
+        storage_keys,
+        storage_bytes,
+        total_bytes,
+        expires_at: Utc::now() + chrono::Duration::from_std(CONFIRMATION_TTL).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfirmationTokens, DestructiveAction, CONFIRMATION_TTL};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[test]
+    fn tokens_work_once_for_their_own_action() {
+        let tokens = ConfirmationTokens::default();
+        let (app_id, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let token = tokens.issue(DestructiveAction::DeleteApp, app_id);
+        assert!(tokens
+            .redeem(
+                &token,
+                DestructiveAction::DeleteApp,
+                app_id,
+                CONFIRMATION_TTL
+            )
+            .is_ok());
+        assert!(tokens
+            .redeem(
+                &token,
+                DestructiveAction::DeleteApp,
+                app_id,
+                CONFIRMATION_TTL
+            )
+            .is_err());
+
+        let token = tokens.issue(DestructiveAction::StorageClear, app_id);
+        assert!(tokens
+            .redeem(
+                &token,
+                DestructiveAction::StorageClear,
+                other,
+                CONFIRMATION_TTL
+            )
+            .is_err());
+        let token = tokens.issue(DestructiveAction::StorageClear, app_id);
+        assert!(tokens
+            .redeem(
+                &token,
+                DestructiveAction::DeleteApp,
+                app_id,
+                CONFIRMATION_TTL
+            )
+            .is_err());
+        let token = tokens.issue(DestructiveAction::StorageClear, app_id);
+        assert!(tokens
+            .redeem(
+                &token,
+                DestructiveAction::StorageClear,
+                app_id,
+                Duration::ZERO
+            )
+            .is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::commands::apps::{get_active_profile_data_dir, load_index};
+use crate::commands::artifacts::{app_artifacts, ArtifactKind};
+use crate::commands::power::wait_until_resumed;
+use crate::commands::settings::load_settings;
+use crate::commands::timeline::load_timelines;
+use crate::models::{AppUsageSummary, GenerationKind, WeeklyDigest, DIGESTS_KEPT};
+use crate::utils::{path_size, write_atomic};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use tokio::time::{sleep, Duration};
+
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DIGESTS_DIR: &str = "digests";
+
+/// Digests cover the active profile's library, so each profile keeps its own.
+fn get_digests_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join(DIGESTS_DIR))
+}
+
+/// The digest JSON files, oldest first. Names are the dates they were
+/// written, so name order is date order.
+fn digest_files(app_handle: &AppHandle) -> Result<Vec<PathBuf>, String> {
+    let Ok(entries) = fs::read_dir(get_digests_dir(app_handle)?) else {
+        return Ok(Vec::new());
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn load_latest_digest(app_handle: &AppHandle) -> Result<Option<WeeklyDigest>, String> {
+    let Some(path) = digest_files(app_handle)?.pop() else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read digest: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse digest: {}", e))
+}
+
+fn compile_digest(
+    app_handle: &AppHandle,
+    previous: Option<&WeeklyDigest>,
+) -> Result<WeeklyDigest, String> {
+    let now = Utc::now();
+    let period_start = WeeklyDigest::next_period_start(previous, now);
+    let index = load_index(app_handle)?;
+    let mut apps = Vec::with_capacity(index.apps.len());
+    let mut failed_runs = 0;
+
+    for app in &index.apps {
+        let (mut generations, mut edits) = (0, 0);
+        for run in load_timelines(app_handle, app.id)?.runs {
+            if run.started_at < period_start {
+                continue;
+            }
+            match run.kind {
+                GenerationKind::Generate => generations += 1,
+                GenerationKind::Edit => edits += 1,
+            }
+            if run.error.is_some() {
+                failed_runs += 1;
+            }
+        }
+        let storage_bytes = app_artifacts(app_handle, app.id)?
+            .iter()
+            .filter(|artifact| artifact.kind == ArtifactKind::Storage)
+            .map(|artifact| path_size(&artifact.path))
+            .sum();
+        let baseline =
+            previous.and_then(|digest| digest.apps.iter().find(|summary| summary.app_id == app.id));
+        apps.push(AppUsageSummary::new(
+            app,
+            period_start,
+            baseline,
+            generations,
+            edits,
+            storage_bytes,
+        ));
+    }
+
+    let apps_created = index
+        .apps
+        .iter()
+        .filter(|app| app.created_at >= period_start)
+        .count();
+    Ok(WeeklyDigest::new(
+        now,
+        period_start,
+        apps_created,
+        failed_runs,
+        apps,
+    ))
+}
+
+/// Writes the digest as `<date>.json` for the dashboard and `<date>.md`
+/// for reading, dropping all but the newest `DIGESTS_KEPT`.
+fn save_digest(app_handle: &AppHandle, digest: &WeeklyDigest) -> Result<(), String> {
+    let dir = get_digests_dir(app_handle)?;
+    let stem = digest.generated_at.format("%Y-%m-%d").to_string();
+    let content = serde_json::to_string_pretty(digest)
+        .map_err(|e| format!("Failed to serialize digest: {}", e))?;
+    write_atomic(&dir.join(format!("{}.json", stem)), &content)?;
+    write_atomic(&dir.join(format!("{}.md", stem)), &digest.to_markdown())?;
+
+    let files = digest_files(app_handle)?;
+    let overflow = files.len().saturating_sub(DIGESTS_KEPT);
+    for path in &files[..overflow] {
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(path.with_extension("md"));
+    }
+    Ok(())
+}
+
+/// Compiles a digest if the user opted in and a week has passed since the
+/// last one.
+fn run_digest_if_due(app_handle: &AppHandle) -> Result<(), String> {
+    if !load_settings(app_handle)?.weekly_digest {
+        return Ok(());
+    }
+    let latest = load_latest_digest(app_handle)?;
+    if !WeeklyDigest::is_due(latest.as_ref(), Utc::now()) {
+        return Ok(());
+    }
+    let digest = compile_digest(app_handle, latest.as_ref())?;
+    save_digest(app_handle, &digest)
+}
+
+/// Returns the most recent weekly digest, or `None` before the first one.
+/// Older digests stay readable as files in the profile's `digests` folder.
+#[tauri::command]
+pub fn get_latest_digest(app_handle: AppHandle) -> Result<Option<WeeklyDigest>, String> {
+    load_latest_digest(&app_handle)
+}
+
+/// Checks hourly whether a digest is due, for the lifetime of the app.
+/// Nothing is compiled in low-power mode.
+pub fn start_digest_task(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            wait_until_resumed(&app_handle).await;
+            if let Err(err) = run_digest_if_due(&app_handle) {
+                eprintln!("Failed to compile weekly digest: {}", err);
+            }
+            sleep(DIGEST_CHECK_INTERVAL).await;
+        }
+    });
+}
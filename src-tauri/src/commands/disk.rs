@@ -0,0 +1,94 @@
+use crate::commands::apps::{get_app_data_dir, load_index};
+use crate::commands::artifacts::{app_artifacts, ArtifactKind};
+use crate::commands::settings::load_settings;
+use crate::commands::versions::load_snapshot_store;
+use crate::utils::{available_disk_space, path_size};
+use std::cmp::Reverse;
+use tauri::{AppHandle, Emitter};
+use uuid::Uuid;
+
+#[derive(Clone, serde::Serialize)]
+pub struct DiskSpaceWarning {
+    pub available_bytes: u64,
+    pub required_bytes: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct AppDiskUsage {
+    pub app_id: Uuid,
+    pub total_bytes: u64,
+    pub versions_bytes: u64,
+    /// Bytes not written because identical version snapshots share a file.
+    pub dedup_saved_bytes: u64,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct DiskUsageReport {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub versions_bytes: u64,
+    pub dedup_saved_bytes: u64,
+    pub apps: Vec<AppDiskUsage>,
+}
+
+/// Reports how much space Trove's data takes, per app and overall, and how
+/// much snapshot deduplication has saved. Apps are sorted largest first.
+#[tauri::command]
+pub fn get_disk_usage(app_handle: AppHandle) -> Result<DiskUsageReport, String> {
+    let data_dir = get_app_data_dir(&app_handle)?;
+    let mut apps = Vec::new();
+    for app in load_index(&app_handle)?.apps {
+        let mut usage = AppDiskUsage {
+            app_id: app.id,
+            total_bytes: 0,
+            versions_bytes: 0,
+            dedup_saved_bytes: load_snapshot_store(&app_handle, app.id)?.saved_bytes(),
+        };
+        for artifact in app_artifacts(&app_handle, app.id)? {
+            let bytes = path_size(&artifact.path);
+            usage.total_bytes += bytes;
+            if artifact.kind == ArtifactKind::Versions {
+                usage.versions_bytes += bytes;
+            }
+        }
+        apps.push(usage);
+    }
+    apps.sort_by_key(|app| Reverse(app.total_bytes));
+
+    Ok(DiskUsageReport {
+        total_bytes: path_size(&data_dir),
+        available_bytes: available_disk_space(&data_dir)?,
+        versions_bytes: apps.iter().map(|app| app.versions_bytes).sum(),
+        dedup_saved_bytes: apps.iter().map(|app| app.dedup_saved_bytes).sum(),
+        apps,
+    })
+}
+
+/// Fails early (and emits `disk-space-warning`) when writing `needed_bytes`
+/// would leave less than the configured minimum free, rather than risking a
+/// truncated write.
+pub fn ensure_disk_space(app_handle: &AppHandle, needed_bytes: u64) -> Result<(), String> {
+    let settings = load_settings(app_handle)?;
+    let data_dir = get_app_data_dir(app_handle)?;
+    let available = available_disk_space(&data_dir)?;
+    let required = settings
+        .min_free_disk_mb
+        .saturating_mul(1024 * 1024)
+        .saturating_add(needed_bytes);
+
+    if available < required {
+        let _ = app_handle.emit(
+            "disk-space-warning",
+            DiskSpaceWarning {
+                available_bytes: available,
+                required_bytes: required,
+            },
+        );
+        return Err(format!(
+            "Not enough disk space: {} MB free, {} MB required",
+            available / (1024 * 1024),
+            required.div_ceil(1024 * 1024)
+        ));
+    }
+    Ok(())
+}
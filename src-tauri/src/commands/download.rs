@@ -0,0 +1,218 @@
+use crate::commands::apps::{ensure_app_trusted, get_app_internal};
+use crate::commands::audit::record_bridge_call;
+use crate::commands::permissions::{load_permissions, save_permissions};
+use crate::models::PermissionManifest;
+use reqwest::redirect::Policy;
+use reqwest::{Client, Url};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::oneshot;
+
+/// Largest file an app can download through the bridge.
+const APP_DOWNLOAD_MAX_BYTES: u64 = 200 * 1024 * 1024;
+/// Redirects a download may follow, the same cap reqwest uses by default.
+const APP_DOWNLOAD_MAX_REDIRECTS: usize = 10;
+
+/// Reduces an app-supplied name to a plain file name for the save dialog.
+fn sanitize_file_name(raw: &str) -> String {
+    let name: String = raw
+        .chars()
+        .filter(|ch| !ch.is_control() && !matches!(ch, '/' | '\\' | ':'))
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() {
+        "download".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn parse_download_url(url: &str) -> Result<(Url, String), String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid download URL: {}", e))?;
+    if parsed.scheme() != "https" && parsed.scheme() != "http" {
+        return Err("Downloads must use http or https".to_string());
+    }
+    let host = parsed
+        .host_str()
+        .ok_or("Download URL has no host")?
+        .to_ascii_lowercase();
+    Ok((parsed, host))
+}
+
+/// Checks the app's download allowlist, asking the user to allow the domain
+/// the first time an app tries to download from it.
+async fn authorize_domain(
+    app_handle: &AppHandle,
+    app_id: &str,
+    app_name: &str,
+    host: &str,
+) -> Result<(), String> {
+    let mut manifest = load_permissions(app_handle, app_id)?;
+    if manifest.can_download_from(host) {
+        return Ok(());
+    }
+
+    let (tx, rx) = oneshot::channel();
+    app_handle
+        .dialog()
+        .message(format!("Allow \"{}\" to download files from {}?", app_name, host))
+        .title("Allow downloads")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Allow".to_string(),
+            "Deny".to_string(),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+    if !rx.await.unwrap_or(false) {
+        return Err(format!("Downloads from {} are not allowed for this app", host));
+    }
+
+    manifest.allow_download_domain(host);
+    save_permissions(app_handle, app_id, &manifest)
+}
+
+async fn pick_destination(app_handle: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let (tx, rx) = oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .set_file_name(file_name)
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let path = rx
+        .await
+        .map_err(|_| "Save dialog closed unexpectedly".to_string())?
+        .ok_or("Download cancelled")?;
+    path.into_path()
+        .map_err(|e| format!("Invalid download location: {}", e))
+}
+
+/// Follows a redirect only to a host already on the app's allowlist, so an
+/// allowed host can't hand the download off to any other.
+fn allowlisted_redirects(manifest: PermissionManifest) -> Policy {
+    Policy::custom(move |attempt| {
+        if attempt.previous().len() >= APP_DOWNLOAD_MAX_REDIRECTS {
+            return attempt.error("Download redirected too many times");
+        }
+        match attempt.url().host_str().map(str::to_ascii_lowercase) {
+            Some(host) if manifest.can_download_from(&host) => attempt.follow(),
+            Some(host) => attempt.error(format!(
+                "Download redirected to {}, which is not allowed for this app",
+                host
+            )),
+            None => attempt.error("Download redirected to a URL without a host"),
+        }
+    })
+}
+
+/// Streams the response body to `dest` via a `.part` file, enforcing the
+/// size limit as bytes arrive since `Content-Length` can't be trusted.
+async fn stream_to_file(
+    url: Url,
+    manifest: PermissionManifest,
+    dest: &Path,
+) -> Result<u64, String> {
+    let client = Client::builder()
+        .redirect(allowlisted_redirects(manifest))
+        .build()
+        .map_err(|e| format!("Failed to download file: {}", e))?;
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Failed to download file: {}", e))?;
+    if response.content_length().unwrap_or(0) > APP_DOWNLOAD_MAX_BYTES {
+        return Err("Download exceeds the size limit".to_string());
+    }
+
+    let mut part_name = dest.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+    let mut file = fs::File::create(&part_path)
+        .map_err(|e| format!("Failed to create download file: {}", e))?;
+    let mut written: u64 = 0;
+    let result = async {
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to download file: {}", e))?
+        {
+            written += chunk.len() as u64;
+            if written > APP_DOWNLOAD_MAX_BYTES {
+                return Err("Download exceeds the size limit".to_string());
+            }
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write download: {}", e))?;
+        }
+        file.sync_all()
+            .map_err(|e| format!("Failed to write download: {}", e))
+    }
+    .await;
+
+    if let Err(err) = result {
+        let _ = fs::remove_file(&part_path);
+        return Err(err);
+    }
+    fs::rename(&part_path, dest).map_err(|e| format!("Failed to finalize download: {}", e))?;
+    Ok(written)
+}
+
+async fn download_for_app(
+    app_handle: &AppHandle,
+    app_id: &str,
+    url: &str,
+    suggested_name: &str,
+) -> Result<String, String> {
+    let app = get_app_internal(app_handle, app_id)?;
+    ensure_app_trusted(app_handle, app_id)?;
+    let (url, host) = parse_download_url(url)?;
+    authorize_domain(app_handle, app_id, &app.name, &host).await?;
+
+    let file_name = sanitize_file_name(suggested_name);
+    let dest = pick_destination(app_handle, &file_name).await?;
+    // Reloaded so redirects may use the domain the user just allowed.
+    let manifest = load_permissions(app_handle, app_id)?;
+    stream_to_file(url, manifest, &dest).await?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Bridge command: downloads a file from an allowlisted domain to a location
+/// the user picks. Returns the saved path.
+#[tauri::command]
+pub async fn app_download(
+    app_handle: AppHandle,
+    app_id: String,
+    url: String,
+    suggested_name: String,
+) -> Result<String, String> {
+    let result = download_for_app(&app_handle, &app_id, &url, &suggested_name).await;
+    record_bridge_call(&app_handle, &app_id, "download", Some(&url), result.is_ok());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_download_url, sanitize_file_name};
+
+    #[test]
+    fn sanitize_file_name_strips_path_parts() {
+        assert_eq!(sanitize_file_name("episode 1.mp3"), "episode 1.mp3");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "etcpasswd");
+        assert_eq!(sanitize_file_name("  "), "download");
+    }
+
+    #[test]
+    fn parse_download_url_normalizes_host_and_rejects_other_schemes() {
+        let (_, host) = parse_download_url("https://Media.Example.com/ep1.mp3").unwrap();
+        assert_eq!(host, "media.example.com");
+        assert!(parse_download_url("file:///etc/passwd").is_err());
+        assert!(parse_download_url("not a url").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::commands::apps::get_active_profile_data_dir;
+use crate::models::{DraftsIndex, GenerationDraft, DRAFT_FIELD_MAX_BYTES};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+fn get_drafts_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join("drafts.json"))
+}
+
+fn load_drafts(app_handle: &AppHandle) -> Result<DraftsIndex, String> {
+    let path = get_drafts_path(app_handle)?;
+    if !path.exists() {
+        return Ok(DraftsIndex::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read drafts: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse drafts: {}", e))
+}
+
+fn save_drafts(app_handle: &AppHandle, drafts: &DraftsIndex) -> Result<(), String> {
+    let path = get_drafts_path(app_handle)?;
+    let content = serde_json::to_string_pretty(drafts)
+        .map_err(|e| format!("Failed to serialize drafts: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Persists an unfinished generation form. Drafts are not validated like
+/// real generations, only bounded so a runaway paste can't bloat the file.
+#[tauri::command]
+pub fn save_generation_draft(
+    app_handle: AppHandle,
+    id: Option<String>,
+    name: String,
+    prompt: String,
+    emoji: String,
+    background_color: String,
+) -> Result<GenerationDraft, String> {
+    let draft_id = match id {
+        Some(id) => parse_uuid(&id)?,
+        None => Uuid::new_v4(),
+    };
+    if name.len() + prompt.len() > DRAFT_FIELD_MAX_BYTES {
+        return Err("Draft is too large to save".to_string());
+    }
+
+    let draft = GenerationDraft {
+        id: draft_id,
+        name,
+        prompt,
+        emoji,
+        background_color,
+        saved_at: Utc::now(),
+    };
+
+    let mut drafts = load_drafts(&app_handle)?;
+    drafts.upsert(draft.clone());
+    save_drafts(&app_handle, &drafts)?;
+
+    Ok(draft)
+}
+
+#[tauri::command]
+pub fn get_generation_drafts(app_handle: AppHandle) -> Result<Vec<GenerationDraft>, String> {
+    Ok(load_drafts(&app_handle)?.drafts)
+}
+
+#[tauri::command]
+pub fn delete_generation_draft(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let uuid = parse_uuid(&id)?;
+    let mut drafts = load_drafts(&app_handle)?;
+    if drafts.remove(uuid).is_some() {
+        save_drafts(&app_handle, &drafts)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::commands::apps::{
+    get_app_context_path, get_app_file_path, get_app_internal, load_app_context, save_app,
+    save_app_context,
+};
+use crate::commands::versions::{create_restore_point, record_version};
+use crate::models::{AppMetadata, ChangeKind, PendingEdit};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+pub(crate) fn get_pending_paths(
+    app_handle: &AppHandle,
+    id: Uuid,
+) -> Result<(PathBuf, PathBuf), String> {
+    Ok((
+        get_app_file_path(app_handle, id, "pending.json")?,
+        get_app_file_path(app_handle, id, "pending.html")?,
+    ))
+}
+
+/// Overwrites an app with the output of an edit, taking a restore point of
+/// the original first and recording the new version. A new context is
+/// written first and put back if the save fails, so the app never ends up
+/// with half an edit. An edit that leaves the content unchanged doesn't
+/// record a version.
+pub fn apply_edit(
+    app_handle: &AppHandle,
+    original: &AppMetadata,
+    app: &mut AppMetadata,
+    html_content: &str,
+    context: Option<&str>,
+) -> Result<(), String> {
+    create_restore_point(app_handle, original)?;
+    let previous_context = match context {
+        Some(context) => {
+            let previous = load_app_context(app_handle, app.id)?;
+            save_app_context(app_handle, app.id, context)?;
+            Some(previous)
+        }
+        None => None,
+    };
+    let changed = match save_app(app_handle, app, html_content) {
+        Ok(changed) => changed,
+        Err(err) => {
+            match previous_context {
+                Some(Some(previous)) => {
+                    let _ = save_app_context(app_handle, app.id, &previous);
+                }
+                Some(None) => {
+                    let _ = get_app_context_path(app_handle, app.id).map(fs::remove_file);
+                }
+                None => {}
+            }
+            return Err(err);
+        }
+    };
+    if changed {
+        record_version(app_handle, app, ChangeKind::Edited, html_content)?;
+    }
+    Ok(())
+}
+
+pub fn hold_pending_edit(
+    app_handle: &AppHandle,
+    pending: &PendingEdit,
+    html_content: &str,
+) -> Result<(), String> {
+    let (meta_path, html_path) = get_pending_paths(app_handle, pending.app.id)?;
+    let content = serde_json::to_string_pretty(pending)
+        .map_err(|e| format!("Failed to serialize pending edit: {}", e))?;
+
+    write_atomic(&html_path, html_content)?;
+    write_atomic(&meta_path, &content)
+}
+
+pub fn delete_pending_edit(app_handle: &AppHandle, id: Uuid) -> Result<(), String> {
+    let (meta_path, html_path) = get_pending_paths(app_handle, id)?;
+    for path in [meta_path, html_path] {
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete pending edit: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies an edit that was held back as suspicious, after the user has
+/// reviewed it.
+#[tauri::command]
+pub fn force_apply_edit(app_handle: AppHandle, id: String) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    let (meta_path, html_path) = get_pending_paths(&app_handle, uuid)?;
+    if !meta_path.exists() || !html_path.exists() {
+        return Err("No pending edit for this app".to_string());
+    }
+
+    let content = fs::read_to_string(&meta_path)
+        .map_err(|e| format!("Failed to read pending edit: {}", e))?;
+    let pending: PendingEdit = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse pending edit: {}", e))?;
+    let html = fs::read_to_string(&html_path)
+        .map_err(|e| format!("Failed to read pending edit HTML: {}", e))?;
+
+    let original = get_app_internal(&app_handle, &id)?;
+    let mut app = pending.app;
+    app.updated_at = Utc::now();
+    apply_edit(&app_handle, &original, &mut app, &html, pending.context.as_deref())?;
+    delete_pending_edit(&app_handle, uuid)?;
+
+    Ok(app)
+}
+
+#[tauri::command]
+pub fn discard_pending_edit(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let uuid = parse_uuid(&id)?;
+    delete_pending_edit(&app_handle, uuid)
+}
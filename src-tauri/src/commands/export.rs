@@ -0,0 +1,373 @@
+use crate::commands::apps::{get_app_internal, load_index};
+use crate::commands::artifacts::{app_artifacts, ArtifactKind};
+use crate::commands::config::load_app_config;
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::storage::load_storage;
+use crate::commands::versions::load_changelog;
+use crate::models::{AppBundle, AppMetadata, ChangeKind, ChangelogEntry, APP_BUNDLE_FORMAT_VERSION};
+use crate::utils::{parse_uuid, write_atomic};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+use uuid::Uuid;
+
+const BULK_EXPORT_PARALLELISM: usize = 4;
+
+/// Bulk exports in flight, keyed by export id, so cancelling one leaves
+/// the others running.
+#[derive(Default)]
+pub struct ExportJobs(Mutex<HashMap<Uuid, Arc<AtomicBool>>>);
+
+impl ExportJobs {
+    fn start(&self, export_id: Uuid) -> Result<Arc<AtomicBool>, String> {
+        let mut jobs = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if jobs.contains_key(&export_id) {
+            return Err(format!("Export {} is already running", export_id));
+        }
+        let cancelled = Arc::new(AtomicBool::new(false));
+        jobs.insert(export_id, cancelled.clone());
+        Ok(cancelled)
+    }
+
+    fn finish(&self, export_id: Uuid) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&export_id);
+    }
+
+    fn cancel(&self, export_id: Uuid) -> Result<(), String> {
+        let jobs = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let cancelled = jobs
+            .get(&export_id)
+            .ok_or_else(|| format!("Export not found: {}", export_id))?;
+        cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Removes an export from `ExportJobs` however `bulk_export_apps` returns.
+struct ExportJob<'a> {
+    jobs: &'a ExportJobs,
+    id: Uuid,
+}
+
+impl Drop for ExportJob<'_> {
+    fn drop(&mut self) {
+        self.jobs.finish(self.id);
+    }
+}
+
+/// Writes an app, its HTML, and its storage data to a single bundle file.
+/// Config values are left out unless `include_config` is set, since they
+/// may hold machine-specific URLs or keys. Other artifacts (history, logs,
+/// permissions) stay local to this machine. Locked apps must be unlocked
+/// first.
+#[tauri::command]
+pub fn export_app_bundle(
+    app_handle: AppHandle,
+    id: String,
+    dest: String,
+    include_config: Option<bool>,
+) -> Result<(), String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    write_app_bundle(&app_handle, app, Path::new(&dest), include_config.unwrap_or(false))
+}
+
+fn write_app_bundle(
+    app_handle: &AppHandle,
+    app: AppMetadata,
+    dest: &Path,
+    include_config: bool,
+) -> Result<(), String> {
+    ensure_unlocked(app_handle, &app_handle.state::<UnlockedApps>(), app.id)?;
+    let id = app.id.to_string();
+    let artifacts = app_artifacts(app_handle, app.id)?;
+    let html_path = artifacts
+        .iter()
+        .find(|artifact| artifact.kind == ArtifactKind::Html)
+        .map(|artifact| artifact.path.clone())
+        .ok_or_else(|| "App HTML file not found".to_string())?;
+    let html = fs::read_to_string(html_path)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+    let has_storage = artifacts
+        .iter()
+        .any(|artifact| artifact.kind == ArtifactKind::Storage && artifact.exists());
+    let storage = if has_storage {
+        Some(load_storage(app_handle, &id)?)
+    } else {
+        None
+    };
+
+    let config = if include_config {
+        Some(load_app_config(app_handle, app.id)?.values)
+    } else {
+        None
+    };
+
+    let bundle = AppBundle {
+        format_version: APP_BUNDLE_FORMAT_VERSION,
+        app,
+        html,
+        storage,
+        config,
+    };
+    let content = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize app bundle: {}", e))?;
+
+    write_atomic(dest, &content)
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct ExportProgress {
+    pub export_id: Uuid,
+    pub app_id: Uuid,
+    pub app_name: String,
+    pub completed: usize,
+    pub total: usize,
+    pub percent: u8,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct BulkExportFailure {
+    pub app_id: Uuid,
+    pub error: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct BulkExportReport {
+    pub export_id: Uuid,
+    pub exported: usize,
+    pub failed: Vec<BulkExportFailure>,
+    pub cancelled: bool,
+}
+
+/// Writes a bundle per app into `dest_dir`, named by app id, for backups
+/// or moving a whole library. Exports every app when `ids` is unset. Runs
+/// on the blocking pool with a few apps at a time, emitting
+/// `export-progress` after each one; `cancel_export` stops it between apps.
+/// Pass `export_id` to be able to cancel it before it returns; a fresh id
+/// is used otherwise. Locked apps are reported as failures unless unlocked
+/// first.
+#[tauri::command]
+pub async fn bulk_export_apps(
+    app_handle: AppHandle,
+    window: Window,
+    export_jobs: State<'_, ExportJobs>,
+    ids: Option<Vec<String>>,
+    dest_dir: String,
+    include_config: Option<bool>,
+    export_id: Option<String>,
+) -> Result<BulkExportReport, String> {
+    let export_id = match export_id {
+        Some(export_id) => parse_uuid(&export_id)?,
+        None => Uuid::new_v4(),
+    };
+    let cancelled = export_jobs.start(export_id)?;
+    let _job = ExportJob {
+        jobs: export_jobs.inner(),
+        id: export_id,
+    };
+
+    let apps = match ids {
+        Some(ids) => ids
+            .iter()
+            .map(|id| get_app_internal(&app_handle, id))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => load_index(&app_handle)?.apps,
+    };
+    let dest_dir = PathBuf::from(dest_dir);
+    fs::create_dir_all(&dest_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let total = apps.len();
+    let apps = Arc::new(apps);
+    let next = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let failed = Arc::new(Mutex::new(Vec::new()));
+    let include_config = include_config.unwrap_or(false);
+
+    let workers: Vec<_> = (0..BULK_EXPORT_PARALLELISM.min(total))
+        .map(|_| {
+            let (app_handle, window, dest_dir) =
+                (app_handle.clone(), window.clone(), dest_dir.clone());
+            let (apps, next, completed, failed) =
+                (apps.clone(), next.clone(), completed.clone(), failed.clone());
+            let cancelled = cancelled.clone();
+            tauri::async_runtime::spawn_blocking(move || loop {
+                if cancelled.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Some(app) = apps.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                    break;
+                };
+                let dest = dest_dir.join(format!("{}.json", app.id));
+                let result = write_app_bundle(&app_handle, app.clone(), &dest, include_config);
+                if let Err(error) = result {
+                    if let Ok(mut failed) = failed.lock() {
+                        failed.push(BulkExportFailure {
+                            app_id: app.id,
+                            error,
+                        });
+                    }
+                }
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                let _ = window.emit(
+                    "export-progress",
+                    ExportProgress {
+                        export_id,
+                        app_id: app.id,
+                        app_name: app.name.clone(),
+                        completed: done,
+                        total,
+                        percent: (done * 100 / total) as u8,
+                    },
+                );
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker
+            .await
+            .map_err(|e| format!("Export worker failed: {}", e))?;
+    }
+
+    let failed = failed.lock().map(|failed| failed.clone()).unwrap_or_default();
+    Ok(BulkExportReport {
+        export_id,
+        exported: completed.load(Ordering::SeqCst) - failed.len(),
+        failed,
+        cancelled: cancelled.load(Ordering::SeqCst),
+    })
+}
+
+/// Stops the `bulk_export_apps` with `export_id` once the apps in progress
+/// finish.
+#[tauri::command]
+pub fn cancel_export(export_jobs: State<'_, ExportJobs>, export_id: String) -> Result<(), String> {
+    export_jobs.cancel(parse_uuid(&export_id)?)
+}
+
+fn change_label(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Created => "Created",
+        ChangeKind::Edited => "Edited",
+        ChangeKind::Imported => "Imported",
+        ChangeKind::Restored => "Restored",
+        ChangeKind::RuntimeUpgraded => "Runtime upgraded",
+    }
+}
+
+fn push_quoted(out: &mut String, text: &str) {
+    for line in text.lines() {
+        out.push_str("> ");
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Renders apps and their prompt history as a markdown "recipe book".
+/// Apps without a recorded history fall back to their current prompt.
+fn render_prompts_markdown(apps: &[(AppMetadata, Vec<ChangelogEntry>)]) -> String {
+    let mut out = String::from("# Trove Prompts\n");
+    for (app, history) in apps {
+        out.push_str(&format!("\n## {} {}\n\n", app.emoji, app.name));
+        out.push_str(&format!("_Created {}_\n\n", app.created_at.format("%Y-%m-%d")));
+
+        if history.is_empty() {
+            push_quoted(&mut out, &app.prompt);
+            continue;
+        }
+        for (idx, entry) in history.iter().enumerate() {
+            if idx > 0 {
+                out.push('\n');
+            }
+            out.push_str(&format!(
+                "### {} · {}\n\n",
+                change_label(entry.kind),
+                entry.created_at.format("%Y-%m-%d %H:%M UTC")
+            ));
+            push_quoted(&mut out, &entry.prompt);
+        }
+    }
+    out
+}
+
+#[tauri::command]
+pub fn export_prompts_markdown(app_handle: AppHandle, dest: String) -> Result<(), String> {
+    let index = load_index(&app_handle)?;
+    let mut apps = Vec::with_capacity(index.apps.len());
+    for app in index.apps {
+        let history = load_changelog(&app_handle, app.id)?.entries;
+        apps.push((app, history));
+    }
+    apps.sort_by_key(|(app, _)| app.created_at);
+
+    write_atomic(Path::new(&dest), &render_prompts_markdown(&apps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_prompts_markdown, ExportJobs};
+    use crate::models::{AppMetadata, ChangeKind, ChangelogEntry};
+    use chrono::Utc;
+    use std::sync::atomic::Ordering;
+    use uuid::Uuid;
+
+    fn make_app() -> AppMetadata {
+        AppMetadata::new(
+            "Todo".to_string(),
+            "A todo list".to_string(),
+            "✅".to_string(),
+            "#000000".to_string(),
+        )
+    }
+
+    #[test]
+    fn renders_prompt_history_as_quotes() {
+        let app = make_app();
+        let history = vec![ChangelogEntry {
+            version_id: Uuid::new_v4(),
+            kind: ChangeKind::Edited,
+            name: app.name.clone(),
+            prompt: "Add due dates\nand tags".to_string(),
+            html_bytes: 10,
+            created_at: Utc::now(),
+            pruned: false,
+            generated_with: None,
+            node_count: None,
+            script_count: None,
+        }];
+
+        let markdown = render_prompts_markdown(&[(app, history)]);
+
+        assert!(markdown.contains("## ✅ Todo"));
+        assert!(markdown.contains("### Edited · "));
+        assert!(markdown.contains("> Add due dates\n> and tags\n"));
+    }
+
+    #[test]
+    fn cancelling_an_export_leaves_others_running() {
+        let jobs = ExportJobs::default();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let first_cancelled = jobs.start(first).unwrap();
+        let second_cancelled = jobs.start(second).unwrap();
+        assert!(jobs.start(first).is_err());
+
+        jobs.cancel(first).unwrap();
+        assert!(first_cancelled.load(Ordering::SeqCst));
+        assert!(!second_cancelled.load(Ordering::SeqCst));
+
+        jobs.finish(first);
+        assert!(jobs.cancel(first).is_err());
+    }
+
+    #[test]
+    fn falls_back_to_current_prompt_without_history() {
+        let markdown = render_prompts_markdown(&[(make_app(), Vec::new())]);
+        assert!(markdown.contains("> A todo list\n"));
+    }
+}
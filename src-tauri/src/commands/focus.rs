@@ -0,0 +1,62 @@
+use crate::commands::apps::get_app_data_dir;
+use crate::models::FocusMode;
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+pub const FOCUS_MODE_BLOCKED_ERROR: &str = "App is blocked by focus mode";
+
+fn get_focus_mode_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app_handle)?.join("focus_mode.json"))
+}
+
+fn load_focus_mode(app_handle: &AppHandle) -> Result<FocusMode, String> {
+    let path = get_focus_mode_path(app_handle)?;
+    if !path.exists() {
+        return Ok(FocusMode::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read focus mode: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse focus mode: {}", e))
+}
+
+/// Fails with `FOCUS_MODE_BLOCKED_ERROR` while focus mode blocks the app.
+pub(crate) fn ensure_not_focus_blocked(app_handle: &AppHandle, id: Uuid) -> Result<(), String> {
+    if load_focus_mode(app_handle)?.blocks(id, Utc::now()) {
+        return Err(FOCUS_MODE_BLOCKED_ERROR.to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_focus_mode(app_handle: AppHandle) -> Result<FocusMode, String> {
+    load_focus_mode(&app_handle)
+}
+
+/// Blocks the given apps from opening until `until`, or until focus mode
+/// is turned off when no end time is given. Persisted, so restarting Trove
+/// doesn't lift it.
+#[tauri::command]
+pub fn set_focus_mode(
+    app_handle: AppHandle,
+    enabled: bool,
+    blocked_app_ids: Vec<String>,
+    until: Option<DateTime<Utc>>,
+) -> Result<FocusMode, String> {
+    let blocked_app_ids = blocked_app_ids
+        .iter()
+        .map(|id| parse_uuid(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let focus_mode = FocusMode::new(enabled, blocked_app_ids, until, Utc::now())?;
+
+    let content = serde_json::to_string_pretty(&focus_mode)
+        .map_err(|e| format!("Failed to serialize focus mode: {}", e))?;
+    write_atomic(&get_focus_mode_path(&app_handle)?, &content)?;
+
+    Ok(focus_mode)
+}
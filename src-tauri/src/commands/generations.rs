@@ -0,0 +1,528 @@
+use crate::commands::agent::GenerationProgress;
+use crate::commands::settings::load_settings;
+use crate::utils::parse_uuid;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+use tauri_plugin_shell::process::CommandChild;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+pub const MAX_QUEUED_GENERATIONS: usize = 20;
+/// Finished jobs listed by `list_generation_jobs`, newest first.
+const FINISHED_JOBS_KEPT: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// A generation job as shown to the frontend, sent with every status change
+/// as `generation-job-updated`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationJobInfo {
+    pub job_id: Uuid,
+    /// The app an edit is changing; unset for new apps.
+    pub app_id: Option<Uuid>,
+    pub name: String,
+    pub status: GenerationJobStatus,
+    /// Place in the queue, from zero; only set while queued.
+    pub position: Option<usize>,
+    pub queued_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+struct Job {
+    info: GenerationJobInfo,
+    cancelled: Arc<AtomicBool>,
+    child: Option<CommandChild>,
+    /// Id of the clarifying question the job's sidecar is waiting on.
+    pending_question: Option<String>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    /// Queued and running jobs.
+    active: HashMap<Uuid, Job>,
+    /// Ids of queued jobs, oldest first.
+    queue: VecDeque<Uuid>,
+    finished: VecDeque<GenerationJobInfo>,
+}
+
+impl Jobs {
+    fn running(&self) -> impl Iterator<Item = &Job> {
+        self.active
+            .values()
+            .filter(|job| job.info.status == GenerationJobStatus::Running)
+    }
+
+    /// The oldest queued job that may start with at most `limit` running.
+    /// Jobs editing an app that is already being edited are skipped until
+    /// that edit finishes.
+    fn next_startable(&self, limit: usize) -> Option<Uuid> {
+        if self.running().count() >= limit {
+            return None;
+        }
+        self.queue.iter().copied().find(|job_id| {
+            let app_id = self.active.get(job_id).and_then(|job| job.info.app_id);
+            app_id.is_none() || !self.running().any(|job| job.info.app_id == app_id)
+        })
+    }
+
+    fn info(&self, job: &Job) -> GenerationJobInfo {
+        GenerationJobInfo {
+            position: self.queue.iter().position(|id| *id == job.info.job_id),
+            ..job.info.clone()
+        }
+    }
+}
+
+/// Generations in flight, keyed by job id, so several can run at once and
+/// each can be cancelled or steered on its own. Requests over the limit are
+/// queued and started in order as others finish.
+#[derive(Default)]
+pub struct GenerationManager {
+    jobs: Mutex<Jobs>,
+    /// Woken whenever a queued job might be able to start.
+    changed: Notify,
+}
+
+impl GenerationManager {
+    /// Lets queued jobs recheck whether they may start, e.g. after the
+    /// concurrency limit was raised.
+    pub(crate) fn reschedule(&self) {
+        self.changed.notify_waiters();
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Jobs>, String> {
+        self.jobs
+            .lock()
+            .map_err(|_| "Failed to track generations".to_string())
+    }
+
+    fn enqueue(
+        &self,
+        job_id: Uuid,
+        app_id: Option<Uuid>,
+        name: &str,
+    ) -> Result<(Arc<AtomicBool>, GenerationJobInfo), String> {
+        let mut jobs = self.lock()?;
+        if jobs.active.contains_key(&job_id) {
+            return Err(format!("Generation {} is already running", job_id));
+        }
+        if jobs.queue.len() >= MAX_QUEUED_GENERATIONS {
+            return Err(format!(
+                "At most {} generations can wait in the queue",
+                MAX_QUEUED_GENERATIONS
+            ));
+        }
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let job = Job {
+            info: GenerationJobInfo {
+                job_id,
+                app_id,
+                name: name.to_string(),
+                status: GenerationJobStatus::Queued,
+                position: None,
+                queued_at: Utc::now(),
+                started_at: None,
+                finished_at: None,
+            },
+            cancelled: cancelled.clone(),
+            child: None,
+            pending_question: None,
+        };
+        jobs.queue.push_back(job_id);
+        let info = jobs.info(&job);
+        jobs.active.insert(job_id, job);
+        Ok((cancelled, info))
+    }
+
+    /// Starts the job if it is next in line and one of `limit` slots is
+    /// free. Fails once the job has been cancelled while waiting.
+    fn try_start(&self, job_id: Uuid, limit: usize) -> Result<Option<GenerationJobInfo>, String> {
+        let mut jobs = self.lock()?;
+        let cancelled = jobs
+            .active
+            .get(&job_id)
+            .is_none_or(|job| job.cancelled.load(Ordering::SeqCst));
+        if cancelled {
+            return Err("Generation cancelled".to_string());
+        }
+        if jobs.next_startable(limit) != Some(job_id) {
+            return Ok(None);
+        }
+        jobs.queue.retain(|id| *id != job_id);
+        let Some(job) = jobs.active.get_mut(&job_id) else {
+            return Ok(None);
+        };
+        job.info.status = GenerationJobStatus::Running;
+        job.info.started_at = Some(Utc::now());
+        let info = job.info.clone();
+        // Another slot may still be free for the next job in line.
+        self.changed.notify_waiters();
+        Ok(Some(info))
+    }
+
+    fn finish(&self, job_id: Uuid, status: GenerationJobStatus) -> Option<GenerationJobInfo> {
+        let mut jobs = self.lock().ok()?;
+        jobs.queue.retain(|id| *id != job_id);
+        let mut info = jobs.active.remove(&job_id)?.info;
+        info.status = status;
+        info.finished_at = Some(Utc::now());
+        jobs.finished.push_front(info.clone());
+        jobs.finished.truncate(FINISHED_JOBS_KEPT);
+        self.changed.notify_waiters();
+        Some(info)
+    }
+
+    pub(crate) fn set_child(&self, job_id: Uuid, child: CommandChild) -> Result<(), String> {
+        let mut jobs = self.lock()?;
+        let job = jobs
+            .active
+            .get_mut(&job_id)
+            .ok_or_else(|| "Generation is no longer running".to_string())?;
+        job.child = Some(child);
+        Ok(())
+    }
+
+    /// Forgets the job's sidecar once it has exited.
+    pub(crate) fn clear_child(&self, job_id: Uuid) {
+        if let Ok(mut jobs) = self.lock() {
+            if let Some(job) = jobs.active.get_mut(&job_id) {
+                job.child = None;
+                job.pending_question = None;
+            }
+        }
+    }
+
+    pub(crate) fn kill_child(&self, job_id: Uuid) {
+        if let Ok(mut jobs) = self.lock() {
+            if let Some(child) = jobs
+                .active
+                .get_mut(&job_id)
+                .and_then(|job| job.child.take())
+            {
+                let _ = child.kill();
+            }
+        }
+    }
+
+    /// Flags the job as cancelled and kills its sidecar; a queued job stops
+    /// waiting. Returns whether the job was queued or running.
+    fn cancel(&self, job_id: Uuid) -> bool {
+        let cancelled = match self.lock() {
+            Ok(jobs) => jobs.active.get(&job_id).map(|job| job.cancelled.clone()),
+            Err(_) => None,
+        };
+        let Some(cancelled) = cancelled else {
+            return false;
+        };
+        cancelled.store(true, Ordering::SeqCst);
+        self.kill_child(job_id);
+        self.changed.notify_waiters();
+        true
+    }
+
+    pub(crate) fn with_child<T>(
+        &self,
+        job_id: Uuid,
+        f: impl FnOnce(&mut CommandChild) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut jobs = self.lock()?;
+        let child = jobs
+            .active
+            .get_mut(&job_id)
+            .and_then(|job| job.child.as_mut())
+            .ok_or_else(|| "No generation is running".to_string())?;
+        f(child)
+    }
+
+    pub(crate) fn set_pending_question(&self, job_id: Uuid, question_id: Option<String>) {
+        if let Ok(mut jobs) = self.lock() {
+            if let Some(job) = jobs.active.get_mut(&job_id) {
+                job.pending_question = question_id;
+            }
+        }
+    }
+
+    /// The job whose sidecar asked the question with this id.
+    pub(crate) fn job_for_question(&self, question_id: &str) -> Option<Uuid> {
+        let jobs = self.lock().ok()?;
+        jobs.active
+            .iter()
+            .find(|(_, job)| job.pending_question.as_deref() == Some(question_id))
+            .map(|(job_id, _)| *job_id)
+    }
+
+    /// Running jobs in the order they started, then the queue in order,
+    /// then recently finished jobs, newest first.
+    fn list(&self) -> Vec<GenerationJobInfo> {
+        let Ok(jobs) = self.lock() else {
+            return Vec::new();
+        };
+        let mut running: Vec<GenerationJobInfo> =
+            jobs.running().map(|job| job.info.clone()).collect();
+        running.sort_by_key(|info| info.started_at);
+        let queued = jobs
+            .queue
+            .iter()
+            .filter_map(|job_id| jobs.active.get(job_id))
+            .map(|job| jobs.info(job));
+        running
+            .into_iter()
+            .chain(queued)
+            .chain(jobs.finished.iter().cloned())
+            .collect()
+    }
+
+    fn ids_with_status(&self, status: GenerationJobStatus) -> Vec<Uuid> {
+        self.list()
+            .into_iter()
+            .filter(|info| info.status == status)
+            .map(|info| info.job_id)
+            .collect()
+    }
+
+    /// The given job, or the only running one when no id is passed, so
+    /// callers that run one generation at a time needn't track ids.
+    pub(crate) fn resolve(&self, job_id: Option<&str>) -> Result<Uuid, String> {
+        if let Some(job_id) = job_id {
+            return parse_uuid(job_id);
+        }
+        match self
+            .ids_with_status(GenerationJobStatus::Running)
+            .as_slice()
+        {
+            [only] => Ok(*only),
+            [] => Err("No generation is running".to_string()),
+            _ => Err("Several generations are running; pass a job id".to_string()),
+        }
+    }
+}
+
+/// A generation registered with the `GenerationManager` for as long as
+/// this value lives. Dropping it records how the job ended.
+pub(crate) struct GenerationJob {
+    app_handle: AppHandle,
+    pub(crate) id: Uuid,
+    cancelled: Arc<AtomicBool>,
+    completed: AtomicBool,
+}
+
+impl GenerationJob {
+    /// Queues a job under the caller's id, or a fresh one, and waits for its
+    /// turn. Edits pass their app so it isn't edited twice at once. `name`
+    /// labels the job in `list_generation_jobs`.
+    pub(crate) async fn start(
+        app_handle: &AppHandle,
+        window: &Window,
+        job_id: Option<String>,
+        app_id: Option<Uuid>,
+        name: &str,
+    ) -> Result<Self, String> {
+        let id = match job_id {
+            Some(job_id) => parse_uuid(&job_id)?,
+            None => Uuid::new_v4(),
+        };
+        let (cancelled, info) = app_handle
+            .state::<GenerationManager>()
+            .enqueue(id, app_id, name)?;
+        let job = Self {
+            app_handle: app_handle.clone(),
+            id,
+            cancelled,
+            completed: AtomicBool::new(false),
+        };
+        job.announce(info);
+        job.wait_for_turn(window).await?;
+        Ok(job)
+    }
+
+    async fn wait_for_turn(&self, window: &Window) -> Result<(), String> {
+        let manager = self.manager();
+        let mut waiting = false;
+        loop {
+            let mut changed = std::pin::pin!(manager.changed.notified());
+            changed.as_mut().enable();
+            // Read on every pass, so a changed limit applies to waiting jobs.
+            let limit = load_settings(&self.app_handle)?.max_concurrent_generations;
+            if let Some(info) = manager.try_start(self.id, limit)? {
+                self.announce(info);
+                return Ok(());
+            }
+            if !waiting {
+                waiting = true;
+                self.emit(
+                    window,
+                    "generation-progress",
+                    GenerationProgress {
+                        message: "Waiting for other generations to finish".to_string(),
+                    },
+                );
+            }
+            changed.await;
+        }
+    }
+
+    fn announce(&self, info: GenerationJobInfo) {
+        let _ = self.app_handle.emit("generation-job-updated", info);
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Marks the job as having produced its app, just before reporting it.
+    pub(crate) fn complete(&self) {
+        self.completed.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn manager(&self) -> State<'_, GenerationManager> {
+        self.app_handle.state::<GenerationManager>()
+    }
+
+    /// Emits a generation event to `window`, tagged with this job's id.
+    pub(crate) fn emit<T: Serialize + Clone>(&self, window: &Window, event: &str, payload: T) {
+        emit_job_event(window, self.id, event, payload);
+    }
+}
+
+impl Drop for GenerationJob {
+    fn drop(&mut self) {
+        let status = if self.completed.load(Ordering::SeqCst) {
+            GenerationJobStatus::Completed
+        } else if self.is_cancelled() {
+            GenerationJobStatus::Cancelled
+        } else {
+            GenerationJobStatus::Failed
+        };
+        if let Some(info) = self.manager().finish(self.id, status) {
+            self.announce(info);
+        }
+    }
+}
+
+/// A generation event payload with the job it belongs to.
+#[derive(Clone, Serialize)]
+pub struct JobEvent<T> {
+    pub job_id: Uuid,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+pub(crate) fn emit_job_event<T: Serialize + Clone>(
+    window: &Window,
+    job_id: Uuid,
+    event: &str,
+    payload: T,
+) {
+    let _ = window.emit(event, JobEvent { job_id, payload });
+}
+
+#[tauri::command]
+pub fn list_generation_jobs(manager: State<'_, GenerationManager>) -> Vec<GenerationJobInfo> {
+    manager.list()
+}
+
+/// Cancels one generation, or every queued and running one when no id is
+/// given. Returns the ids of the jobs cancelled.
+pub(crate) fn cancel_generations(
+    manager: &GenerationManager,
+    job_id: Option<&str>,
+) -> Result<Vec<Uuid>, String> {
+    let job_ids = match job_id {
+        Some(job_id) => vec![parse_uuid(job_id)?],
+        None => [GenerationJobStatus::Running, GenerationJobStatus::Queued]
+            .into_iter()
+            .flat_map(|status| manager.ids_with_status(status))
+            .collect(),
+    };
+    Ok(job_ids
+        .into_iter()
+        .filter(|job_id| manager.cancel(*job_id))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cancel_generations, GenerationJobStatus, GenerationManager};
+    use std::sync::atomic::Ordering;
+    use uuid::Uuid;
+
+    #[test]
+    fn queues_jobs_beyond_the_limit_in_order() {
+        const LIMIT: usize = 3;
+        let manager = GenerationManager::default();
+        let app_id = Uuid::new_v4();
+        let jobs: Vec<Uuid> = (0..LIMIT + 2).map(|_| Uuid::new_v4()).collect();
+        let flags: Vec<_> = jobs
+            .iter()
+            .enumerate()
+            .map(|(i, job)| {
+                let app = (i == 0 || i == LIMIT).then_some(app_id);
+                manager.enqueue(*job, app, "App").unwrap().0
+            })
+            .collect();
+        assert!(manager.enqueue(jobs[0], None, "App").is_err());
+
+        // Out of turn, a job waits even while slots are free.
+        assert!(manager.try_start(jobs[1], LIMIT).unwrap().is_none());
+        for job in &jobs[..LIMIT] {
+            assert!(manager.try_start(*job, LIMIT).unwrap().is_some());
+        }
+        assert!(manager.resolve(None).is_err());
+        let last = *jobs.last().unwrap();
+        assert!(manager.try_start(last, LIMIT).unwrap().is_none());
+        let listed = manager.list();
+        assert_eq!(listed[LIMIT + 1].job_id, last);
+        assert_eq!(listed[LIMIT + 1].position, Some(1));
+
+        // The queued edit of the same app lets the job behind it go first.
+        manager.finish(jobs[1], GenerationJobStatus::Completed);
+        assert!(manager.try_start(jobs[LIMIT], LIMIT).unwrap().is_none());
+        assert!(manager.try_start(last, LIMIT).unwrap().is_some());
+
+        let cancelled = cancel_generations(&manager, Some(&jobs[2].to_string())).unwrap();
+        assert_eq!(cancelled, vec![jobs[2]]);
+        assert!(flags[2].load(Ordering::SeqCst));
+        assert!(!flags[0].load(Ordering::SeqCst));
+        manager.finish(jobs[2], GenerationJobStatus::Cancelled);
+
+        cancel_generations(&manager, Some(&jobs[LIMIT].to_string())).unwrap();
+        assert_eq!(
+            manager.try_start(jobs[LIMIT], LIMIT).unwrap_err(),
+            "Generation cancelled"
+        );
+        manager.finish(jobs[LIMIT], GenerationJobStatus::Cancelled);
+        manager.finish(last, GenerationJobStatus::Failed);
+
+        assert_eq!(manager.resolve(None).unwrap(), jobs[0]);
+        manager.set_pending_question(jobs[0], Some("q1".to_string()));
+        assert_eq!(manager.job_for_question("q1"), Some(jobs[0]));
+        manager.clear_child(jobs[0]);
+        assert_eq!(manager.job_for_question("q1"), None);
+
+        let statuses: Vec<GenerationJobStatus> =
+            manager.list().iter().map(|info| info.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                GenerationJobStatus::Running,
+                GenerationJobStatus::Failed,
+                GenerationJobStatus::Cancelled,
+                GenerationJobStatus::Cancelled,
+                GenerationJobStatus::Completed,
+            ]
+        );
+    }
+}
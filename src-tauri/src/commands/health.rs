@@ -0,0 +1,146 @@
+use crate::commands::app_errors::load_app_errors;
+use crate::commands::apps::{get_app_html_path, get_app_internal};
+use crate::commands::edits::get_pending_paths;
+use crate::commands::storage::load_storage;
+use crate::commands::versions::{get_snapshot_path, load_changelog};
+use crate::html::{check_structure, lint_bridge_usage};
+use crate::models::{AppRuntimeError, LintWarning};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+const HEALTH_RECENT_ERRORS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Warning,
+    Broken,
+}
+
+/// One structured health check of an app, for badging it in the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppHealth {
+    pub app_id: Uuid,
+    pub status: HealthStatus,
+    pub html_error: Option<String>,
+    pub storage_error: Option<String>,
+    pub lint_warnings: Vec<LintWarning>,
+    pub missing_files: Vec<String>,
+    pub recent_errors: Vec<AppRuntimeError>,
+}
+
+impl AppHealth {
+    /// Anything that stops the app loading or loses data is broken; lint
+    /// findings and runtime errors only warrant a warning.
+    fn compute_status(&self) -> HealthStatus {
+        let unloadable = self.html_error.is_some() || !self.missing_files.is_empty();
+        if unloadable || self.storage_error.is_some() {
+            HealthStatus::Broken
+        } else if !self.lint_warnings.is_empty() || !self.recent_errors.is_empty() {
+            HealthStatus::Warning
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[tauri::command]
+pub fn get_app_health(app_handle: AppHandle, id: String) -> Result<AppHealth, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    let mut missing_files = Vec::new();
+
+    let html_path = get_app_html_path(&app_handle, app.id)?;
+    let (html_error, lint_warnings) = match fs::read_to_string(&html_path) {
+        Ok(html) => {
+            // Nothing is rewritten here, so report every finding as unfixed.
+            let (_, mut warnings) = lint_bridge_usage(&html);
+            for warning in &mut warnings {
+                warning.fixed = false;
+            }
+            (check_structure(&html).err(), warnings)
+        }
+        Err(err) => {
+            if !html_path.exists() {
+                missing_files.push(file_name(&html_path));
+            }
+            (Some(format!("Failed to read app HTML: {}", err)), Vec::new())
+        }
+    };
+
+    let storage_error = load_storage(&app_handle, &id).err();
+
+    for entry in load_changelog(&app_handle, app.id)?.entries {
+        if entry.pruned {
+            continue;
+        }
+        let path = get_snapshot_path(&app_handle, app.id, entry.version_id)?;
+        if !path.exists() {
+            missing_files.push(file_name(&path));
+        }
+    }
+    let (pending_meta, pending_html) = get_pending_paths(&app_handle, app.id)?;
+    if pending_meta.exists() && !pending_html.exists() {
+        missing_files.push(file_name(&pending_html));
+    }
+
+    let mut recent_errors = load_app_errors(&app_handle, app.id)?.errors;
+    let skip = recent_errors.len().saturating_sub(HEALTH_RECENT_ERRORS);
+    recent_errors.drain(..skip);
+
+    let mut health = AppHealth {
+        app_id: app.id,
+        status: HealthStatus::Healthy,
+        html_error,
+        storage_error,
+        lint_warnings,
+        missing_files,
+        recent_errors,
+    };
+    health.status = health.compute_status();
+    Ok(health)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppHealth, HealthStatus};
+    use crate::models::{LintRule, LintWarning};
+    use uuid::Uuid;
+
+    fn healthy() -> AppHealth {
+        AppHealth {
+            app_id: Uuid::nil(),
+            status: HealthStatus::Healthy,
+            html_error: None,
+            storage_error: None,
+            lint_warnings: Vec::new(),
+            missing_files: Vec::new(),
+            recent_errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn status_reflects_the_worst_finding() {
+        assert_eq!(healthy().compute_status(), HealthStatus::Healthy);
+
+        let mut warned = healthy();
+        warned.lint_warnings.push(LintWarning {
+            rule: LintRule::WebStorage,
+            message: "uses localStorage".to_string(),
+            fixed: false,
+        });
+        assert_eq!(warned.compute_status(), HealthStatus::Warning);
+
+        warned.missing_files.push("abc.html".to_string());
+        assert_eq!(warned.compute_status(), HealthStatus::Broken);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::commands::apps::{get_active_profile_data_dir, get_app_file_path, get_app_internal};
+use crate::models::{GenerationAttempt, GenerationHistory};
+use crate::utils::write_atomic;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use uuid::Uuid;
+
+pub(crate) fn get_history_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "history.json")
+}
+
+/// Attempts that never produced an app, such as failed first generations,
+/// have no app directory and are kept with the profile instead.
+fn get_unsaved_history_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join("generation_history.json"))
+}
+
+/// The app's history, or the profile's unsaved attempts when `id` is unset.
+fn resolve_history_path(app_handle: &AppHandle, id: Option<String>) -> Result<PathBuf, String> {
+    match id {
+        Some(id) => get_history_path(app_handle, get_app_internal(app_handle, &id)?.id),
+        None => get_unsaved_history_path(app_handle),
+    }
+}
+
+fn load_history(path: &Path) -> Result<GenerationHistory, String> {
+    if !path.exists() {
+        return Ok(GenerationHistory::default());
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read generation history: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse generation history: {}", e))
+}
+
+/// Like timelines, failures are only logged so bookkeeping never fails a
+/// generation.
+fn append_attempt(path: Result<PathBuf, String>, attempt: GenerationAttempt) {
+    let result = path.and_then(|path| {
+        let mut history = load_history(&path)?;
+        history.push(attempt);
+        let content = serde_json::to_string_pretty(&history)
+            .map_err(|e| format!("Failed to serialize generation history: {}", e))?;
+        write_atomic(&path, &content)
+    });
+    if let Err(err) = result {
+        eprintln!("Failed to record generation history: {}", err);
+    }
+}
+
+/// Appends an attempt to the app's generation history.
+pub(crate) fn record_generation_attempt(
+    app_handle: &AppHandle,
+    id: Uuid,
+    attempt: GenerationAttempt,
+) {
+    append_attempt(get_history_path(app_handle, id), attempt);
+}
+
+/// Appends an attempt that didn't leave an app behind to the profile's
+/// history.
+pub(crate) fn record_unsaved_attempt(app_handle: &AppHandle, attempt: GenerationAttempt) {
+    append_attempt(get_unsaved_history_path(app_handle), attempt);
+}
+
+/// Returns every recorded generation and edit of an app, oldest first.
+/// Without an `id`, returns the attempts that never produced an app.
+#[tauri::command]
+pub fn get_generation_history(
+    app_handle: AppHandle,
+    id: Option<String>,
+) -> Result<Vec<GenerationAttempt>, String> {
+    let path = resolve_history_path(&app_handle, id)?;
+    Ok(load_history(&path)?.attempts)
+}
+
+#[tauri::command]
+pub fn clear_generation_history(app_handle: AppHandle, id: Option<String>) -> Result<(), String> {
+    let path = resolve_history_path(&app_handle, id)?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to clear generation history: {}", e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use crate::commands::apps::{get_app_html_path, get_openable_html_path, load_index};
+use crate::commands::lock::UnlockedApps;
+use crate::commands::power::PowerState;
+use crate::commands::settings::load_settings;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+
+/// How many of the most opened apps are read into the cache at startup.
+const HTML_CACHE_WARM_APPS: usize = 10;
+
+struct CachedHtml {
+    html: Arc<str>,
+    modified: SystemTime,
+    last_used: u64,
+}
+
+/// Least-recently-used entries keyed by path, bounded by total HTML bytes.
+#[derive(Default)]
+struct HtmlCacheEntries {
+    entries: HashMap<PathBuf, CachedHtml>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl HtmlCacheEntries {
+    /// The cached HTML, unless the file changed since it was read.
+    fn get(&mut self, path: &Path, modified: SystemTime) -> Option<Arc<str>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(path)?;
+        if entry.modified != modified {
+            self.remove(path);
+            return None;
+        }
+        entry.last_used = self.clock;
+        Some(entry.html.clone())
+    }
+
+    fn insert(&mut self, path: PathBuf, html: Arc<str>, modified: SystemTime, max_bytes: usize) {
+        self.remove(&path);
+        if html.len() > max_bytes {
+            return;
+        }
+        while self.bytes + html.len() > max_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+        self.clock += 1;
+        self.bytes += html.len();
+        let entry = CachedHtml {
+            html,
+            modified,
+            last_used: self.clock,
+        };
+        self.entries.insert(path, entry);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(entry) = self.entries.remove(path) {
+            self.bytes -= entry.html.len();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+}
+
+/// Keeps frequently opened apps' HTML in memory so large apps don't wait
+/// on a cold disk. Sized by `html_cache_mb`; disabled when that's unset.
+#[derive(Default)]
+pub struct HtmlCache {
+    entries: Mutex<HtmlCacheEntries>,
+}
+
+fn cache_limit_bytes(app_handle: &AppHandle) -> Option<usize> {
+    let megabytes = load_settings(app_handle).ok()?.html_cache_mb?;
+    usize::try_from(megabytes.checked_mul(1024 * 1024)?).ok()
+}
+
+impl HtmlCache {
+    /// Reads `path`, from memory when the cached copy is still current.
+    fn read(&self, app_handle: &AppHandle, path: &Path) -> Result<Arc<str>, String> {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+        let Some(max_bytes) = cache_limit_bytes(app_handle) else {
+            self.clear();
+            return fs::read_to_string(path)
+                .map(Arc::from)
+                .map_err(|e| format!("Failed to read app HTML: {}", e));
+        };
+
+        if let Some(html) = self.entries.lock().ok().and_then(|mut c| c.get(path, modified)) {
+            return Ok(html);
+        }
+        let html: Arc<str> = fs::read_to_string(path)
+            .map(Arc::from)
+            .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(path.to_path_buf(), html.clone(), modified, max_bytes);
+        }
+        Ok(html)
+    }
+
+    /// Reads `path` into the cache in the background, unless background
+    /// work is paused for low-power mode.
+    pub(crate) fn warm(&self, app_handle: &AppHandle, path: PathBuf) {
+        if cache_limit_bytes(app_handle).is_none() || app_handle.state::<PowerState>().is_paused() {
+            return;
+        }
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let _ = app_handle.state::<HtmlCache>().read(&app_handle, &path);
+        });
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// The app's HTML, served from memory when it was read recently.
+#[tauri::command]
+pub fn get_app_html(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    html_cache: State<'_, HtmlCache>,
+    id: String,
+) -> Result<String, String> {
+    let path = get_openable_html_path(&app_handle, &unlocked, &id)?;
+    html_cache.read(&app_handle, &path).map(|html| html.to_string())
+}
+
+/// Pre-reads the most opened apps' HTML so their first open is fast. Skipped
+/// in low-power mode; the power task runs it again on AC power.
+pub fn start_html_cache_warmup(app_handle: AppHandle) {
+    if cache_limit_bytes(&app_handle).is_none() || app_handle.state::<PowerState>().is_paused() {
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let Ok(index) = load_index(&app_handle) else {
+            return;
+        };
+        let mut apps: Vec<_> = index
+            .apps
+            .into_iter()
+            .filter(|app| app.open_count > 0 && !app.locked)
+            .collect();
+        apps.sort_by_key(|app| Reverse(app.open_count));
+        let cache = app_handle.state::<HtmlCache>();
+        for app in apps.into_iter().take(HTML_CACHE_WARM_APPS) {
+            if let Ok(path) = get_app_html_path(&app_handle, app.id) {
+                let _ = cache.read(&app_handle, &path);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlCacheEntries;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn evicts_least_recently_used_and_stale_entries() {
+        let mut cache = HtmlCacheEntries::default();
+        let modified = SystemTime::UNIX_EPOCH;
+        let (a, b, c) = (PathBuf::from("a"), PathBuf::from("b"), PathBuf::from("c"));
+        cache.insert(a.clone(), Arc::from("aaaa"), modified, 10);
+        cache.insert(b.clone(), Arc::from("bbbb"), modified, 10);
+        assert!(cache.get(&a, modified).is_some());
+
+        cache.insert(c.clone(), Arc::from("cccc"), modified, 10);
+        assert!(cache.get(&b, modified).is_none());
+        assert!(cache.get(&a, modified).is_some());
+        assert_eq!(cache.bytes, 8);
+
+        assert!(cache.get(&c, modified + Duration::from_secs(1)).is_none());
+        assert_eq!(cache.bytes, 4);
+        cache.insert(PathBuf::from("big"), Arc::from("x".repeat(11)), modified, 10);
+        assert!(cache.get(Path::new("big"), modified).is_none());
+    }
+}
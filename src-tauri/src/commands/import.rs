@@ -0,0 +1,222 @@
+use crate::commands::agent::MAX_HTML_BYTES;
+use crate::commands::apps::{get_app_internal, save_app};
+use crate::commands::config::save_app_config;
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::settings::load_settings;
+use crate::commands::storage::{delete_storage_file, save_storage, StorageLocks};
+use crate::commands::versions::{create_restore_point, record_version};
+use crate::models::{
+    normalize_category, normalize_tags, validate_name_prompt, AppBundle, AppConfig, AppMetadata,
+    AppOrigin, ChangeKind, ImportStrategy, APP_BUNDLE_FORMAT_VERSION, APP_NAME_MAX_LENGTH,
+};
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+/// Bundles carry storage data on top of the HTML, so allow some headroom.
+const MAX_BUNDLE_BYTES: u64 = (MAX_HTML_BYTES as u64) * 4;
+/// Room for multi-codepoint emoji such as flags and ZWJ sequences.
+const EMOJI_MAX_CHARS: usize = 16;
+
+pub(crate) fn default_emoji() -> String {
+    "📦".to_string()
+}
+
+pub(crate) fn default_background_color() -> String {
+    "#64748B".to_string()
+}
+
+/// Turns an untrusted name (file stem, link parameter) into a valid app name.
+pub(crate) fn sanitize_import_name(raw: &str) -> String {
+    let name: String = raw
+        .trim()
+        .chars()
+        .filter(|ch| !ch.is_control())
+        .take(APP_NAME_MAX_LENGTH)
+        .collect();
+    if name.trim().is_empty() {
+        "Imported App".to_string()
+    } else {
+        name.trim().to_string()
+    }
+}
+
+/// Keeps an untrusted emoji if it's short and printable, else the default.
+pub(crate) fn sanitize_import_emoji(raw: &str) -> String {
+    let emoji = raw.trim();
+    let valid = !emoji.is_empty()
+        && emoji.chars().count() <= EMOJI_MAX_CHARS
+        && !emoji.chars().any(char::is_control);
+    if valid {
+        emoji.to_string()
+    } else {
+        default_emoji()
+    }
+}
+
+/// Keeps an untrusted `#rgb` or `#rrggbb` color, else the default.
+pub(crate) fn sanitize_import_color(raw: &str) -> String {
+    let color = raw.trim();
+    let valid = color.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    if valid {
+        color.to_string()
+    } else {
+        default_background_color()
+    }
+}
+
+fn name_from_path(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    sanitize_import_name(stem)
+}
+
+/// Saves imported HTML as a new app. Imported HTML is untrusted, so the app
+/// is always quarantined until the user calls `trust_app`. New imports land
+/// as drafts for review; replacing an existing app keeps its draft state.
+pub(crate) fn import_html(
+    app_handle: &AppHandle,
+    mut app: AppMetadata,
+    html: &str,
+    origin: AppOrigin,
+) -> Result<AppMetadata, String> {
+    if html.len() > MAX_HTML_BYTES {
+        return Err("Imported HTML exceeded size limit".to_string());
+    }
+    if html.trim().is_empty() {
+        return Err("Imported file is empty".to_string());
+    }
+    let settings = load_settings(app_handle)?;
+    validate_name_prompt(&app.name, &app.prompt, settings.prompt_max_length)?;
+
+    app.quarantined = true;
+    app.draft = get_app_internal(app_handle, &app.id.to_string())
+        .map(|existing| existing.draft)
+        .unwrap_or(true);
+    app.origin = origin;
+    save_app(app_handle, &mut app, html)?;
+    record_version(app_handle, &app, ChangeKind::Imported, html)?;
+
+    Ok(app)
+}
+
+/// Imports a standalone HTML file as a new, quarantined app.
+#[tauri::command]
+pub fn import_app(app_handle: AppHandle, path: String) -> Result<AppMetadata, String> {
+    let source = Path::new(&path);
+    let metadata =
+        fs::metadata(source).map_err(|e| format!("Failed to inspect import file: {}", e))?;
+    if !metadata.is_file() {
+        return Err("Import path is not a file".to_string());
+    }
+    if metadata.len() as usize > MAX_HTML_BYTES {
+        return Err("Imported HTML exceeded size limit".to_string());
+    }
+
+    let html = fs::read_to_string(source)
+        .map_err(|e| format!("Failed to read import file: {}", e))?;
+
+    let name = name_from_path(source);
+    let prompt = format!("Imported from {}", source.display());
+    let app = AppMetadata::new(name, prompt, default_emoji(), default_background_color());
+    let origin = AppOrigin::Imported {
+        source: source.display().to_string(),
+    };
+    import_html(&app_handle, app, &html, origin)
+}
+
+/// Imports an app bundle. When the bundle's app id already exists locally,
+/// `strategy` decides whether to replace it, keep both under a fresh id, or
+/// skip the import and return the existing app. Replacing a locked app
+/// requires it to be unlocked.
+#[tauri::command]
+pub async fn import_app_bundle(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
+    path: String,
+    strategy: ImportStrategy,
+) -> Result<AppMetadata, String> {
+    let metadata =
+        fs::metadata(&path).map_err(|e| format!("Failed to inspect bundle file: {}", e))?;
+    if metadata.len() > MAX_BUNDLE_BYTES {
+        return Err("App bundle exceeded size limit".to_string());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read bundle file: {}", e))?;
+    let bundle: AppBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse app bundle: {}", e))?;
+    if bundle.format_version != APP_BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported app bundle version: {}",
+            bundle.format_version
+        ));
+    }
+
+    // Validated up front so a bad value can't leave a half-imported app.
+    let config = match bundle.config {
+        Some(values) => {
+            let mut config = AppConfig::default();
+            for (key, value) in values {
+                config.set(key, value)?;
+            }
+            Some(config)
+        }
+        None => None,
+    };
+
+    let mut app = bundle.app;
+    app.name = sanitize_import_name(&app.name);
+    app.emoji = sanitize_import_emoji(&app.emoji);
+    app.background_color = sanitize_import_color(&app.background_color);
+    app.tags = normalize_tags(&app.tags);
+    app.category = app.category.as_deref().and_then(normalize_category);
+    // Pending suggestions would be accepted without normalization.
+    app.suggested_tags = None;
+    // Passwords aren't exported, so the imported copy starts unlocked. A
+    // replaced app keeps its existing lock, and its autostart, which this
+    // machine's login item was registered for.
+    app.locked = false;
+    app.autostart = false;
+    let mut origin = AppOrigin::Imported { source: path };
+
+    if let Ok(existing) = get_app_internal(&app_handle, &app.id.to_string()) {
+        match strategy {
+            ImportStrategy::Skip => return Ok(existing),
+            ImportStrategy::Replace => {
+                ensure_unlocked(&app_handle, &unlocked, existing.id)?;
+                create_restore_point(&app_handle, &existing)?;
+                app.locked = existing.locked;
+                app.autostart = existing.autostart;
+            }
+            ImportStrategy::KeepBoth => {
+                app.id = Uuid::new_v4();
+                app.created_at = Utc::now();
+                origin = AppOrigin::Duplicate {
+                    parent_id: existing.id,
+                };
+            }
+        }
+    }
+    app.updated_at = Utc::now();
+
+    let app = import_html(&app_handle, app, &bundle.html, origin)?;
+    let app_id = app.id.to_string();
+    let _lock = locks.lock(app.id).await;
+    match &bundle.storage {
+        Some(storage) => save_storage(&app_handle, &app_id, storage)?,
+        None => delete_storage_file(&app_handle, &app_id)?,
+    }
+    // Bundles exported without config leave any existing config in place.
+    if let Some(config) = &config {
+        save_app_config(&app_handle, app.id, config)?;
+    }
+
+    Ok(app)
+}
@@ -0,0 +1,101 @@
+use crate::commands::apps::{get_app_html_path, get_app_internal};
+use crate::commands::audit::load_audit_log;
+use crate::commands::permissions::load_permissions;
+use crate::commands::storage::load_storage;
+use crate::commands::versions::{load_changelog, load_restore_points};
+use crate::models::{AppMetadata, AuditEntry, ChangelogEntry};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+const AUDIT_TAIL_ENTRIES: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageKeyInfo {
+    pub key: String,
+    pub value_type: &'static str,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppPermissions {
+    pub quarantined: bool,
+    pub read_folders: Vec<PathBuf>,
+}
+
+/// Everything the developer panel shows about one app, gathered in a
+/// single call.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppInspection {
+    pub app: AppMetadata,
+    pub html_bytes: Option<u64>,
+    pub storage_keys: Vec<StorageKeyInfo>,
+    pub storage_error: Option<String>,
+    pub versions: Vec<ChangelogEntry>,
+    pub restore_points: usize,
+    pub permissions: AppPermissions,
+    pub audit_tail: Vec<AuditEntry>,
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn inspect_storage(app_handle: &AppHandle, id: &str) -> Result<Vec<StorageKeyInfo>, String> {
+    let storage = load_storage(app_handle, id)?;
+    let mut keys: Vec<StorageKeyInfo> = storage
+        .iter()
+        .map(|(key, value)| StorageKeyInfo {
+            key: key.clone(),
+            value_type: value_type(value),
+            bytes: serde_json::to_string(value).map(|s| s.len()).unwrap_or(0),
+        })
+        .collect();
+    keys.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(keys)
+}
+
+#[tauri::command]
+pub fn inspect_app(app_handle: AppHandle, id: String) -> Result<AppInspection, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+
+    let html_bytes = fs::metadata(get_app_html_path(&app_handle, app.id)?)
+        .ok()
+        .map(|meta| meta.len());
+    // A corrupt storage file is exactly what the panel should surface,
+    // so report the error instead of failing the whole inspection.
+    let (storage_keys, storage_error) = match inspect_storage(&app_handle, &id) {
+        Ok(keys) => (keys, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+    let versions = load_changelog(&app_handle, app.id)?.entries;
+    let restore_points = load_restore_points(&app_handle, app.id)?.points.len();
+    let read_folders = load_permissions(&app_handle, &id)?.read_folders;
+
+    let mut audit_tail = load_audit_log(&app_handle, &id)?.entries;
+    let skip = audit_tail.len().saturating_sub(AUDIT_TAIL_ENTRIES);
+    audit_tail.drain(..skip);
+
+    Ok(AppInspection {
+        permissions: AppPermissions {
+            quarantined: app.quarantined,
+            read_folders,
+        },
+        app,
+        html_bytes,
+        storage_keys,
+        storage_error,
+        versions,
+        restore_points,
+        audit_tail,
+    })
+}
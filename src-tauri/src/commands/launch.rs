@@ -0,0 +1,237 @@
+use crate::commands::app_windows::{clear_indicators, AppWindows};
+use crate::commands::apps::{get_app_html_path, get_visible_app, update_index};
+use crate::commands::focus::ensure_not_focus_blocked;
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::settings::load_settings;
+use crate::html::{runtime_version, RUNTIME_MIN_COMPATIBLE_VERSION, RUNTIME_VERSION};
+use crate::models::{AppKind, AppMetadata, OpenMode, OpenSource};
+use crate::utils::parse_uuid;
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder, Window};
+use uuid::Uuid;
+
+#[derive(Clone, serde::Serialize)]
+pub struct AppOpened {
+    pub app: AppMetadata,
+    pub source: OpenSource,
+}
+
+/// Sent when an opened app's runtime is older than the host still supports;
+/// `upgrade_apps_runtime` brings it up to date.
+#[derive(Clone, serde::Serialize)]
+pub struct AppRuntimeIncompatible {
+    pub app_id: Uuid,
+    pub runtime_version: u32,
+    pub current_version: u32,
+}
+
+/// Bumps an app's launch stats and broadcasts `app-opened` to every window,
+/// so surfaces with "recent" lists stay in sync without polling. Also warns
+/// with `app-runtime-incompatible` if the app's runtime is too old.
+pub(crate) fn record_app_opened(
+    app_handle: &AppHandle,
+    id: &str,
+    source: OpenSource,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(id)?;
+    let opened_app = update_index(app_handle, |index| {
+        let Some(app) = index.get_mut(uuid) else {
+            return Ok(None);
+        };
+        app.open_count += 1;
+        app.last_opened_at = Some(Utc::now());
+        if app.runtime_version.is_none() {
+            app.runtime_version = get_app_html_path(app_handle, uuid)
+                .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))
+                .ok()
+                .and_then(|html| runtime_version(&html));
+        }
+        Ok(Some(app.clone()))
+    })?;
+    let opened_app = match opened_app {
+        Some(app) => app,
+        // Gallery apps belong to the main profile, so opening them from
+        // another profile leaves their stats alone.
+        None => get_visible_app(app_handle, uuid)?,
+    };
+
+    if let Some(version) = opened_app.runtime_version {
+        if version < RUNTIME_MIN_COMPATIBLE_VERSION {
+            let _ = app_handle.emit(
+                "app-runtime-incompatible",
+                AppRuntimeIncompatible {
+                    app_id: uuid,
+                    runtime_version: version,
+                    current_version: RUNTIME_VERSION,
+                },
+            );
+        }
+    }
+
+    let _ = app_handle.emit(
+        "app-opened",
+        AppOpened {
+            app: opened_app.clone(),
+            source,
+        },
+    );
+
+    Ok(opened_app)
+}
+
+#[tauri::command]
+pub fn mark_app_opened(
+    app_handle: AppHandle,
+    window: Window,
+    windows: State<'_, AppWindows>,
+    id: String,
+    source: OpenSource,
+) -> Result<AppMetadata, String> {
+    let app = record_app_opened(&app_handle, &id, source)?;
+    if windows.host(app.id, window.label()) {
+        if let Some(window) = app_handle.get_webview_window(window.label()) {
+            clear_indicators(&window);
+        }
+    }
+    Ok(app)
+}
+
+const MAIN_WINDOW_LABEL: &str = "main";
+pub(crate) const APP_WINDOW_LABEL_PREFIX: &str = "app-";
+
+/// Sent to the main window when an app should be shown in its panel.
+#[derive(Clone, serde::Serialize)]
+pub struct OpenAppInPanel {
+    pub app: AppMetadata,
+    pub source: OpenSource,
+}
+
+fn show_in_panel(
+    app_handle: &AppHandle,
+    app: AppMetadata,
+    source: OpenSource,
+) -> Result<(), String> {
+    let window = app_handle
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .ok_or_else(|| "Main window not found".to_string())?;
+    let _ = window.show();
+    let _ = window.set_focus();
+    app_handle
+        .emit_to(MAIN_WINDOW_LABEL, "open-app-in-panel", OpenAppInPanel { app, source })
+        .map_err(|e| format!("Failed to open app: {}", e))
+}
+
+/// Opens the app in its own window, or focuses it if already open. The
+/// window loads the regular frontend, which renders just this app based on
+/// the window label.
+pub(crate) fn open_app_window(app_handle: &AppHandle, app: &AppMetadata) -> Result<(), String> {
+    let label = format!("{}{}", APP_WINDOW_LABEL_PREFIX, app.id);
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        let _ = window.show();
+        return window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus app window: {}", e));
+    }
+
+    let builder =
+        WebviewWindowBuilder::new(app_handle, label, WebviewUrl::App("index.html".into()))
+            .title(&app.name);
+    let builder = match app.app_kind {
+        AppKind::Standard => builder
+            .inner_size(960.0, 720.0)
+            .min_inner_size(360.0, 280.0),
+        // Widgets are sized like their generated layout and stay out of the
+        // way: no title bar, and above other windows.
+        AppKind::Widget => builder
+            .inner_size(360.0, 280.0)
+            .min_inner_size(240.0, 160.0)
+            .decorations(false)
+            .always_on_top(true),
+        AppKind::Fullscreen => builder
+            .inner_size(960.0, 720.0)
+            .min_inner_size(360.0, 280.0)
+            .fullscreen(true),
+    };
+    builder
+        .build()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open app window: {}", e))
+}
+
+/// Closes every app window, e.g. before switching profiles, so no app keeps
+/// running against the wrong profile's storage.
+pub(crate) fn close_app_windows(app_handle: &AppHandle) {
+    for (label, window) in app_handle.webview_windows() {
+        if label.starts_with(APP_WINDOW_LABEL_PREFIX) {
+            let _ = window.close();
+        }
+    }
+}
+
+fn open_in_browser(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open browser: {}", e))
+}
+
+/// Opens an app wherever the user wants it: the app's own override first,
+/// then the default from settings. Panel and window opens are recorded by
+/// the frontend once the app renders; browser opens are recorded here since
+/// nothing else sees them.
+#[tauri::command]
+pub fn open_app(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+    source: Option<OpenSource>,
+) -> Result<OpenMode, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&id)?)?;
+    ensure_not_focus_blocked(&app_handle, app.id)?;
+    ensure_unlocked(&app_handle, &unlocked, app.id)?;
+    let settings = load_settings(&app_handle)?;
+    let mode = app.open_mode.unwrap_or(settings.default_open_mode);
+    let source = source.unwrap_or(OpenSource::Grid);
+
+    match mode {
+        OpenMode::Panel => show_in_panel(&app_handle, app, source)?,
+        OpenMode::Window => open_app_window(&app_handle, &app)?,
+        OpenMode::Browser => {
+            open_in_browser(&get_app_html_path(&app_handle, app.id)?)?;
+            record_app_opened(&app_handle, &id, source)?;
+        }
+    }
+    Ok(mode)
+}
+
+/// Sets or clears (with `None`) an app's open mode override.
+#[tauri::command]
+pub fn set_app_open_mode(
+    app_handle: AppHandle,
+    id: String,
+    mode: Option<OpenMode>,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.open_mode = mode;
+        Ok(app.clone())
+    })
+}
@@ -0,0 +1,98 @@
+use crate::commands::apps::load_index;
+use crate::models::AppMetadata;
+use crate::utils::parse_uuid;
+use std::collections::HashSet;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+/// The family of an app: the chain of apps it was remixed or duplicated
+/// from (nearest first) and the apps derived directly from it.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AppLineage {
+    pub app: AppMetadata,
+    pub ancestors: Vec<AppMetadata>,
+    pub children: Vec<AppMetadata>,
+}
+
+fn build_lineage(apps: &[AppMetadata], id: Uuid) -> Option<AppLineage> {
+    let find = |id: Uuid| apps.iter().find(|app| app.id == id);
+    let app = find(id)?.clone();
+
+    let mut ancestors = Vec::new();
+    let mut seen = HashSet::from([app.id]);
+    let mut next = app.origin.parent_id();
+    while let Some(parent_id) = next {
+        // Parents may have been deleted; the chain simply ends there.
+        let Some(parent) = find(parent_id) else { break };
+        if !seen.insert(parent.id) {
+            break;
+        }
+        ancestors.push(parent.clone());
+        next = parent.origin.parent_id();
+    }
+
+    let children = apps
+        .iter()
+        .filter(|candidate| candidate.origin.parent_id() == Some(app.id))
+        .cloned()
+        .collect();
+
+    Some(AppLineage {
+        app,
+        ancestors,
+        children,
+    })
+}
+
+#[tauri::command]
+pub fn get_app_lineage(app_handle: AppHandle, id: String) -> Result<AppLineage, String> {
+    let uuid = parse_uuid(&id)?;
+    let index = load_index(&app_handle)?;
+    build_lineage(&index.apps, uuid).ok_or_else(|| format!("App not found: {}", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_lineage;
+    use crate::models::{AppMetadata, AppOrigin};
+
+    fn make_app(name: &str, origin: AppOrigin) -> AppMetadata {
+        let mut app = AppMetadata::new(
+            name.to_string(),
+            "prompt".to_string(),
+            "✨".to_string(),
+            "#000000".to_string(),
+        );
+        app.origin = origin;
+        app
+    }
+
+    #[test]
+    fn collects_ancestors_and_children() {
+        let root = make_app("root", AppOrigin::Generated);
+        let remix = make_app("remix", AppOrigin::Remix { parent_id: root.id });
+        let copy = make_app("copy", AppOrigin::Duplicate { parent_id: remix.id });
+        let (remix_id, copy_id, root_id) = (remix.id, copy.id, root.id);
+        let apps = vec![root, remix, copy];
+
+        let lineage = build_lineage(&apps, remix_id).expect("app exists");
+
+        assert_eq!(lineage.ancestors.len(), 1);
+        assert_eq!(lineage.ancestors[0].id, root_id);
+        assert_eq!(lineage.children.len(), 1);
+        assert_eq!(lineage.children[0].id, copy_id);
+    }
+
+    #[test]
+    fn stops_at_cycles() {
+        let mut a = make_app("a", AppOrigin::Generated);
+        let b = make_app("b", AppOrigin::Remix { parent_id: a.id });
+        a.origin = AppOrigin::Remix { parent_id: b.id };
+        let a_id = a.id;
+        let apps = vec![a, b];
+
+        let lineage = build_lineage(&apps, a_id).expect("app exists");
+
+        assert_eq!(lineage.ancestors.len(), 1);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::commands::apps::{app_file_in, get_app_home_dir, get_app_internal, update_index};
+use crate::commands::settings::load_settings;
+use crate::models::{AppLock, AppMetadata};
+use crate::utils::{parse_uuid, write_atomic};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::time::{sleep, Duration};
+use uuid::Uuid;
+
+pub const APP_LOCKED_ERROR: &str = "App is locked";
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Clone, serde::Serialize)]
+pub struct AppLocked {
+    pub app_id: Uuid,
+}
+
+/// Locked apps unlocked during this session, with when each was last used.
+/// Never persisted, so every app is locked again after a restart.
+#[derive(Default)]
+pub struct UnlockedApps {
+    apps: Mutex<HashMap<Uuid, Instant>>,
+}
+
+impl UnlockedApps {
+    /// Whether the app is unlocked; counts as activity if it is.
+    fn touch(&self, id: Uuid) -> bool {
+        let Ok(mut apps) = self.apps.lock() else {
+            return false;
+        };
+        match apps.get_mut(&id) {
+            Some(last_active) => {
+                *last_active = Instant::now();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the app is unlocked, without counting as activity.
+    pub(crate) fn contains(&self, id: Uuid) -> bool {
+        self.apps.lock().is_ok_and(|apps| apps.contains_key(&id))
+    }
+
+    fn insert(&self, id: Uuid) {
+        if let Ok(mut apps) = self.apps.lock() {
+            apps.insert(id, Instant::now());
+        }
+    }
+
+    /// Removes and returns apps idle for at least `idle`.
+    fn expire_idle(&self, idle: Duration) -> Vec<Uuid> {
+        let Ok(mut apps) = self.apps.lock() else {
+            return Vec::new();
+        };
+        let expired: Vec<Uuid> = apps
+            .iter()
+            .filter(|(_, last_active)| last_active.elapsed() >= idle)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &expired {
+            apps.remove(id);
+        }
+        expired
+    }
+
+    fn remove(&self, id: Uuid) {
+        if let Ok(mut apps) = self.apps.lock() {
+            apps.remove(&id);
+        }
+    }
+}
+
+pub(crate) fn get_app_lock_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    Ok(app_file_in(&get_app_home_dir(app_handle, id)?, id, "lock.json"))
+}
+
+fn load_app_lock(app_handle: &AppHandle, id: Uuid) -> Result<Option<AppLock>, String> {
+    let path = get_app_lock_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read app lock: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse app lock: {}", e))
+}
+
+/// Fails with `APP_LOCKED_ERROR` unless the app has no password or was
+/// unlocked this session. The lock file, not the `locked` flag, decides, so
+/// a stale flag restored by undo or import can't bypass it.
+pub(crate) fn ensure_unlocked(
+    app_handle: &AppHandle,
+    unlocked: &UnlockedApps,
+    id: Uuid,
+) -> Result<(), String> {
+    check_unlocked(unlocked, id, get_app_lock_path(app_handle, id)?.exists())
+}
+
+fn check_unlocked(unlocked: &UnlockedApps, id: Uuid, has_password: bool) -> Result<(), String> {
+    if unlocked.touch(id) || !has_password {
+        return Ok(());
+    }
+    Err(APP_LOCKED_ERROR.to_string())
+}
+
+fn set_locked_flag(
+    app_handle: &AppHandle,
+    id: Uuid,
+    locked: bool,
+) -> Result<AppMetadata, String> {
+    update_index(app_handle, |index| {
+        let app = index
+            .get_mut(id)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.locked = locked;
+        Ok(app.clone())
+    })
+}
+
+/// Sets or changes an app's password. Changing it requires the app to be
+/// unlocked first.
+#[tauri::command]
+pub fn set_app_password(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+    password: String,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    // Gallery apps resolve to the main profile's lock, which only the main
+    // profile may change.
+    get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+
+    let lock = AppLock::new(&password)?;
+    let content = serde_json::to_string_pretty(&lock)
+        .map_err(|e| format!("Failed to serialize app lock: {}", e))?;
+    write_atomic(&get_app_lock_path(&app_handle, uuid)?, &content)?;
+
+    // The user just proved they know the password.
+    unlocked.insert(uuid);
+    set_locked_flag(&app_handle, uuid, true)
+}
+
+#[tauri::command]
+pub fn remove_app_password(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+
+    let path = get_app_lock_path(&app_handle, uuid)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete app lock: {}", e))?;
+    }
+    unlocked.remove(uuid);
+    set_locked_flag(&app_handle, uuid, false)
+}
+
+/// Unlocks a password-protected app until it sits idle past the configured
+/// timeout, or until Trove quits.
+#[tauri::command]
+pub fn unlock_app(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+    password: String,
+) -> Result<(), String> {
+    let uuid = parse_uuid(&id)?;
+    let Some(lock) = load_app_lock(&app_handle, uuid)? else {
+        return Ok(());
+    };
+    if !lock.verify(&password) {
+        return Err("Incorrect password".to_string());
+    }
+    unlocked.insert(uuid);
+    Ok(())
+}
+
+/// Keeps an unlocked app's session alive. The frontend calls this while the
+/// app is in use, e.g. when it talks to the bridge.
+#[tauri::command]
+pub fn record_app_activity(unlocked: State<'_, UnlockedApps>, id: String) -> Result<(), String> {
+    unlocked.touch(parse_uuid(&id)?);
+    Ok(())
+}
+
+/// Locks idle apps again per `app_lock_idle_minutes`, emitting `app-locked`
+/// so windows showing them can hide their content.
+pub fn start_auto_lock_task(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sleep(AUTO_LOCK_CHECK_INTERVAL).await;
+            let idle_minutes = match load_settings(&app_handle) {
+                Ok(settings) => settings.app_lock_idle_minutes,
+                Err(err) => {
+                    eprintln!("Failed to load settings for auto-lock: {}", err);
+                    continue;
+                }
+            };
+            let Some(idle_minutes) = idle_minutes else {
+                continue;
+            };
+            let idle = Duration::from_secs(u64::from(idle_minutes) * 60);
+            for app_id in app_handle.state::<UnlockedApps>().expire_idle(idle) {
+                let _ = app_handle.emit("app-locked", AppLocked { app_id });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_unlocked, UnlockedApps, APP_LOCKED_ERROR};
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[test]
+    fn expires_only_idle_sessions() {
+        let unlocked = UnlockedApps::default();
+        let id = Uuid::new_v4();
+        unlocked.insert(id);
+
+        assert!(unlocked.expire_idle(Duration::from_secs(60)).is_empty());
+        assert!(unlocked.touch(id));
+        assert_eq!(unlocked.expire_idle(Duration::ZERO), vec![id]);
+        assert!(!unlocked.touch(id));
+    }
+
+    #[test]
+    fn refuses_locked_apps_until_unlocked() {
+        let unlocked = UnlockedApps::default();
+        let id = Uuid::new_v4();
+        assert_eq!(check_unlocked(&unlocked, id, false), Ok(()));
+        assert_eq!(
+            check_unlocked(&unlocked, id, true),
+            Err(APP_LOCKED_ERROR.to_string())
+        );
+
+        unlocked.insert(id);
+        assert_eq!(check_unlocked(&unlocked, id, true), Ok(()));
+        unlocked.remove(id);
+        assert!(check_unlocked(&unlocked, id, true).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use crate::commands::apps::{
+    get_app_data_dir, get_apps_dir_path, load_gallery_apps, load_index, APP_HTML_FILE,
+    INDEX_FILE_NAME, STAGING_DIR_NAME,
+};
+use crate::commands::cleanup::{run_cleanup, CleanupReport};
+use crate::commands::power::wait_until_resumed;
+use crate::commands::search::prune_search_index;
+use crate::commands::storage::{migrate_storage_format, StorageLocks};
+use crate::commands::versions::{
+    get_blob_path, get_snapshot_store_path, get_versions_dir, load_changelog,
+    load_restore_points, load_snapshot_store, save_snapshot_store,
+};
+use crate::utils::path_size;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager};
+use tokio::time::{sleep, Duration};
+use uuid::Uuid;
+
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Leftovers younger than this may belong to a write or generation still in
+/// progress, so they are left for the next run.
+const STALE_AFTER: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct MaintenanceReport {
+    pub cleanup: CleanupReport,
+    pub temp_files_removed: usize,
+    pub orphans_removed: usize,
+    pub snapshots_removed: usize,
+    pub search_entries_removed: usize,
+    /// Storage files rewritten in their app's chosen format.
+    pub storage_files_converted: usize,
+    /// Bytes freed by this pass, not counting `cleanup.bytes_reclaimed`.
+    pub bytes_reclaimed: u64,
+}
+
+fn is_stale(path: &Path, now: SystemTime) -> bool {
+    fs::symlink_metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .is_some_and(|age| age >= STALE_AFTER)
+}
+
+fn remove_path(path: &Path) -> Result<u64, String> {
+    let size = path_size(path);
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+    Ok(size)
+}
+
+/// The app an apps-directory entry belongs to: its `<uuid>` directory, or
+/// a flat-layout leftover such as `<uuid>.data.json` or `<uuid>.versions`.
+fn owning_app_id(file_name: &str) -> Option<Uuid> {
+    if let Ok(id) = Uuid::parse_str(file_name) {
+        return Some(id);
+    }
+    let (id, rest) = file_name.split_once('.')?;
+    if rest.is_empty() {
+        return None;
+    }
+    Uuid::parse_str(id).ok()
+}
+
+/// Removes stale `.tmp` files left by interrupted `write_atomic` calls.
+/// Asset directories are skipped, since apps may ship files that happen to
+/// end in `.tmp`.
+fn remove_temp_files(
+    dir: &Path,
+    now: SystemTime,
+    report: &mut MaintenanceReport,
+) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            let is_assets = name == "assets" || name.ends_with(".assets");
+            if !is_assets && name != STAGING_DIR_NAME {
+                remove_temp_files(&path, now, report)?;
+            }
+        } else if name.ends_with(".tmp") && is_stale(&path, now) {
+            report.bytes_reclaimed += remove_path(&path)?;
+            report.temp_files_removed += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Removes staged saves that never got swapped into place.
+fn remove_stale_staging(
+    apps_dir: &Path,
+    now: SystemTime,
+    report: &mut MaintenanceReport,
+) -> Result<(), String> {
+    let Ok(entries) = fs::read_dir(apps_dir.join(STAGING_DIR_NAME)) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_stale(&path, now) {
+            report.bytes_reclaimed += remove_path(&path)?;
+            report.temp_files_removed += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Whether a flat-layout leftover is the app itself, its HTML or assets,
+/// rather than storage or something derived from it. Those are never
+/// deleted, since an app missing from the index can still be recovered.
+fn is_app_content(file_name: &str) -> bool {
+    file_name.ends_with(".html") || file_name.ends_with(".assets")
+}
+
+/// Removes per-app files, such as storage or logs, whose app is no longer in
+/// the index. Only called when the index exists, since a missing index
+/// loads as empty and would make every app look orphaned.
+fn remove_orphans(
+    apps_dir: &Path,
+    live: &HashSet<Uuid>,
+    now: SystemTime,
+    report: &mut MaintenanceReport,
+) -> Result<(), String> {
+    let entries =
+        fs::read_dir(apps_dir).map_err(|e| format!("Failed to read apps directory: {}", e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(id) = owning_app_id(&name) else {
+            continue;
+        };
+        let path = entry.path();
+        // An app directory that still has its HTML is an app missing from
+        // the index, not an orphan.
+        if is_app_content(&name) || path.join(APP_HTML_FILE).exists() {
+            continue;
+        }
+        if !live.contains(&id) && is_stale(&path, now) {
+            report.bytes_reclaimed += remove_path(&path)?;
+            report.orphans_removed += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes snapshots in an app's versions folder that neither its changelog
+/// nor its restore points refer to, e.g. ones whose entry was pruned but
+/// whose file survived a crash, along with blobs nothing points at.
+fn compact_versions(
+    app_handle: &AppHandle,
+    id: Uuid,
+    now: SystemTime,
+    report: &mut MaintenanceReport,
+) -> Result<(), String> {
+    let versions_dir = get_versions_dir(app_handle, id)?;
+    let Ok(entries) = fs::read_dir(&versions_dir) else {
+        return Ok(());
+    };
+
+    let mut referenced: HashSet<Uuid> = load_changelog(app_handle, id)?
+        .entries
+        .iter()
+        .filter(|entry| !entry.pruned)
+        .map(|entry| entry.version_id)
+        .collect();
+    referenced.extend(load_restore_points(app_handle, id)?.points.iter().map(|point| point.id));
+
+    // Snapshots from before content addressing, one file per id.
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(snapshot_id) = name
+            .strip_suffix(".html")
+            .and_then(|stem| Uuid::parse_str(stem).ok())
+        else {
+            continue;
+        };
+        if !referenced.contains(&snapshot_id) && is_stale(&path, now) {
+            report.bytes_reclaimed += remove_path(&path)?;
+            report.snapshots_removed += 1;
+        }
+    }
+
+    let mut store = load_snapshot_store(app_handle, id)?;
+    let unreferenced: Vec<Uuid> = store
+        .snapshots
+        .keys()
+        .filter(|snapshot_id| !referenced.contains(snapshot_id))
+        .copied()
+        .collect();
+    for snapshot_id in &unreferenced {
+        // A snapshot is written just before its changelog entry, so a fresh
+        // blob may belong to a save that is still in progress.
+        let Some(key) = store.key_for(*snapshot_id) else {
+            continue;
+        };
+        if !is_stale(&get_blob_path(app_handle, id, key)?, now) {
+            continue;
+        }
+        if let Some(key) = store.release(*snapshot_id) {
+            let blob_path = get_blob_path(app_handle, id, &key)?;
+            if blob_path.exists() {
+                report.bytes_reclaimed += remove_path(&blob_path)?;
+            }
+        }
+        report.snapshots_removed += 1;
+    }
+    if !unreferenced.is_empty() {
+        save_snapshot_store(app_handle, id, &store)?;
+    }
+
+    let blobs_dir = versions_dir.join("blobs");
+    if let Ok(blobs) = fs::read_dir(&blobs_dir) {
+        for blob in blobs.flatten() {
+            let path = blob.path();
+            let name = blob.file_name().to_string_lossy().to_string();
+            let known = name
+                .strip_suffix(".html")
+                .is_some_and(|key| store.blobs.contains_key(key));
+            if !known && !name.ends_with(".tmp") && is_stale(&path, now) {
+                report.bytes_reclaimed += remove_path(&path)?;
+            }
+        }
+    }
+
+    if store.snapshots.is_empty() {
+        let _ = fs::remove_dir(&blobs_dir);
+        if !blobs_dir.exists() {
+            let _ = fs::remove_file(get_snapshot_store_path(app_handle, id)?);
+        }
+    }
+    let is_empty = fs::read_dir(&versions_dir).is_ok_and(|mut entries| entries.next().is_none());
+    if is_empty {
+        let _ = fs::remove_dir(&versions_dir);
+    }
+    Ok(())
+}
+
+/// Runs retention cleanup, clears what interrupted operations and deleted
+/// apps left behind, and converts storage to each app's chosen format.
+pub fn run_maintenance_pass(app_handle: &AppHandle) -> Result<MaintenanceReport, String> {
+    let mut report = MaintenanceReport {
+        cleanup: run_cleanup(app_handle)?,
+        ..MaintenanceReport::default()
+    };
+    let now = SystemTime::now();
+    let apps_dir = get_apps_dir_path(app_handle)?;
+    let index = load_index(app_handle)?;
+    let live: HashSet<Uuid> = index.apps.iter().map(|app| app.id).collect();
+
+    remove_temp_files(&get_app_data_dir(app_handle)?, now, &mut report)?;
+    remove_stale_staging(&apps_dir, now, &mut report)?;
+    // Gallery apps keep their storage in this profile's apps directory too.
+    let mut owned = live.clone();
+    owned.extend(load_gallery_apps(app_handle)?.iter().map(|app| app.id));
+    if apps_dir.join(INDEX_FILE_NAME).exists() {
+        remove_orphans(&apps_dir, &owned, now, &mut report)?;
+    }
+    for id in &live {
+        compact_versions(app_handle, *id, now, &mut report)?;
+    }
+    report.search_entries_removed = prune_search_index(app_handle, &live)?;
+
+    // Restores and imports write storage as JSON; bring those apps back to
+    // the format they asked for. Apps busy with a write wait for next time.
+    let locks = app_handle.state::<StorageLocks>();
+    for app in &index.apps {
+        let Some(_lock) = locks.try_lock(app.id) else {
+            continue;
+        };
+        let app_id = app.id.to_string();
+        if migrate_storage_format(app_handle, &app_id, app.storage_format)? {
+            report.storage_files_converted += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn run_maintenance(app_handle: AppHandle) -> Result<MaintenanceReport, String> {
+    run_maintenance_pass(&app_handle)
+}
+
+/// Runs maintenance periodically for the lifetime of the app. A pass that
+/// falls due in low-power mode waits for AC power.
+pub fn start_maintenance_task(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sleep(MAINTENANCE_INTERVAL).await;
+            wait_until_resumed(&app_handle).await;
+            if let Err(err) = run_maintenance_pass(&app_handle) {
+                eprintln!("Periodic maintenance failed: {}", err);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_app_content, owning_app_id};
+    use uuid::Uuid;
+
+    #[test]
+    fn recognizes_per_app_entries_only() {
+        let id = Uuid::new_v4();
+        assert_eq!(owning_app_id(&format!("{}.data.json", id)), Some(id));
+        assert_eq!(owning_app_id(&format!("{}.versions", id)), Some(id));
+        assert_eq!(owning_app_id(&id.to_string()), Some(id));
+        assert_eq!(owning_app_id(&format!("{}.", id)), None);
+        assert_eq!(owning_app_id("index.json"), None);
+        assert_eq!(owning_app_id(".staging"), None);
+    }
+
+    #[test]
+    fn keeps_app_html_and_assets() {
+        let id = Uuid::new_v4();
+        assert!(is_app_content(&format!("{}.html", id)));
+        assert!(is_app_content(&format!("{}.assets", id)));
+        assert!(!is_app_content(&format!("{}.data.json", id)));
+        assert!(!is_app_content(&format!("{}.versions", id)));
+    }
+}
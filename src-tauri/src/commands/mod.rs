@@ -1,7 +1,98 @@
 mod apps;
+mod app_errors;
+mod app_windows;
 mod agent;
+mod artifacts;
+mod audit;
+mod autostart;
+mod cleanup;
+mod clipboard;
+mod config;
+mod confirm;
+mod digest;
+mod disk;
+mod download;
+mod drafts;
+mod edits;
+mod export;
+mod focus;
+mod generations;
+mod health;
+mod history;
+mod html_cache;
+mod import;
+mod inspect;
+mod launch;
+mod lineage;
+mod lock;
+mod maintenance;
+mod naming;
+mod permissions;
+mod power;
+mod profiles;
+mod providers;
+mod qr;
+mod runtime;
+mod screenshot;
+mod search;
+mod settings;
+mod share;
+mod shortcuts;
+mod sounds;
 mod storage;
+mod tags;
+mod templates;
+mod theme;
+mod timeline;
+mod timers;
+mod variants;
+mod versions;
 
 pub use apps::*;
+pub use app_errors::*;
+pub use app_windows::*;
 pub use agent::*;
+pub use audit::*;
+pub use autostart::*;
+pub use cleanup::*;
+pub use clipboard::*;
+pub use config::*;
+pub use confirm::*;
+pub use digest::*;
+pub use disk::*;
+pub use download::*;
+pub use drafts::*;
+pub use edits::*;
+pub use export::*;
+pub use focus::*;
+pub use generations::*;
+pub use health::*;
+pub use history::*;
+pub use html_cache::*;
+pub use import::*;
+pub use inspect::*;
+pub use launch::*;
+pub use lineage::*;
+pub use lock::*;
+pub use maintenance::*;
+pub use naming::*;
+pub use permissions::*;
+pub use power::*;
+pub use profiles::*;
+pub use providers::*;
+pub use qr::*;
+pub use runtime::*;
+pub use screenshot::*;
+pub use search::*;
+pub use settings::*;
+pub use share::*;
+pub use shortcuts::*;
+pub use sounds::*;
 pub use storage::*;
+pub use tags::*;
+pub use templates::*;
+pub use theme::*;
+pub use timeline::*;
+pub use timers::*;
+pub use variants::*;
+pub use versions::*;
@@ -0,0 +1,46 @@
+use crate::commands::settings::load_settings;
+use crate::commands::tags::run_single_turn_sidecar;
+use crate::models::{default_emoji, emoji_for_prompt, validate_prompt, NameSuggestion};
+use tauri::AppHandle;
+
+/// Runs the sidecar in name mode, which prints one `NAME:` line with JSON
+/// name and emoji suggestions for the prompt.
+pub(crate) async fn run_namer(
+    app_handle: &AppHandle,
+    prompt: &str,
+) -> Result<Vec<NameSuggestion>, String> {
+    let args = vec!["--suggest-name".to_string(), prompt.to_string()];
+    let json = run_single_turn_sidecar(app_handle, args, "NAME:", "Name suggestion").await?;
+    NameSuggestion::parse_list(&json)
+}
+
+/// Suggests a few names with emoji for a prompt, from a quick model turn.
+#[tauri::command]
+pub async fn suggest_app_name(
+    app_handle: AppHandle,
+    prompt: String,
+) -> Result<Vec<NameSuggestion>, String> {
+    let settings = load_settings(&app_handle)?;
+    let prompt = prompt.trim();
+    validate_prompt(prompt, settings.prompt_max_length)?;
+    run_namer(&app_handle, prompt).await
+}
+
+/// Picks an icon for a prompt from common app themes, asking the agent
+/// only when no keyword matches. Falls back to the default icon.
+#[tauri::command]
+pub async fn suggest_emoji(app_handle: AppHandle, prompt: String) -> Result<String, String> {
+    let settings = load_settings(&app_handle)?;
+    let prompt = prompt.trim();
+    validate_prompt(prompt, settings.prompt_max_length)?;
+    if let Some(emoji) = emoji_for_prompt(prompt) {
+        return Ok(emoji.to_string());
+    }
+    match run_namer(&app_handle, prompt).await {
+        Ok(suggestions) => Ok(suggestions[0].emoji.clone()),
+        Err(err) => {
+            eprintln!("Failed to suggest an emoji: {}", err);
+            Ok(default_emoji())
+        }
+    }
+}
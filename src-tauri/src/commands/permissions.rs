@@ -0,0 +1,287 @@
+use crate::commands::apps::{
+    ensure_app_trusted, get_app_file_path, get_app_internal, update_index,
+};
+use crate::commands::audit::record_bridge_call;
+use crate::models::{validate_name, AppMetadata, PermissionManifest};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::oneshot;
+
+/// Largest file an app can read through the bridge.
+const APP_READ_FILE_MAX_BYTES: u64 = 5 * 1024 * 1024;
+
+pub(crate) fn get_permissions_path(
+    app_handle: &AppHandle,
+    app_id: &str,
+) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, parse_uuid(app_id)?, "permissions.json")
+}
+
+pub(crate) fn load_permissions(
+    app_handle: &AppHandle,
+    app_id: &str,
+) -> Result<PermissionManifest, String> {
+    let path = get_permissions_path(app_handle, app_id)?;
+    if !path.exists() {
+        return Ok(PermissionManifest::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read permissions: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse permissions: {}", e))
+}
+
+pub(crate) fn save_permissions(
+    app_handle: &AppHandle,
+    app_id: &str,
+    manifest: &PermissionManifest,
+) -> Result<(), String> {
+    let path = get_permissions_path(app_handle, app_id)?;
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize permissions: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+fn canonical_folder(path: &str) -> Result<PathBuf, String> {
+    let folder = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve folder: {}", e))?;
+    if !folder.is_dir() {
+        return Err(format!("Not a folder: {}", path));
+    }
+    Ok(folder)
+}
+
+#[tauri::command]
+pub fn get_app_permissions(
+    app_handle: AppHandle,
+    app_id: String,
+) -> Result<PermissionManifest, String> {
+    get_app_internal(&app_handle, &app_id)?;
+    load_permissions(&app_handle, &app_id)
+}
+
+/// Grants an app read access to a folder. Called by the host UI after the
+/// user picks the folder, never from the app bridge itself.
+#[tauri::command]
+pub fn grant_app_folder(
+    app_handle: AppHandle,
+    app_id: String,
+    path: String,
+) -> Result<PermissionManifest, String> {
+    get_app_internal(&app_handle, &app_id)?;
+    let folder = canonical_folder(&path)?;
+
+    let mut manifest = load_permissions(&app_handle, &app_id)?;
+    manifest.grant_read_folder(folder);
+    save_permissions(&app_handle, &app_id, &manifest)?;
+    Ok(manifest)
+}
+
+#[tauri::command]
+pub fn revoke_app_folder(
+    app_handle: AppHandle,
+    app_id: String,
+    path: String,
+) -> Result<PermissionManifest, String> {
+    get_app_internal(&app_handle, &app_id)?;
+
+    let mut manifest = load_permissions(&app_handle, &app_id)?;
+    if !manifest.revoke_read_folder(Path::new(&path)) {
+        return Err(format!("Folder not granted: {}", path));
+    }
+    save_permissions(&app_handle, &app_id, &manifest)?;
+    Ok(manifest)
+}
+
+#[tauri::command]
+pub fn revoke_app_download_domain(
+    app_handle: AppHandle,
+    app_id: String,
+    domain: String,
+) -> Result<PermissionManifest, String> {
+    get_app_internal(&app_handle, &app_id)?;
+
+    let mut manifest = load_permissions(&app_handle, &app_id)?;
+    let before = manifest.download_domains.len();
+    manifest
+        .download_domains
+        .retain(|allowed| !allowed.eq_ignore_ascii_case(&domain));
+    if manifest.download_domains.len() == before {
+        return Err(format!("Domain not allowed: {}", domain));
+    }
+    save_permissions(&app_handle, &app_id, &manifest)?;
+    Ok(manifest)
+}
+
+#[tauri::command]
+pub fn revoke_app_rename(
+    app_handle: AppHandle,
+    app_id: String,
+) -> Result<PermissionManifest, String> {
+    get_app_internal(&app_handle, &app_id)?;
+
+    let mut manifest = load_permissions(&app_handle, &app_id)?;
+    manifest.can_rename = false;
+    save_permissions(&app_handle, &app_id, &manifest)?;
+    Ok(manifest)
+}
+
+fn read_granted_file(app_handle: &AppHandle, app_id: &str, path: &str) -> Result<String, String> {
+    // Grants survive a replacing import, which quarantines the new HTML.
+    ensure_app_trusted(app_handle, app_id)?;
+    read_file_in_grants(&load_permissions(app_handle, app_id)?, path)
+}
+
+fn read_file_in_grants(manifest: &PermissionManifest, path: &str) -> Result<String, String> {
+    // Canonicalizing resolves symlinks and `..`, so a path can't point out
+    // of a granted folder while appearing to be inside it.
+    let file = Path::new(path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve file: {}", e))?;
+    if !manifest.can_read(&file) {
+        return Err("File is outside the folders granted to this app".to_string());
+    }
+
+    let metadata = fs::metadata(&file).map_err(|e| format!("Failed to read file: {}", e))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", path));
+    }
+    if metadata.len() > APP_READ_FILE_MAX_BYTES {
+        return Err("File exceeds the size limit".to_string());
+    }
+
+    fs::read_to_string(&file).map_err(|e| format!("Failed to read file: {}", e))
+}
+
+/// Bridge command: reads a UTF-8 text file from a folder the user granted
+/// to this app. Quarantined apps can't read files until trusted.
+#[tauri::command]
+pub fn app_read_file(
+    app_handle: AppHandle,
+    app_id: String,
+    path: String,
+) -> Result<String, String> {
+    let result = read_granted_file(&app_handle, &app_id, &path);
+    record_bridge_call(&app_handle, &app_id, "read_file", Some(&path), result.is_ok());
+    result
+}
+
+/// An app renamed itself through the bridge.
+#[derive(Clone, serde::Serialize)]
+pub struct AppRenamed {
+    pub app: AppMetadata,
+}
+
+/// Asks the user, the first time an app tries to rename itself, whether it
+/// may. Once allowed, later renames go through without asking.
+async fn authorize_rename(
+    app_handle: &AppHandle,
+    app: &AppMetadata,
+    new_name: &str,
+) -> Result<(), String> {
+    let app_id = app.id.to_string();
+    let mut manifest = load_permissions(app_handle, &app_id)?;
+    if manifest.can_rename {
+        return Ok(());
+    }
+
+    let (tx, rx) = oneshot::channel();
+    app_handle
+        .dialog()
+        .message(format!(
+            "Allow \"{}\" to rename itself to \"{}\"? It can then rename itself again \
+             without asking.",
+            app.name, new_name
+        ))
+        .title("Allow renaming")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Allow".to_string(),
+            "Deny".to_string(),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+    if !rx.await.unwrap_or(false) {
+        return Err("Renaming is not allowed for this app".to_string());
+    }
+
+    manifest.can_rename = true;
+    save_permissions(app_handle, &app_id, &manifest)
+}
+
+async fn rename_for_app(
+    app_handle: &AppHandle,
+    app_id: &str,
+    new_name: &str,
+) -> Result<AppMetadata, String> {
+    let app = get_app_internal(app_handle, app_id)?;
+    ensure_app_trusted(app_handle, app_id)?;
+    let name = new_name.trim();
+    validate_name(name)?;
+    if name == app.name {
+        return Ok(app);
+    }
+    authorize_rename(app_handle, &app, name).await?;
+
+    let updated_app = update_index(app_handle, |index| {
+        let app = index
+            .get_mut(app.id)
+            .ok_or_else(|| format!("App not found: {}", app_id))?;
+        app.name = name.to_string();
+        app.updated_at = Utc::now();
+        Ok(app.clone())
+    })?;
+    crate::commands::search::index_app(app_handle, &updated_app, None);
+    let _ = app_handle.emit("app-renamed", AppRenamed { app: updated_app.clone() });
+
+    Ok(updated_app)
+}
+
+/// Bridge command: renames the app, e.g. after the user names the project
+/// a tracker follows. Asks the user the first time.
+#[tauri::command]
+pub async fn request_rename(
+    app_handle: AppHandle,
+    app_id: String,
+    new_name: String,
+) -> Result<AppMetadata, String> {
+    let result = rename_for_app(&app_handle, &app_id, &new_name).await;
+    record_bridge_call(&app_handle, &app_id, "rename", Some(&new_name), result.is_ok());
+    result
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::read_file_in_grants;
+    use crate::models::PermissionManifest;
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn refuses_symlinks_out_of_granted_folders() {
+        let root = std::env::temp_dir().join(format!("trove-grants-{}", Uuid::new_v4()));
+        let granted = root.join("granted");
+        fs::create_dir_all(&granted).unwrap();
+        fs::write(granted.join("notes.txt"), "mine").unwrap();
+        fs::write(root.join("secret.txt"), "not yours").unwrap();
+        std::os::unix::fs::symlink(root.join("secret.txt"), granted.join("link.txt")).unwrap();
+
+        let mut manifest = PermissionManifest::default();
+        manifest.grant_read_folder(granted.canonicalize().unwrap());
+        let read =
+            |name: &str| read_file_in_grants(&manifest, &granted.join(name).to_string_lossy());
+
+        assert_eq!(read("notes.txt"), Ok("mine".to_string()));
+        assert!(read("link.txt").is_err());
+        assert!(read("../secret.txt").is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
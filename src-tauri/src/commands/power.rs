@@ -0,0 +1,189 @@
+use crate::commands::html_cache::start_html_cache_warmup;
+use crate::commands::settings::{load_settings, save_settings};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::{sleep, Duration};
+
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Whether background work is paused right now, kept current by the power
+/// task so subsystems can check it without reading the power source.
+#[derive(Default)]
+pub struct PowerState {
+    paused: AtomicBool,
+}
+
+impl PowerState {
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+}
+
+/// Sent to every window when background work pauses or resumes.
+#[derive(Clone, serde::Serialize)]
+pub struct PowerStatus {
+    pub low_power_mode: bool,
+    /// `None` when the power source can't be read, e.g. on Windows or a
+    /// desktop without a battery driver.
+    pub on_battery: Option<bool>,
+    /// Maintenance, digests and HTML cache warm-up are on hold. Timers and
+    /// auto-lock keep running, since the user is waiting on them.
+    pub background_paused: bool,
+}
+
+/// Reads `pmset`'s power source line, e.g. "Now drawing from 'AC Power'".
+/// Low Power Mode turned on in System Settings counts as battery too.
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let batt = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    let batt = String::from_utf8_lossy(&batt.stdout);
+    let low_power = std::process::Command::new("pmset")
+        .arg("-g")
+        .output()
+        .ok()
+        .is_some_and(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .any(|line| line.split_whitespace().eq(["lowpowermode", "1"]))
+        });
+    if batt.contains("'Battery Power'") || low_power {
+        Some(true)
+    } else if batt.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let supplies: Vec<(String, bool)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let read = |name: &str| {
+                std::fs::read_to_string(entry.path().join(name))
+                    .map(|value| value.trim().to_string())
+                    .unwrap_or_default()
+            };
+            (read("type"), read("online") == "1")
+        })
+        .collect();
+    on_battery_from_supplies(&supplies)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+/// Decides from `/sys/class/power_supply` entries, as (type, online): any
+/// online mains adapter means AC; otherwise a battery means battery power.
+#[cfg(any(target_os = "linux", test))]
+fn on_battery_from_supplies(supplies: &[(String, bool)]) -> Option<bool> {
+    if supplies
+        .iter()
+        .any(|(kind, online)| *online && matches!(kind.as_str(), "Mains" | "USB"))
+    {
+        return Some(false);
+    }
+    supplies
+        .iter()
+        .any(|(kind, _)| kind == "Battery")
+        .then_some(true)
+}
+
+fn read_power_status(app_handle: &AppHandle) -> Result<PowerStatus, String> {
+    let low_power_mode = load_settings(app_handle)?.low_power_mode;
+    let on_battery = on_battery();
+    Ok(PowerStatus {
+        low_power_mode,
+        on_battery,
+        // Without a power source to go by, the setting acts as a plain
+        // switch.
+        background_paused: low_power_mode && on_battery.unwrap_or(true),
+    })
+}
+
+/// Stores the current status, announcing it and warming the HTML cache
+/// again when background work resumes.
+fn refresh_power_status(app_handle: &AppHandle) -> Result<PowerStatus, String> {
+    let status = read_power_status(app_handle)?;
+    let state = app_handle.state::<PowerState>();
+    let was_paused = state
+        .paused
+        .swap(status.background_paused, Ordering::SeqCst);
+    if was_paused != status.background_paused {
+        let _ = app_handle.emit("power-status-changed", status.clone());
+        if was_paused {
+            start_html_cache_warmup(app_handle.clone());
+        }
+    }
+    Ok(status)
+}
+
+/// Waits for background work to be allowed, for tasks that would rather
+/// run late than run on battery.
+pub(crate) async fn wait_until_resumed(app_handle: &AppHandle) {
+    while app_handle.state::<PowerState>().is_paused() {
+        sleep(POWER_CHECK_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub fn get_power_status(app_handle: AppHandle) -> Result<PowerStatus, String> {
+    refresh_power_status(&app_handle)
+}
+
+/// Turns low-power mode on or off. While it's on and the machine runs on
+/// battery, background subsystems pause; they pick up again on AC power.
+#[tauri::command]
+pub fn set_low_power_mode(app_handle: AppHandle, enabled: bool) -> Result<PowerStatus, String> {
+    let mut settings = load_settings(&app_handle)?;
+    settings.low_power_mode = enabled;
+    save_settings(&app_handle, &settings)?;
+    refresh_power_status(&app_handle)
+}
+
+/// Follows the power source for the lifetime of the app. The first check
+/// happens before returning, so subsystems started after it see the right
+/// state.
+pub fn start_power_task(app_handle: AppHandle) {
+    if let Err(err) = refresh_power_status(&app_handle) {
+        eprintln!("Failed to check power status: {}", err);
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            sleep(POWER_CHECK_INTERVAL).await;
+            if let Err(err) = refresh_power_status(&app_handle) {
+                eprintln!("Failed to check power status: {}", err);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::on_battery_from_supplies;
+
+    #[test]
+    fn mains_adapter_wins_over_battery() {
+        let supply = |kind: &str, online| (kind.to_string(), online);
+        assert_eq!(
+            on_battery_from_supplies(&[supply("Battery", false), supply("Mains", true)]),
+            Some(false)
+        );
+        assert_eq!(
+            on_battery_from_supplies(&[supply("Battery", false), supply("Mains", false)]),
+            Some(true)
+        );
+        assert_eq!(
+            on_battery_from_supplies(&[supply("Mains", true)]),
+            Some(false)
+        );
+        assert_eq!(on_battery_from_supplies(&[]), None);
+    }
+}
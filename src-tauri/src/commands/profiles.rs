@@ -0,0 +1,246 @@
+use crate::commands::apps::{
+    get_app_data_dir, get_app_dir_in, get_apps_dir_path, get_profile_apps_dir,
+    get_profile_data_dir, load_index_in, save_index_in, update_index,
+};
+use crate::commands::autostart::sync_login_item;
+use crate::commands::launch::close_app_windows;
+use crate::commands::search::{invalidate_profile_search_index, unindex_app};
+use crate::commands::settings::{apply_settings, load_settings};
+use crate::models::{AppLock, AppMetadata, Settings, UserProfile};
+use crate::utils::{parse_uuid, write_atomic};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Clone, serde::Serialize)]
+pub struct ProfileSwitched {
+    pub profile: Option<String>,
+}
+
+const PROFILE_PIN_REQUIRED_ERROR: &str = "The parent PIN is required";
+
+/// The parent's PIN, kept beside the settings rather than in them so the
+/// settings the UI sends back can't drop it.
+fn get_profile_pin_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app_handle)?.join("profile_pin.json"))
+}
+
+fn load_profile_pin(app_handle: &AppHandle) -> Result<Option<AppLock>, String> {
+    let path = get_profile_pin_path(app_handle)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read parent PIN: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse parent PIN: {}", e))
+}
+
+/// Restricted (non-main) profiles can't be left or reconfigured without the
+/// PIN, once one is set.
+fn check_profile_pin(
+    restricted: bool,
+    stored: Option<&AppLock>,
+    pin: Option<&str>,
+) -> Result<(), String> {
+    match stored {
+        Some(stored) if restricted && !pin.is_some_and(|pin| stored.verify(pin)) => {
+            Err(PROFILE_PIN_REQUIRED_ERROR.to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn ensure_profile_pin(
+    app_handle: &AppHandle,
+    settings: &Settings,
+    pin: Option<&str>,
+) -> Result<(), String> {
+    let stored = load_profile_pin(app_handle)?;
+    check_profile_pin(settings.current_profile.is_some(), stored.as_ref(), pin)
+}
+
+/// Sets or changes the parent's PIN. Only the main profile can, and
+/// changing it takes the current one.
+#[tauri::command]
+pub fn set_profile_pin(
+    app_handle: AppHandle,
+    pin: String,
+    current_pin: Option<String>,
+) -> Result<(), String> {
+    let settings = load_settings(&app_handle)?;
+    if settings.current_profile.is_some() {
+        return Err("Switch to the main profile to change the parent PIN".to_string());
+    }
+    let stored = load_profile_pin(&app_handle)?;
+    check_profile_pin(true, stored.as_ref(), current_pin.as_deref())?;
+
+    let content = serde_json::to_string_pretty(&AppLock::new(&pin)?)
+        .map_err(|e| format!("Failed to serialize parent PIN: {}", e))?;
+    write_atomic(&get_profile_pin_path(&app_handle)?, &content)
+}
+
+/// Adds a profile with an empty library of its own. `shared_app_ids` are
+/// main profile apps shown in it through the gallery.
+#[tauri::command]
+pub fn create_profile(
+    app_handle: AppHandle,
+    name: String,
+    shared_app_ids: Vec<String>,
+    pin: Option<String>,
+) -> Result<Settings, String> {
+    let app_ids = shared_app_ids
+        .iter()
+        .map(|id| parse_uuid(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut settings = load_settings(&app_handle)?;
+    ensure_profile_pin(&app_handle, &settings, pin.as_deref())?;
+    settings.profiles.push(UserProfile {
+        name: name.trim().to_string(),
+        app_ids,
+    });
+    apply_settings(&app_handle, settings)
+}
+
+/// Makes `name` the active profile, or the main profile when `None`. A
+/// parent PIN has to be set before entering a restricted profile, and
+/// leaving one takes it. App windows are closed first and
+/// `profile-switched` tells the main window to reload its apps.
+#[tauri::command]
+pub fn switch_profile(
+    app_handle: AppHandle,
+    name: Option<String>,
+    pin: Option<String>,
+) -> Result<Settings, String> {
+    let mut settings = load_settings(&app_handle)?;
+    if settings.current_profile == name {
+        return Ok(settings);
+    }
+    ensure_profile_pin(&app_handle, &settings, pin.as_deref())?;
+    if name.is_some() && load_profile_pin(&app_handle)?.is_none() {
+        return Err("Set a parent PIN before switching to a restricted profile".to_string());
+    }
+    settings.current_profile = name;
+    settings.validate()?;
+
+    close_app_windows(&app_handle);
+    let settings = apply_settings(&app_handle, settings)?;
+    let _ = app_handle.emit(
+        "profile-switched",
+        ProfileSwitched {
+            profile: settings.current_profile.clone(),
+        },
+    );
+    Ok(settings)
+}
+
+/// Deletes a profile along with its apps and their data. The active profile
+/// can't be deleted.
+#[tauri::command]
+pub fn delete_profile(
+    app_handle: AppHandle,
+    name: String,
+    pin: Option<String>,
+) -> Result<Settings, String> {
+    let mut settings = load_settings(&app_handle)?;
+    ensure_profile_pin(&app_handle, &settings, pin.as_deref())?;
+    if settings.current_profile.as_deref() == Some(name.as_str()) {
+        return Err("Switch to another profile before deleting this one".to_string());
+    }
+    let position = settings
+        .profiles
+        .iter()
+        .position(|profile| profile.name == name)
+        .ok_or_else(|| format!("Profile not found: {}", name))?;
+
+    let data_dir = get_profile_data_dir(&app_handle, Some(&name))?;
+    if data_dir.exists() {
+        fs::remove_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to delete profile data: {}", e))?;
+    }
+    settings.profiles.remove(position);
+    apply_settings(&app_handle, settings)
+}
+
+/// Moves an app and all of its files from the active profile into another
+/// one (`None` for the main profile). Autostart is turned off, since only
+/// the active profile's apps are started.
+#[tauri::command]
+pub fn move_app_to_profile(
+    app_handle: AppHandle,
+    id: String,
+    profile: Option<String>,
+    pin: Option<String>,
+) -> Result<AppMetadata, String> {
+    let settings = load_settings(&app_handle)?;
+    ensure_profile_pin(&app_handle, &settings, pin.as_deref())?;
+    if profile == settings.current_profile {
+        return Err("App is already in that profile".to_string());
+    }
+    if let Some(name) = &profile {
+        if !settings.profiles.iter().any(|candidate| &candidate.name == name) {
+            return Err(format!("Profile not found: {}", name));
+        }
+    }
+
+    let uuid = parse_uuid(&id)?;
+    let (app, had_autostart) = update_index(&app_handle, |index| {
+        let mut app = index
+            .remove(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        let target_dir = get_profile_apps_dir(&app_handle, profile.as_deref())?;
+        let mut target_index = load_index_in(&target_dir)?;
+        if target_index.get(uuid).is_some() {
+            return Err("App already exists in that profile".to_string());
+        }
+
+        // Every file of the app lives in its directory, so one rename moves it.
+        let source = get_app_dir_in(&get_apps_dir_path(&app_handle)?, uuid);
+        let target = get_app_dir_in(&target_dir, uuid);
+        let moved = source.exists();
+        if moved {
+            fs::rename(&source, &target)
+                .map_err(|e| format!("Failed to move app files: {}", e))?;
+        }
+
+        let had_autostart = app.autostart;
+        app.autostart = false;
+        target_index.add(app.clone());
+        // The source index keeps the app when this fails, so its files go back.
+        if let Err(err) = save_index_in(&target_dir, &target_index) {
+            if moved {
+                let _ = fs::rename(&target, &source);
+            }
+            return Err(err);
+        }
+        Ok((app, had_autostart))
+    })?;
+    unindex_app(&app_handle, uuid);
+    invalidate_profile_search_index(&app_handle, profile.as_deref());
+    if had_autostart {
+        sync_login_item(&app_handle)?;
+    }
+
+    Ok(app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_profile_pin, PROFILE_PIN_REQUIRED_ERROR};
+    use crate::models::AppLock;
+
+    #[test]
+    fn restricted_profiles_take_the_parent_pin() {
+        let stored = AppLock::new("2468").expect("PIN should hash");
+        let refused = Err(PROFILE_PIN_REQUIRED_ERROR.to_string());
+
+        assert_eq!(check_profile_pin(false, Some(&stored), None), Ok(()));
+        assert_eq!(check_profile_pin(true, None, None), Ok(()));
+        assert_eq!(check_profile_pin(true, Some(&stored), None), refused);
+        assert_eq!(check_profile_pin(true, Some(&stored), Some("1357")), refused);
+        assert_eq!(check_profile_pin(true, Some(&stored), Some("2468")), Ok(()));
+    }
+}
@@ -0,0 +1,519 @@
+use crate::commands::agent::{
+    emit_progress, GenerationWorkspace, ProgressThrottle, SidecarRequest, MAX_HTML_BYTES,
+};
+use crate::commands::apps::get_app_data_dir;
+use crate::commands::generations::GenerationJob;
+use crate::models::{
+    AppKind, GeneratedWith, GenerationTimeline, ModelProvider, Settings, TimelineEvent,
+};
+use crate::utils::write_atomic;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, Window};
+use tokio::time::{timeout, Duration, Instant};
+
+/// Longest an HTTP provider may take to stream a whole generation.
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Read from the environment rather than settings, which get exported.
+const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+/// The only host the `OPENAI_API_KEY` key is sent to; other compatible
+/// servers get a key only when one is stored for their base URL.
+const OPENAI_API_HOST: &str = "api.openai.com";
+const HTML_START_TAG: &[u8] = b"<html";
+
+/// A condensed form of the sidecar's system prompt; HTTP providers get no
+/// tools, so the existing HTML is sent inline for edits instead.
+const SYSTEM_PROMPT: &str = "You are an expert web developer. Generate a single, \
+self-contained HTML file that implements the user's request.
+
+Requirements:
+1. Output ONLY valid HTML - no explanations, markdown, or code blocks.
+2. Put all CSS in <style> tags and all JS in <script> tags; use no external dependencies.
+3. Use modern CSS and vanilla JavaScript, with a clean, responsive, minimalist design.
+4. Handle errors in JavaScript.
+
+To save data between sessions use the global TroveStorage API, never localStorage or \
+sessionStorage: await TroveStorage.get(key), TroveStorage.set(key, value), \
+TroveStorage.delete(key), TroveStorage.clear() and TroveStorage.getAll().
+
+Trove keeps a data-prefers-trove-theme attribute (\"light\" or \"dark\") on <html>; style \
+dark mode with :root[data-prefers-trove-theme=\"dark\"] selectors.
+
+Start your response directly with <!DOCTYPE html> and end with </html>.";
+
+/// Generates `request` through an HTTP provider, streaming the reply into
+/// `output` so a failed run can still salvage what arrived.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_http_provider(
+    window: &Window,
+    job: &GenerationJob,
+    provider: ModelProvider,
+    settings: &Settings,
+    request: &SidecarRequest<'_>,
+    workspace: &GenerationWorkspace,
+    timeline: &mut GenerationTimeline,
+    output: &mut String,
+) -> Result<String, String> {
+    let existing_html = match &request.edit_path {
+        Some(path) => {
+            Some(fs::read_to_string(path).map_err(|e| format!("Failed to read app HTML: {}", e))?)
+        }
+        None => None,
+    };
+    let context_path = workspace.context_path();
+    let context = if context_path.exists() {
+        Some(
+            fs::read_to_string(&context_path)
+                .map_err(|e| format!("Failed to read reference context: {}", e))?,
+        )
+    } else {
+        None
+    };
+    let user_prompt = build_user_prompt(request, existing_html.as_deref(), context.as_deref());
+    let (url, model) = match provider {
+        ModelProvider::OpenAi => (
+            format!(
+                "{}/chat/completions",
+                settings.openai_base_url.trim_end_matches('/')
+            ),
+            settings.openai_model.trim(),
+        ),
+        ModelProvider::Ollama => (
+            format!(
+                "{}/api/chat",
+                settings.ollama_base_url.trim_end_matches('/')
+            ),
+            settings.ollama_model.trim(),
+        ),
+        ModelProvider::ClaudeCode => {
+            return Err("Claude Code generations run through the sidecar".to_string())
+        }
+    };
+    let body = request_body(provider, model, &user_prompt, request.creativity);
+
+    let base_url = match provider {
+        ModelProvider::Ollama => &settings.ollama_base_url,
+        _ => &settings.openai_base_url,
+    };
+    let stored_key = load_provider_keys(window.app_handle())?.remove(&key_url(base_url));
+    let env_key = std::env::var(OPENAI_API_KEY_ENV).ok();
+    let api_key = choose_api_key(provider, base_url, stored_key, env_key)?;
+
+    let mut builder = reqwest::Client::new()
+        .post(url.as_str())
+        .header("Content-Type", "application/json")
+        .body(body.to_string());
+    if let Some(api_key) = api_key {
+        builder = builder.bearer_auth(api_key);
+    }
+
+    let deadline = Instant::now() + PROVIDER_TIMEOUT;
+    let mut progress = ProgressThrottle::default();
+    progress.offer(
+        &format!("Connecting to {}...", provider.label()),
+        Instant::now(),
+    );
+    emit_progress(window, job, &mut progress);
+
+    let mut send = std::pin::pin!(builder.send());
+    let response = loop {
+        check_running(job, deadline)?;
+        if let Ok(result) = timeout(Duration::from_millis(200), &mut send).await {
+            break result;
+        }
+    };
+    let mut response = response
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("{} request failed: {}", provider.label(), e))?;
+    timeline.mark(TimelineEvent::Spawned);
+
+    let mut line_buffer: Vec<u8> = Vec::new();
+    let mut reported_model: Option<String> = None;
+    let mut html_started = false;
+    let mut done = false;
+    while !done {
+        check_running(job, deadline)?;
+        progress.flush(Instant::now(), false);
+        emit_progress(window, job, &mut progress);
+
+        let chunk = match timeout(Duration::from_millis(200), response.chunk()).await {
+            Ok(chunk) => chunk.map_err(|e| format!("{} stream failed: {}", provider.label(), e))?,
+            Err(_) => continue,
+        };
+        let Some(chunk) = chunk else { break };
+        line_buffer.extend_from_slice(&chunk);
+
+        while let Some(newline) = line_buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = line_buffer.drain(..=newline).collect();
+            let update = parse_stream_line(provider, &String::from_utf8_lossy(&line))?;
+            if let Some(text) = update.text {
+                timeline.mark(TimelineEvent::FirstProgress);
+                let scan_from = output.len().saturating_sub(HTML_START_TAG.len() - 1);
+                output.push_str(&text);
+                if output.len() > MAX_HTML_BYTES {
+                    return Err("Generated HTML exceeded size limit".to_string());
+                }
+                if !html_started && contains_html_start(&output.as_bytes()[scan_from..]) {
+                    html_started = true;
+                    timeline.mark(TimelineEvent::HtmlStart);
+                }
+                progress.offer(
+                    &format!("Receiving HTML content... ({} KB)", output.len() / 1024),
+                    Instant::now(),
+                );
+            }
+            reported_model = update.model.or(reported_model);
+            done |= update.done;
+        }
+        if line_buffer.len() > MAX_HTML_BYTES {
+            return Err(format!("{} sent an oversized stream line", provider.label()));
+        }
+    }
+    timeline.mark(TimelineEvent::Terminated);
+    progress.flush(Instant::now(), true);
+    emit_progress(window, job, &mut progress);
+
+    let html = extract_html(output)
+        .ok_or_else(|| format!("{} did not return an HTML document", provider.label()))?;
+    timeline.mark(TimelineEvent::HtmlEnd);
+    timeline.generated_with = Some(GeneratedWith {
+        sidecar_version: None,
+        model: Some(reported_model.unwrap_or_else(|| model.to_string())),
+    });
+    Ok(html)
+}
+
+/// API keys by provider base URL. Kept in their own file rather than in
+/// settings, which get exported.
+fn get_provider_keys_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app_handle)?.join("provider_keys.json"))
+}
+
+fn load_provider_keys(app_handle: &AppHandle) -> Result<HashMap<String, String>, String> {
+    let path = get_provider_keys_path(app_handle)?;
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read provider keys: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse provider keys: {}", e))
+}
+
+/// The form a base URL is stored under, so a trailing slash doesn't make
+/// a configured key miss.
+fn key_url(base_url: &str) -> String {
+    base_url.trim().trim_end_matches('/').to_string()
+}
+
+/// The key to send, if any. A key stored for the exact base URL wins;
+/// otherwise only api.openai.com gets `OPENAI_API_KEY`, and requires it.
+fn choose_api_key(
+    provider: ModelProvider,
+    base_url: &str,
+    stored_key: Option<String>,
+    env_key: Option<String>,
+) -> Result<Option<String>, String> {
+    if let Some(key) = stored_key.filter(|key| !key.trim().is_empty()) {
+        return Ok(Some(key.trim().to_string()));
+    }
+    if provider != ModelProvider::OpenAi || !is_openai_host(base_url) {
+        return Ok(None);
+    }
+    match env_key.filter(|key| !key.trim().is_empty()) {
+        Some(key) => Ok(Some(key.trim().to_string())),
+        None => Err(format!(
+            "Set {} or store a key to use the OpenAI provider",
+            OPENAI_API_KEY_ENV
+        )),
+    }
+}
+
+/// Stores the API key sent to `base_url`, or forgets it when `api_key` is
+/// empty or missing.
+#[tauri::command]
+pub fn set_provider_api_key(
+    app_handle: AppHandle,
+    base_url: String,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    reqwest::Url::parse(base_url.trim()).map_err(|e| format!("Invalid base URL: {}", e))?;
+    let mut keys = load_provider_keys(&app_handle)?;
+    match api_key.map(|key| key.trim().to_string()) {
+        Some(key) if !key.is_empty() => keys.insert(key_url(&base_url), key),
+        _ => keys.remove(&key_url(&base_url)),
+    };
+
+    let content = serde_json::to_string_pretty(&keys)
+        .map_err(|e| format!("Failed to serialize provider keys: {}", e))?;
+    write_atomic(&get_provider_keys_path(&app_handle)?, &content)
+}
+
+/// Base URLs that have a stored key; the keys themselves never leave the
+/// backend.
+#[tauri::command]
+pub fn list_provider_key_urls(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    let mut urls: Vec<String> = load_provider_keys(&app_handle)?.into_keys().collect();
+    urls.sort();
+    Ok(urls)
+}
+
+/// Whether `base_url` points at OpenAI itself over HTTPS.
+fn is_openai_host(base_url: &str) -> bool {
+    reqwest::Url::parse(base_url).is_ok_and(|url| {
+        url.scheme() == "https"
+            && url
+                .host_str()
+                .is_some_and(|host| host.eq_ignore_ascii_case(OPENAI_API_HOST))
+    })
+}
+
+/// Looks for `<html` in newly streamed bytes; callers pass the new text
+/// plus enough of what came before to catch a tag split across chunks.
+fn contains_html_start(bytes: &[u8]) -> bool {
+    bytes
+        .windows(HTML_START_TAG.len())
+        .any(|window| window.eq_ignore_ascii_case(HTML_START_TAG))
+}
+
+fn check_running(job: &GenerationJob, deadline: Instant) -> Result<(), String> {
+    if job.is_cancelled() {
+        return Err("Generation cancelled".to_string());
+    }
+    if Instant::now() >= deadline {
+        return Err("Generation timed out".to_string());
+    }
+    Ok(())
+}
+
+/// The user message, mirroring the sections the sidecar adds to its prompt.
+fn build_user_prompt(
+    request: &SidecarRequest<'_>,
+    existing_html: Option<&str>,
+    context: Option<&str>,
+) -> String {
+    let mut prompt = match existing_html {
+        Some(html) => format!(
+            "Update the existing app \"{}\". Its current HTML is below. Apply the new \
+             requirements while preserving working parts unless they conflict.\n\n\
+             <current_html>\n{}\n</current_html>\n\n{}",
+            request.name, html, request.prompt
+        ),
+        None => format!(
+            "Create a web app called \"{}\" with the following functionality:\n\n{}",
+            request.name, request.prompt
+        ),
+    };
+    if let Some(context) = context.map(str::trim).filter(|context| !context.is_empty()) {
+        prompt.push_str(&format!(
+            "\n\nReference material provided by the user:\n<reference>\n{}\n</reference>",
+            context
+        ));
+    }
+    if let Some(language) = request.language {
+        prompt.push_str(&format!(
+            "\n\nWrite all user-facing text in the language with BCP 47 tag \"{}\", and set \
+             <html lang=\"{}\">. Keep code identifiers in English.",
+            language, language
+        ));
+    }
+    if request.accessibility_mode {
+        prompt.push_str(
+            "\n\nUse semantic HTML with accessible names, make everything keyboard \
+             operable, announce dynamic updates with aria-live, and meet WCAG AA contrast.",
+        );
+    }
+    match request.app_kind {
+        AppKind::Standard => {}
+        AppKind::Widget => prompt.push_str(
+            "\n\nLayout: a widget in a small window of about 360x280 pixels. Show one \
+             glanceable thing that fits without scrolling.",
+        ),
+        AppKind::Fullscreen => prompt.push_str(
+            "\n\nLayout: this app runs fullscreen. Fill the whole viewport with no page \
+             scrolling and scale content to the screen size.",
+        ),
+    }
+    prompt.push_str(
+        "\n\nRemember: Output ONLY the complete HTML file, starting with <!DOCTYPE html> \
+         and ending with </html>.",
+    );
+    prompt
+}
+
+fn request_body(
+    provider: ModelProvider,
+    model: &str,
+    user_prompt: &str,
+    creativity: Option<f32>,
+) -> Value {
+    let messages = json!([
+        { "role": "system", "content": SYSTEM_PROMPT },
+        { "role": "user", "content": user_prompt },
+    ]);
+    // Creativity maps onto sampling temperature instead of prompt guidance.
+    let temperature = creativity.map(|creativity| 0.2 + f64::from(creativity) * 0.8);
+    let mut body = json!({ "model": model, "messages": messages, "stream": true });
+    if let Some(temperature) = temperature {
+        match provider {
+            ModelProvider::Ollama => body["options"] = json!({ "temperature": temperature }),
+            _ => body["temperature"] = json!(temperature),
+        }
+    }
+    body
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct StreamUpdate {
+    text: Option<String>,
+    model: Option<String>,
+    done: bool,
+}
+
+/// Parses one line of a streamed reply: server-sent events for OpenAI,
+/// newline-delimited JSON for Ollama.
+fn parse_stream_line(provider: ModelProvider, line: &str) -> Result<StreamUpdate, String> {
+    let line = line.trim();
+    let payload = match provider {
+        ModelProvider::OpenAi => match line.strip_prefix("data:").map(str::trim) {
+            Some("[DONE]") => {
+                return Ok(StreamUpdate {
+                    done: true,
+                    ..StreamUpdate::default()
+                })
+            }
+            Some(payload) => payload,
+            None => return Ok(StreamUpdate::default()),
+        },
+        _ => line,
+    };
+    if payload.is_empty() {
+        return Ok(StreamUpdate::default());
+    }
+    let value: Value = serde_json::from_str(payload)
+        .map_err(|e| format!("Invalid {} response: {}", provider.label(), e))?;
+    let error = match &value["error"] {
+        Value::String(message) => Some(message.as_str()),
+        error => error["message"].as_str(),
+    };
+    if let Some(error) = error {
+        return Err(format!("{} error: {}", provider.label(), error));
+    }
+    let text = match provider {
+        ModelProvider::Ollama => value["message"]["content"].as_str(),
+        _ => value["choices"][0]["delta"]["content"].as_str(),
+    };
+    Ok(StreamUpdate {
+        text: text.filter(|text| !text.is_empty()).map(str::to_string),
+        model: value["model"].as_str().map(str::to_string),
+        done: value["done"].as_bool().unwrap_or(false),
+    })
+}
+
+/// The HTML document inside a model reply, dropping any prose or code
+/// fences around it.
+fn extract_html(reply: &str) -> Option<String> {
+    let lower = reply.to_ascii_lowercase();
+    let start = lower.find("<!doctype").or_else(|| lower.find("<html"))?;
+    let end = lower.rfind("</html>")? + "</html>".len();
+    (end > start).then(|| reply[start..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        choose_api_key, contains_html_start, extract_html, is_openai_host, parse_stream_line,
+        StreamUpdate,
+    };
+    use crate::models::ModelProvider;
+
+    #[test]
+    fn sends_the_key_only_to_openai() {
+        assert!(is_openai_host("https://api.openai.com/v1"));
+        assert!(is_openai_host("https://API.OpenAI.com/v1/"));
+        assert!(!is_openai_host("http://api.openai.com/v1"));
+        assert!(!is_openai_host("https://api.openai.com.example.net/v1"));
+        assert!(!is_openai_host("http://localhost:8080/v1"));
+    }
+
+    #[test]
+    fn prefers_a_key_stored_for_the_base_url() {
+        let stored = || Some("sk-stored".to_string());
+        let env = || Some("sk-env".to_string());
+        let openai = "https://api.openai.com/v1";
+        let router = "https://openrouter.ai/api/v1";
+        assert_eq!(
+            choose_api_key(ModelProvider::OpenAi, router, stored(), env()).unwrap(),
+            stored()
+        );
+        assert_eq!(
+            choose_api_key(ModelProvider::OpenAi, router, None, env()).unwrap(),
+            None
+        );
+        assert_eq!(
+            choose_api_key(ModelProvider::OpenAi, openai, None, env()).unwrap(),
+            env()
+        );
+        assert!(choose_api_key(ModelProvider::OpenAi, openai, None, None).is_err());
+        assert_eq!(
+            choose_api_key(ModelProvider::Ollama, "http://localhost:11434", None, env()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_html_start_in_the_scanned_tail() {
+        assert!(contains_html_start(b"Sure!\n<!DOCTYPE html><HTML lang=\"en\">"));
+        assert!(!contains_html_start(b"<!DOCTYPE html>"));
+        // A tag split across chunks is found from the previous chunk's tail.
+        let before = "Sure! <!DOCTYPE html><ht";
+        let after = format!("{}ml>", before);
+        assert!(contains_html_start(&after.as_bytes()[before.len() - 4..]));
+    }
+
+    #[test]
+    fn extracts_document_from_reply() {
+        let reply = "Here you go:\n```html\n<!DOCTYPE html><html><body>Hi</body></HTML>\n```";
+        assert_eq!(
+            extract_html(reply).as_deref(),
+            Some("<!DOCTYPE html><html><body>Hi</body></HTML>")
+        );
+        assert_eq!(extract_html("<html><body>cut off"), None);
+    }
+
+    #[test]
+    fn parses_streamed_lines_per_provider() {
+        let openai = parse_stream_line(
+            ModelProvider::OpenAi,
+            r#"data: {"model":"gpt-4.1","choices":[{"delta":{"content":"<html>"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(openai.text.as_deref(), Some("<html>"));
+        assert_eq!(openai.model.as_deref(), Some("gpt-4.1"));
+        assert!(
+            parse_stream_line(ModelProvider::OpenAi, "data: [DONE]")
+                .unwrap()
+                .done
+        );
+        assert_eq!(
+            parse_stream_line(ModelProvider::OpenAi, ": keep-alive").unwrap(),
+            StreamUpdate::default()
+        );
+
+        let ollama = parse_stream_line(
+            ModelProvider::Ollama,
+            r#"{"model":"qwen2.5-coder","message":{"content":""},"done":true}"#,
+        )
+        .unwrap();
+        assert_eq!(ollama.text, None);
+        assert!(ollama.done);
+        assert!(
+            parse_stream_line(ModelProvider::Ollama, r#"{"error":"model not found"}"#)
+                .unwrap_err()
+                .contains("model not found")
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::commands::apps::{get_app_html_path, get_app_internal};
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::utils::base85_encode;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use qrcode::{Color, EcLevel, QrCode};
+use std::fs;
+use std::io::Write;
+use tauri::{AppHandle, State};
+
+/// Byte-mode capacity of the largest QR code (version 40) at low error
+/// correction.
+const QR_MAX_PAYLOAD_BYTES: usize = 2953;
+const QR_PAYLOAD_PREFIX: &str = "trove:1:";
+const QR_MODULE_PIXELS: usize = 8;
+const QR_QUIET_ZONE_MODULES: usize = 4;
+
+/// Deflates the HTML and base85-encodes it behind a short format prefix.
+fn encode_qr_payload(html: &str) -> Result<String, String> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(html.as_bytes())
+        .map_err(|e| format!("Failed to compress app: {}", e))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress app: {}", e))?;
+    Ok(format!("{}{}", QR_PAYLOAD_PREFIX, base85_encode(&compressed)))
+}
+
+/// Renders a QR code as an 8-bit grayscale PNG with a quiet zone.
+fn render_qr_png(code: &QrCode) -> Result<Vec<u8>, String> {
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + 2 * QR_QUIET_ZONE_MODULES) * QR_MODULE_PIXELS;
+
+    let mut pixels = vec![0xffu8; size * size];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x0 = (index % modules + QR_QUIET_ZONE_MODULES) * QR_MODULE_PIXELS;
+        let y0 = (index / modules + QR_QUIET_ZONE_MODULES) * QR_MODULE_PIXELS;
+        for y in y0..y0 + QR_MODULE_PIXELS {
+            pixels[y * size + x0..y * size + x0 + QR_MODULE_PIXELS].fill(0);
+        }
+    }
+
+    let mut png_bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_bytes, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("Failed to render QR code: {}", e))?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| format!("Failed to render QR code: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to render QR code: {}", e))?;
+    Ok(png_bytes)
+}
+
+/// Encodes a small app's HTML into a QR code and returns it as PNG bytes.
+#[tauri::command]
+pub fn export_app_qr(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+) -> Result<Vec<u8>, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, app.id)?;
+    let html = fs::read_to_string(get_app_html_path(&app_handle, app.id)?)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+
+    let payload = encode_qr_payload(&html)?;
+    if payload.len() > QR_MAX_PAYLOAD_BYTES {
+        return Err(format!(
+            "App is too large for a QR code ({} bytes compressed, max {})",
+            payload.len(),
+            QR_MAX_PAYLOAD_BYTES
+        ));
+    }
+
+    let code = QrCode::with_error_correction_level(payload.as_bytes(), EcLevel::L)
+        .map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    render_qr_png(&code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_qr_payload, QR_MAX_PAYLOAD_BYTES, QR_PAYLOAD_PREFIX};
+
+    #[test]
+    fn repetitive_html_compresses_to_fit() {
+        let html = format!("<!DOCTYPE html><html><body>{}</body></html>", "<p>hi</p>".repeat(2000));
+        let payload = encode_qr_payload(&html).unwrap();
+        assert!(payload.starts_with(QR_PAYLOAD_PREFIX));
+        assert!(payload.len() < QR_MAX_PAYLOAD_BYTES);
+        assert!(payload.len() < html.len() / 10);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::commands::apps::{get_app_html_path, load_index, save_app};
+use crate::commands::versions::{create_restore_point, record_version};
+use crate::html::{inject_runtime, runtime_version, RUNTIME_VERSION};
+use crate::models::{AppMetadata, ChangeKind};
+use std::fs;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RuntimeUpgradeFailure {
+    pub app_id: Uuid,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RuntimeUpgradeReport {
+    pub upgraded: usize,
+    pub already_current: usize,
+    /// Apps with no runtime block, e.g. HTML imported from outside Trove.
+    pub without_runtime: usize,
+    pub failed: Vec<RuntimeUpgradeFailure>,
+}
+
+fn upgrade_app_runtime(
+    app_handle: &AppHandle,
+    app: &AppMetadata,
+    html: &str,
+) -> Result<(), String> {
+    let upgraded = inject_runtime(html);
+    let mut updated_app = app.clone();
+    create_restore_point(app_handle, app)?;
+    if save_app(app_handle, &mut updated_app, &upgraded)? {
+        record_version(app_handle, &updated_app, ChangeKind::RuntimeUpgraded, &upgraded)?;
+    }
+    Ok(())
+}
+
+/// Rewrites the runtime block in every app whose copy is older than the
+/// current `trove-runtime.js`, so bridge fixes reach existing apps without
+/// regenerating them. Each rewrite is undoable and recorded as a version.
+/// One app failing doesn't stop the rest.
+#[tauri::command]
+pub fn upgrade_apps_runtime(app_handle: AppHandle) -> Result<RuntimeUpgradeReport, String> {
+    let mut report = RuntimeUpgradeReport::default();
+    for app in load_index(&app_handle)?.apps {
+        let result = fs::read_to_string(get_app_html_path(&app_handle, app.id)?)
+            .map_err(|e| format!("Failed to read app HTML: {}", e))
+            .and_then(|html| match runtime_version(&html) {
+                None => {
+                    report.without_runtime += 1;
+                    Ok(())
+                }
+                Some(version) if version >= RUNTIME_VERSION => {
+                    report.already_current += 1;
+                    Ok(())
+                }
+                Some(_) => {
+                    upgrade_app_runtime(&app_handle, &app, &html)?;
+                    report.upgraded += 1;
+                    Ok(())
+                }
+            });
+        if let Err(error) = result {
+            report.failed.push(RuntimeUpgradeFailure {
+                app_id: app.id,
+                error,
+            });
+        }
+    }
+    Ok(report)
+}
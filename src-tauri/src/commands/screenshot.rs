@@ -0,0 +1,101 @@
+use crate::commands::app_windows::AppWindows;
+use crate::commands::apps::get_visible_app;
+use crate::commands::launch::APP_WINDOW_LABEL_PREFIX;
+use crate::utils::{parse_uuid, write_atomic_bytes};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, State};
+
+/// Encodes raw RGBA pixels, row by row from the top, as a PNG.
+fn encode_rgba_png(width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>, String> {
+    let mut png_bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png_bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    writer
+        .write_image_data(pixels)
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    Ok(png_bytes)
+}
+
+/// Captures the on-screen window with this title belonging to Trove itself,
+/// as it looks right now.
+fn capture_window(title: &str) -> Result<Vec<u8>, String> {
+    let pid = std::process::id();
+    let windows = xcap::Window::all().map_err(|e| format!("Failed to list windows: {}", e))?;
+    let window = windows
+        .into_iter()
+        .find(|window| {
+            window.pid().is_ok_and(|window_pid| window_pid == pid)
+                && window
+                    .title()
+                    .is_ok_and(|window_title| window_title == title)
+        })
+        .ok_or_else(|| "App window is not on screen".to_string())?;
+    if window.is_minimized().unwrap_or(false) {
+        return Err("App window is minimized".to_string());
+    }
+    let image = window
+        .capture_image()
+        .map_err(|e| format!("Failed to capture app window: {}", e))?;
+    encode_rgba_png(image.width(), image.height(), image.as_raw())
+}
+
+/// Saves a PNG of the window currently showing the app, e.g. to share a
+/// game or dashboard mid-session, and returns the path written. An app in
+/// its own window is captured from there; an app in the main panel is
+/// captured with the rest of the Trove window. `.png` is added to `dest`
+/// when missing.
+#[tauri::command]
+pub fn capture_app_screenshot(
+    app_handle: AppHandle,
+    windows: State<'_, AppWindows>,
+    id: String,
+    dest: String,
+) -> Result<String, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&id)?)?;
+    let mut labels = windows.labels_for(app.id);
+    labels.sort_by_key(|label| !label.starts_with(APP_WINDOW_LABEL_PREFIX));
+    let window = labels
+        .iter()
+        .find_map(|label| app_handle.get_webview_window(label))
+        .ok_or_else(|| format!("App is not open: {}", app.name))?;
+    let title = window
+        .title()
+        .map_err(|e| format!("Failed to read window title: {}", e))?;
+
+    let png_bytes = capture_window(&title)?;
+    let mut dest = PathBuf::from(dest);
+    if !dest
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+    {
+        dest.as_mut_os_string().push(".png");
+    }
+    write_atomic_bytes(&dest, &png_bytes)?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_rgba_png;
+
+    #[test]
+    fn encodes_rgba_pixels_losslessly() {
+        let pixels = [255, 0, 0, 255, 0, 128, 255, 64];
+        let png_bytes = encode_rgba_png(2, 1, &pixels).unwrap();
+
+        let decoder = png::Decoder::new(png_bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut decoded).unwrap();
+        assert_eq!((info.width, info.height), (2, 1));
+        assert_eq!(&decoded[..info.buffer_size()], &pixels);
+        assert!(encode_rgba_png(2, 2, &pixels).is_err());
+    }
+}
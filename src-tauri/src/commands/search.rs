@@ -0,0 +1,201 @@
+use crate::commands::apps::{
+    get_active_profile_data_dir, get_app_html_path, get_profile_data_dir, load_index,
+};
+use crate::commands::lock::UnlockedApps;
+use crate::html::visible_text;
+use crate::models::{
+    AppMetadata, SearchDocument, SearchIndex, SEARCH_INDEX_VERSION, SEARCH_TEXT_MAX_BYTES,
+};
+use crate::utils::write_atomic;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Manager, State};
+use uuid::Uuid;
+
+const SEARCH_RESULTS_DEFAULT_LIMIT: usize = 50;
+
+#[derive(Clone, serde::Serialize)]
+pub struct SearchResult {
+    pub app: AppMetadata,
+    pub score: u32,
+    pub snippet: String,
+}
+
+const SEARCH_INDEX_FILE_NAME: &str = "search_index.json";
+
+/// Serializes reads and writes of the stored search index. Saves finish in
+/// parallel, and an update working from a stale copy would drop the other's
+/// entry.
+#[derive(Default)]
+pub struct SearchIndexLock(Mutex<()>);
+
+fn lock_search_index(app_handle: &AppHandle) -> MutexGuard<'_, ()> {
+    app_handle
+        .state::<SearchIndexLock>()
+        .inner()
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+fn get_search_index_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join(SEARCH_INDEX_FILE_NAME))
+}
+
+fn read_search_text(app_handle: &AppHandle, id: Uuid) -> String {
+    get_app_html_path(app_handle, id)
+        .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))
+        .map(|html| visible_text(&html, SEARCH_TEXT_MAX_BYTES))
+        .unwrap_or_default()
+}
+
+fn rebuild_search_index(app_handle: &AppHandle) -> Result<SearchIndex, String> {
+    let mut search_index = SearchIndex::default();
+    for app in load_index(app_handle)?.apps {
+        let document = SearchDocument {
+            name: app.name,
+            prompt: app.prompt,
+            text: read_search_text(app_handle, app.id),
+        };
+        search_index.upsert(app.id, document);
+    }
+    save_search_index(app_handle, &search_index)?;
+    Ok(search_index)
+}
+
+/// Loads the stored index, rebuilding it from the apps on disk when it is
+/// missing, unreadable, or from an older format. The index is only a cache,
+/// so it never fails a load on its own. Callers hold `SearchIndexLock`.
+fn load_search_index(app_handle: &AppHandle) -> Result<SearchIndex, String> {
+    let path = get_search_index_path(app_handle)?;
+    let stored = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<SearchIndex>(&content).ok())
+        .filter(|index| index.version == SEARCH_INDEX_VERSION);
+
+    match stored {
+        Some(index) => Ok(index),
+        None => rebuild_search_index(app_handle),
+    }
+}
+
+fn save_search_index(app_handle: &AppHandle, index: &SearchIndex) -> Result<(), String> {
+    let content = serde_json::to_string(index)
+        .map_err(|e| format!("Failed to serialize search index: {}", e))?;
+
+    write_atomic(&get_search_index_path(app_handle)?, &content)
+}
+
+/// Drops the stored index so the next search rebuilds it. Used when an
+/// incremental update fails, rather than failing the save it belongs to.
+fn invalidate_search_index(app_handle: &AppHandle) {
+    if let Ok(path) = get_search_index_path(app_handle) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Drops a profile's stored index, e.g. after an app is moved into it while
+/// another profile is active.
+pub(crate) fn invalidate_profile_search_index(app_handle: &AppHandle, profile: Option<&str>) {
+    let _guard = lock_search_index(app_handle);
+    if let Ok(dir) = get_profile_data_dir(app_handle, profile) {
+        let _ = fs::remove_file(dir.join(SEARCH_INDEX_FILE_NAME));
+    }
+}
+
+/// Updates an app's entry after it is saved. Pass the HTML when it changed;
+/// otherwise the previously indexed text is kept.
+pub(crate) fn index_app(app_handle: &AppHandle, app: &AppMetadata, html: Option<&str>) {
+    let _guard = lock_search_index(app_handle);
+    let result = load_search_index(app_handle).and_then(|mut search_index| {
+        let text = match html {
+            Some(html) => visible_text(html, SEARCH_TEXT_MAX_BYTES),
+            None => match search_index.documents.get(&app.id) {
+                Some(existing) => existing.text.clone(),
+                None => read_search_text(app_handle, app.id),
+            },
+        };
+        let document = SearchDocument {
+            name: app.name.clone(),
+            prompt: app.prompt.clone(),
+            text,
+        };
+        search_index.upsert(app.id, document);
+        save_search_index(app_handle, &search_index)
+    });
+    if result.is_err() {
+        invalidate_search_index(app_handle);
+    }
+}
+
+pub(crate) fn unindex_app(app_handle: &AppHandle, id: Uuid) {
+    let _guard = lock_search_index(app_handle);
+    let result = load_search_index(app_handle).and_then(|mut search_index| {
+        if search_index.remove(id).is_some() {
+            save_search_index(app_handle, &search_index)?;
+        }
+        Ok(())
+    });
+    if result.is_err() {
+        invalidate_search_index(app_handle);
+    }
+}
+
+/// Drops entries for apps that no longer exist and returns how many were
+/// removed.
+pub(crate) fn prune_search_index(
+    app_handle: &AppHandle,
+    live: &HashSet<Uuid>,
+) -> Result<usize, String> {
+    let _guard = lock_search_index(app_handle);
+    let mut search_index = load_search_index(app_handle)?;
+    let stale: Vec<Uuid> = search_index
+        .documents
+        .keys()
+        .filter(|id| !live.contains(id))
+        .copied()
+        .collect();
+    for id in &stale {
+        search_index.remove(*id);
+    }
+    if !stale.is_empty() {
+        save_search_index(app_handle, &search_index)?;
+    }
+    Ok(stale.len())
+}
+
+/// Searches app names, prompts, and visible HTML text. Drafts are left out,
+/// matching `list_apps`, and so are locked apps until they are unlocked,
+/// since a match or snippet would give away their contents.
+#[tauri::command]
+pub fn search_apps(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchResult>, String> {
+    let search_index = {
+        let _guard = lock_search_index(&app_handle);
+        load_search_index(&app_handle)?
+    };
+    let apps_index = load_index(&app_handle)?;
+    let limit = limit.unwrap_or(SEARCH_RESULTS_DEFAULT_LIMIT);
+
+    Ok(search_index
+        .search(&query)
+        .into_iter()
+        .filter_map(|hit| {
+            let app = apps_index
+                .get(hit.id)
+                .filter(|app| !app.draft && (!app.locked || unlocked.contains(app.id)))?;
+            Some(SearchResult {
+                app: app.clone(),
+                score: hit.score,
+                snippet: hit.snippet,
+            })
+        })
+        .take(limit)
+        .collect())
+}
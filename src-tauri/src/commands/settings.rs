@@ -0,0 +1,125 @@
+use crate::commands::apps::get_app_data_dir;
+use crate::commands::generations::GenerationManager;
+use crate::commands::profiles::ensure_profile_pin;
+use crate::commands::tags::SingleTurnSlots;
+use crate::commands::templates::{
+    get_prompt_templates, merge_templates, validate_imported_templates,
+};
+use crate::models::{Settings, SettingsProfile, SETTINGS_PROFILE_FORMAT_VERSION};
+use crate::utils::write_atomic;
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const MAX_SETTINGS_PROFILE_BYTES: u64 = 1024 * 1024;
+
+fn get_settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_app_data_dir(app_handle)?.join("settings.json"))
+}
+
+pub fn load_settings(app_handle: &AppHandle) -> Result<Settings, String> {
+    let path = get_settings_path(app_handle)?;
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read settings: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
+}
+
+pub(crate) fn save_settings(app_handle: &AppHandle, settings: &Settings) -> Result<(), String> {
+    let path = get_settings_path(app_handle)?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    write_atomic(&path, &content)?;
+    // A raised concurrency limit may let queued generations and sidecars
+    // start.
+    app_handle.state::<GenerationManager>().reschedule();
+    app_handle.state::<SingleTurnSlots>().reschedule();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_settings(app_handle: AppHandle) -> Result<Settings, String> {
+    load_settings(&app_handle)
+}
+
+pub(crate) fn apply_settings(
+    app_handle: &AppHandle,
+    settings: Settings,
+) -> Result<Settings, String> {
+    settings.validate()?;
+    save_settings(app_handle, &settings)?;
+    Ok(settings)
+}
+
+/// Saves settings from the UI. Inside a restricted profile this takes the
+/// parent's PIN.
+#[tauri::command]
+pub fn update_settings(
+    app_handle: AppHandle,
+    settings: Settings,
+    pin: Option<String>,
+) -> Result<Settings, String> {
+    ensure_profile_pin(&app_handle, &load_settings(&app_handle)?, pin.as_deref())?;
+    apply_settings(&app_handle, settings)
+}
+
+/// Writes the current settings and prompt templates to a profile file that
+/// `import_settings` can apply on another machine. Settings tied to this
+/// machine are written as their defaults.
+#[tauri::command]
+pub fn export_settings(app_handle: AppHandle, dest: String) -> Result<(), String> {
+    let settings = load_settings(&app_handle)?.keeping_machine_settings(&Settings::default());
+    let profile = SettingsProfile {
+        format_version: SETTINGS_PROFILE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        settings,
+        templates: get_prompt_templates(app_handle)?,
+    };
+    let content = serde_json::to_string_pretty(&profile)
+        .map_err(|e| format!("Failed to serialize settings profile: {}", e))?;
+
+    write_atomic(Path::new(&dest), &content)
+}
+
+/// Replaces the current settings with those from a profile file and adds
+/// its templates. Settings missing from an older profile fall back to their
+/// defaults; user profiles, provider endpoints and sidecar security keep
+/// this machine's values. Inside a restricted profile this takes the
+/// parent's PIN.
+#[tauri::command]
+pub fn import_settings(
+    app_handle: AppHandle,
+    path: String,
+    pin: Option<String>,
+) -> Result<Settings, String> {
+    let current = load_settings(&app_handle)?;
+    ensure_profile_pin(&app_handle, &current, pin.as_deref())?;
+    let metadata = fs::metadata(&path)
+        .map_err(|e| format!("Failed to inspect settings profile: {}", e))?;
+    if metadata.len() > MAX_SETTINGS_PROFILE_BYTES {
+        return Err("Settings profile exceeded size limit".to_string());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read settings profile: {}", e))?;
+    let profile: SettingsProfile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse settings profile: {}", e))?;
+    if profile.format_version != SETTINGS_PROFILE_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported settings profile version: {}",
+            profile.format_version
+        ));
+    }
+
+    let settings = profile.settings.keeping_machine_settings(&current);
+    settings.validate()?;
+    let templates = validate_imported_templates(&profile.templates, settings.prompt_max_length)?;
+    let settings = apply_settings(&app_handle, settings)?;
+    merge_templates(&app_handle, templates)?;
+    Ok(settings)
+}
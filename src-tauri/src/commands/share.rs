@@ -0,0 +1,343 @@
+use crate::commands::agent::MAX_HTML_BYTES;
+use crate::commands::apps::{get_app_html_path, get_app_internal};
+use crate::commands::import::{
+    import_html, sanitize_import_color, sanitize_import_emoji, sanitize_import_name,
+};
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::storage::load_storage;
+use crate::html::find_open_tag;
+use crate::models::{AppMetadata, AppOrigin};
+use crate::utils::{
+    base64_url_decode, base64_url_encode, percent_decode, percent_encode, write_atomic,
+};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+const SHARE_LINK_PREFIX: &str = "trove://import?";
+const SHARE_LINK_VERSION: &str = "2";
+/// Links from before the HTML was compressed carry it as plain base64.
+const SHARE_LINK_LEGACY_VERSION: &str = "1";
+/// Apps whose compressed HTML is above this size don't fit in a link and
+/// have to be exported instead.
+pub const SHARE_LINK_MAX_PAYLOAD_BYTES: usize = 16 * 1024;
+
+#[derive(Clone, serde::Serialize)]
+pub struct ShareLinkFailed {
+    pub url: String,
+    pub error: String,
+}
+
+/// Runs before the bridge script that generation injects, and pins
+/// `TroveStorage` and `TroveFiles` to read-only stubs so the bridge can't
+/// replace them. `__TROVE_SNAPSHOT_DATA__` is swapped for the storage JSON.
+const SNAPSHOT_SCRIPT: &str = r#"<script>
+(function() {
+  var data = __TROVE_SNAPSHOT_DATA__;
+  function copy(value) {
+    return value === undefined ? null : JSON.parse(JSON.stringify(value));
+  }
+  function readOnly() {
+    return Promise.reject(new Error('This is a read-only snapshot'));
+  }
+  function pin(name, value) {
+    Object.defineProperty(window, name, {
+      value: Object.freeze(value),
+      writable: false
+    });
+  }
+  pin('TroveStorage', {
+    get: function(key) {
+      var found = Object.prototype.hasOwnProperty.call(data, key);
+      return Promise.resolve(found ? copy(data[key]) : null);
+    },
+    set: readOnly,
+    delete: readOnly,
+    clear: readOnly,
+    getAll: function() { return Promise.resolve(copy(data)); }
+  });
+  pin('TroveFiles', { requestFolder: readOnly, read: readOnly, download: readOnly });
+})();
+</script>
+"#;
+
+/// Serializes storage for embedding in a `<script>`. `<` is escaped so the
+/// data can't close the script tag, and U+2028/2029 so older engines don't
+/// treat them as line breaks inside string literals.
+fn script_safe_json(storage: &Map<String, Value>) -> Result<String, String> {
+    let json = serde_json::to_string(storage)
+        .map_err(|e| format!("Failed to serialize storage: {}", e))?;
+    Ok(json
+        .replace('<', "\\u003c")
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029"))
+}
+
+/// Embeds `storage` into the app's HTML as read-only data. The script goes
+/// right after `<head>` (or `<html>`) so it runs before any app code.
+fn build_share_snapshot(html: &str, storage: &Map<String, Value>) -> Result<String, String> {
+    let script = SNAPSHOT_SCRIPT.replace("__TROVE_SNAPSHOT_DATA__", &script_safe_json(storage)?);
+    // ASCII lowercasing keeps byte offsets aligned with `html`.
+    let lower = html.to_ascii_lowercase();
+    let tag_end = |name: &str| {
+        find_open_tag(&lower, name)
+            .and_then(|start| lower[start..].find('>').map(|end| start + end + 1))
+    };
+    let insert_at = tag_end("head").or_else(|| tag_end("html")).unwrap_or(0);
+
+    let mut output = html.to_string();
+    output.insert_str(insert_at, &script);
+    Ok(output)
+}
+
+fn compress_share_html(html: &str) -> Result<Vec<u8>, String> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(html.as_bytes())
+        .map_err(|e| format!("Failed to compress app: {}", e))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress app: {}", e))
+}
+
+/// Inflates a link's HTML, refusing anything that expands past what a
+/// generated app may hold.
+fn inflate_share_html(compressed: &[u8]) -> Result<String, String> {
+    let mut html = Vec::new();
+    DeflateDecoder::new(compressed)
+        .take(MAX_HTML_BYTES as u64 + 1)
+        .read_to_end(&mut html)
+        .map_err(|_| "Share link content is corrupt".to_string())?;
+    if html.len() > MAX_HTML_BYTES {
+        return Err("Share link content is too large".to_string());
+    }
+    String::from_utf8(html).map_err(|_| "Share link content is not valid UTF-8".to_string())
+}
+
+fn parse_share_link(url: &str) -> Result<HashMap<String, String>, String> {
+    let query = url
+        .strip_prefix(SHARE_LINK_PREFIX)
+        .ok_or("Not a Trove share link")?;
+
+    let mut params = HashMap::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        params.insert(percent_decode(key)?, percent_decode(value)?);
+    }
+    Ok(params)
+}
+
+/// Builds a `trove://import` link embedding the app's deflated HTML, so
+/// small apps can be shared between Trove users with a single link.
+#[tauri::command]
+pub fn get_app_share_link(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+) -> Result<String, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, app.id)?;
+    let html = fs::read_to_string(get_app_html_path(&app_handle, app.id)?)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+    let compressed = compress_share_html(&html)?;
+    if compressed.len() > SHARE_LINK_MAX_PAYLOAD_BYTES {
+        return Err(format!(
+            "App is too large to share as a link (max {} KB compressed); export it instead",
+            SHARE_LINK_MAX_PAYLOAD_BYTES / 1024
+        ));
+    }
+
+    Ok(format!(
+        "{}v={}&name={}&emoji={}&color={}&prompt={}&html={}",
+        SHARE_LINK_PREFIX,
+        SHARE_LINK_VERSION,
+        percent_encode(&app.name),
+        percent_encode(&app.emoji),
+        percent_encode(&app.background_color),
+        percent_encode(&app.prompt),
+        base64_url_encode(&compressed)
+    ))
+}
+
+/// Writes the app's current HTML with a frozen copy of its storage to a
+/// standalone file that opens in any browser. Storage reads come from the
+/// embedded copy and every write is rejected, so the recipient can look but
+/// not change anything.
+#[tauri::command]
+pub fn export_snapshot(
+    app_handle: AppHandle,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+    dest: String,
+) -> Result<(), String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    ensure_unlocked(&app_handle, &unlocked, app.id)?;
+    let html = fs::read_to_string(get_app_html_path(&app_handle, app.id)?)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+    let storage = load_storage(&app_handle, &app.id.to_string())?;
+
+    write_atomic(Path::new(&dest), &build_share_snapshot(&html, &storage)?)
+}
+
+/// Imports a `trove://import` link, whether pasted or opened through the
+/// deep-link handler. The embedded app is imported quarantined like any
+/// other untrusted HTML.
+#[tauri::command]
+pub fn import_share_link(app_handle: AppHandle, url: String) -> Result<AppMetadata, String> {
+    let params = parse_share_link(url.trim())?;
+    let compressed = match params.get("v").map(String::as_str) {
+        Some(SHARE_LINK_VERSION) => true,
+        Some(SHARE_LINK_LEGACY_VERSION) => false,
+        _ => return Err("Unsupported share link version".to_string()),
+    };
+
+    let encoded_html = params.get("html").ok_or("Share link has no app content")?;
+    if encoded_html.len() > SHARE_LINK_MAX_PAYLOAD_BYTES * 4 / 3 + 4 {
+        return Err("Share link content is too large".to_string());
+    }
+    let payload = base64_url_decode(encoded_html)?;
+    let html = if compressed {
+        inflate_share_html(&payload)?
+    } else {
+        String::from_utf8(payload)
+            .map_err(|_| "Share link content is not valid UTF-8".to_string())?
+    };
+
+    let name = sanitize_import_name(params.get("name").map(String::as_str).unwrap_or_default());
+    let prompt = params
+        .get("prompt")
+        .map(|prompt| prompt.trim().to_string())
+        .filter(|prompt| !prompt.is_empty())
+        .unwrap_or_else(|| "Imported from a share link".to_string());
+    let emoji = sanitize_import_emoji(params.get("emoji").map(String::as_str).unwrap_or_default());
+    let background_color =
+        sanitize_import_color(params.get("color").map(String::as_str).unwrap_or_default());
+
+    let app = AppMetadata::new(name, prompt, emoji, background_color);
+    let origin = AppOrigin::Imported {
+        source: "share_link".to_string(),
+    };
+    import_html(&app_handle, app, &html, origin)
+}
+
+/// Offers to import every `trove://` link the OS opens Trove with, since any
+/// web page can open one. New apps show up through `app-added`; links that
+/// fail are sent as `share-link-failed`.
+pub fn start_share_link_handler(app_handle: &AppHandle) {
+    // macOS registers the scheme from the bundle; elsewhere it happens here.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(err) = app_handle.deep_link().register_all() {
+        eprintln!("Failed to register the trove:// scheme: {}", err);
+    }
+
+    let handle = app_handle.clone();
+    app_handle.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            import_opened_link(&handle, url.as_str());
+        }
+    });
+    // On macOS the link that launched the app also arrives as an event.
+    #[cfg(not(target_os = "macos"))]
+    if let Ok(Some(urls)) = app_handle.deep_link().get_current() {
+        for url in urls {
+            import_opened_link(app_handle, url.as_str());
+        }
+    }
+}
+
+/// Asks the user before importing an opened link; nothing is written if
+/// they decline.
+fn import_opened_link(app_handle: &AppHandle, url: &str) {
+    let name = match parse_share_link(url.trim()) {
+        Ok(params) => {
+            sanitize_import_name(params.get("name").map(String::as_str).unwrap_or_default())
+        }
+        Err(error) => {
+            report_failed_link(app_handle, url, error);
+            return;
+        }
+    };
+
+    let handle = app_handle.clone();
+    let url = url.to_string();
+    app_handle
+        .dialog()
+        .message(format!(
+            "Import \"{}\" from a share link? It will open quarantined until you trust it.",
+            name
+        ))
+        .title("Import shared app")
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Import".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            if let Err(error) = import_share_link(handle.clone(), url.clone()) {
+                report_failed_link(&handle, &url, error);
+            }
+        });
+}
+
+fn report_failed_link(app_handle: &AppHandle, url: &str, error: String) {
+    let failed = ShareLinkFailed {
+        url: url.to_string(),
+        error,
+    };
+    let _ = app_handle.emit("share-link-failed", failed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_share_snapshot, compress_share_html, inflate_share_html, parse_share_link};
+    use serde_json::{json, Map};
+
+    #[test]
+    fn parses_share_link_params() {
+        let params = parse_share_link("trove://import?v=1&name=My%20App&html=PGh0bWw-")
+            .expect("link should parse");
+        assert_eq!(params.get("v").map(String::as_str), Some("1"));
+        assert_eq!(params.get("name").map(String::as_str), Some("My App"));
+        assert_eq!(params.get("html").map(String::as_str), Some("PGh0bWw-"));
+    }
+
+    #[test]
+    fn share_html_round_trips_through_compression() {
+        let html = "<html><body>".to_string() + &"<p>hello</p>".repeat(500) + "</body></html>";
+        let compressed = compress_share_html(&html).expect("HTML should compress");
+        assert!(compressed.len() < html.len() / 10);
+        assert_eq!(inflate_share_html(&compressed), Ok(html));
+        assert!(inflate_share_html(b"not deflate").is_err());
+    }
+
+    #[test]
+    fn snapshot_embeds_escaped_data_before_app_scripts() {
+        let mut storage = Map::new();
+        storage.insert("note".to_string(), json!("</script><b>"));
+        let html = "<!DOCTYPE html><html><HEAD><script>app()</script></head></html>";
+
+        let snapshot = build_share_snapshot(html, &storage).expect("snapshot should build");
+
+        let data_at = snapshot.find(r#"{"note":"\u003c/script>\u003cb>"}"#);
+        let app_at = snapshot.find("app()");
+        assert!(snapshot.starts_with("<!DOCTYPE html><html><HEAD><script>"));
+        assert!(data_at.is_some() && data_at < app_at);
+        assert_eq!(snapshot.matches("</script>").count(), 2);
+    }
+
+    #[test]
+    fn rejects_other_schemes() {
+        assert!(parse_share_link("https://example.com/import?v=1").is_err());
+    }
+}
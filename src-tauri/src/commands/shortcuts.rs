@@ -0,0 +1,119 @@
+use crate::commands::app_windows::AppWindows;
+use crate::commands::audit::record_bridge_call;
+use crate::models::{Accelerator, AppShortcut, MAX_APP_SHORTCUTS, SHORTCUT_ID_MAX_LENGTH};
+use crate::utils::parse_uuid;
+use tauri::{AppHandle, Emitter, State, Window};
+use uuid::Uuid;
+
+/// Sent to the window whose app registered the shortcut that was pressed.
+#[derive(Clone, serde::Serialize)]
+pub struct AppShortcutPressed {
+    pub app_id: Uuid,
+    pub id: String,
+}
+
+fn register(
+    window: &Window,
+    windows: &AppWindows,
+    app_id: &str,
+    id: &str,
+    accelerator: &str,
+) -> Result<String, String> {
+    if id.is_empty() || id.len() > SHORTCUT_ID_MAX_LENGTH {
+        return Err(format!(
+            "Shortcut id must be 1 to {} characters",
+            SHORTCUT_ID_MAX_LENGTH
+        ));
+    }
+    let accelerator = Accelerator::parse(accelerator)?;
+    windows.with_shortcuts(parse_uuid(app_id)?, window.label(), |shortcuts| {
+        shortcuts.retain(|shortcut| shortcut.id != id);
+        if let Some(taken) = shortcuts.iter().find(|s| s.accelerator == accelerator) {
+            return Err(format!(
+                "{} is already used by shortcut {}",
+                accelerator, taken.id
+            ));
+        }
+        if shortcuts.len() >= MAX_APP_SHORTCUTS {
+            return Err(format!(
+                "At most {} shortcuts can be registered",
+                MAX_APP_SHORTCUTS
+            ));
+        }
+        let normalized = accelerator.to_string();
+        shortcuts.push(AppShortcut {
+            id: id.to_string(),
+            accelerator,
+        });
+        Ok(normalized)
+    })
+}
+
+/// Bridge command: registers a shortcut on the calling window for as long
+/// as it shows the app. Returns the accelerator in the form key presses
+/// are reported in, e.g. "CmdOrCtrl+Shift+K".
+#[tauri::command]
+pub fn register_app_shortcut(
+    app_handle: AppHandle,
+    window: Window,
+    windows: State<'_, AppWindows>,
+    app_id: String,
+    id: String,
+    accelerator: String,
+) -> Result<String, String> {
+    let result = register(&window, &windows, &app_id, &id, &accelerator);
+    record_bridge_call(
+        &app_handle,
+        &app_id,
+        "register_shortcut",
+        Some(&id),
+        result.is_ok(),
+    );
+    result
+}
+
+#[tauri::command]
+pub fn unregister_app_shortcut(
+    window: Window,
+    windows: State<'_, AppWindows>,
+    app_id: String,
+    id: String,
+) -> Result<(), String> {
+    windows.with_shortcuts(parse_uuid(&app_id)?, window.label(), |shortcuts| {
+        shortcuts.retain(|shortcut| shortcut.id != id);
+        Ok(())
+    })
+}
+
+/// Bridge command: reports a key press the runtime caught in the app. If it
+/// matches one of the window's shortcuts, `app-shortcut` is sent to the
+/// window. Returns whether it matched.
+#[tauri::command]
+pub fn app_shortcut_pressed(
+    window: Window,
+    windows: State<'_, AppWindows>,
+    app_id: String,
+    accelerator: String,
+) -> Result<bool, String> {
+    let app_id = parse_uuid(&app_id)?;
+    let Ok(accelerator) = Accelerator::parse(&accelerator) else {
+        return Ok(false);
+    };
+    let matched = windows.with_shortcuts(app_id, window.label(), |shortcuts| {
+        Ok(shortcuts
+            .iter()
+            .find(|shortcut| shortcut.accelerator == accelerator)
+            .map(|shortcut| shortcut.id.clone()))
+    })?;
+    let Some(id) = matched else {
+        return Ok(false);
+    };
+    window
+        .emit_to(
+            window.label(),
+            "app-shortcut",
+            AppShortcutPressed { app_id, id },
+        )
+        .map_err(|e| format!("Failed to deliver shortcut: {}", e))?;
+    Ok(true)
+}
@@ -0,0 +1,140 @@
+use crate::commands::apps::get_visible_app;
+use crate::commands::audit::record_bridge_call;
+use crate::utils::parse_uuid;
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, Sink};
+use serde::Deserialize;
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::AppHandle;
+
+const SAMPLE_RATE: u32 = 44_100;
+/// Further requests are dropped while this many sounds are playing, so a
+/// looping app can't pile them up.
+const MAX_CONCURRENT_SOUNDS: usize = 4;
+
+static PLAYING_SOUNDS: AtomicUsize = AtomicUsize::new(0);
+
+/// The sounds apps can play with `TroveApp.playSound`. They are synthesized
+/// rather than shipped as files, and played from Rust because webview
+/// autoplay rules block audio that doesn't follow a click, such as a
+/// timer's ding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppSound {
+    Ding,
+    Chime,
+    Success,
+    Alert,
+    Tick,
+}
+
+impl AppSound {
+    /// Notes as (frequency in Hz, start in ms, length in ms).
+    fn notes(self) -> &'static [(f32, u32, u32)] {
+        match self {
+            AppSound::Ding => &[(1318.5, 0, 900)],
+            AppSound::Chime => &[(1046.5, 0, 700), (1318.5, 150, 700), (1568.0, 300, 900)],
+            AppSound::Success => &[(784.0, 0, 180), (1046.5, 140, 500)],
+            AppSound::Alert => &[(880.0, 0, 160), (880.0, 220, 160), (880.0, 440, 160)],
+            AppSound::Tick => &[(2000.0, 0, 40)],
+        }
+    }
+}
+
+/// Renders a sound as mono samples: each note is a sine with a short
+/// attack and an exponential decay, mixed and kept within [-1, 1].
+fn synthesize(sound: AppSound) -> Vec<f32> {
+    let notes = sound.notes();
+    let ms_to_samples = |ms: u32| (ms as u64 * SAMPLE_RATE as u64 / 1000) as usize;
+    let total = notes
+        .iter()
+        .map(|(_, start, length)| ms_to_samples(start + length))
+        .max()
+        .unwrap_or(0);
+    let mut samples = vec![0.0f32; total];
+    let attack = ms_to_samples(5).max(1);
+
+    for &(frequency, start, length) in notes {
+        let offset = ms_to_samples(start);
+        let count = ms_to_samples(length);
+        for i in 0..count {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let envelope = (i as f32 / attack as f32).min(1.0)
+                * (-5.0 * i as f32 / count as f32).exp();
+            samples[offset + i] += (TAU * frequency * t).sin() * envelope;
+        }
+    }
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 1.0 {
+        samples.iter_mut().for_each(|s| *s /= peak);
+    }
+    samples
+}
+
+fn play(sound: AppSound, volume: f32) -> Result<(), String> {
+    let (_stream, handle) =
+        OutputStream::try_default().map_err(|e| format!("No audio output: {}", e))?;
+    let sink = Sink::try_new(&handle).map_err(|e| format!("Failed to play sound: {}", e))?;
+    sink.set_volume(volume);
+    sink.append(SamplesBuffer::new(1, SAMPLE_RATE, synthesize(sound)));
+    sink.sleep_until_end();
+    Ok(())
+}
+
+fn start_sound(
+    app_handle: &AppHandle,
+    app_id: &str,
+    sound: AppSound,
+    volume: Option<f32>,
+) -> Result<(), String> {
+    get_visible_app(app_handle, parse_uuid(app_id)?)?;
+    let volume = volume.unwrap_or(1.0);
+    if !(0.0..=1.0).contains(&volume) {
+        return Err("Volume must be between 0 and 1".to_string());
+    }
+    if PLAYING_SOUNDS.fetch_add(1, Ordering::SeqCst) >= MAX_CONCURRENT_SOUNDS {
+        PLAYING_SOUNDS.fetch_sub(1, Ordering::SeqCst);
+        return Ok(());
+    }
+    // The output stream isn't Send, so it lives and dies on this thread.
+    std::thread::spawn(move || {
+        if let Err(err) = play(sound, volume) {
+            eprintln!("Failed to play sound: {}", err);
+        }
+        PLAYING_SOUNDS.fetch_sub(1, Ordering::SeqCst);
+    });
+    Ok(())
+}
+
+/// Bridge command: plays one of the built-in sounds at `volume` (0 to 1,
+/// default 1) without waiting for it to finish. A sound dropped because
+/// too many are playing is not an error.
+#[tauri::command]
+pub fn app_play_sound(
+    app_handle: AppHandle,
+    app_id: String,
+    sound_id: AppSound,
+    volume: Option<f32>,
+) -> Result<(), String> {
+    let result = start_sound(&app_handle, &app_id, sound_id, volume);
+    record_bridge_call(&app_handle, &app_id, "play_sound", None, result.is_ok());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{synthesize, AppSound, SAMPLE_RATE};
+
+    #[test]
+    fn synthesized_sounds_fit_their_notes_and_never_clip() {
+        let chime = synthesize(AppSound::Chime);
+        assert_eq!(chime.len(), (1200 * SAMPLE_RATE / 1000) as usize);
+        for sound in [AppSound::Ding, AppSound::Chime, AppSound::Success, AppSound::Alert] {
+            let samples = synthesize(sound);
+            assert!(samples.iter().all(|s| s.abs() <= 1.0));
+            assert!(samples.iter().any(|s| s.abs() > 0.5));
+        }
+    }
+}
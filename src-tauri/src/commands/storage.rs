@@ -1,95 +1,649 @@
-use crate::commands::apps::get_apps_dir_path;
-use crate::utils::{parse_uuid, write_atomic};
+use crate::commands::app_windows::{emit_to_app_windows, AppWindows, StorageChanged};
+use crate::commands::apps::{
+    ensure_app_trusted, get_app_file_path, get_visible_app, update_index,
+};
+use crate::commands::audit::record_bridge_call;
+use crate::commands::confirm::{redeem_confirmation, ConfirmationTokens, DestructiveAction};
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::csv::{csv_to_rows, rows_to_csv};
+use crate::ics::records_to_ics;
+use crate::models::{
+    AppMetadata, DeletedStorage, DeletedStorageEntry, IcsFieldMapping, StorageFormat,
+};
+use crate::msgpack::{from_msgpack, to_msgpack};
+use crate::utils::{chunk_str, parse_uuid, write_atomic, write_atomic_bytes};
+use chrono::Utc;
 use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tauri::AppHandle;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State, Window};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use uuid::Uuid;
 
-fn get_storage_path(app_handle: &AppHandle, app_id: &str) -> Result<PathBuf, String> {
+/// Largest CSV file `storage_import_csv` will read.
+const CSV_IMPORT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+/// `storage_get_stream` sends values in pieces of at most this many bytes.
+const STORAGE_STREAM_CHUNK_BYTES: usize = 256 * 1024;
+
+/// One piece of a value's JSON text from `storage_get_stream`.
+#[derive(Clone, serde::Serialize)]
+pub struct StorageChunk {
+    pub stream_id: String,
+    pub index: usize,
+    pub data: String,
+    pub done: bool,
+}
+
+/// Per-app locks serializing storage load/modify/save cycles, so rapid
+/// concurrent writes from one app can't interleave and drop updates.
+/// Keyed by the parsed id, so every spelling `parse_uuid` accepts for an app
+/// shares one lock, and ids that don't parse never get an entry.
+#[derive(Default)]
+pub struct StorageLocks {
+    locks: Mutex<HashMap<Uuid, Arc<AsyncMutex<()>>>>,
+}
+
+impl StorageLocks {
+    pub async fn lock(&self, app_id: Uuid) -> OwnedMutexGuard<()> {
+        self.entry(app_id).lock_owned().await
+    }
+
+    /// Takes the app's lock only if nobody holds it, for background work
+    /// that can come back later.
+    pub fn try_lock(&self, app_id: Uuid) -> Option<OwnedMutexGuard<()>> {
+        self.entry(app_id).try_lock_owned().ok()
+    }
+
+    fn entry(&self, app_id: Uuid) -> Arc<AsyncMutex<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.entry(app_id).or_default().clone()
+    }
+}
+
+pub(crate) fn get_storage_path(app_handle: &AppHandle, app_id: &str) -> Result<PathBuf, String> {
     // Validate app_id is a valid UUID to prevent path traversal
-    parse_uuid(app_id)?;
-    let apps_dir = get_apps_dir_path(app_handle)?;
-    Ok(apps_dir.join(format!("{}.data.json", app_id)))
+    get_app_file_path(app_handle, parse_uuid(app_id)?, "data.json")
+}
+
+/// Where storage lives once the app has switched to `StorageFormat::MessagePack`.
+pub(crate) fn get_msgpack_storage_path(
+    app_handle: &AppHandle,
+    app_id: &str,
+) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, parse_uuid(app_id)?, "data.msgpack")
+}
+
+/// The format the app's storage is currently written in. A MessagePack
+/// file wins over a JSON one, since a migration writes it before removing
+/// the JSON file.
+fn stored_format(app_handle: &AppHandle, app_id: &str) -> Result<Option<StorageFormat>, String> {
+    if get_msgpack_storage_path(app_handle, app_id)?.exists() {
+        Ok(Some(StorageFormat::MessagePack))
+    } else if get_storage_path(app_handle, app_id)?.exists() {
+        Ok(Some(StorageFormat::Json))
+    } else {
+        Ok(None)
+    }
+}
+
+pub(crate) fn storage_exists(app_handle: &AppHandle, app_id: &str) -> Result<bool, String> {
+    Ok(stored_format(app_handle, app_id)?.is_some())
+}
+
+pub(crate) fn load_storage(
+    app_handle: &AppHandle,
+    app_id: &str,
+) -> Result<Map<String, Value>, String> {
+    match stored_format(app_handle, app_id)? {
+        None => Ok(Map::new()),
+        Some(StorageFormat::Json) => {
+            let content = fs::read_to_string(get_storage_path(app_handle, app_id)?)
+                .map_err(|e| format!("Failed to read storage file: {}", e))?;
+
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse storage file: {}", e))
+        }
+        Some(StorageFormat::MessagePack) => {
+            let bytes = fs::read(get_msgpack_storage_path(app_handle, app_id)?)
+                .map_err(|e| format!("Failed to read storage file: {}", e))?;
+            match from_msgpack(&bytes) {
+                Ok(Value::Object(map)) => Ok(map),
+                Ok(_) => Err("Failed to parse storage file: not an object".to_string()),
+                Err(e) => Err(format!("Failed to parse storage file: {}", e)),
+            }
+        }
+    }
+}
+
+/// Writes storage in the format it is already kept in, JSON for new apps.
+pub(crate) fn save_storage(
+    app_handle: &AppHandle,
+    app_id: &str,
+    data: &Map<String, Value>,
+) -> Result<(), String> {
+    let format = stored_format(app_handle, app_id)?.unwrap_or_default();
+    save_storage_as(app_handle, app_id, data, format)
+}
+
+/// Writes storage in `format` and then removes the file in the other one,
+/// so an interrupted migration still loads the complete data.
+fn save_storage_as(
+    app_handle: &AppHandle,
+    app_id: &str,
+    data: &Map<String, Value>,
+    format: StorageFormat,
+) -> Result<(), String> {
+    let json_path = get_storage_path(app_handle, app_id)?;
+    let msgpack_path = get_msgpack_storage_path(app_handle, app_id)?;
+    let stale_path = match format {
+        StorageFormat::Json => {
+            let content = serde_json::to_string_pretty(data)
+                .map_err(|e| format!("Failed to serialize storage: {}", e))?;
+            write_atomic(&json_path, &content)?;
+            msgpack_path
+        }
+        StorageFormat::MessagePack => {
+            let bytes = to_msgpack(&Value::Object(data.clone()))
+                .map_err(|e| format!("Failed to serialize storage: {}", e))?;
+            write_atomic_bytes(&msgpack_path, &bytes)?;
+            json_path
+        }
+    };
+    if stale_path.exists() {
+        fs::remove_file(&stale_path)
+            .map_err(|e| format!("Failed to remove old storage file: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Rewrites storage in the app's chosen format if it is kept in the other
+/// one, e.g. after a restore or import wrote JSON. Returns whether it did.
+pub(crate) fn migrate_storage_format(
+    app_handle: &AppHandle,
+    app_id: &str,
+    format: StorageFormat,
+) -> Result<bool, String> {
+    match stored_format(app_handle, app_id)? {
+        Some(current) if current != format => {
+            let data = load_storage(app_handle, app_id)?;
+            save_storage_as(app_handle, app_id, &data, format)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+pub(crate) fn get_deleted_storage_path(
+    app_handle: &AppHandle,
+    app_id: &str,
+) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, parse_uuid(app_id)?, "deleted.json")
 }
 
-fn load_storage(app_handle: &AppHandle, app_id: &str) -> Result<Map<String, Value>, String> {
-    let path = get_storage_path(app_handle, app_id)?;
+fn load_deleted_storage(app_handle: &AppHandle, app_id: &str) -> Result<DeletedStorage, String> {
+    let path = get_deleted_storage_path(app_handle, app_id)?;
     if !path.exists() {
-        return Ok(Map::new());
+        return Ok(DeletedStorage::default());
     }
 
     let content = fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read storage file: {}", e))?;
+        .map_err(|e| format!("Failed to read deleted storage: {}", e))?;
 
-    serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse storage file: {}", e))
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse deleted storage: {}", e))
 }
 
-fn save_storage(
+fn save_deleted_storage(
     app_handle: &AppHandle,
     app_id: &str,
-    data: &Map<String, Value>,
+    deleted: &DeletedStorage,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(deleted)
+        .map_err(|e| format!("Failed to serialize deleted storage: {}", e))?;
+
+    write_atomic(&get_deleted_storage_path(app_handle, app_id)?, &content)
+}
+
+/// Stashes removed entries before they are dropped from storage, so a
+/// misfiring "clear" button in a generated app can be undone.
+fn stash_deleted_entries(
+    app_handle: &AppHandle,
+    app_id: &str,
+    entries: Map<String, Value>,
+) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut deleted = load_deleted_storage(app_handle, app_id)?;
+    for (key, value) in entries {
+        deleted.record(key, value);
+    }
+    save_deleted_storage(app_handle, app_id, &deleted)
+}
+
+/// Gates a storage write made for the app and records it in the audit log.
+/// Locked apps must be unlocked first, as for reads.
+fn authorize_write(
+    app_handle: &AppHandle,
+    unlocked: &UnlockedApps,
+    app_id: &str,
+    action: &str,
+    key: Option<&str>,
 ) -> Result<(), String> {
-    let path = get_storage_path(app_handle, app_id)?;
+    let result = ensure_app_trusted(app_handle, app_id)
+        .and_then(|()| ensure_unlocked(app_handle, unlocked, parse_uuid(app_id)?));
+    record_bridge_call(app_handle, app_id, action, key, result.is_ok());
+    result
+}
 
-    let content = serde_json::to_string_pretty(data)
-        .map_err(|e| format!("Failed to serialize storage: {}", e))?;
+/// Like `authorize_write`, for writes from the app's own bridge, which are
+/// also refused while its storage is read-only.
+fn authorize_bridge_write(
+    app_handle: &AppHandle,
+    unlocked: &UnlockedApps,
+    app_id: &str,
+    action: &str,
+    key: Option<&str>,
+) -> Result<(), String> {
+    let result = ensure_app_trusted(app_handle, app_id).and_then(|()| {
+        let uuid = parse_uuid(app_id)?;
+        ensure_unlocked(app_handle, unlocked, uuid)?;
+        let app = get_visible_app(app_handle, uuid)?;
+        if app.storage_read_only {
+            return Err("App storage is read-only".to_string());
+        }
+        Ok(())
+    });
+    record_bridge_call(app_handle, app_id, action, key, result.is_ok());
+    result
+}
 
-    write_atomic(&path, &content)
+/// Tells the windows showing the app that its data changed. Bridge writes
+/// skip the window they came from, which already has the new value.
+fn notify_storage_changed(
+    app_handle: &AppHandle,
+    windows: &AppWindows,
+    app_id: &str,
+    key: Option<String>,
+    except: Option<&Window>,
+) {
+    let Ok(app_id) = parse_uuid(app_id) else {
+        return;
+    };
+    emit_to_app_windows(
+        app_handle,
+        windows,
+        app_id,
+        except.map(|window| window.label()),
+        "storage-changed",
+        StorageChanged { app_id, key },
+    );
 }
 
+/// Reads one key. Like the other reads, refused while the app is locked.
 #[tauri::command]
-pub fn storage_get(
+pub async fn storage_get(
     app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
     app_id: String,
     key: String,
 ) -> Result<Option<Value>, String> {
+    let uuid = parse_uuid(&app_id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+    let _lock = locks.lock(uuid).await;
     let storage = load_storage(&app_handle, &app_id)?;
     Ok(storage.get(&key).cloned())
 }
 
 #[tauri::command]
-pub fn storage_set(
+#[allow(clippy::too_many_arguments)]
+pub async fn storage_set(
     app_handle: AppHandle,
+    window: Window,
+    locks: State<'_, StorageLocks>,
+    windows: State<'_, AppWindows>,
+    unlocked: State<'_, UnlockedApps>,
     app_id: String,
     key: String,
     value: Value,
 ) -> Result<(), String> {
+    authorize_bridge_write(&app_handle, &unlocked, &app_id, "storage.set", Some(&key))?;
+    let _lock = locks.lock(parse_uuid(&app_id)?).await;
     let mut storage = load_storage(&app_handle, &app_id)?;
-    storage.insert(key, value);
-    save_storage(&app_handle, &app_id, &storage)
+    storage.insert(key.clone(), value);
+    save_storage(&app_handle, &app_id, &storage)?;
+    notify_storage_changed(&app_handle, &windows, &app_id, Some(key), Some(&window));
+    Ok(())
 }
 
 #[tauri::command]
-pub fn storage_delete(
+pub async fn storage_delete(
     app_handle: AppHandle,
+    window: Window,
+    locks: State<'_, StorageLocks>,
+    windows: State<'_, AppWindows>,
+    unlocked: State<'_, UnlockedApps>,
     app_id: String,
     key: String,
 ) -> Result<(), String> {
+    authorize_bridge_write(&app_handle, &unlocked, &app_id, "storage.delete", Some(&key))?;
+    let _lock = locks.lock(parse_uuid(&app_id)?).await;
     let mut storage = load_storage(&app_handle, &app_id)?;
-    storage.remove(&key);
-    save_storage(&app_handle, &app_id, &storage)
+    let Some(value) = storage.remove(&key) else {
+        return Ok(());
+    };
+    stash_deleted_entries(&app_handle, &app_id, Map::from_iter([(key.clone(), value)]))?;
+    save_storage(&app_handle, &app_id, &storage)?;
+    notify_storage_changed(&app_handle, &windows, &app_id, Some(key), Some(&window));
+    Ok(())
+}
+
+/// Bridge command: clears the app's own storage. Trove's UI uses
+/// `clear_app_storage` instead.
+#[tauri::command]
+pub async fn storage_clear(
+    app_handle: AppHandle,
+    window: Window,
+    locks: State<'_, StorageLocks>,
+    windows: State<'_, AppWindows>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+) -> Result<(), String> {
+    authorize_bridge_write(&app_handle, &unlocked, &app_id, "storage.clear", None)?;
+    let _lock = locks.lock(parse_uuid(&app_id)?).await;
+    clear_storage(&app_handle, &app_id)?;
+    notify_storage_changed(&app_handle, &windows, &app_id, None, Some(&window));
+    Ok(())
+}
+
+/// Clears an app's storage from Trove's UI, with a token from
+/// `request_delete_confirmation`. Like other management commands, it works
+/// on quarantined apps and read-only storage.
+#[tauri::command]
+pub async fn clear_app_storage(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    windows: State<'_, AppWindows>,
+    tokens: State<'_, ConfirmationTokens>,
+    id: String,
+    confirm_token: String,
+) -> Result<(), String> {
+    redeem_confirmation(&tokens, &confirm_token, DestructiveAction::StorageClear, &id)?;
+    let _lock = locks.lock(parse_uuid(&id)?).await;
+    clear_storage(&app_handle, &id)?;
+    notify_storage_changed(&app_handle, &windows, &id, None, None);
+    Ok(())
 }
 
+/// Deletes the storage file, keeping its entries recoverable. Callers hold
+/// the app's storage lock.
+fn clear_storage(app_handle: &AppHandle, app_id: &str) -> Result<(), String> {
+    let storage = load_storage(app_handle, app_id)?;
+    stash_deleted_entries(app_handle, app_id, storage)?;
+    delete_storage_file(app_handle, app_id)
+}
+
+/// Sends the key's value to the calling window as `storage-chunk` events
+/// carrying its JSON text, so a multi-megabyte value isn't serialized into a
+/// single IPC message. The caller picks `stream_id` and listens before
+/// invoking; the last chunk has `done` set. A missing key streams `null`.
+/// Returns the JSON's length in bytes.
 #[tauri::command]
-pub fn storage_clear(app_handle: AppHandle, app_id: String) -> Result<(), String> {
-    delete_storage_file(&app_handle, &app_id)
+pub async fn storage_get_stream(
+    app_handle: AppHandle,
+    window: Window,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+    key: String,
+    stream_id: String,
+) -> Result<usize, String> {
+    parse_uuid(&stream_id)?;
+    let uuid = parse_uuid(&app_id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+    let json = {
+        let _lock = locks.lock(uuid).await;
+        let storage = load_storage(&app_handle, &app_id)?;
+        serde_json::to_string(storage.get(&key).unwrap_or(&Value::Null))
+            .map_err(|e| format!("Failed to serialize storage value: {}", e))?
+    };
+
+    let chunks = chunk_str(&json, STORAGE_STREAM_CHUNK_BYTES);
+    let last = chunks.len() - 1;
+    for (index, data) in chunks.into_iter().enumerate() {
+        window
+            .emit_to(
+                window.label(),
+                "storage-chunk",
+                StorageChunk {
+                    stream_id: stream_id.clone(),
+                    index,
+                    data: data.to_string(),
+                    done: index == last,
+                },
+            )
+            .map_err(|e| format!("Failed to stream storage value: {}", e))?;
+    }
+    Ok(json.len())
 }
 
 #[tauri::command]
-pub fn storage_get_all(
+pub async fn storage_get_all(
     app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
     app_id: String,
 ) -> Result<HashMap<String, Value>, String> {
+    let uuid = parse_uuid(&app_id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+    let _lock = locks.lock(uuid).await;
     let storage = load_storage(&app_handle, &app_id)?;
     Ok(storage.into_iter().collect())
 }
 
+/// Lists recently deleted keys, most recent first.
+#[tauri::command]
+pub async fn storage_list_deleted(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+) -> Result<Vec<DeletedStorageEntry>, String> {
+    let uuid = parse_uuid(&app_id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+    let _lock = locks.lock(uuid).await;
+    let mut entries = load_deleted_storage(&app_handle, &app_id)?.entries;
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Puts a deleted key back into the app's storage and returns its value. A
+/// value written to the key since the deletion is stashed in its place, so
+/// restoring can itself be undone. Locked apps must be unlocked first.
+#[tauri::command]
+pub async fn storage_undelete(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    windows: State<'_, AppWindows>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+    key: String,
+) -> Result<Value, String> {
+    let uuid = parse_uuid(&app_id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+    let _lock = locks.lock(uuid).await;
+    let mut deleted = load_deleted_storage(&app_handle, &app_id)?;
+    let entry = deleted
+        .take(&key)
+        .ok_or_else(|| format!("No deleted value for key: {}", key))?;
+
+    let mut storage = load_storage(&app_handle, &app_id)?;
+    if let Some(current) = storage.insert(key.clone(), entry.value.clone()) {
+        deleted.record(key.clone(), current);
+    }
+    save_storage(&app_handle, &app_id, &storage)?;
+    save_deleted_storage(&app_handle, &app_id, &deleted)?;
+    notify_storage_changed(&app_handle, &windows, &app_id, Some(key), None);
+    Ok(entry.value)
+}
+
+/// The key's value as CSV, for opening tracker and logger data in a
+/// spreadsheet. The value must be an array of objects. Locked apps must be
+/// unlocked first.
+#[tauri::command]
+pub async fn storage_export_csv(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+    key: String,
+) -> Result<String, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&app_id)?)?;
+    ensure_unlocked(&app_handle, &unlocked, app.id)?;
+    let _lock = locks.lock(app.id).await;
+    let storage = load_storage(&app_handle, &app_id)?;
+    let value = storage
+        .get(&key)
+        .ok_or_else(|| format!("No value for key: {}", key))?;
+    rows_to_csv(value)
+}
+
+/// Replaces the key's value with the rows of the CSV file at `path` and
+/// returns how many were imported. `mapping` renames columns to fields and
+/// drops the rest. The previous value is stashed so the import can be undone.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn storage_import_csv(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    windows: State<'_, AppWindows>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+    key: String,
+    path: String,
+    mapping: Option<HashMap<String, String>>,
+) -> Result<usize, String> {
+    authorize_write(&app_handle, &unlocked, &app_id, "storage.set", Some(&key))?;
+    let size = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read CSV file: {}", e))?
+        .len();
+    if size > CSV_IMPORT_MAX_BYTES {
+        return Err(format!(
+            "CSV file is too large ({} MB max)",
+            CSV_IMPORT_MAX_BYTES / (1024 * 1024)
+        ));
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read CSV file: {}", e))?;
+    let rows = csv_to_rows(&content, mapping.as_ref())?;
+    let count = rows.len();
+
+    let _lock = locks.lock(parse_uuid(&app_id)?).await;
+    let mut storage = load_storage(&app_handle, &app_id)?;
+    if let Some(previous) = storage.insert(key.clone(), Value::Array(rows)) {
+        stash_deleted_entries(&app_handle, &app_id, Map::from_iter([(key.clone(), previous)]))?;
+    }
+    save_storage(&app_handle, &app_id, &storage)?;
+    notify_storage_changed(&app_handle, &windows, &app_id, Some(key), None);
+    Ok(count)
+}
+
+/// Writes the key's records to `dest` as an iCalendar file, one event per
+/// record, so planner and habit apps can feed the system calendar. Returns
+/// how many events were written. Locked apps must be unlocked first.
+#[tauri::command]
+pub async fn export_ics(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
+    app_id: String,
+    key: String,
+    field_mapping: IcsFieldMapping,
+    dest: String,
+) -> Result<usize, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&app_id)?)?;
+    ensure_unlocked(&app_handle, &unlocked, app.id)?;
+    let _lock = locks.lock(app.id).await;
+    let storage = load_storage(&app_handle, &app_id)?;
+    let records = storage
+        .get(&key)
+        .ok_or_else(|| format!("No value for key: {}", key))?;
+    let ics = records_to_ics(records, &field_mapping, &app.name, &app_id, Utc::now())?;
+    write_atomic(Path::new(&dest), &ics)?;
+    Ok(records.as_array().map_or(0, Vec::len))
+}
+
 pub fn delete_storage_file(app_handle: &AppHandle, app_id: &str) -> Result<(), String> {
-    let path = get_storage_path(app_handle, app_id)?;
-    if path.exists() {
-        fs::remove_file(&path).map_err(|e| format!("Failed to delete storage file: {}", e))?;
+    for path in [
+        get_storage_path(app_handle, app_id)?,
+        get_msgpack_storage_path(app_handle, app_id)?,
+    ] {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to delete storage file: {}", e))?;
+        }
     }
     Ok(())
 }
+
+/// Chooses how the app's storage is kept on disk and converts the existing
+/// data right away. Bridge reads and writes work the same in either format.
+#[tauri::command]
+pub async fn set_storage_format(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    id: String,
+    format: StorageFormat,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    let _lock = locks.lock(parse_uuid(&id)?).await;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.storage_format = format;
+        let updated_app = app.clone();
+
+        migrate_storage_format(&app_handle, &id, format)?;
+        Ok(updated_app)
+    })
+}
+
+/// Makes the app's storage read-only from its bridge, e.g. for a viewer
+/// generated over a dataset that shouldn't change.
+#[tauri::command]
+pub fn set_storage_read_only(
+    app_handle: AppHandle,
+    id: String,
+    read_only: bool,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.storage_read_only = read_only;
+        Ok(app.clone())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StorageLocks;
+    use crate::utils::parse_uuid;
+
+    #[test]
+    fn every_spelling_of_an_id_shares_one_lock() {
+        let locks = StorageLocks::default();
+        let id = "0b1c3e4a-9f2d-4c6b-8a7e-5d4f3c2b1a09";
+        let _held = locks.try_lock(parse_uuid(id).unwrap()).unwrap();
+        for spelling in [
+            id.to_uppercase(),
+            format!("{{{}}}", id),
+            format!("urn:uuid:{}", id),
+        ] {
+            assert!(locks.try_lock(parse_uuid(&spelling).unwrap()).is_none());
+        }
+    }
+}
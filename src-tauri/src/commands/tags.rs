@@ -0,0 +1,216 @@
+use crate::commands::agent::resolve_sidecar_path;
+use crate::commands::apps::update_index;
+use crate::commands::settings::load_settings;
+use crate::models::{merge_tags, AppMetadata, TagSuggestion};
+use crate::sandbox::scrub_env;
+use crate::utils::parse_uuid;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::CommandEvent;
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::Notify;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+/// Classifying and naming are a single short model turn; anything slower is
+/// dropped rather than left running in the background.
+const SINGLE_TURN_TIMEOUT: Duration = Duration::from_secs(60);
+const SINGLE_TURN_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+#[derive(Clone, serde::Serialize)]
+pub struct TagsSuggested {
+    pub app: AppMetadata,
+}
+
+/// Counts single-turn sidecars (tags, names, icons) running at once. They
+/// share `max_concurrent_generations` but are counted apart from
+/// generations, so a burst of suggestions can't hold up a generation.
+#[derive(Default)]
+pub struct SingleTurnSlots {
+    running: Mutex<usize>,
+    /// Woken whenever a waiting sidecar might be able to start.
+    changed: Notify,
+}
+
+impl SingleTurnSlots {
+    /// Lets waiting sidecars recheck whether they may start, e.g. after the
+    /// concurrency limit was raised.
+    pub(crate) fn reschedule(&self) {
+        self.changed.notify_waiters();
+    }
+
+    fn try_acquire(&self, limit: usize) -> bool {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if *running >= limit {
+            return false;
+        }
+        *running += 1;
+        true
+    }
+}
+
+/// A running single-turn sidecar; dropping it frees the slot.
+struct SingleTurnSlot<'a>(&'a SingleTurnSlots);
+
+impl Drop for SingleTurnSlot<'_> {
+    fn drop(&mut self) {
+        let mut running = self.0.running.lock().unwrap_or_else(|e| e.into_inner());
+        *running = running.saturating_sub(1);
+        drop(running);
+        self.0.reschedule();
+    }
+}
+
+async fn acquire_single_turn_slot(app_handle: &AppHandle) -> Result<SingleTurnSlot<'_>, String> {
+    let slots = app_handle.state::<SingleTurnSlots>().inner();
+    loop {
+        let mut changed = std::pin::pin!(slots.changed.notified());
+        changed.as_mut().enable();
+        // Read on every pass, so a changed limit applies to waiting sidecars.
+        let limit = load_settings(app_handle)?.max_concurrent_generations;
+        if slots.try_acquire(limit) {
+            return Ok(SingleTurnSlot(slots));
+        }
+        changed.await;
+    }
+}
+
+/// Runs the sidecar for a single short model turn with `args` and returns
+/// the payload of the line it prints starting with `prefix`. `label` names
+/// the task in errors. Waits for a free `SingleTurnSlots` slot first.
+pub(crate) async fn run_single_turn_sidecar(
+    app_handle: &AppHandle,
+    args: Vec<String>,
+    prefix: &str,
+    label: &str,
+) -> Result<String, String> {
+    let _slot = acquire_single_turn_slot(app_handle).await?;
+    let sidecar_path = resolve_sidecar_path(app_handle, "trove-sidecar")?;
+    let (mut rx, child) = app_handle
+        .shell()
+        .sidecar(&sidecar_path)
+        .map_err(|e| format!("Failed to create sidecar: {}", e))?
+        .args(args)
+        .env_clear()
+        .envs(scrub_env(std::env::vars()))
+        .current_dir(std::env::temp_dir())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn sidecar: {}", e))?;
+
+    let collect = async {
+        let mut stdout: Vec<u8> = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(chunk) => {
+                    if stdout.len() + chunk.len() > SINGLE_TURN_MAX_OUTPUT_BYTES {
+                        return Err(format!("{} output exceeded size limit", label));
+                    }
+                    stdout.extend_from_slice(&chunk);
+                }
+                CommandEvent::Error(err) => return Err(format!("Sidecar error: {}", err)),
+                CommandEvent::Terminated(_) => break,
+                _ => {}
+            }
+        }
+        Ok(stdout)
+    };
+
+    let stdout = match timeout(SINGLE_TURN_TIMEOUT, collect).await {
+        Ok(result) => result?,
+        Err(_) => {
+            let _ = child.kill();
+            return Err(format!("{} timed out", label));
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&stdout);
+    for line in stdout.lines().map(str::trim) {
+        if let Some(payload) = line.strip_prefix(prefix) {
+            return Ok(payload.to_string());
+        }
+        if let Some(message) = line.strip_prefix("ERROR:") {
+            return Err(message.to_string());
+        }
+    }
+    Err(format!("{} returned nothing", label))
+}
+
+/// Runs the sidecar in classify mode, which prints one `SUGGESTION:` line
+/// with JSON tags and a category for the app's name and prompt.
+async fn run_classifier(
+    app_handle: &AppHandle,
+    name: &str,
+    prompt: &str,
+) -> Result<TagSuggestion, String> {
+    let args = vec!["--classify".to_string(), name.to_string(), prompt.to_string()];
+    let json = run_single_turn_sidecar(app_handle, args, "SUGGESTION:", "Tag suggestion").await?;
+    TagSuggestion::parse(&json)
+}
+
+/// Suggests tags for a freshly generated app in the background and stores
+/// them as pending on the app. Failures are only logged: suggestions are a
+/// nicety and must never affect the generation that triggered them.
+pub(crate) fn spawn_tag_suggestion(app_handle: AppHandle, app: &AppMetadata) {
+    let (id, name, prompt) = (app.id, app.name.clone(), app.prompt.clone());
+    tauri::async_runtime::spawn(async move {
+        let result = match run_classifier(&app_handle, &name, &prompt).await {
+            Ok(suggestion) => store_suggestion(&app_handle, id, suggestion),
+            Err(err) => Err(err),
+        };
+        if let Err(err) = result {
+            eprintln!("Failed to suggest tags for {}: {}", id, err);
+        }
+    });
+}
+
+fn store_suggestion(
+    app_handle: &AppHandle,
+    id: Uuid,
+    suggestion: TagSuggestion,
+) -> Result<(), String> {
+    let updated_app = update_index(app_handle, |index| {
+        // The app may have been deleted while the classifier ran.
+        Ok(index.get_mut(id).map(|app| {
+            app.suggested_tags = Some(suggestion);
+            app.clone()
+        }))
+    })?;
+    if let Some(app) = updated_app {
+        let _ = app_handle.emit("tags-suggested", TagsSuggested { app });
+    }
+    Ok(())
+}
+
+/// Applies an app's pending suggestion: its tags are merged into the app's
+/// tags and its category replaces the current one.
+#[tauri::command]
+pub fn accept_suggested_tags(app_handle: AppHandle, id: String) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        let suggestion = app
+            .suggested_tags
+            .take()
+            .ok_or_else(|| "App has no suggested tags".to_string())?;
+        merge_tags(&mut app.tags, &suggestion.tags);
+        if suggestion.category.is_some() {
+            app.category = suggestion.category;
+        }
+        Ok(app.clone())
+    })
+}
+
+#[tauri::command]
+pub fn dismiss_suggested_tags(app_handle: AppHandle, id: String) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    update_index(&app_handle, |index| {
+        let app = index
+            .get_mut(uuid)
+            .ok_or_else(|| format!("App not found: {}", id))?;
+        app.suggested_tags = None;
+        Ok(app.clone())
+    })
+}
@@ -0,0 +1,146 @@
+use crate::commands::apps::get_active_profile_data_dir;
+use crate::commands::settings::load_settings;
+use crate::models::{
+    parse_template_variables, validate_prompt, PromptTemplate, TemplatesIndex,
+    APP_NAME_MAX_LENGTH,
+};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+fn get_templates_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join("templates.json"))
+}
+
+fn load_templates(app_handle: &AppHandle) -> Result<TemplatesIndex, String> {
+    let path = get_templates_path(app_handle)?;
+    if !path.exists() {
+        return Ok(TemplatesIndex::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read templates: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse templates: {}", e))
+}
+
+fn save_templates(app_handle: &AppHandle, templates: &TemplatesIndex) -> Result<(), String> {
+    let path = get_templates_path(app_handle)?;
+    let content = serde_json::to_string_pretty(templates)
+        .map_err(|e| format!("Failed to serialize templates: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Checks a template's name and prompt and reads its variables from the
+/// `{placeholders}` in the prompt.
+fn build_template(
+    id: Uuid,
+    name: &str,
+    prompt: &str,
+    prompt_max_length: usize,
+) -> Result<PromptTemplate, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.len() > APP_NAME_MAX_LENGTH {
+        return Err(format!(
+            "Template name must be 1 to {} characters",
+            APP_NAME_MAX_LENGTH
+        ));
+    }
+    let prompt = prompt.trim().to_string();
+    validate_prompt(&prompt, prompt_max_length)?;
+    let variables = parse_template_variables(&prompt)?;
+
+    Ok(PromptTemplate {
+        id,
+        name,
+        prompt,
+        variables,
+        saved_at: Utc::now(),
+    })
+}
+
+/// Saves a prompt template, creating it when `id` is `None`.
+#[tauri::command]
+pub fn save_prompt_template(
+    app_handle: AppHandle,
+    id: Option<String>,
+    name: String,
+    prompt: String,
+) -> Result<PromptTemplate, String> {
+    let template_id = match id {
+        Some(id) => parse_uuid(&id)?,
+        None => Uuid::new_v4(),
+    };
+    let prompt_max_length = load_settings(&app_handle)?.prompt_max_length;
+    let template = build_template(template_id, &name, &prompt, prompt_max_length)?;
+
+    let mut templates = load_templates(&app_handle)?;
+    templates.upsert(template.clone())?;
+    save_templates(&app_handle, &templates)?;
+
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn get_prompt_templates(app_handle: AppHandle) -> Result<Vec<PromptTemplate>, String> {
+    Ok(load_templates(&app_handle)?.templates)
+}
+
+/// Rechecks templates from a settings profile as if they were typed here.
+pub(crate) fn validate_imported_templates(
+    imported: &[PromptTemplate],
+    prompt_max_length: usize,
+) -> Result<Vec<PromptTemplate>, String> {
+    imported
+        .iter()
+        .map(|template| {
+            build_template(template.id, &template.name, &template.prompt, prompt_max_length)
+        })
+        .collect()
+}
+
+/// Adds templates to the active profile's, replacing any with the same id.
+pub(crate) fn merge_templates(
+    app_handle: &AppHandle,
+    imported: Vec<PromptTemplate>,
+) -> Result<(), String> {
+    let mut templates = load_templates(app_handle)?;
+    for template in imported {
+        templates.upsert(template)?;
+    }
+    save_templates(app_handle, &templates)
+}
+
+#[tauri::command]
+pub fn delete_prompt_template(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let uuid = parse_uuid(&id)?;
+    let mut templates = load_templates(&app_handle)?;
+    if templates.remove(uuid).is_some() {
+        save_templates(&app_handle, &templates)?;
+    }
+    Ok(())
+}
+
+/// Fills in a template's variables and returns the prompt to generate
+/// from, checked against the same limits as a typed prompt.
+#[tauri::command]
+pub fn instantiate_template(
+    app_handle: AppHandle,
+    id: String,
+    vars: HashMap<String, String>,
+) -> Result<String, String> {
+    let uuid = parse_uuid(&id)?;
+    let templates = load_templates(&app_handle)?;
+    let template = templates
+        .get(uuid)
+        .ok_or_else(|| format!("Template not found: {}", id))?;
+
+    let prompt = template.instantiate(&vars)?;
+    validate_prompt(&prompt, load_settings(&app_handle)?.prompt_max_length)?;
+    Ok(prompt)
+}
@@ -0,0 +1,50 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Theme, Window};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemTheme {
+    Light,
+    Dark,
+}
+
+impl From<Theme> for SystemTheme {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::Dark => SystemTheme::Dark,
+            _ => SystemTheme::Light,
+        }
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct SystemThemeChanged {
+    pub theme: SystemTheme,
+}
+
+/// Every window reports the same appearance change, so remember the last
+/// theme broadcast and only emit when it actually changes.
+static LAST_THEME: Mutex<Option<SystemTheme>> = Mutex::new(None);
+
+/// Broadcasts `system-theme-changed` to all webviews. The host forwards it
+/// into app iframes, whose bridge updates the `data-prefers-trove-theme`
+/// attribute so apps can follow the system theme without polling.
+pub(crate) fn broadcast_system_theme(app_handle: &AppHandle, theme: Theme) {
+    let theme = SystemTheme::from(theme);
+    if let Ok(mut last) = LAST_THEME.lock() {
+        if *last == Some(theme) {
+            return;
+        }
+        *last = Some(theme);
+    }
+    let _ = app_handle.emit("system-theme-changed", SystemThemeChanged { theme });
+}
+
+/// Current appearance, for seeding apps before any change event arrives.
+#[tauri::command]
+pub fn get_system_theme(window: Window) -> Result<SystemTheme, String> {
+    window
+        .theme()
+        .map(SystemTheme::from)
+        .map_err(|e| format!("Failed to read system theme: {}", e))
+}
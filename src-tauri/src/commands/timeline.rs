@@ -0,0 +1,55 @@
+use crate::commands::apps::{get_app_file_path, get_app_internal};
+use crate::models::{GenerationTimeline, GenerationTimelines};
+use crate::utils::write_atomic;
+use std::fs;
+use std::path::PathBuf;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+pub(crate) fn get_timeline_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "timeline.json")
+}
+
+pub(crate) fn load_timelines(
+    app_handle: &AppHandle,
+    id: Uuid,
+) -> Result<GenerationTimelines, String> {
+    let path = get_timeline_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(GenerationTimelines::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read generation timeline: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse generation timeline: {}", e))
+}
+
+/// Appends a generation run to the app's timeline. Failures are logged
+/// rather than surfaced so diagnostics never fail a generation.
+pub(crate) fn record_generation_timeline(
+    app_handle: &AppHandle,
+    id: Uuid,
+    timeline: GenerationTimeline,
+) {
+    let result = load_timelines(app_handle, id).and_then(|mut timelines| {
+        timelines.push(timeline);
+        let content = serde_json::to_string_pretty(&timelines)
+            .map_err(|e| format!("Failed to serialize generation timeline: {}", e))?;
+        write_atomic(&get_timeline_path(app_handle, id)?, &content)
+    });
+    if let Err(err) = result {
+        eprintln!("Failed to record generation timeline for {}: {}", id, err);
+    }
+}
+
+/// Returns the recorded sidecar runs for an app, oldest first.
+#[tauri::command]
+pub fn get_generation_timeline(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<Vec<GenerationTimeline>, String> {
+    let app = get_app_internal(&app_handle, &id)?;
+    Ok(load_timelines(&app_handle, app.id)?.runs)
+}
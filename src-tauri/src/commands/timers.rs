@@ -0,0 +1,203 @@
+use crate::commands::apps::{get_active_profile_data_dir, get_visible_app};
+use crate::commands::audit::record_bridge_call;
+use crate::models::{
+    AppTimer, TimersIndex, TIMER_ID_MAX_LENGTH, TIMER_LABEL_MAX_LENGTH, TIMER_MAX_DURATION_SECS,
+};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::{Duration as ChronoDuration, Utc};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use tokio::time::{sleep, Duration};
+use uuid::Uuid;
+
+const TIMER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Serializes load/modify/save of the timers file between the commands and
+/// the background task.
+static TIMERS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Sent to every window when an app's timer runs out, whether or not the
+/// app is still open.
+#[derive(Clone, serde::Serialize)]
+pub struct TimerFinished {
+    pub app_id: Uuid,
+    pub id: String,
+    pub label: Option<String>,
+}
+
+/// Timers belong to the active profile, like the apps that started them.
+fn get_timers_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join("timers.json"))
+}
+
+fn load_timers(app_handle: &AppHandle) -> Result<TimersIndex, String> {
+    let path = get_timers_path(app_handle)?;
+    if !path.exists() {
+        return Ok(TimersIndex::default());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read timers: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse timers: {}", e))
+}
+
+fn save_timers(app_handle: &AppHandle, timers: &TimersIndex) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(timers)
+        .map_err(|e| format!("Failed to serialize timers: {}", e))?;
+
+    write_atomic(&get_timers_path(app_handle)?, &content)
+}
+
+fn validate_timer(id: &str, duration_secs: u64, label: Option<&str>) -> Result<(), String> {
+    if id.is_empty() || id.len() > TIMER_ID_MAX_LENGTH {
+        return Err(format!(
+            "Timer id must be 1 to {} characters",
+            TIMER_ID_MAX_LENGTH
+        ));
+    }
+    if duration_secs == 0 || duration_secs > TIMER_MAX_DURATION_SECS {
+        return Err(format!(
+            "Timer duration must be between 1 and {} seconds",
+            TIMER_MAX_DURATION_SECS
+        ));
+    }
+    if let Some(label) = label {
+        if label.chars().count() > TIMER_LABEL_MAX_LENGTH || label.chars().any(char::is_control) {
+            return Err(format!(
+                "Timer label must be at most {} characters on one line",
+                TIMER_LABEL_MAX_LENGTH
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn start(
+    app_handle: &AppHandle,
+    app_id: &str,
+    id: String,
+    duration_secs: u64,
+    label: Option<String>,
+) -> Result<AppTimer, String> {
+    let app = get_visible_app(app_handle, parse_uuid(app_id)?)?;
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    validate_timer(&id, duration_secs, label.as_deref())?;
+
+    let now = Utc::now();
+    let timer = AppTimer {
+        app_id: app.id,
+        id,
+        label,
+        duration_secs,
+        started_at: now,
+        ends_at: now + ChronoDuration::seconds(duration_secs as i64),
+    };
+    let _lock = TIMERS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut timers = load_timers(app_handle)?;
+    timers.start(timer.clone())?;
+    save_timers(app_handle, &timers)?;
+    Ok(timer)
+}
+
+/// Bridge command: starts (or restarts) the app's timer `id`, which keeps
+/// counting in Trove after the app closes. When it runs out Trove shows a
+/// notification and sends `timer-finished`.
+#[tauri::command]
+pub fn start_timer(
+    app_handle: AppHandle,
+    app_id: String,
+    id: String,
+    duration_secs: u64,
+    label: Option<String>,
+) -> Result<AppTimer, String> {
+    let key = id.clone();
+    let result = start(&app_handle, &app_id, id, duration_secs, label);
+    record_bridge_call(
+        &app_handle,
+        &app_id,
+        "start_timer",
+        Some(&key),
+        result.is_ok(),
+    );
+    result
+}
+
+#[tauri::command]
+pub fn get_timers(app_handle: AppHandle, app_id: String) -> Result<Vec<AppTimer>, String> {
+    let app = get_visible_app(&app_handle, parse_uuid(&app_id)?)?;
+    let _lock = TIMERS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_timers(&app_handle)?.for_app(app.id))
+}
+
+/// Stops a running timer without firing it. Returns whether it existed.
+#[tauri::command]
+pub fn cancel_timer(app_handle: AppHandle, app_id: String, id: String) -> Result<bool, String> {
+    let app_id = parse_uuid(&app_id)?;
+    let _lock = TIMERS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut timers = load_timers(&app_handle)?;
+    let cancelled = timers.cancel(app_id, &id).is_some();
+    if cancelled {
+        save_timers(&app_handle, &timers)?;
+    }
+    Ok(cancelled)
+}
+
+/// Fires every timer that has run out, including ones that ran out while
+/// Trove was closed. Timers of apps that no longer exist are dropped
+/// silently.
+fn fire_due_timers(app_handle: &AppHandle) -> Result<(), String> {
+    let due = {
+        let _lock = TIMERS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut timers = load_timers(app_handle)?;
+        let due = timers.take_due(Utc::now());
+        if !due.is_empty() {
+            save_timers(app_handle, &timers)?;
+        }
+        due
+    };
+
+    for timer in due {
+        let Ok(app) = get_visible_app(app_handle, timer.app_id) else {
+            continue;
+        };
+        let body = timer
+            .label
+            .clone()
+            .unwrap_or_else(|| "Timer finished".to_string());
+        if let Err(err) = app_handle
+            .notification()
+            .builder()
+            .title(format!("{} {}", app.emoji, app.name))
+            .body(body)
+            .show()
+        {
+            eprintln!("Failed to show timer notification: {}", err);
+        }
+        let _ = app_handle.emit(
+            "timer-finished",
+            TimerFinished {
+                app_id: timer.app_id,
+                id: timer.id,
+                label: timer.label,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Checks for finished timers for the lifetime of the app.
+pub fn start_timer_task(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(err) = fire_due_timers(&app_handle) {
+                eprintln!("Failed to check timers: {}", err);
+            }
+            sleep(TIMER_CHECK_INTERVAL).await;
+        }
+    });
+}
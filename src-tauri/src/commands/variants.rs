@@ -0,0 +1,183 @@
+use crate::commands::agent::{
+    lint_generated_html, run_sidecar, GenerationWorkspace, SidecarRequest, MAX_HTML_BYTES,
+};
+use crate::commands::apps::{get_active_profile_data_dir, publish_draft, remove_app, save_app};
+use crate::commands::disk::ensure_disk_space;
+use crate::commands::generations::GenerationJob;
+use crate::commands::history::{record_generation_attempt, record_unsaved_attempt};
+use crate::commands::settings::load_settings;
+use crate::commands::versions::record_version;
+use crate::models::{
+    default_background_color, default_emoji, validate_name_prompt, AppKind, AppMetadata,
+    ChangeKind, GenerationAttempt, GenerationKind, GenerationTimeline, GenerationVariant,
+    VariantsIndex, MAX_VARIANTS_PER_EXPERIMENT,
+};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Window};
+use uuid::Uuid;
+
+#[derive(Clone, serde::Serialize)]
+pub struct VariantComplete {
+    pub variant: GenerationVariant,
+    pub app: AppMetadata,
+    pub index: usize,
+    pub count: usize,
+}
+
+fn get_variants_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(get_active_profile_data_dir(app_handle)?.join("variants.json"))
+}
+
+fn load_variants(app_handle: &AppHandle) -> Result<VariantsIndex, String> {
+    let path = get_variants_path(app_handle)?;
+    if !path.exists() {
+        return Ok(VariantsIndex::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read variants: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse variants: {}", e))
+}
+
+fn save_variants(app_handle: &AppHandle, index: &VariantsIndex) -> Result<(), String> {
+    let path = get_variants_path(app_handle)?;
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize variants: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Runs the same prompt `count` times, one after another, saving each result
+/// as a draft app. Variants that finished are kept even if a later run fails
+/// or is cancelled.
+#[tauri::command]
+pub async fn generate_variants(
+    app_handle: AppHandle,
+    window: Window,
+    name: String,
+    prompt: String,
+    count: usize,
+    job_id: Option<String>,
+) -> Result<Vec<GenerationVariant>, String> {
+    let trimmed_name = name.trim().to_string();
+    let trimmed_prompt = prompt.trim().to_string();
+    let settings = load_settings(&app_handle)?;
+    validate_name_prompt(&trimmed_name, &trimmed_prompt, settings.prompt_max_length)?;
+    if count == 0 || count > MAX_VARIANTS_PER_EXPERIMENT {
+        return Err(format!(
+            "Variant count must be between 1 and {}",
+            MAX_VARIANTS_PER_EXPERIMENT
+        ));
+    }
+
+    let job = GenerationJob::start(&app_handle, &window, job_id, None, &trimmed_name).await?;
+    let experiment_id = Uuid::new_v4();
+    let mut variants = Vec::new();
+    for index in 0..count {
+        ensure_disk_space(&app_handle, MAX_HTML_BYTES as u64)?;
+        let workspace = GenerationWorkspace::create(None)?;
+        let mut timeline = GenerationTimeline::new(GenerationKind::Generate);
+        let request = SidecarRequest {
+            name: &trimmed_name,
+            prompt: &trimmed_prompt,
+            edit_path: None,
+            creativity: None,
+            language: settings.default_language.as_deref(),
+            accessibility_mode: false,
+            app_kind: AppKind::Standard,
+            provider: None,
+        };
+        let html = match run_sidecar(
+            &app_handle,
+            &window,
+            &job,
+            request,
+            &workspace,
+            &mut timeline,
+        )
+        .await
+        {
+            Ok(html) => html,
+            Err(err) => {
+                let attempt =
+                    GenerationAttempt::from_timeline(&timeline, &trimmed_prompt, Some(&err));
+                record_unsaved_attempt(&app_handle, attempt);
+                return Err(err);
+            }
+        };
+
+        let mut app = AppMetadata::new(
+            trimmed_name.clone(),
+            trimmed_prompt.clone(),
+            default_emoji(),
+            default_background_color(),
+        );
+        app.language = settings.default_language.clone();
+        app.draft = true;
+        app.generated_with = timeline.generated_with.clone();
+        let html = lint_generated_html(&mut app, html);
+        save_app(&app_handle, &mut app, &html)?;
+        let attempt = GenerationAttempt::from_timeline(&timeline, &trimmed_prompt, None);
+        record_generation_attempt(&app_handle, app.id, attempt);
+        record_version(&app_handle, &app, ChangeKind::Created, &html)?;
+
+        let variant = GenerationVariant {
+            id: app.id,
+            experiment_id,
+            html_bytes: html.len() as u64,
+            created_at: Utc::now(),
+        };
+        let mut variants_index = load_variants(&app_handle)?;
+        variants_index.variants.push(variant.clone());
+        save_variants(&app_handle, &variants_index)?;
+
+        let _ = window.emit(
+            "variant-complete",
+            VariantComplete {
+                variant: variant.clone(),
+                app,
+                index,
+                count,
+            },
+        );
+        variants.push(variant);
+    }
+
+    job.complete();
+    Ok(variants)
+}
+
+#[tauri::command]
+pub fn get_variants(app_handle: AppHandle) -> Result<Vec<GenerationVariant>, String> {
+    Ok(load_variants(&app_handle)?.variants)
+}
+
+/// Publishes a variant's draft app. The other variants of the experiment are
+/// left for the user to compare or discard.
+#[tauri::command]
+pub fn promote_variant(app_handle: AppHandle, draft_id: String) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&draft_id)?;
+    let mut index = load_variants(&app_handle)?;
+    index
+        .remove(uuid)
+        .ok_or_else(|| format!("Variant not found: {}", draft_id))?;
+
+    let app = publish_draft(app_handle.clone(), draft_id)?;
+    save_variants(&app_handle, &index)?;
+    Ok(app)
+}
+
+/// Deletes every unpromoted variant along with its draft app.
+#[tauri::command]
+pub fn discard_variants(app_handle: AppHandle) -> Result<(), String> {
+    let index = load_variants(&app_handle)?;
+    for variant in &index.variants {
+        // The draft may already be gone, e.g. removed by stale-draft cleanup.
+        let _ = remove_app(&app_handle, &variant.id.to_string());
+    }
+    save_variants(&app_handle, &VariantsIndex::default())
+}
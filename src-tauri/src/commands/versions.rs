@@ -0,0 +1,359 @@
+use crate::commands::apps::{get_app_file_path, get_app_html_path, save_app};
+use crate::commands::lock::{ensure_unlocked, UnlockedApps};
+use crate::commands::storage::{
+    delete_storage_file, load_storage, save_storage, storage_exists, StorageLocks,
+};
+use crate::commands::agent::MAX_HTML_BYTES;
+use crate::html::html_complexity;
+use crate::models::{
+    content_key, AppGrowth, AppMetadata, ChangeKind, Changelog, ChangelogEntry, RestorePoint,
+    RestorePoints, SnapshotStore, VersionGrowth, MAX_RESTORE_POINTS,
+};
+use crate::utils::{parse_uuid, write_atomic};
+use chrono::Utc;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+use uuid::Uuid;
+
+pub(crate) fn get_versions_dir(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "versions")
+}
+
+pub(crate) fn get_changelog_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "changelog.json")
+}
+
+pub(crate) fn get_restore_points_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    get_app_file_path(app_handle, id, "restore.json")
+}
+
+/// Where snapshots were stored, one full copy per id, before they became
+/// content-addressed. Still read and cleaned up for older apps.
+pub(crate) fn get_legacy_snapshot_path(
+    app_handle: &AppHandle,
+    id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<PathBuf, String> {
+    Ok(get_versions_dir(app_handle, id)?.join(format!("{}.html", snapshot_id)))
+}
+
+pub(crate) fn get_snapshot_store_path(app_handle: &AppHandle, id: Uuid) -> Result<PathBuf, String> {
+    Ok(get_versions_dir(app_handle, id)?.join("snapshots.json"))
+}
+
+pub(crate) fn get_blob_path(
+    app_handle: &AppHandle,
+    id: Uuid,
+    key: &str,
+) -> Result<PathBuf, String> {
+    Ok(get_versions_dir(app_handle, id)?
+        .join("blobs")
+        .join(format!("{}.html", key)))
+}
+
+pub(crate) fn load_snapshot_store(
+    app_handle: &AppHandle,
+    id: Uuid,
+) -> Result<SnapshotStore, String> {
+    let path = get_snapshot_store_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(SnapshotStore::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read snapshot store: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse snapshot store: {}", e))
+}
+
+pub(crate) fn save_snapshot_store(
+    app_handle: &AppHandle,
+    id: Uuid,
+    store: &SnapshotStore,
+) -> Result<(), String> {
+    let path = get_snapshot_store_path(app_handle, id)?;
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize snapshot store: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Stores `html` as snapshot `snapshot_id` of an app. Content already held
+/// by another snapshot is shared rather than written again.
+pub(crate) fn write_snapshot(
+    app_handle: &AppHandle,
+    id: Uuid,
+    snapshot_id: Uuid,
+    html: &str,
+) -> Result<(), String> {
+    let mut store = load_snapshot_store(app_handle, id)?;
+    let mut attempt = 0;
+    let key = loop {
+        let key = content_key(html, attempt);
+        let blob_path = get_blob_path(app_handle, id, &key)?;
+        match fs::read_to_string(&blob_path) {
+            Ok(existing) if existing == html => break key,
+            Ok(_) => attempt += 1,
+            Err(_) => {
+                write_atomic(&blob_path, html)?;
+                break key;
+            }
+        }
+    };
+
+    store.add(snapshot_id, key, html.len() as u64);
+    save_snapshot_store(app_handle, id, &store)
+}
+
+/// The file holding a snapshot's HTML, whether shared or legacy.
+pub(crate) fn get_snapshot_path(
+    app_handle: &AppHandle,
+    id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<PathBuf, String> {
+    match load_snapshot_store(app_handle, id)?.key_for(snapshot_id) {
+        Some(key) => get_blob_path(app_handle, id, key),
+        None => get_legacy_snapshot_path(app_handle, id, snapshot_id),
+    }
+}
+
+pub(crate) fn read_snapshot(
+    app_handle: &AppHandle,
+    id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<String, String> {
+    let path = get_snapshot_path(app_handle, id, snapshot_id)?;
+    fs::read_to_string(&path).map_err(|e| format!("Failed to read snapshot: {}", e))
+}
+
+/// Drops a snapshot, deleting its HTML once no other snapshot shares it.
+/// Returns the bytes freed on disk.
+pub(crate) fn delete_snapshot(
+    app_handle: &AppHandle,
+    id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<u64, String> {
+    let mut freed = 0;
+    let legacy_path = get_legacy_snapshot_path(app_handle, id, snapshot_id)?;
+    if legacy_path.exists() {
+        freed += fs::metadata(&legacy_path).map(|meta| meta.len()).unwrap_or(0);
+        fs::remove_file(&legacy_path)
+            .map_err(|e| format!("Failed to delete snapshot: {}", e))?;
+    }
+
+    let mut store = load_snapshot_store(app_handle, id)?;
+    if store.key_for(snapshot_id).is_none() {
+        return Ok(freed);
+    }
+    if let Some(key) = store.release(snapshot_id) {
+        let blob_path = get_blob_path(app_handle, id, &key)?;
+        if blob_path.exists() {
+            freed += fs::metadata(&blob_path).map(|meta| meta.len()).unwrap_or(0);
+            fs::remove_file(&blob_path)
+                .map_err(|e| format!("Failed to delete snapshot: {}", e))?;
+        }
+    }
+    save_snapshot_store(app_handle, id, &store)?;
+    Ok(freed)
+}
+
+pub fn load_changelog(app_handle: &AppHandle, id: Uuid) -> Result<Changelog, String> {
+    let path = get_changelog_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(Changelog::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read changelog: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse changelog: {}", e))
+}
+
+pub(crate) fn save_changelog(
+    app_handle: &AppHandle,
+    id: Uuid,
+    changelog: &Changelog,
+) -> Result<(), String> {
+    let path = get_changelog_path(app_handle, id)?;
+    let content = serde_json::to_string_pretty(changelog)
+        .map_err(|e| format!("Failed to serialize changelog: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Snapshots the HTML an app was just saved with and appends a changelog
+/// entry describing the change.
+pub fn record_version(
+    app_handle: &AppHandle,
+    app: &AppMetadata,
+    kind: ChangeKind,
+    html_content: &str,
+) -> Result<ChangelogEntry, String> {
+    let complexity = html_complexity(html_content);
+    let entry = ChangelogEntry {
+        version_id: Uuid::new_v4(),
+        kind,
+        name: app.name.clone(),
+        prompt: app.prompt.clone(),
+        html_bytes: html_content.len() as u64,
+        created_at: Utc::now(),
+        pruned: false,
+        // Imports, restores and runtime upgrades carry over metadata from
+        // elsewhere rather than running the sidecar.
+        generated_with: matches!(kind, ChangeKind::Created | ChangeKind::Edited)
+            .then(|| app.generated_with.clone())
+            .flatten(),
+        node_count: Some(complexity.node_count),
+        script_count: Some(complexity.script_count),
+    };
+
+    write_snapshot(app_handle, app.id, entry.version_id, html_content)?;
+
+    let mut changelog = load_changelog(app_handle, app.id)?;
+    changelog.entries.push(entry.clone());
+    save_changelog(app_handle, app.id, &changelog)?;
+
+    Ok(entry)
+}
+
+pub(crate) fn load_restore_points(
+    app_handle: &AppHandle,
+    id: Uuid,
+) -> Result<RestorePoints, String> {
+    let path = get_restore_points_path(app_handle, id)?;
+    if !path.exists() {
+        return Ok(RestorePoints::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read restore points: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse restore points: {}", e))
+}
+
+fn save_restore_points(
+    app_handle: &AppHandle,
+    id: Uuid,
+    points: &RestorePoints,
+) -> Result<(), String> {
+    let path = get_restore_points_path(app_handle, id)?;
+    let content = serde_json::to_string_pretty(points)
+        .map_err(|e| format!("Failed to serialize restore points: {}", e))?;
+
+    write_atomic(&path, &content)
+}
+
+/// Captures the app's current HTML, metadata, and storage so the next
+/// overwrite can be reverted with `undo_last_edit`.
+pub fn create_restore_point(app_handle: &AppHandle, app: &AppMetadata) -> Result<(), String> {
+    let html_path = get_app_html_path(app_handle, app.id)?;
+    let html = fs::read_to_string(&html_path)
+        .map_err(|e| format!("Failed to read app HTML: {}", e))?;
+
+    let app_id = app.id.to_string();
+    let storage = if storage_exists(app_handle, &app_id)? {
+        Some(load_storage(app_handle, &app_id)?)
+    } else {
+        None
+    };
+
+    let point = RestorePoint {
+        id: Uuid::new_v4(),
+        app: app.clone(),
+        storage,
+        created_at: Utc::now(),
+    };
+    write_snapshot(app_handle, app.id, point.id, &html)?;
+
+    let mut points = load_restore_points(app_handle, app.id)?;
+    points.points.push(point);
+    while points.points.len() > MAX_RESTORE_POINTS {
+        let dropped = points.points.remove(0);
+        delete_snapshot(app_handle, app.id, dropped.id)?;
+    }
+    save_restore_points(app_handle, app.id, &points)
+}
+
+/// Reverts the most recent edit, restoring HTML, metadata, and storage
+/// together from the restore point taken before it. Locked apps must be
+/// unlocked first. The whole undo holds the app's storage lock, so two
+/// undos can't pop the same restore point.
+#[tauri::command]
+pub async fn undo_last_edit(
+    app_handle: AppHandle,
+    locks: State<'_, StorageLocks>,
+    unlocked: State<'_, UnlockedApps>,
+    id: String,
+) -> Result<AppMetadata, String> {
+    let uuid = parse_uuid(&id)?;
+    ensure_unlocked(&app_handle, &unlocked, uuid)?;
+    let _lock = locks.lock(uuid).await;
+    let mut points = load_restore_points(&app_handle, uuid)?;
+    let point = points
+        .points
+        .pop()
+        .ok_or_else(|| "No edit to undo".to_string())?;
+
+    let html = read_snapshot(&app_handle, uuid, point.id)?;
+
+    let mut app = point.app;
+    app.updated_at = Utc::now();
+    let changed = save_app(&app_handle, &mut app, &html)?;
+
+    match &point.storage {
+        Some(storage) => save_storage(&app_handle, &id, storage)?,
+        None => delete_storage_file(&app_handle, &id)?,
+    }
+
+    save_restore_points(&app_handle, uuid, &points)?;
+    delete_snapshot(&app_handle, uuid, point.id)?;
+    if changed {
+        record_version(&app_handle, &app, ChangeKind::Restored, &html)?;
+    }
+
+    Ok(app)
+}
+
+#[tauri::command]
+pub fn get_app_changelog(app_handle: AppHandle, id: String) -> Result<Vec<ChangelogEntry>, String> {
+    let uuid = parse_uuid(&id)?;
+    Ok(load_changelog(&app_handle, uuid)?.entries)
+}
+
+/// Byte size, element count and script count of each saved version, so
+/// bloat from repeated edits shows up before an app hits the size limit.
+/// Versions recorded before counts were tracked are measured from their
+/// snapshot while it's still kept.
+#[tauri::command]
+pub fn get_app_growth(app_handle: AppHandle, id: String) -> Result<AppGrowth, String> {
+    let uuid = parse_uuid(&id)?;
+    let versions = load_changelog(&app_handle, uuid)?
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let (mut node_count, mut script_count) = (entry.node_count, entry.script_count);
+            if node_count.is_none() && !entry.pruned {
+                if let Ok(html) = read_snapshot(&app_handle, uuid, entry.version_id) {
+                    let complexity = html_complexity(&html);
+                    node_count = Some(complexity.node_count);
+                    script_count = Some(complexity.script_count);
+                }
+            }
+            VersionGrowth {
+                version_id: entry.version_id,
+                kind: entry.kind,
+                created_at: entry.created_at,
+                html_bytes: entry.html_bytes,
+                node_count,
+                script_count,
+            }
+        })
+        .collect();
+
+    Ok(AppGrowth {
+        versions,
+        size_limit_bytes: MAX_HTML_BYTES as u64,
+    })
+}
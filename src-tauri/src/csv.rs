@@ -0,0 +1,228 @@
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Renders an array of objects as CSV. Columns are every key in the order
+/// first seen; nested values are written as JSON text. Text a spreadsheet
+/// would run as a formula is prefixed with `'`.
+pub fn rows_to_csv(value: &Value) -> Result<String, String> {
+    let rows = value
+        .as_array()
+        .ok_or_else(|| "Value is not an array of objects".to_string())?;
+    let mut columns: Vec<&str> = Vec::new();
+    for row in rows {
+        let object = row
+            .as_object()
+            .ok_or_else(|| "Value is not an array of objects".to_string())?;
+        for key in object.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let mut csv = String::new();
+    write_record(&mut csv, columns.iter().map(|column| column.to_string()));
+    for row in rows.iter().filter_map(Value::as_object) {
+        write_record(
+            &mut csv,
+            columns
+                .iter()
+                .map(|column| row.get(*column).map(cell_text).unwrap_or_default()),
+        );
+    }
+    Ok(csv)
+}
+
+/// Parses CSV into an array of objects keyed by the header row. With a
+/// `mapping` from column to field name, only mapped columns are kept. The
+/// `'` that `rows_to_csv` puts before formula-like text is dropped.
+pub fn csv_to_rows(
+    csv: &str,
+    mapping: Option<&HashMap<String, String>>,
+) -> Result<Vec<Value>, String> {
+    let mut records = parse_records(csv)?.into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| "CSV has no header row".to_string())?;
+    if let Some(mapping) = mapping {
+        if let Some(missing) = mapping.keys().find(|column| !header.contains(column)) {
+            return Err(format!("CSV has no column named {}", missing));
+        }
+    }
+    let fields: Vec<Option<String>> = header
+        .iter()
+        .map(|column| match mapping {
+            Some(mapping) => mapping.get(column).cloned(),
+            None => Some(column.clone()),
+        })
+        .collect();
+
+    let mut rows = Vec::new();
+    for (line, record) in records.enumerate() {
+        if record.len() == 1 && record[0].is_empty() {
+            continue;
+        }
+        if record.len() != header.len() {
+            return Err(format!(
+                "CSV row {} has {} fields, expected {}",
+                line + 2,
+                record.len(),
+                header.len()
+            ));
+        }
+        let mut row = Map::new();
+        for (field, cell) in fields.iter().zip(record) {
+            if let Some(field) = field {
+                row.insert(field.clone(), cell_value(unescape_formula(cell)));
+            }
+        }
+        rows.push(Value::Object(row));
+    }
+    Ok(rows)
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Numbers and booleans come back typed when their text round-trips exactly;
+/// empty cells become null and everything else stays a string.
+fn cell_value(cell: String) -> Value {
+    match cell.as_str() {
+        "" => return Value::Null,
+        "true" => return Value::Bool(true),
+        "false" => return Value::Bool(false),
+        _ => {}
+    }
+    if let Ok(number) = cell.parse::<Number>() {
+        if number.to_string() == cell {
+            return Value::Number(number);
+        }
+    }
+    Value::String(cell)
+}
+
+/// Whether a spreadsheet would treat `cell` as a formula. Numbers such as
+/// `-5` are left alone.
+fn is_formula_like(cell: &str) -> bool {
+    cell.starts_with(['=', '+', '-', '@']) && cell.parse::<Number>().is_err()
+}
+
+fn unescape_formula(cell: String) -> String {
+    match cell.strip_prefix('\'') {
+        Some(rest) if is_formula_like(rest) => rest.to_string(),
+        _ => cell,
+    }
+}
+
+fn write_record(csv: &mut String, cells: impl Iterator<Item = String>) {
+    for (position, cell) in cells.enumerate() {
+        if position > 0 {
+            csv.push(',');
+        }
+        let cell = if is_formula_like(&cell) {
+            format!("'{}", cell)
+        } else {
+            cell
+        };
+        if cell.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&cell.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(&cell);
+        }
+    }
+    csv.push_str("\r\n");
+}
+
+fn parse_records(csv: &str) -> Result<Vec<Vec<String>>, String> {
+    let csv = csv.strip_prefix('\u{feff}').unwrap_or(csv);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => quoted = false,
+                _ => cell.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if cell.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut cell)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut cell));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => cell.push(c),
+        }
+    }
+    if quoted {
+        return Err("CSV has an unterminated quoted field".to_string());
+    }
+    if !cell.is_empty() || !record.is_empty() {
+        record.push(cell);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_to_rows, rows_to_csv};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn round_trips_rows_through_csv() {
+        let rows = json!([
+            { "name": "Run, easy", "km": 5, "done": true },
+            { "name": "Say \"hi\"\nlater", "notes": null, "tags": ["a"] }
+        ]);
+        let csv = rows_to_csv(&rows).unwrap();
+        assert!(csv.starts_with("done,km,name,notes,tags\r\ntrue,5,\"Run, easy\",,\r\n"));
+
+        let parsed = csv_to_rows(&csv, None).unwrap();
+        assert_eq!(parsed[0]["name"], "Run, easy");
+        assert_eq!(parsed[0]["km"], 5);
+        assert_eq!(parsed[0]["done"], true);
+        assert_eq!(parsed[1]["name"], "Say \"hi\"\nlater");
+        assert_eq!(parsed[1]["km"], serde_json::Value::Null);
+        assert_eq!(parsed[1]["tags"], "[\"a\"]");
+
+        assert!(rows_to_csv(&json!({ "name": "x" })).is_err());
+    }
+
+    #[test]
+    fn escapes_formulas_but_not_negative_numbers() {
+        let rows = json!([{ "a": "=HYPERLINK(\"x\")", "b": "@SUM(1)", "c": -5 }]);
+        let csv = rows_to_csv(&rows).unwrap();
+        assert!(csv.ends_with("\"'=HYPERLINK(\"\"x\"\")\",'@SUM(1),-5\r\n"));
+        assert_eq!(csv_to_rows(&csv, None).unwrap(), rows.as_array().unwrap().clone());
+    }
+
+    #[test]
+    fn maps_columns_and_keeps_non_canonical_numbers_as_text() {
+        let mapping = HashMap::from([("Zip Code".to_string(), "zip".to_string())]);
+        let rows = csv_to_rows("Zip Code,City\n02134,Boston\n", Some(&mapping)).unwrap();
+        assert_eq!(rows, vec![json!({ "zip": "02134" })]);
+
+        let missing = HashMap::from([("State".to_string(), "state".to_string())]);
+        assert!(csv_to_rows("Zip Code\n1\n", Some(&missing)).is_err());
+        assert!(csv_to_rows("a,b\n1\n", None).is_err());
+    }
+}
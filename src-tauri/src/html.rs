@@ -0,0 +1,411 @@
+use crate::models::{LintRule, LintWarning};
+
+const VIEWPORT_META: &str =
+    r#"<meta name="viewport" content="width=device-width, initial-scale=1">"#;
+
+/// Version of `trove-runtime.js`. Apps whose runtime block is older are
+/// rewritten by `upgrade_apps_runtime`.
+pub const RUNTIME_VERSION: u32 = 9;
+/// Oldest runtime the host side of the bridge still fully supports. Raise it
+/// when a host change breaks what older runtimes rely on; opening an app
+/// below it emits `app-runtime-incompatible`.
+pub const RUNTIME_MIN_COMPATIBLE_VERSION: u32 = 1;
+const RUNTIME_SCRIPT: &str = include_str!("trove-runtime.js");
+const RUNTIME_BLOCK_START: &str = "<script data-trove-runtime=\"";
+/// How the sidecar injected the runtime before blocks were versioned; such
+/// blocks count as version 1.
+const LEGACY_RUNTIME_START: &str = "<script>\n(function() {\n  var TIMEOUT_MS = 5000;";
+const SCRIPT_END: &str = "</script>";
+
+/// Edits that shrink an app below this fraction of its previous size are
+/// treated as suspicious and held back for confirmation.
+pub const SUSPICIOUS_SHRINK_RATIO: f64 = 0.3;
+
+/// Finds the first opening `<name` tag in lowercased HTML, so `<head` doesn't
+/// match `<header`.
+pub fn find_open_tag(lower: &str, name: &str) -> Option<usize> {
+    let pattern = format!("<{}", name);
+    lower.match_indices(&pattern).map(|(idx, _)| idx).find(|idx| {
+        let next = lower[idx + pattern.len()..].chars().next();
+        next.is_some_and(|c| c == '>' || c == '/' || c.is_ascii_whitespace())
+    })
+}
+
+/// Checks the basic document structure the sidecar also enforces:
+/// `<html>`, `<head>`, and `<body>` present and properly ordered.
+pub fn check_structure(html: &str) -> Result<(), String> {
+    let lower = html.to_lowercase();
+    let doctype = lower.find("<!doctype");
+    let html_open = lower.find("<html");
+    let html_close = lower.rfind("</html>");
+    let head_open = find_open_tag(&lower, "head");
+    let head_close = lower.find("</head>");
+    let body_open = lower.find("<body");
+    let body_close = lower.rfind("</body>");
+
+    let (Some(html_open), Some(html_close)) = (html_open, html_close) else {
+        return Err("Missing a valid <html> structure".to_string());
+    };
+    if html_close < html_open {
+        return Err("Missing a valid <html> structure".to_string());
+    }
+    if doctype.is_some_and(|idx| idx > html_open) {
+        return Err("DOCTYPE must appear before <html>".to_string());
+    }
+    match (head_open, head_close) {
+        (Some(open), Some(close)) if close > open => {}
+        _ => return Err("Missing a valid <head> section".to_string()),
+    }
+    match (body_open, body_close) {
+        (Some(open), Some(close)) if close > open => {
+            if close > html_close {
+                return Err("Body must close before </html>".to_string());
+            }
+        }
+        _ => return Err("Missing a valid <body> section".to_string()),
+    }
+    Ok(())
+}
+
+/// Returns why an edit's output looks worse than the original, if it does.
+pub fn suspicious_edit_reason(previous: &str, next: &str) -> Option<String> {
+    if let Err(err) = check_structure(next) {
+        return Some(format!("New HTML failed validation: {}", err));
+    }
+    if !previous.is_empty() && (next.len() as f64) < previous.len() as f64 * SUSPICIOUS_SHRINK_RATIO
+    {
+        let percent = next.len() * 100 / previous.len();
+        return Some(format!(
+            "New HTML is only {}% of the previous size",
+            percent
+        ));
+    }
+    None
+}
+
+fn has_viewport_meta(lower: &str) -> bool {
+    ["name=\"viewport\"", "name='viewport'", "name=viewport"]
+        .iter()
+        .any(|pattern| lower.contains(pattern))
+}
+
+/// Finds `fetch(` calls whose first argument is a literal http(s) URL.
+fn has_remote_fetch(lower: &str) -> bool {
+    lower.match_indices("fetch(").any(|(idx, call)| {
+        let arg = lower[idx + call.len()..].trim_start();
+        let arg = arg.trim_start_matches(['"', '\'', '`']);
+        arg.starts_with("https://") || arg.starts_with("http://")
+    })
+}
+
+/// Checks generated HTML for common bridge mistakes. Safe fixes are applied
+/// to the returned HTML; everything found is reported either way.
+pub fn lint_bridge_usage(html: &str) -> (String, Vec<LintWarning>) {
+    // ASCII lowercasing keeps byte offsets, so indices into `lower` are
+    // valid in `html` too.
+    let lower = html.to_ascii_lowercase();
+    let mut warnings = Vec::new();
+    let mut output = html.to_string();
+
+    if lower.contains("localstorage.") || lower.contains("sessionstorage.") {
+        warnings.push(LintWarning {
+            rule: LintRule::WebStorage,
+            message: "Uses localStorage or sessionStorage, which won't persist; use TroveStorage"
+                .to_string(),
+            fixed: false,
+        });
+    }
+    if has_remote_fetch(&lower) {
+        warnings.push(LintWarning {
+            rule: LintRule::RemoteFetch,
+            message: "Fetches a remote URL, which fails offline and from file:// pages"
+                .to_string(),
+            fixed: false,
+        });
+    }
+    if !has_viewport_meta(&lower) {
+        let head_end = find_open_tag(&lower, "head")
+            .and_then(|start| lower[start..].find('>').map(|end| start + end + 1));
+        if let Some(head_end) = head_end {
+            output.insert_str(head_end, VIEWPORT_META);
+        }
+        warnings.push(LintWarning {
+            rule: LintRule::MissingViewport,
+            message: "Missing a viewport meta tag".to_string(),
+            fixed: head_end.is_some(),
+        });
+    }
+
+    (output, warnings)
+}
+
+/// Finds the first runtime block, returning its byte range (including the
+/// trailing newline, if any) and version.
+fn find_runtime_block(html: &str) -> Option<(std::ops::Range<usize>, u32)> {
+    let versioned = html.find(RUNTIME_BLOCK_START).map(|start| {
+        let digits = &html[start + RUNTIME_BLOCK_START.len()..];
+        let version = digits
+            .split('"')
+            .next()
+            .and_then(|digits| digits.parse().ok())
+            .unwrap_or(0);
+        (start, version)
+    });
+    let legacy = html.find(LEGACY_RUNTIME_START).map(|start| (start, 1));
+    let (start, version) = match (versioned, legacy) {
+        (Some(a), Some(b)) => a.min(b),
+        (found, None) | (None, found) => found?,
+    };
+
+    let mut end = start + html[start..].find(SCRIPT_END)? + SCRIPT_END.len();
+    if html[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some((start..end, version))
+}
+
+/// Version of the runtime embedded in `html`, or `None` if it has none
+/// (e.g. HTML imported from elsewhere).
+pub fn runtime_version(html: &str) -> Option<u32> {
+    find_runtime_block(html).map(|(_, version)| version)
+}
+
+/// Replaces any runtime blocks in `html` with a single current one before
+/// `</head>`. HTML without a `</head>` is returned unchanged.
+pub fn inject_runtime(html: &str) -> String {
+    let mut output = html.to_string();
+    while let Some((range, _)) = find_runtime_block(&output) {
+        output.replace_range(range, "");
+    }
+
+    // ASCII lowercasing keeps byte offsets aligned with `output`.
+    let Some(head_end) = output.to_ascii_lowercase().find("</head>") else {
+        return html.to_string();
+    };
+    let block = format!(
+        "{}{}\">\n{}{}\n",
+        RUNTIME_BLOCK_START, RUNTIME_VERSION, RUNTIME_SCRIPT, SCRIPT_END
+    );
+    output.insert_str(head_end, &block);
+    output
+}
+
+/// Extracts the text a user would read: tags, comments, and the contents of
+/// `<script>` and `<style>` are dropped and whitespace is collapsed. Stops
+/// after `max_bytes` of output so multi-MB apps stay cheap to index.
+pub fn visible_text(html: &str, max_bytes: usize) -> String {
+    // ASCII lowercasing keeps byte offsets aligned with `html`.
+    let lower = html.to_ascii_lowercase();
+    let mut text = String::new();
+    let mut pos = 0;
+
+    while pos < html.len() && text.len() < max_bytes {
+        let Some(offset) = html[pos..].find('<') else {
+            push_text(&mut text, &html[pos..], max_bytes);
+            break;
+        };
+        push_text(&mut text, &html[pos..pos + offset], max_bytes);
+        let tag_start = pos + offset;
+
+        let skip_until = if lower[tag_start..].starts_with("<!--") {
+            Some("-->")
+        } else if lower[tag_start..].starts_with("<script") {
+            Some("</script>")
+        } else if lower[tag_start..].starts_with("<style") {
+            Some("</style>")
+        } else {
+            None
+        };
+        pos = match skip_until {
+            Some(end) => lower[tag_start..]
+                .find(end)
+                .map_or(html.len(), |idx| tag_start + idx + end.len()),
+            None => html[tag_start..]
+                .find('>')
+                .map_or(html.len(), |idx| tag_start + idx + 1),
+        };
+        if !text.is_empty() && !text.ends_with(' ') {
+            text.push(' ');
+        }
+    }
+
+    text.trim_end().to_string()
+}
+
+/// Rough size of a document's structure, from a quick scan rather than a
+/// real parse: element start tags, and how many of them are scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HtmlComplexity {
+    pub node_count: u64,
+    pub script_count: u64,
+}
+
+/// Counts start tags, skipping comments and the bodies of scripts and
+/// styles so `a<b` in code isn't mistaken for markup.
+pub fn html_complexity(html: &str) -> HtmlComplexity {
+    let lower = html.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let mut complexity = HtmlComplexity {
+        node_count: 0,
+        script_count: 0,
+    };
+    let mut pos = 0;
+
+    while let Some(offset) = lower[pos..].find('<') {
+        let tag_start = pos + offset;
+        let rest = &lower[tag_start..];
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(html.len(), |idx| tag_start + idx + 3);
+            continue;
+        }
+        if !bytes.get(tag_start + 1).is_some_and(u8::is_ascii_alphabetic) {
+            pos = tag_start + 1;
+            continue;
+        }
+
+        complexity.node_count += 1;
+        let raw_text_end = if rest.starts_with("<script") {
+            complexity.script_count += 1;
+            Some("</script>")
+        } else if rest.starts_with("<style") {
+            Some("</style>")
+        } else {
+            None
+        };
+        pos = match raw_text_end {
+            Some(end) => rest.find(end).map_or(html.len(), |idx| tag_start + idx + end.len()),
+            None => rest.find('>').map_or(html.len(), |idx| tag_start + idx + 1),
+        };
+    }
+
+    complexity
+}
+
+fn push_text(text: &mut String, raw: &str, max_bytes: usize) {
+    let decoded = raw
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    for word in decoded.split_whitespace() {
+        if text.len() + word.len() + 1 > max_bytes {
+            return;
+        }
+        if !text.is_empty() && !text.ends_with(' ') {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_structure, find_open_tag, html_complexity, inject_runtime, lint_bridge_usage,
+        runtime_version, suspicious_edit_reason, visible_text, LEGACY_RUNTIME_START,
+        RUNTIME_VERSION,
+    };
+    use crate::models::LintRule;
+
+    const VALID: &str = "<!DOCTYPE html><html><head></head><body><p>hi</p></body></html>";
+
+    #[test]
+    fn accepts_well_formed_document() {
+        assert!(check_structure(VALID).is_ok());
+    }
+
+    #[test]
+    fn rejects_document_without_body() {
+        let err = check_structure("<html><head></head></html>").expect_err("should fail");
+        assert_eq!(err, "Missing a valid <body> section");
+    }
+
+    #[test]
+    fn flags_edit_that_shrinks_dramatically() {
+        let previous = VALID.replace("<p>hi</p>", &"<p>hi</p>".repeat(100));
+        let reason = suspicious_edit_reason(&previous, VALID).expect("should be suspicious");
+        assert!(reason.starts_with("New HTML is only"));
+    }
+
+    #[test]
+    fn allows_edit_of_similar_size() {
+        assert!(suspicious_edit_reason(VALID, VALID).is_none());
+    }
+
+    #[test]
+    fn open_tags_match_whole_names() {
+        let lower = "<html><body><header></header><head >";
+        assert_eq!(find_open_tag(lower, "head"), lower.rfind("<head"));
+        assert_eq!(find_open_tag("<header>", "head"), None);
+    }
+
+    #[test]
+    fn lint_inserts_missing_viewport_meta() {
+        let (html, warnings) = lint_bridge_usage(VALID);
+        assert!(html.contains("<head><meta name=\"viewport\""));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].rule, LintRule::MissingViewport);
+        assert!(warnings[0].fixed);
+
+        let (again, warnings) = lint_bridge_usage(&html);
+        assert_eq!(again, html);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn lint_flags_web_storage_and_remote_fetch() {
+        let html = VALID.replace(
+            "<p>hi</p>",
+            "<script>localStorage.setItem('a', 1); fetch( 'https://api.example.com');</script>",
+        );
+        let (_, warnings) = lint_bridge_usage(&html);
+        let rules: Vec<LintRule> = warnings.iter().map(|warning| warning.rule).collect();
+        assert!(rules.contains(&LintRule::WebStorage));
+        assert!(rules.contains(&LintRule::RemoteFetch));
+
+        let local = VALID.replace("<p>hi</p>", "<script>fetch('./data.json')</script>");
+        let (_, warnings) = lint_bridge_usage(&local);
+        assert!(warnings.iter().all(|warning| warning.rule != LintRule::RemoteFetch));
+    }
+
+    #[test]
+    fn visible_text_skips_markup_scripts_and_styles() {
+        let html = "<html><head><style>p { color: red; }</style></head><body>\
+            <h1>Budget&nbsp;Tracker</h1><!-- note --><p>Track   spending</p>\
+            <script>const hidden = 1;</script><p>Tom &amp; Jerry</p></body></html>";
+        assert_eq!(visible_text(html, 1024), "Budget Tracker Track spending Tom & Jerry");
+        assert_eq!(visible_text(html, 14), "Budget Tracker");
+    }
+
+    #[test]
+    fn inject_runtime_replaces_legacy_and_versioned_blocks() {
+        assert_eq!(runtime_version(VALID), None);
+        let legacy = format!(
+            "<html><head>\n{} }})();\n</script>\n</head><body></body></html>",
+            LEGACY_RUNTIME_START
+        );
+        assert_eq!(runtime_version(&legacy), Some(1));
+
+        let upgraded = inject_runtime(&legacy);
+        assert_eq!(runtime_version(&upgraded), Some(RUNTIME_VERSION));
+        assert!(!upgraded.contains(LEGACY_RUNTIME_START));
+        assert_eq!(upgraded.matches("</script>").count(), 1);
+        assert_eq!(inject_runtime(&upgraded), upgraded);
+
+        let fresh = inject_runtime(VALID);
+        assert_eq!(runtime_version(&fresh), Some(RUNTIME_VERSION));
+        assert_eq!(inject_runtime("<p>no head</p>"), "<p>no head</p>");
+    }
+
+    #[test]
+    fn counts_elements_and_scripts_outside_raw_text() {
+        let html = "<!DOCTYPE html><html><head><style>a<b{}</style></head>\
+            <body><!-- <div> --><p>1 < 2</p><script>if (a<b) {}</script>\
+            <SCRIPT src=\"x.js\"></SCRIPT></body></html>";
+        let complexity = html_complexity(html);
+        assert_eq!(complexity.node_count, 7);
+        assert_eq!(complexity.script_count, 2);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::models::IcsFieldMapping;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::{Map, Value};
+
+/// iCalendar lines longer than this many octets must be folded.
+const ICS_LINE_MAX_OCTETS: usize = 75;
+
+enum EventTime {
+    Utc(DateTime<Utc>),
+    /// A time without a zone, shown at that wall-clock time wherever the
+    /// calendar is.
+    Floating(NaiveDateTime),
+    AllDay(NaiveDate),
+}
+
+impl EventTime {
+    /// Accepts RFC 3339 timestamps, zone-less `YYYY-MM-DDTHH:MM[:SS]`,
+    /// plain `YYYY-MM-DD` dates, and epoch milliseconds as `Date.now()`
+    /// stores them.
+    fn parse(value: &Value) -> Option<Self> {
+        if let Some(millis) = value.as_i64() {
+            return DateTime::from_timestamp_millis(millis).map(Self::Utc);
+        }
+        let text = value.as_str()?.trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return Some(Self::Utc(time.with_timezone(&Utc)));
+        }
+        for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+            if let Ok(time) = NaiveDateTime::parse_from_str(text, format) {
+                return Some(Self::Floating(time));
+            }
+        }
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()
+            .map(Self::AllDay)
+    }
+
+    fn property(&self, name: &str) -> String {
+        match self {
+            Self::Utc(time) => format!("{}:{}", name, time.format("%Y%m%dT%H%M%SZ")),
+            Self::Floating(time) => format!("{}:{}", name, time.format("%Y%m%dT%H%M%S")),
+            Self::AllDay(date) => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+        }
+    }
+}
+
+/// Renders stored records as an iCalendar file with one event per record.
+/// All-day events without an end last one day; timed ones without an end
+/// are instants.
+pub fn records_to_ics(
+    records: &Value,
+    mapping: &IcsFieldMapping,
+    calendar_name: &str,
+    app_id: &str,
+    now: DateTime<Utc>,
+) -> Result<String, String> {
+    let records = records
+        .as_array()
+        .ok_or_else(|| "Value is not an array of records".to_string())?;
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Trove//Trove//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+    ];
+    for (position, record) in records.iter().enumerate() {
+        let number = position + 1;
+        let record = record
+            .as_object()
+            .ok_or_else(|| format!("Record {} is not an object", number))?;
+        let title = field_text(record, &mapping.title)
+            .ok_or_else(|| format!("Record {} has no {}", number, mapping.title))?;
+        let start = record
+            .get(&mapping.start)
+            .and_then(EventTime::parse)
+            .ok_or_else(|| format!("Record {} has no valid date in {}", number, mapping.start))?;
+        let end = match &mapping.end {
+            Some(field) => match record.get(field) {
+                None | Some(Value::Null) => None,
+                Some(value) => Some(EventTime::parse(value).ok_or_else(|| {
+                    format!("Record {} has no valid date in {}", number, field)
+                })?),
+            },
+            None => None,
+        };
+        let end = match (end, &start) {
+            (None, EventTime::AllDay(date)) => {
+                let next = date
+                    .succ_opt()
+                    .ok_or_else(|| format!("Record {} has a date out of range", number))?;
+                Some(EventTime::AllDay(next))
+            }
+            (end, _) => end,
+        };
+        let uid = mapping
+            .uid
+            .as_ref()
+            .and_then(|field| field_text(record, field))
+            .unwrap_or_else(|| position.to_string());
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}-{}@trove", escape_text(&uid), app_id));
+        lines.push(format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")));
+        lines.push(start.property("DTSTART"));
+        if let Some(end) = end {
+            lines.push(end.property("DTEND"));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&title)));
+        for (name, field) in [
+            ("DESCRIPTION", &mapping.description),
+            ("LOCATION", &mapping.location),
+        ] {
+            if let Some(text) = field.as_ref().and_then(|field| field_text(record, field)) {
+                lines.push(format!("{}:{}", name, escape_text(&text)));
+            }
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines.iter().map(|line| fold_line(line)).collect())
+}
+
+fn field_text(record: &Map<String, Value>, field: &str) -> Option<String> {
+    match record.get(field)? {
+        Value::Null => None,
+        Value::String(text) if text.trim().is_empty() => None,
+        Value::String(text) => Some(text.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Splits a content line into CRLF-terminated chunks of at most 75 octets,
+/// continuation lines starting with a space, without splitting a character.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > ICS_LINE_MAX_OCTETS {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::records_to_ics;
+    use crate::models::IcsFieldMapping;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn mapping() -> IcsFieldMapping {
+        IcsFieldMapping {
+            title: "habit".to_string(),
+            start: "date".to_string(),
+            end: Some("until".to_string()),
+            description: Some("notes".to_string()),
+            location: None,
+            uid: Some("id".to_string()),
+        }
+    }
+
+    #[test]
+    fn renders_dated_records_as_events() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        let records = json!([
+            { "id": "a1", "habit": "Run; 5k", "date": "2026-10-17", "notes": "slow,\neasy" },
+            { "habit": "Call", "date": "2026-10-18T14:30:00Z", "until": "2026-10-18T15:00" },
+            { "habit": "Read", "date": 1792224000000_i64 }
+        ]);
+        let ics = records_to_ics(&records, &mapping(), "Habits", "app", now).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("UID:a1-app@trove\r\nDTSTAMP:20261016T090000Z\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20261017\r\nDTEND;VALUE=DATE:20261018\r\n"));
+        assert!(ics.contains("SUMMARY:Run\\; 5k\r\nDESCRIPTION:slow\\,\\neasy\r\n"));
+        assert!(ics.contains("UID:1-app@trove\r\n"));
+        assert!(ics.contains("DTSTART:20261018T143000Z\r\nDTEND:20261018T150000\r\n"));
+        assert!(ics.contains("DTSTART:20261017T080000Z\r\nSUMMARY:Read\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn rejects_records_without_a_date_and_folds_long_lines() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        let missing = json!([{ "habit": "Run", "date": "someday" }]);
+        assert!(records_to_ics(&missing, &mapping(), "Habits", "app", now).is_err());
+        let last_day = json!([{ "habit": "Run", "date": "+262142-12-31" }]);
+        assert!(records_to_ics(&last_day, &mapping(), "Habits", "app", now).is_err());
+
+        let long = json!([{ "habit": "é".repeat(60), "date": "2026-10-17" }]);
+        let ics = records_to_ics(&long, &mapping(), "Habits", "app", now).unwrap();
+        assert!(ics.split("\r\n").all(|line| line.len() <= 75));
+        assert!(ics.contains("\r\n é"));
+    }
+}
@@ -1,13 +1,48 @@
 mod commands;
+mod csv;
+mod html;
+mod ics;
 mod models;
+mod msgpack;
+mod protocol;
+mod sandbox;
 mod utils;
 
 use commands::{
-    cancel_generation, delete_app, edit_app, generate_app, get_app_path, list_apps,
-    storage_clear, storage_delete, storage_get, storage_get_all, storage_set,
-    update_app_metadata,
+    accept_suggested_tags, answer_generation_question, app_download, app_play_sound, app_read_file,
+    app_shortcut_pressed, auto_fix_app, broadcast_system_theme, bulk_export_apps, cancel_export,
+    cancel_generation, cancel_timer, capture_app_screenshot, clear_app_errors, clear_app_storage,
+    clear_generation_history, clear_indicators, create_profile, delete_app, delete_app_config,
+    delete_generation_draft, delete_profile, delete_prompt_template, discard_pending_edit,
+    discard_variants, dismiss_suggested_tags, edit_app, export_app_bundle, export_app_qr,
+    export_ics, export_prompts_markdown, export_settings, export_snapshot, finish_generation_early,
+    force_apply_edit, generate_app, generate_from_clipboard, generate_variants, get_app,
+    get_app_audit_log, get_app_changelog, get_app_config, get_app_errors, get_app_growth,
+    get_app_health, get_app_html, get_app_lineage, get_app_path, get_app_permissions,
+    get_app_share_link, get_disk_usage, get_focus_mode, get_generation_drafts,
+    get_generation_history, get_generation_timeline, get_latest_digest, get_own_metadata,
+    get_power_status, get_prompt_templates, get_settings, get_system_theme, get_timers,
+    get_variants, grant_app_folder, import_app, import_app_bundle, import_settings,
+    import_share_link, inspect_app, instantiate_template, launch_autostart_apps, list_apps,
+    list_apps_light, list_drafts, list_generation_jobs, list_provider_key_urls, mark_app_opened,
+    move_app_to_profile, open_app, pause_generation, promote_variant, publish_draft,
+    record_app_activity, recover_interrupted_saves, register_app_shortcut, remix_app,
+    remove_app_password, report_app_error, request_delete_confirmation, request_rename,
+    resume_generation, revoke_app_download_domain, revoke_app_folder, revoke_app_rename,
+    run_cleanup_now, run_maintenance, save_generation_draft, save_prompt_template, search_apps,
+    set_app_autostart, set_app_badge, set_app_config, set_app_open_mode, set_app_password,
+    set_app_progress, set_focus_mode, set_low_power_mode, set_profile_pin, set_provider_api_key,
+    set_storage_format, set_storage_read_only, start_auto_lock_task, start_digest_task,
+    start_html_cache_warmup, start_maintenance_task, start_power_task, start_share_link_handler,
+    start_timer, start_timer_task, storage_clear, storage_delete, storage_export_csv, storage_get,
+    storage_get_all, storage_get_stream, storage_import_csv, storage_list_deleted, storage_set,
+    storage_undelete, suggest_app_name, suggest_emoji, switch_profile, trust_app, undo_last_edit,
+    unlock_app, unregister_app_shortcut, update_app_metadata, update_settings,
+    upgrade_apps_runtime, AppWindows, AuditLocks, ConfirmationTokens, ExportJobs,
+    GenerationManager, HtmlCache, IndexLock, PowerState, SearchIndexLock, SingleTurnSlots,
+    StorageLocks, UnlockedApps,
 };
-use tauri::Manager;
+use tauri::{Manager, WindowEvent};
 
 #[cfg(target_os = "macos")]
 use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
@@ -16,8 +51,32 @@ use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .manage(IndexLock::default())
+        .manage(SearchIndexLock::default())
+        .manage(SingleTurnSlots::default())
+        .manage(ExportJobs::default())
+        .manage(StorageLocks::default())
+        .manage(AuditLocks::default())
+        .manage(AppWindows::default())
+        .manage(UnlockedApps::default())
+        .manage(HtmlCache::default())
+        .manage(PowerState::default())
+        .manage(ConfirmationTokens::default())
+        .manage(GenerationManager::default())
+        .register_asynchronous_uri_scheme_protocol(
+            protocol::APP_ASSET_SCHEME,
+            |ctx, request, responder| {
+                let app_handle = ctx.app_handle().clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    responder.respond(protocol::serve_app_asset(&app_handle, &request));
+                });
+            },
+        )
         .setup(|app| {
             #[cfg(target_os = "macos")]
             {
@@ -25,20 +84,160 @@ pub fn run() {
                 apply_vibrancy(&window, NSVisualEffectMaterial::Sidebar, None, None)
                     .expect("Failed to apply vibrancy");
             }
+            recover_interrupted_saves(app.handle());
+            start_power_task(app.handle().clone());
+            start_maintenance_task(app.handle().clone());
+            start_auto_lock_task(app.handle().clone());
+            start_timer_task(app.handle().clone());
+            start_digest_task(app.handle().clone());
+            start_html_cache_warmup(app.handle().clone());
+            launch_autostart_apps(app.handle());
+            start_share_link_handler(app.handle());
             Ok(())
         })
+        .on_window_event(|window, event| match event {
+            WindowEvent::ThemeChanged(theme) => {
+                broadcast_system_theme(window.app_handle(), *theme);
+            }
+            WindowEvent::Destroyed => {
+                let had_indicators = window.state::<AppWindows>().release_window(window.label());
+                // Elsewhere they close with the window; the dock outlives it.
+                if had_indicators && cfg!(target_os = "macos") {
+                    if let Some(main) = window.get_webview_window("main") {
+                        clear_indicators(&main);
+                    }
+                }
+            }
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             list_apps,
+            list_apps_light,
+            get_app,
+            search_apps,
             get_app_path,
+            get_app_html,
+            set_app_password,
+            remove_app_password,
+            unlock_app,
+            record_app_activity,
+            request_delete_confirmation,
             delete_app,
             generate_app,
+            generate_from_clipboard,
+            suggest_app_name,
+            suggest_emoji,
+            save_prompt_template,
+            get_prompt_templates,
+            delete_prompt_template,
+            instantiate_template,
             edit_app,
             cancel_generation,
+            finish_generation_early,
+            pause_generation,
+            resume_generation,
+            answer_generation_question,
+            list_generation_jobs,
             update_app_metadata,
+            import_app,
+            trust_app,
+            get_app_audit_log,
+            save_generation_draft,
+            get_generation_drafts,
+            delete_generation_draft,
+            get_settings,
+            update_settings,
+            export_settings,
+            import_settings,
+            set_provider_api_key,
+            list_provider_key_urls,
+            get_app_changelog,
+            get_app_growth,
+            get_disk_usage,
+            get_latest_digest,
+            get_power_status,
+            set_low_power_mode,
+            undo_last_edit,
+            force_apply_edit,
+            discard_pending_edit,
+            inspect_app,
+            get_app_share_link,
+            import_share_link,
+            export_app_bundle,
+            bulk_export_apps,
+            cancel_export,
+            import_app_bundle,
+            export_prompts_markdown,
+            export_snapshot,
+            mark_app_opened,
+            open_app,
+            set_app_open_mode,
+            set_app_autostart,
+            get_focus_mode,
+            set_focus_mode,
+            set_profile_pin,
+            create_profile,
+            switch_profile,
+            delete_profile,
+            move_app_to_profile,
+            accept_suggested_tags,
+            dismiss_suggested_tags,
+            run_cleanup_now,
+            run_maintenance,
+            upgrade_apps_runtime,
+            remix_app,
+            get_app_lineage,
+            get_app_permissions,
+            grant_app_folder,
+            revoke_app_folder,
+            app_read_file,
+            revoke_app_download_domain,
+            revoke_app_rename,
+            request_rename,
+            app_download,
+            export_app_qr,
+            capture_app_screenshot,
+            get_generation_timeline,
+            get_generation_history,
+            clear_generation_history,
+            generate_variants,
+            get_variants,
+            promote_variant,
+            discard_variants,
+            list_drafts,
+            publish_draft,
+            get_system_theme,
+            report_app_error,
+            get_app_errors,
+            clear_app_errors,
+            auto_fix_app,
+            get_app_health,
             storage_get,
+            storage_get_stream,
             storage_set,
             storage_delete,
             storage_clear,
+            clear_app_storage,
+            set_storage_format,
+            set_storage_read_only,
+            storage_list_deleted,
+            storage_undelete,
+            storage_export_csv,
+            storage_import_csv,
+            export_ics,
+            get_app_config,
+            get_own_metadata,
+            set_app_badge,
+            set_app_progress,
+            app_play_sound,
+            register_app_shortcut,
+            unregister_app_shortcut,
+            app_shortcut_pressed,
+            start_timer,
+            get_timers,
+            cancel_timer,
+            set_app_config,
+            delete_app_config,
             storage_get_all
         ])
         .run(tauri::generate_context!())
@@ -1,35 +1,54 @@
+use crate::models::{GeneratedWith, LintWarning, TagSuggestion};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 pub const APP_NAME_MAX_LENGTH: usize = 60;
 pub const APP_PROMPT_MAX_LENGTH: usize = 2000;
+/// Characters of the prompt kept in `AppMetadata::to_listing`.
+pub const PROMPT_PREVIEW_LENGTH: usize = 80;
+pub const APP_CONTEXT_MAX_BYTES: usize = 512 * 1024;
 
-pub fn validate_name_prompt(name: &str, prompt: &str) -> Result<(), String> {
+pub fn validate_name_prompt(
+    name: &str,
+    prompt: &str,
+    prompt_max_length: usize,
+) -> Result<(), String> {
+    validate_name(name)?;
+    validate_prompt(prompt, prompt_max_length)
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
     let trimmed_name = name.trim();
-    let trimmed_prompt = prompt.trim();
 
     if trimmed_name.is_empty() {
         return Err("App name is required".to_string());
     }
-    if trimmed_prompt.is_empty() {
-        return Err("Prompt is required".to_string());
-    }
     if trimmed_name.len() > APP_NAME_MAX_LENGTH {
         return Err(format!(
             "App name must be at most {} characters",
             APP_NAME_MAX_LENGTH
         ));
     }
-    if trimmed_prompt.len() > APP_PROMPT_MAX_LENGTH {
+    if trimmed_name.chars().any(|ch| ch.is_control()) {
+        return Err("App name contains invalid characters".to_string());
+    }
+    Ok(())
+}
+
+pub fn validate_prompt(prompt: &str, prompt_max_length: usize) -> Result<(), String> {
+    let trimmed_prompt = prompt.trim();
+
+    if trimmed_prompt.is_empty() {
+        return Err("Prompt is required".to_string());
+    }
+    if trimmed_prompt.len() > prompt_max_length {
         return Err(format!(
             "Prompt must be at most {} characters",
-            APP_PROMPT_MAX_LENGTH
+            prompt_max_length
         ));
     }
-    if trimmed_name.chars().any(|ch| ch.is_control()) {
-        return Err("App name contains invalid characters".to_string());
-    }
     if trimmed_prompt.chars().any(|ch| {
         ch == '\0' || (ch.is_control() && !matches!(ch, '\n' | '\r' | '\t'))
     }) {
@@ -39,14 +58,83 @@ pub fn validate_name_prompt(name: &str, prompt: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn default_emoji() -> String {
+/// Creativity is a 0–1 dial forwarded to the sidecar, where it steers how
+/// conventional or inventive the generated design is.
+pub fn validate_creativity(creativity: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&creativity) {
+        return Err("Creativity must be between 0 and 1".to_string());
+    }
+    Ok(())
+}
+
+/// Languages are BCP 47 tags such as `en`, `pt-BR` or `zh-Hant`. Only the
+/// shape is checked; the sidecar passes the tag on to the model as-is.
+pub fn validate_language(language: &str) -> Result<(), String> {
+    let valid_shape = !language.is_empty()
+        && language.len() <= 35
+        && language.split('-').all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|ch| ch.is_ascii_alphanumeric())
+        });
+    if !valid_shape {
+        return Err(format!("Invalid language tag: {}", language));
+    }
+    Ok(())
+}
+
+/// Validates reference material attached to a generation. Context is kept
+/// out of the prompt, so it only needs a size bound and no NUL bytes.
+pub fn validate_context(context: &str) -> Result<(), String> {
+    if context.len() > APP_CONTEXT_MAX_BYTES {
+        return Err(format!(
+            "Reference context must be at most {} KB",
+            APP_CONTEXT_MAX_BYTES / 1024
+        ));
+    }
+    if context.contains('\0') {
+        return Err("Reference context contains invalid characters".to_string());
+    }
+    Ok(())
+}
+
+pub(crate) fn default_emoji() -> String {
     "✨".to_string()
 }
 
-fn default_background_color() -> String {
+pub(crate) fn default_background_color() -> String {
     "#6366F1".to_string()
 }
 
+/// Where an app came from. Apps saved before origins were tracked were all
+/// generated locally, hence the default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppOrigin {
+    #[default]
+    Generated,
+    Imported {
+        source: String,
+    },
+    Gallery {
+        id: String,
+    },
+    Remix {
+        parent_id: Uuid,
+    },
+    Duplicate {
+        parent_id: Uuid,
+    },
+}
+
+impl AppOrigin {
+    /// The app this one was derived from, for remixes and duplicates.
+    pub fn parent_id(&self) -> Option<Uuid> {
+        match self {
+            AppOrigin::Remix { parent_id } | AppOrigin::Duplicate { parent_id } => Some(*parent_id),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppMetadata {
     pub id: Uuid,
@@ -58,6 +146,71 @@ pub struct AppMetadata {
     pub background_color: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Imported apps start quarantined: their bridge can't write storage
+    /// until the user reviews them and calls `trust_app`.
+    #[serde(default)]
+    pub quarantined: bool,
+    #[serde(default)]
+    pub open_count: u64,
+    #[serde(default)]
+    pub last_opened_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub origin: AppOrigin,
+    /// Hash of the app's HTML and assets as last written by `save_app`.
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Creativity the app was last generated with; edits reuse it unless a
+    /// new value is given.
+    #[serde(default)]
+    pub creativity: Option<f32>,
+    /// Language the app's UI text was generated in; edits keep using it.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Generated with the accessibility pass (ARIA labels, keyboard
+    /// navigation, high-contrast support); edits keep it on.
+    #[serde(default)]
+    pub accessibility_mode: bool,
+    /// Bridge misuse found in the last generated HTML.
+    #[serde(default)]
+    pub lint_warnings: Vec<LintWarning>,
+    /// Drafts (unpromoted variants, imports awaiting review) are kept out of
+    /// the main grid until published.
+    #[serde(default)]
+    pub draft: bool,
+    /// Overrides the default open mode from settings for this app.
+    #[serde(default)]
+    pub open_mode: Option<OpenMode>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Awaiting the user's review; see `accept_suggested_tags`.
+    #[serde(default)]
+    pub suggested_tags: Option<TagSuggestion>,
+    /// Password protected; the hash lives in the app's lock file and the
+    /// app can't be opened until `unlock_app` succeeds this session.
+    #[serde(default)]
+    pub locked: bool,
+    /// Version of the injected runtime in the app's HTML as last saved;
+    /// unset for HTML without one or saved before versions were recorded.
+    #[serde(default)]
+    pub runtime_version: Option<u32>,
+    /// Layout the app was generated for; edits keep it.
+    #[serde(default)]
+    pub app_kind: AppKind,
+    /// Opened in its own window whenever Trove starts.
+    #[serde(default)]
+    pub autostart: bool,
+    /// Sidecar version and model of the latest generation or edit.
+    #[serde(default)]
+    pub generated_with: Option<GeneratedWith>,
+    /// How the app's storage is kept on disk; see `set_storage_format`.
+    #[serde(default)]
+    pub storage_format: StorageFormat,
+    /// The app's own bridge can read its storage but not change it; CSV
+    /// import and undelete from Trove still work.
+    #[serde(default)]
+    pub storage_read_only: bool,
 }
 
 impl AppMetadata {
@@ -71,7 +224,54 @@ impl AppMetadata {
             background_color,
             created_at: now,
             updated_at: now,
+            quarantined: false,
+            open_count: 0,
+            last_opened_at: None,
+            origin: AppOrigin::Generated,
+            content_hash: None,
+            creativity: None,
+            language: None,
+            accessibility_mode: false,
+            lint_warnings: Vec::new(),
+            draft: false,
+            open_mode: None,
+            tags: Vec::new(),
+            category: None,
+            suggested_tags: None,
+            locked: false,
+            runtime_version: None,
+            app_kind: AppKind::Standard,
+            autostart: false,
+            generated_with: None,
+            storage_format: StorageFormat::Json,
+            storage_read_only: false,
+        }
+    }
+
+    /// The metadata as JSON without the prompt, which can run to thousands
+    /// of characters, and with its opening as `prompt_preview` instead.
+    pub fn to_listing(&self) -> Result<Value, String> {
+        let mut listing =
+            serde_json::to_value(self).map_err(|e| format!("Failed to serialize app: {}", e))?;
+        if let Value::Object(fields) = &mut listing {
+            fields.remove("prompt");
+            fields.insert(
+                "prompt_preview".to_string(),
+                Value::String(self.prompt.chars().take(PROMPT_PREVIEW_LENGTH).collect()),
+            );
         }
+        Ok(listing)
+    }
+
+    /// Whether cleanup should delete this draft, untouched since before
+    /// `cutoff`. Imported drafts are kept: the HTML came from outside and
+    /// may exist nowhere else, so they wait for the user to review them.
+    pub fn is_expired_draft(&self, cutoff: DateTime<Utc>) -> bool {
+        let imported = matches!(
+            self.origin,
+            AppOrigin::Imported { .. } | AppOrigin::Gallery { .. }
+        );
+        self.draft && !imported && self.updated_at < cutoff
     }
 }
 
@@ -100,4 +300,154 @@ impl AppsIndex {
     pub fn get_mut(&mut self, id: Uuid) -> Option<&mut AppMetadata> {
         self.apps.iter_mut().find(|a| a.id == id)
     }
+
+    /// How each app's entry differs from `previous`, in index order with
+    /// removals last.
+    pub fn changes_since(&self, previous: &AppsIndex) -> Vec<IndexChange> {
+        let mut changes: Vec<IndexChange> = self
+            .apps
+            .iter()
+            .filter_map(|app| match previous.get(app.id) {
+                None => Some(IndexChange::Added(app.clone())),
+                Some(old) if serde_json::to_value(old).ok() != serde_json::to_value(app).ok() => {
+                    Some(IndexChange::Updated(app.clone()))
+                }
+                Some(_) => None,
+            })
+            .collect();
+        changes.extend(
+            previous
+                .apps
+                .iter()
+                .filter(|app| self.get(app.id).is_none())
+                .map(|app| IndexChange::Removed(app.id)),
+        );
+        changes
+    }
+}
+
+/// One entry changing between two versions of the index.
+#[derive(Debug, Clone)]
+pub enum IndexChange {
+    Added(AppMetadata),
+    Updated(AppMetadata),
+    Removed(Uuid),
+}
+
+/// Which surface opened an app, reported with `app-opened` events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenSource {
+    Grid,
+    Window,
+    Tray,
+    Hotkey,
+    DeepLink,
+}
+
+/// Where `open_app` shows an app.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenMode {
+    /// Selected in the main window's content panel.
+    #[default]
+    Panel,
+    /// In a window of its own, with the full storage bridge.
+    Window,
+    /// In the default browser, straight from disk. Bridge calls fail there,
+    /// so this suits apps that don't need storage.
+    Browser,
+}
+
+/// On-disk encoding of an app's key-value storage. The commands behave the
+/// same either way; MessagePack parses faster and is smaller for apps that
+/// keep a lot of data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageFormat {
+    #[default]
+    Json,
+    #[serde(rename = "messagepack")]
+    MessagePack,
+}
+
+/// The layout an app is generated for, which also sizes its own window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppKind {
+    #[default]
+    Standard,
+    /// A small glanceable tool, shown in a compact frameless window.
+    Widget,
+    /// Fills the screen, e.g. games, dashboards and presentations.
+    Fullscreen,
+}
+
+impl AppKind {
+    /// Value of the sidecar's `--app-kind` flag; `None` for the default.
+    pub fn sidecar_flag(self) -> Option<&'static str> {
+        match self {
+            AppKind::Standard => None,
+            AppKind::Widget => Some("widget"),
+            AppKind::Fullscreen => Some("fullscreen"),
+        }
+    }
+}
+
+/// A non-HTML file belonging to a multi-file app, addressed by a relative
+/// path inside the app's assets directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppAsset {
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppMetadata, AppOrigin, AppsIndex, IndexChange};
+    use chrono::{Duration, Utc};
+
+    fn app(name: &str) -> AppMetadata {
+        AppMetadata::new(
+            name.to_string(),
+            String::new(),
+            "📝".to_string(),
+            "#6366F1".to_string(),
+        )
+    }
+
+    #[test]
+    fn only_generated_drafts_expire() {
+        let mut draft = app("Draft");
+        draft.draft = true;
+        draft.updated_at = Utc::now() - Duration::days(30);
+        let cutoff = Utc::now() - Duration::days(14);
+        assert!(draft.is_expired_draft(cutoff));
+
+        draft.origin = AppOrigin::Imported {
+            source: "notes.html".to_string(),
+        };
+        assert!(!draft.is_expired_draft(cutoff));
+    }
+
+    #[test]
+    fn reports_each_changed_entry() {
+        let (notes, habits, old) = (app("Notes"), app("Habits"), app("Old"));
+        let previous = AppsIndex {
+            apps: vec![notes.clone(), habits.clone(), old.clone()],
+        };
+        let mut renamed = habits.clone();
+        renamed.name = "Streaks".to_string();
+        let added = app("New");
+        let index = AppsIndex {
+            apps: vec![notes, renamed, added.clone()],
+        };
+
+        let changes = index.changes_since(&previous);
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], IndexChange::Updated(app) if app.name == "Streaks"));
+        assert!(matches!(&changes[1], IndexChange::Added(app) if app.id == added.id));
+        assert!(matches!(changes[2], IndexChange::Removed(id) if id == old.id));
+        assert!(index.changes_since(&index).is_empty());
+    }
 }
@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const APP_ERRORS_MAX: usize = 20;
+pub const APP_ERROR_MESSAGE_MAX_BYTES: usize = 2 * 1024;
+pub const APP_ERROR_STACK_MAX_BYTES: usize = 8 * 1024;
+/// Only the most recent errors go into a fix prompt, with short stacks, so
+/// the prompt stays a reasonable process argument.
+pub const FIX_PROMPT_MAX_ERRORS: usize = 5;
+const FIX_PROMPT_STACK_MAX_BYTES: usize = 1024;
+
+/// An uncaught exception or unhandled rejection as reported by an app's
+/// bridge. Everything here comes from untrusted app code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppErrorReport {
+    pub message: String,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub line: Option<u32>,
+    #[serde(default)]
+    pub column: Option<u32>,
+    #[serde(default)]
+    pub stack: Option<String>,
+}
+
+/// A distinct runtime error seen in an app. Repeats of the same error bump
+/// `count` instead of adding entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRuntimeError {
+    pub message: String,
+    pub source: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub stack: Option<String>,
+    pub count: u32,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl AppRuntimeError {
+    fn matches(&self, report: &AppErrorReport) -> bool {
+        self.message == report.message
+            && self.source == report.source
+            && self.line == report.line
+            && self.column == report.column
+    }
+}
+
+fn truncate_to(value: &mut String, max_bytes: usize) {
+    if value.len() > max_bytes {
+        let mut end = max_bytes;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        value.truncate(end);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppErrorLog {
+    pub errors: Vec<AppRuntimeError>,
+}
+
+impl AppErrorLog {
+    /// Records a report, most recent last, keeping only the newest
+    /// `APP_ERRORS_MAX` distinct errors.
+    pub fn record(&mut self, mut report: AppErrorReport) {
+        truncate_to(&mut report.message, APP_ERROR_MESSAGE_MAX_BYTES);
+        if let Some(source) = report.source.as_mut() {
+            truncate_to(source, APP_ERROR_MESSAGE_MAX_BYTES);
+        }
+        if let Some(stack) = report.stack.as_mut() {
+            truncate_to(stack, APP_ERROR_STACK_MAX_BYTES);
+        }
+
+        let now = Utc::now();
+        if let Some(pos) = self.errors.iter().position(|error| error.matches(&report)) {
+            let mut error = self.errors.remove(pos);
+            error.count = error.count.saturating_add(1);
+            error.last_seen_at = now;
+            error.stack = report.stack.or(error.stack);
+            self.errors.push(error);
+            return;
+        }
+
+        self.errors.push(AppRuntimeError {
+            message: report.message,
+            source: report.source,
+            line: report.line,
+            column: report.column,
+            stack: report.stack,
+            count: 1,
+            first_seen_at: now,
+            last_seen_at: now,
+        });
+        if self.errors.len() > APP_ERRORS_MAX {
+            let overflow = self.errors.len() - APP_ERRORS_MAX;
+            self.errors.drain(..overflow);
+        }
+    }
+}
+
+/// Builds the edit instruction for `auto_fix_app` from an app's description
+/// and its recorded errors, most recent first.
+pub fn build_fix_prompt(prompt: &str, errors: &[AppRuntimeError]) -> String {
+    let mut out = String::from(
+        "The app currently throws the runtime errors below. Fix their root causes \
+         without removing features or changing the design.\n",
+    );
+    for (index, error) in errors.iter().rev().take(FIX_PROMPT_MAX_ERRORS).enumerate() {
+        out.push_str(&format!("\n{}. {}\n", index + 1, error.message));
+        if let Some(line) = error.line {
+            let column = error.column.map(|c| format!(":{}", c)).unwrap_or_default();
+            out.push_str(&format!("   Location: line {}{}\n", line, column));
+        }
+        out.push_str(&format!("   Occurrences: {}\n", error.count));
+        if let Some(stack) = &error.stack {
+            let mut stack = stack.clone();
+            truncate_to(&mut stack, FIX_PROMPT_STACK_MAX_BYTES);
+            out.push_str("   Stack:\n");
+            for line in stack.lines() {
+                out.push_str(&format!("     {}\n", line.trim()));
+            }
+        }
+    }
+    out.push_str(&format!("\nOriginal app description:\n{}", prompt.trim()));
+    out
+}
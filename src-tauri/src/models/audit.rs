@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const AUDIT_LOG_MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub action: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+    pub allowed: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Appends an entry, dropping the oldest ones past the size bound.
+    pub fn push(&mut self, entry: AuditEntry) {
+        self.entries.push(entry);
+        if self.entries.len() > AUDIT_LOG_MAX_ENTRIES {
+            let overflow = self.entries.len() - AUDIT_LOG_MAX_ENTRIES;
+            self.entries.drain(..overflow);
+        }
+    }
+
+    /// Drops the oldest entries until the rest take at most `max_bytes` as
+    /// JSON, and returns how many were dropped. Entries are measured one by
+    /// one, so the saved file's layout can add a little on top.
+    pub fn trim_to_bytes(&mut self, max_bytes: u64) -> usize {
+        let sizes: Vec<u64> = self
+            .entries
+            .iter()
+            .map(|entry| serde_json::to_vec(entry).map_or(0, |json| json.len() as u64))
+            .collect();
+        let mut total: u64 = sizes.iter().sum();
+        let mut dropped = 0;
+        while total > max_bytes && dropped < sizes.len() {
+            total -= sizes[dropped];
+            dropped += 1;
+        }
+        self.entries.drain(..dropped);
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditEntry, AuditLog};
+    use chrono::Utc;
+
+    #[test]
+    fn trims_oldest_entries_past_the_size_limit() {
+        let mut log = AuditLog::default();
+        for action in ["first", "second", "third"] {
+            log.push(AuditEntry {
+                action: action.to_string(),
+                detail: Some("x".repeat(100)),
+                allowed: true,
+                timestamp: Utc::now(),
+            });
+        }
+
+        assert_eq!(log.trim_to_bytes(u64::MAX), 0);
+        assert_eq!(log.trim_to_bytes(400), 1);
+        assert_eq!(log.entries[0].action, "second");
+        assert_eq!(log.trim_to_bytes(0), 2);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::models::AppMetadata;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+pub const APP_BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// A single-file export of an app: metadata, HTML, its storage data, and
+/// optionally its config values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppBundle {
+    pub format_version: u32,
+    pub app: AppMetadata,
+    pub html: String,
+    #[serde(default)]
+    pub storage: Option<Map<String, Value>>,
+    #[serde(default)]
+    pub config: Option<BTreeMap<String, String>>,
+}
+
+/// How to resolve a bundle whose app id already exists locally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStrategy {
+    Replace,
+    KeepBoth,
+    Skip,
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Which fields of a stored record become which parts of a calendar event.
+/// Only `title` and `start` are required.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IcsFieldMapping {
+    pub title: String,
+    pub start: String,
+    #[serde(default)]
+    pub end: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Field holding a stable record id, so re-exporting updates events
+    /// instead of duplicating them. Falls back to the record's position.
+    #[serde(default)]
+    pub uid: Option<String>,
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const APP_CONFIG_MAX_KEYS: usize = 100;
+pub const APP_CONFIG_KEY_MAX_LENGTH: usize = 64;
+pub const APP_CONFIG_VALUE_MAX_BYTES: usize = 4 * 1024;
+
+/// Per-app settings such as an API base URL, set by the user and read by
+/// the app through `TroveConfig`. Kept apart from storage so clearing app
+/// data never loses them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub values: BTreeMap<String, String>,
+}
+
+/// Keys are env-var style: letters, digits, `_`, `-` and `.`.
+pub fn validate_config_key(key: &str) -> Result<(), String> {
+    if key.is_empty() || key.len() > APP_CONFIG_KEY_MAX_LENGTH {
+        return Err(format!(
+            "Config key must be 1 to {} characters",
+            APP_CONFIG_KEY_MAX_LENGTH
+        ));
+    }
+    if !key
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        return Err("Config key may only contain letters, digits, '_', '-' and '.'".to_string());
+    }
+    Ok(())
+}
+
+impl AppConfig {
+    pub fn set(&mut self, key: String, value: String) -> Result<(), String> {
+        validate_config_key(&key)?;
+        if value.len() > APP_CONFIG_VALUE_MAX_BYTES {
+            return Err(format!(
+                "Config value must be at most {} KB",
+                APP_CONFIG_VALUE_MAX_BYTES / 1024
+            ));
+        }
+        if !self.values.contains_key(&key) && self.values.len() >= APP_CONFIG_MAX_KEYS {
+            return Err(format!("Apps can have at most {} config values", APP_CONFIG_MAX_KEYS));
+        }
+        self.values.insert(key, value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppConfig, APP_CONFIG_MAX_KEYS};
+
+    #[test]
+    fn set_validates_keys_and_caps_count() {
+        let mut config = AppConfig::default();
+        config
+            .set("API_BASE_URL".to_string(), "https://example.com".to_string())
+            .expect("key should be valid");
+        assert!(config.set("has space".to_string(), String::new()).is_err());
+        assert!(config.set(String::new(), String::new()).is_err());
+
+        for index in 1..APP_CONFIG_MAX_KEYS {
+            config
+                .set(format!("key{}", index), String::new())
+                .expect("below the cap");
+        }
+        assert!(config.set("one_more".to_string(), String::new()).is_err());
+        assert!(config.set("API_BASE_URL".to_string(), "changed".to_string()).is_ok());
+    }
+}
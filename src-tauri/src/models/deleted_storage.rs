@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const DELETED_STORAGE_MAX_ENTRIES: usize = 50;
+/// Serialized size budget across all stashed values, so one app clearing a
+/// large dataset can't grow the stash without bound.
+pub const DELETED_STORAGE_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/// A storage key removed by `storage.delete` or `storage.clear`, kept so it
+/// can be restored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedStorageEntry {
+    pub key: String,
+    pub value: Value,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl DeletedStorageEntry {
+    fn size(&self) -> usize {
+        self.key.len() + serde_json::to_string(&self.value).map_or(0, |json| json.len())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeletedStorage {
+    pub entries: Vec<DeletedStorageEntry>,
+}
+
+impl DeletedStorage {
+    /// Stashes a removed value, most recent last. Only the latest deletion
+    /// of a key is kept; the oldest entries are dropped past either bound.
+    pub fn record(&mut self, key: String, value: Value) {
+        self.entries.retain(|entry| entry.key != key);
+        self.entries.push(DeletedStorageEntry {
+            key,
+            value,
+            deleted_at: Utc::now(),
+        });
+
+        if self.entries.len() > DELETED_STORAGE_MAX_ENTRIES {
+            let overflow = self.entries.len() - DELETED_STORAGE_MAX_ENTRIES;
+            self.entries.drain(..overflow);
+        }
+        let mut total: usize = self.entries.iter().map(DeletedStorageEntry::size).sum();
+        while total > DELETED_STORAGE_MAX_BYTES && !self.entries.is_empty() {
+            total -= self.entries.remove(0).size();
+        }
+    }
+
+    pub fn take(&mut self, key: &str) -> Option<DeletedStorageEntry> {
+        let pos = self.entries.iter().position(|entry| entry.key == key)?;
+        Some(self.entries.remove(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeletedStorage, DELETED_STORAGE_MAX_BYTES, DELETED_STORAGE_MAX_ENTRIES};
+    use serde_json::json;
+
+    #[test]
+    fn keeps_latest_deletion_per_key_within_bounds() {
+        let mut deleted = DeletedStorage::default();
+        for index in 0..DELETED_STORAGE_MAX_ENTRIES + 5 {
+            deleted.record(format!("key-{}", index), json!(index));
+        }
+        deleted.record("key-10".to_string(), json!("again"));
+
+        assert_eq!(deleted.entries.len(), DELETED_STORAGE_MAX_ENTRIES);
+        assert_eq!(deleted.entries[0].key, "key-5");
+        let restored = deleted.take("key-10").expect("key should be stashed");
+        assert_eq!(restored.value, json!("again"));
+        assert!(deleted.take("key-10").is_none());
+    }
+
+    #[test]
+    fn drops_oldest_entries_past_byte_budget() {
+        let mut deleted = DeletedStorage::default();
+        let large = "x".repeat(DELETED_STORAGE_MAX_BYTES / 2);
+        deleted.record("first".to_string(), json!(large));
+        deleted.record("second".to_string(), json!(large));
+        deleted.record("small".to_string(), json!(1));
+
+        let keys: Vec<&str> = deleted.entries.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, vec!["second", "small"]);
+    }
+}
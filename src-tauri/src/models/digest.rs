@@ -0,0 +1,252 @@
+use crate::models::AppMetadata;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use uuid::Uuid;
+
+pub const DIGEST_INTERVAL_DAYS: i64 = 7;
+/// Older digests are deleted when a new one is written.
+pub const DIGESTS_KEPT: usize = 12;
+
+/// One app's week. Counts that need last week's totals to compare against
+/// are unset for apps the previous digest didn't cover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppUsageSummary {
+    pub app_id: Uuid,
+    pub name: String,
+    pub emoji: String,
+    /// The app's lifetime open count, kept as next week's baseline.
+    pub open_count: u64,
+    pub opened: bool,
+    pub opens: Option<u64>,
+    pub generations: u32,
+    pub edits: u32,
+    pub storage_bytes: u64,
+    pub storage_growth_bytes: Option<i64>,
+}
+
+impl AppUsageSummary {
+    /// Compares an app with its entry in the previous digest. Apps created
+    /// this week count from zero; older apps without an entry (the first
+    /// digest, or a profile switch) only report whether they were opened.
+    pub fn new(
+        app: &AppMetadata,
+        period_start: DateTime<Utc>,
+        previous: Option<&AppUsageSummary>,
+        generations: u32,
+        edits: u32,
+        storage_bytes: u64,
+    ) -> Self {
+        let baseline = match previous {
+            Some(previous) => Some((previous.open_count, previous.storage_bytes)),
+            None if app.created_at >= period_start => Some((0, 0)),
+            None => None,
+        };
+        let opened = app.last_opened_at.is_some_and(|at| at >= period_start);
+        Self {
+            app_id: app.id,
+            name: app.name.clone(),
+            emoji: app.emoji.clone(),
+            open_count: app.open_count,
+            opened,
+            opens: baseline.map(|(open_count, _)| app.open_count.saturating_sub(open_count)),
+            generations,
+            edits,
+            storage_bytes,
+            storage_growth_bytes: baseline.map(|(_, bytes)| storage_bytes as i64 - bytes as i64),
+        }
+    }
+}
+
+/// A summary of the week since the previous digest: which apps were used,
+/// how many generations ran, and how much storage grew.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub generated_at: DateTime<Utc>,
+    pub period_start: DateTime<Utc>,
+    pub apps_used: usize,
+    pub apps_created: usize,
+    pub generations: u32,
+    pub edits: u32,
+    /// Generations and edits of existing apps that ended in an error.
+    pub failed_runs: u32,
+    pub storage_bytes: u64,
+    /// Growth of the apps that have a baseline; see `AppUsageSummary`.
+    pub storage_growth_bytes: i64,
+    /// Every app in the library, most opened first.
+    pub apps: Vec<AppUsageSummary>,
+}
+
+impl WeeklyDigest {
+    pub fn new(
+        generated_at: DateTime<Utc>,
+        period_start: DateTime<Utc>,
+        apps_created: usize,
+        failed_runs: u32,
+        mut apps: Vec<AppUsageSummary>,
+    ) -> Self {
+        apps.sort_by_key(|app| {
+            (
+                std::cmp::Reverse(app.opened),
+                std::cmp::Reverse(app.opens.unwrap_or(0)),
+            )
+        });
+        Self {
+            generated_at,
+            period_start,
+            apps_used: apps.iter().filter(|app| app.opened).count(),
+            apps_created,
+            generations: apps.iter().map(|app| app.generations).sum(),
+            edits: apps.iter().map(|app| app.edits).sum(),
+            failed_runs,
+            storage_bytes: apps.iter().map(|app| app.storage_bytes).sum(),
+            storage_growth_bytes: apps.iter().filter_map(|app| app.storage_growth_bytes).sum(),
+            apps,
+        }
+    }
+
+    /// Where the next digest's week starts: the end of this one, or a week
+    /// back for the first digest.
+    pub fn next_period_start(latest: Option<&WeeklyDigest>, now: DateTime<Utc>) -> DateTime<Utc> {
+        latest.map_or(now - Duration::days(DIGEST_INTERVAL_DAYS), |digest| {
+            digest.generated_at
+        })
+    }
+
+    pub fn is_due(latest: Option<&WeeklyDigest>, now: DateTime<Utc>) -> bool {
+        latest
+            .is_none_or(|digest| now - digest.generated_at >= Duration::days(DIGEST_INTERVAL_DAYS))
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Trove weekly digest\n\n{} to {}\n\n",
+            self.period_start.format("%Y-%m-%d"),
+            self.generated_at.format("%Y-%m-%d")
+        );
+        let _ = writeln!(out, "- Apps used: {}", self.apps_used);
+        let _ = writeln!(out, "- Apps created: {}", self.apps_created);
+        let _ = writeln!(
+            out,
+            "- Generations: {} new, {} edits, {} failed",
+            self.generations, self.edits, self.failed_runs
+        );
+        let _ = writeln!(
+            out,
+            "- Storage: {} ({} this week)",
+            format_bytes(self.storage_bytes as i64),
+            format_growth(self.storage_growth_bytes)
+        );
+
+        let used: Vec<&AppUsageSummary> = self.apps.iter().filter(|app| app.opened).collect();
+        if !used.is_empty() {
+            out.push_str("\n## Apps used\n\n| App | Opens | Generations | Storage growth |\n");
+            out.push_str("| --- | --- | --- | --- |\n");
+            for app in used {
+                let _ = writeln!(
+                    out,
+                    "| {} {} | {} | {} | {} |",
+                    app.emoji,
+                    app.name.replace('|', "\\|"),
+                    app.opens.map_or("-".to_string(), |opens| opens.to_string()),
+                    app.generations + app.edits,
+                    app.storage_growth_bytes
+                        .map_or("-".to_string(), format_growth)
+                );
+            }
+        }
+        out
+    }
+}
+
+fn format_bytes(bytes: i64) -> String {
+    let magnitude = bytes.unsigned_abs() as f64;
+    if magnitude >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if magnitude >= 1024.0 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn format_growth(bytes: i64) -> String {
+    if bytes > 0 {
+        format!("+{}", format_bytes(bytes))
+    } else {
+        format_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppUsageSummary, WeeklyDigest};
+    use crate::models::AppMetadata;
+    use chrono::{Duration, Utc};
+
+    fn app(
+        name: &str,
+        age_days: i64,
+        open_count: u64,
+        opened_days_ago: Option<i64>,
+    ) -> AppMetadata {
+        let now = Utc::now();
+        let mut app = AppMetadata::new(
+            name.to_string(),
+            String::new(),
+            "📝".to_string(),
+            "#6366F1".to_string(),
+        );
+        app.created_at = now - Duration::days(age_days);
+        app.open_count = open_count;
+        app.last_opened_at = opened_days_ago.map(|days| now - Duration::days(days));
+        app
+    }
+
+    #[test]
+    fn compares_apps_with_the_previous_digest() {
+        let now = Utc::now();
+        let period_start = now - Duration::days(7);
+        let (notes, habits, old) = (
+            app("Notes", 30, 12, Some(1)),
+            app("Habits", 2, 3, Some(0)),
+            app("Old", 60, 4, Some(20)),
+        );
+        let last_week = AppUsageSummary::new(&notes, period_start, None, 0, 0, 1000);
+        let last_week = AppUsageSummary {
+            open_count: 5,
+            ..last_week
+        };
+
+        let digest = WeeklyDigest::new(
+            now,
+            period_start,
+            1,
+            1,
+            vec![
+                AppUsageSummary::new(&old, period_start, None, 0, 0, 50),
+                AppUsageSummary::new(&habits, period_start, None, 1, 2, 300),
+                AppUsageSummary::new(&notes, period_start, Some(&last_week), 0, 1, 4096),
+            ],
+        );
+        assert_eq!(digest.apps_used, 2);
+        assert_eq!((digest.generations, digest.edits), (1, 3));
+        assert_eq!(digest.apps[0].name, "Notes");
+        assert_eq!(digest.apps[0].opens, Some(7));
+        assert_eq!(digest.apps[1].storage_growth_bytes, Some(300));
+        assert_eq!(digest.apps[2].opens, None);
+        assert_eq!(digest.storage_growth_bytes, 3096 + 300);
+
+        let markdown = digest.to_markdown();
+        assert!(markdown.contains("- Storage: 4.3 KB (+3.3 KB this week)"));
+        assert!(markdown.contains("| 📝 Notes | 7 | 1 | +3.0 KB |"));
+        assert!(!markdown.contains("Old"));
+
+        assert!(!WeeklyDigest::is_due(
+            Some(&digest),
+            now + Duration::days(6)
+        ));
+        assert!(WeeklyDigest::is_due(Some(&digest), now + Duration::days(7)));
+        assert!(WeeklyDigest::is_due(None, now));
+    }
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const MAX_GENERATION_DRAFTS: usize = 20;
+pub const DRAFT_FIELD_MAX_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationDraft {
+    pub id: Uuid,
+    pub name: String,
+    pub prompt: String,
+    pub emoji: String,
+    pub background_color: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DraftsIndex {
+    pub drafts: Vec<GenerationDraft>,
+}
+
+impl DraftsIndex {
+    /// Inserts or replaces a draft, keeping the most recently saved first
+    /// and dropping the oldest drafts past the bound.
+    pub fn upsert(&mut self, draft: GenerationDraft) {
+        self.drafts.retain(|d| d.id != draft.id);
+        self.drafts.insert(0, draft);
+        self.drafts.truncate(MAX_GENERATION_DRAFTS);
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<GenerationDraft> {
+        let pos = self.drafts.iter().position(|d| d.id == id)?;
+        Some(self.drafts.remove(pos))
+    }
+}
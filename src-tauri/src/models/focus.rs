@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Apps the user has blocked themselves from opening, optionally until a
+/// given time, after which the block lapses on its own.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FocusMode {
+    pub enabled: bool,
+    pub blocked_app_ids: Vec<Uuid>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl FocusMode {
+    pub fn new(
+        enabled: bool,
+        mut blocked_app_ids: Vec<Uuid>,
+        until: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, String> {
+        if enabled && until.is_some_and(|until| until <= now) {
+            return Err("Focus mode end time must be in the future".to_string());
+        }
+        blocked_app_ids.sort();
+        blocked_app_ids.dedup();
+        Ok(Self {
+            enabled,
+            blocked_app_ids,
+            until,
+        })
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.until.is_none_or(|until| now < until)
+    }
+
+    pub fn blocks(&self, id: Uuid, now: DateTime<Utc>) -> bool {
+        self.is_active(now) && self.blocked_app_ids.contains(&id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FocusMode;
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    #[test]
+    fn blocks_listed_apps_until_end_time() {
+        let now = Utc::now();
+        let (blocked, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let until = now + Duration::hours(1);
+        let focus = FocusMode::new(true, vec![blocked, blocked], Some(until), now).unwrap();
+
+        assert_eq!(focus.blocked_app_ids, vec![blocked]);
+        assert!(focus.blocks(blocked, now));
+        assert!(!focus.blocks(other, now));
+        assert!(!focus.blocks(blocked, until));
+        assert!(FocusMode::new(true, vec![blocked], Some(now), now).is_err());
+        assert!(FocusMode::new(false, vec![blocked], Some(now), now).is_ok());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::models::{GenerationKind, GenerationTimeline};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const GENERATION_HISTORY_MAX: usize = 200;
+
+/// One generation or edit of an app, kept so users can see which prompt
+/// produced which result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationAttempt {
+    pub kind: GenerationKind,
+    pub started_at: DateTime<Utc>,
+    pub prompt: String,
+    pub succeeded: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl GenerationAttempt {
+    /// Summarizes a finished run. `error` is why it didn't produce a saved
+    /// app, which may come after the generation itself succeeded, e.g. an
+    /// edit held for review.
+    pub fn from_timeline(timeline: &GenerationTimeline, prompt: &str, error: Option<&str>) -> Self {
+        Self {
+            kind: timeline.kind,
+            started_at: timeline.started_at,
+            prompt: prompt.to_string(),
+            succeeded: error.is_none(),
+            error: error.map(str::to_string),
+            duration_ms: (Utc::now() - timeline.started_at).num_milliseconds(),
+            model: timeline
+                .generated_with
+                .as_ref()
+                .and_then(|with| with.model.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationHistory {
+    pub attempts: Vec<GenerationAttempt>,
+}
+
+impl GenerationHistory {
+    /// Appends an attempt, dropping the oldest past `GENERATION_HISTORY_MAX`.
+    pub fn push(&mut self, attempt: GenerationAttempt) {
+        self.attempts.push(attempt);
+        if self.attempts.len() > GENERATION_HISTORY_MAX {
+            let overflow = self.attempts.len() - GENERATION_HISTORY_MAX;
+            self.attempts.drain(..overflow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GenerationAttempt, GenerationHistory, GENERATION_HISTORY_MAX};
+    use crate::models::{GenerationKind, GenerationTimeline};
+
+    #[test]
+    fn keeps_the_latest_attempts() {
+        let timeline = GenerationTimeline::new(GenerationKind::Edit);
+        let mut history = GenerationHistory::default();
+        history.push(GenerationAttempt::from_timeline(&timeline, "first", None));
+        assert!(history.attempts[0].succeeded);
+
+        let error = Some("Generation cancelled");
+        for _ in 0..GENERATION_HISTORY_MAX {
+            history.push(GenerationAttempt::from_timeline(&timeline, "retry", error));
+        }
+        assert_eq!(history.attempts.len(), GENERATION_HISTORY_MAX);
+        assert!(history
+            .attempts
+            .iter()
+            .all(|attempt| attempt.prompt == "retry"));
+        assert_eq!(
+            history.attempts[0].error.as_deref(),
+            Some("Generation cancelled")
+        );
+        assert!(!history.attempts[0].succeeded);
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Known ways generated HTML misuses the Trove runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    /// `localStorage`/`sessionStorage` don't persist; TroveStorage does.
+    WebStorage,
+    /// Apps load from the local filesystem and must work offline.
+    RemoteFetch,
+    /// Added automatically when missing.
+    MissingViewport,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintWarning {
+    pub rule: LintRule,
+    pub message: String,
+    /// Whether the lint pass already rewrote the HTML to fix this.
+    pub fixed: bool,
+}
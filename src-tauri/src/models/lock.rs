@@ -0,0 +1,61 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const APP_PASSWORD_MIN_LENGTH: usize = 4;
+pub const APP_PASSWORD_MAX_LENGTH: usize = 256;
+
+/// An app's password, stored as an argon2 PHC string so the salt and
+/// parameters travel with the hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLock {
+    pub password_hash: String,
+    pub set_at: DateTime<Utc>,
+}
+
+impl AppLock {
+    pub fn new(password: &str) -> Result<Self, String> {
+        let length = password.chars().count();
+        if !(APP_PASSWORD_MIN_LENGTH..=APP_PASSWORD_MAX_LENGTH).contains(&length) {
+            return Err(format!(
+                "Password must be {} to {} characters",
+                APP_PASSWORD_MIN_LENGTH, APP_PASSWORD_MAX_LENGTH
+            ));
+        }
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| format!("Failed to hash password: {}", e))?
+            .to_string();
+
+        Ok(Self {
+            password_hash,
+            set_at: Utc::now(),
+        })
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        PasswordHash::new(&self.password_hash).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppLock;
+
+    #[test]
+    fn verifies_only_the_original_password() {
+        let lock = AppLock::new("correct horse").expect("password should hash");
+        assert!(lock.password_hash.starts_with("$argon2id$"));
+        assert!(lock.verify("correct horse"));
+        assert!(!lock.verify("correct horse "));
+        assert!(AppLock::new("abc").is_err());
+    }
+}
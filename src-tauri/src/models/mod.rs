@@ -1,3 +1,53 @@
 mod app;
+mod app_error;
+mod audit;
+mod bundle;
+mod calendar;
+mod config;
+mod deleted_storage;
+mod digest;
+mod draft;
+mod focus;
+mod history;
+mod lint;
+mod lock;
+mod naming;
+mod permission;
+mod provider;
+mod search;
+mod settings;
+mod shortcut;
+mod snapshot;
+mod tags;
+mod template;
+mod timeline;
+mod timer;
+mod variant;
+mod version;
 
 pub use app::*;
+pub use app_error::*;
+pub use audit::*;
+pub use bundle::*;
+pub use calendar::*;
+pub use config::*;
+pub use deleted_storage::*;
+pub use digest::*;
+pub use draft::*;
+pub use focus::*;
+pub use history::*;
+pub use lint::*;
+pub use lock::*;
+pub use naming::*;
+pub use permission::*;
+pub use provider::*;
+pub use search::*;
+pub use settings::*;
+pub use shortcut::*;
+pub use snapshot::*;
+pub use tags::*;
+pub use template::*;
+pub use timeline::*;
+pub use timer::*;
+pub use variant::*;
+pub use version::*;
@@ -0,0 +1,180 @@
+use crate::models::{default_emoji, APP_NAME_MAX_LENGTH};
+use serde::{Deserialize, Serialize};
+
+pub const NAME_SUGGESTIONS_MAX: usize = 5;
+const SUGGESTED_EMOJI_MAX_CHARS: usize = 8;
+
+/// Common app themes and their icons, checked before asking the agent.
+const KEYWORD_EMOJIS: &[(&str, &str)] = &[
+    ("todo", "✅"),
+    ("task", "✅"),
+    ("checklist", "✅"),
+    ("note", "📝"),
+    ("journal", "📓"),
+    ("diary", "📓"),
+    ("budget", "💰"),
+    ("expense", "💰"),
+    ("money", "💰"),
+    ("finance", "💰"),
+    ("invoice", "🧾"),
+    ("calendar", "📅"),
+    ("planner", "📅"),
+    ("schedule", "📅"),
+    ("habit", "🎯"),
+    ("goal", "🎯"),
+    ("timer", "⏱️"),
+    ("pomodoro", "🍅"),
+    ("stopwatch", "⏱️"),
+    ("clock", "⏰"),
+    ("alarm", "⏰"),
+    ("weather", "🌤️"),
+    ("recipe", "🍳"),
+    ("meal", "🍽️"),
+    ("grocery", "🛒"),
+    ("groceries", "🛒"),
+    ("shopping", "🛒"),
+    ("workout", "💪"),
+    ("fitness", "💪"),
+    ("exercise", "💪"),
+    ("run", "🏃"),
+    ("water", "💧"),
+    ("sleep", "😴"),
+    ("mood", "🙂"),
+    ("chart", "📊"),
+    ("dashboard", "📊"),
+    ("analytics", "📊"),
+    ("game", "🎮"),
+    ("quiz", "❓"),
+    ("flashcard", "🧠"),
+    ("music", "🎵"),
+    ("playlist", "🎵"),
+    ("photo", "📸"),
+    ("drawing", "🎨"),
+    ("paint", "🎨"),
+    ("color", "🎨"),
+    ("chat", "💬"),
+    ("message", "💬"),
+    ("book", "📚"),
+    ("reading", "📚"),
+    ("travel", "✈️"),
+    ("trip", "✈️"),
+    ("map", "🗺️"),
+    ("home", "🏠"),
+    ("plant", "🪴"),
+    ("garden", "🪴"),
+    ("pet", "🐾"),
+    ("calculator", "🧮"),
+    ("converter", "🔁"),
+    ("password", "🔑"),
+    ("code", "💻"),
+    ("idea", "💡"),
+    ("movie", "🎬"),
+    ("gift", "🎁"),
+];
+
+/// A name and emoji proposed by the agent for a prompt, so the user doesn't
+/// have to invent one before generating.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameSuggestion {
+    pub name: String,
+    pub emoji: String,
+}
+
+/// Picks an icon from the first word in the prompt that names a common app
+/// theme, ignoring case and a plural "s" or "es".
+pub fn emoji_for_prompt(prompt: &str) -> Option<&'static str> {
+    prompt
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .find_map(|word| {
+            let stems = [Some(word.as_str()), word.strip_suffix('s'), word.strip_suffix("es")];
+            stems.into_iter().flatten().find_map(|stem| {
+                KEYWORD_EMOJIS
+                    .iter()
+                    .find(|(keyword, _)| *keyword == stem)
+                    .map(|(_, emoji)| *emoji)
+            })
+        })
+}
+
+#[derive(Deserialize)]
+struct RawNameSuggestion {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    emoji: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawNameSuggestions {
+    #[serde(default)]
+    suggestions: Vec<RawNameSuggestion>,
+}
+
+impl NameSuggestion {
+    /// Parses the namer's JSON output. Model output is untrusted, so names
+    /// that wouldn't pass as an app's are dropped, as are repeats; a bad
+    /// emoji is replaced with the default.
+    pub fn parse_list(raw: &str) -> Result<Vec<Self>, String> {
+        let raw: RawNameSuggestions = serde_json::from_str(raw)
+            .map_err(|e| format!("Failed to parse name suggestions: {}", e))?;
+
+        let mut suggestions: Vec<Self> = Vec::new();
+        for candidate in raw.suggestions {
+            let name = candidate.name.trim().to_string();
+            let valid = !name.is_empty()
+                && name.len() <= APP_NAME_MAX_LENGTH
+                && !name.chars().any(|ch| ch.is_control());
+            let duplicate = suggestions
+                .iter()
+                .any(|existing| existing.name.eq_ignore_ascii_case(&name));
+            if !valid || duplicate || suggestions.len() >= NAME_SUGGESTIONS_MAX {
+                continue;
+            }
+            let emoji = candidate
+                .emoji
+                .map(|emoji| emoji.trim().to_string())
+                .filter(|emoji| {
+                    let count = emoji.chars().count();
+                    count > 0
+                        && count <= SUGGESTED_EMOJI_MAX_CHARS
+                        && !emoji.chars().any(|ch| ch.is_ascii() || ch.is_control())
+                })
+                .unwrap_or_else(default_emoji);
+            suggestions.push(Self { name, emoji });
+        }
+        if suggestions.is_empty() {
+            return Err("Name suggestion was empty".to_string());
+        }
+        Ok(suggestions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{emoji_for_prompt, NameSuggestion};
+
+    #[test]
+    fn parse_list_drops_bad_and_repeated_names() {
+        let suggestions = NameSuggestion::parse_list(
+            r#"{"suggestions":[{"name":" Sip ","emoji":"💧"},{"name":"sip"},{"name":""},{"name":"Water Log","emoji":"water"}]}"#,
+        )
+        .expect("suggestions should parse");
+
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].name, "Sip");
+        assert_eq!(suggestions[0].emoji, "💧");
+        assert_eq!(suggestions[1].name, "Water Log");
+        assert_eq!(suggestions[1].emoji, "✨");
+        assert!(NameSuggestion::parse_list(r#"{"suggestions":[{"name":"\u0007"}]}"#).is_err());
+    }
+
+    #[test]
+    fn picks_emoji_from_first_theme_word() {
+        assert_eq!(emoji_for_prompt("Track my Expenses and a budget"), Some("💰"));
+        assert_eq!(emoji_for_prompt("A list of daily tasks"), Some("✅"));
+        assert_eq!(emoji_for_prompt("Save my favourite recipes."), Some("🍳"));
+        assert_eq!(emoji_for_prompt("A category picker"), None);
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Capabilities the user has explicitly granted to one app, beyond the
+/// storage every app gets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermissionManifest {
+    /// Folders the app may read from via `app_read_file`. Stored
+    /// canonicalized so comparisons aren't fooled by symlinks or `..`.
+    pub read_folders: Vec<PathBuf>,
+    /// Hosts the app may download files from via `app_download`.
+    pub download_domains: Vec<String>,
+    /// The app may rename itself via `request_rename` without asking again.
+    pub can_rename: bool,
+}
+
+impl PermissionManifest {
+    pub fn grant_read_folder(&mut self, folder: PathBuf) {
+        if !self.read_folders.contains(&folder) {
+            self.read_folders.push(folder);
+        }
+    }
+
+    pub fn revoke_read_folder(&mut self, folder: &Path) -> bool {
+        let before = self.read_folders.len();
+        self.read_folders.retain(|granted| granted != folder);
+        self.read_folders.len() != before
+    }
+
+    pub fn allow_download_domain(&mut self, domain: &str) {
+        let domain = domain.to_ascii_lowercase();
+        if !self.download_domains.contains(&domain) {
+            self.download_domains.push(domain);
+        }
+    }
+
+    pub fn can_download_from(&self, domain: &str) -> bool {
+        self.download_domains
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+    }
+
+    /// Whether a canonicalized path falls inside one of the granted folders.
+    pub fn can_read(&self, path: &Path) -> bool {
+        self.read_folders.iter().any(|folder| path.starts_with(folder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PermissionManifest;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn can_read_only_inside_granted_folders() {
+        let mut manifest = PermissionManifest::default();
+        manifest.grant_read_folder(PathBuf::from("/home/me/notes"));
+
+        assert!(manifest.can_read(Path::new("/home/me/notes/todo.md")));
+        assert!(manifest.can_read(Path::new("/home/me/notes/sub/a.md")));
+        assert!(!manifest.can_read(Path::new("/home/me/notes-private/a.md")));
+        assert!(!manifest.can_read(Path::new("/home/me/other.md")));
+
+        assert!(manifest.revoke_read_folder(Path::new("/home/me/notes")));
+        assert!(!manifest.can_read(Path::new("/home/me/notes/todo.md")));
+    }
+
+    #[test]
+    fn download_domains_match_exact_host_case_insensitively() {
+        let mut manifest = PermissionManifest::default();
+        manifest.allow_download_domain("Example.com");
+
+        assert!(manifest.can_download_from("example.com"));
+        assert!(manifest.can_download_from("EXAMPLE.COM"));
+        assert!(!manifest.can_download_from("cdn.example.com"));
+        assert!(!manifest.can_download_from("example.com.evil.net"));
+    }
+}
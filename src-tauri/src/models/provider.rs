@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+pub const OPENAI_DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+pub const OPENAI_DEFAULT_MODEL: &str = "gpt-4.1";
+pub const OLLAMA_DEFAULT_BASE_URL: &str = "http://localhost:11434";
+pub const OLLAMA_DEFAULT_MODEL: &str = "qwen2.5-coder";
+
+/// Which backend writes the HTML for a generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelProvider {
+    /// The bundled sidecar driving Claude Code.
+    #[default]
+    ClaudeCode,
+    /// Any server speaking OpenAI's chat completions API.
+    OpenAi,
+    /// A local Ollama instance.
+    Ollama,
+}
+
+impl ModelProvider {
+    pub fn label(self) -> &'static str {
+        match self {
+            ModelProvider::ClaudeCode => "Claude Code",
+            ModelProvider::OpenAi => "OpenAI",
+            ModelProvider::Ollama => "Ollama",
+        }
+    }
+}
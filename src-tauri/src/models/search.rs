@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Bumped whenever tokenizing or scoring changes so stored indexes are
+/// rebuilt instead of silently mis-ranking.
+pub const SEARCH_INDEX_VERSION: u32 = 1;
+/// Visible HTML text kept per app, for both terms and snippets.
+pub const SEARCH_TEXT_MAX_BYTES: usize = 64 * 1024;
+/// Longer tokens are almost always base64 or minified code, not words.
+const TERM_MAX_BYTES: usize = 40;
+const SNIPPET_CONTEXT_BYTES: usize = 60;
+
+const NAME_WEIGHT: u32 = 8;
+const PROMPT_WEIGHT: u32 = 3;
+const TEXT_WEIGHT: u32 = 1;
+
+/// The searchable text of one app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchDocument {
+    pub name: String,
+    pub prompt: String,
+    pub text: String,
+}
+
+impl SearchDocument {
+    fn terms(&self) -> HashMap<String, u32> {
+        let mut terms = HashMap::new();
+        let fields = [
+            (&self.name, NAME_WEIGHT),
+            (&self.prompt, PROMPT_WEIGHT),
+            (&self.text, TEXT_WEIGHT),
+        ];
+        for (field, weight) in fields {
+            for term in tokenize(field) {
+                *terms.entry(term).or_insert(0) += weight;
+            }
+        }
+        terms
+    }
+
+    /// A short excerpt around the first field that mentions a query term,
+    /// preferring the name, then the prompt, then the app's text.
+    fn snippet(&self, query_terms: &[String]) -> String {
+        for field in [&self.name, &self.prompt, &self.text] {
+            let lower = field.to_lowercase();
+            // Lowercasing can change byte lengths outside ASCII; only trust
+            // offsets when it didn't.
+            if lower.len() != field.len() {
+                continue;
+            }
+            let found = query_terms.iter().filter_map(|term| lower.find(term.as_str())).min();
+            if let Some(idx) = found {
+                return excerpt(field, idx);
+            }
+        }
+        excerpt(&self.prompt, 0)
+    }
+}
+
+fn excerpt(field: &str, idx: usize) -> String {
+    let mut start = idx.saturating_sub(SNIPPET_CONTEXT_BYTES);
+    while !field.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (idx + SNIPPET_CONTEXT_BYTES * 2).min(field.len());
+    while !field.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut snippet = field[start..end].trim().to_string();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < field.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Lowercased alphanumeric runs, skipping ones too long to be words.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty() && token.len() <= TERM_MAX_BYTES)
+        .map(str::to_lowercase)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub id: Uuid,
+    pub score: u32,
+    pub snippet: String,
+}
+
+/// An inverted index over app names, prompts, and visible HTML text. It is
+/// updated one app at a time as apps are saved or deleted, so searching never
+/// has to read HTML from disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchIndex {
+    pub version: u32,
+    pub documents: HashMap<Uuid, SearchDocument>,
+    /// Term -> app -> weighted occurrence count.
+    pub postings: BTreeMap<String, HashMap<Uuid, u32>>,
+}
+
+impl Default for SearchIndex {
+    fn default() -> Self {
+        Self {
+            version: SEARCH_INDEX_VERSION,
+            documents: HashMap::new(),
+            postings: BTreeMap::new(),
+        }
+    }
+}
+
+impl SearchIndex {
+    pub fn upsert(&mut self, id: Uuid, document: SearchDocument) {
+        self.remove(id);
+        for (term, weight) in document.terms() {
+            self.postings.entry(term).or_default().insert(id, weight);
+        }
+        self.documents.insert(id, document);
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<SearchDocument> {
+        let document = self.documents.remove(&id)?;
+        for term in document.terms().into_keys() {
+            if let Some(apps) = self.postings.get_mut(&term) {
+                apps.remove(&id);
+                if apps.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+        Some(document)
+    }
+
+    /// Apps matching every query term, best first. Terms match words by
+    /// prefix so results update while typing; whole-word matches count
+    /// double.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let mut query_terms: Vec<String> = Vec::new();
+        for term in tokenize(query) {
+            if !query_terms.contains(&term) {
+                query_terms.push(term);
+            }
+        }
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut scores: Option<HashMap<Uuid, u32>> = None;
+        for term in &query_terms {
+            let mut term_scores: HashMap<Uuid, u32> = HashMap::new();
+            let matches = self
+                .postings
+                .range(term.clone()..)
+                .take_while(|(indexed, _)| indexed.starts_with(term.as_str()));
+            for (indexed, apps) in matches {
+                let factor = if indexed == term { 2 } else { 1 };
+                for (id, weight) in apps {
+                    *term_scores.entry(*id).or_insert(0) += weight * factor;
+                }
+            }
+
+            scores = Some(match scores {
+                None => term_scores,
+                Some(previous) => {
+                    let matched: HashSet<Uuid> = term_scores.keys().copied().collect();
+                    previous
+                        .into_iter()
+                        .filter(|(id, _)| matched.contains(id))
+                        .map(|(id, score)| (id, score + term_scores[&id]))
+                        .collect()
+                }
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let document = self.documents.get(&id)?;
+                Some(SearchHit {
+                    id,
+                    score,
+                    snippet: document.snippet(&query_terms),
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.cmp(&a.score).then(a.id.cmp(&b.id)));
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchDocument, SearchIndex};
+    use uuid::Uuid;
+
+    fn document(name: &str, prompt: &str, text: &str) -> SearchDocument {
+        SearchDocument {
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn ranks_name_matches_above_text_matches() {
+        let mut index = SearchIndex::default();
+        let budget = Uuid::new_v4();
+        let notes = Uuid::new_v4();
+        index.upsert(budget, document("Budget", "Track spending", "Monthly budget"));
+        index.upsert(notes, document("Notes", "Jot things down", "Remember the budget"));
+
+        let hits = index.search("budg");
+        let ids: Vec<Uuid> = hits.iter().map(|hit| hit.id).collect();
+        assert_eq!(ids, vec![budget, notes]);
+        assert_eq!(hits[1].snippet, "Remember the budget");
+        assert!(index.search("budget spending").iter().all(|hit| hit.id == budget));
+    }
+
+    #[test]
+    fn upsert_and_remove_keep_postings_in_sync() {
+        let mut index = SearchIndex::default();
+        let id = Uuid::new_v4();
+        index.upsert(id, document("Timer", "Pomodoro timer", ""));
+        index.upsert(id, document("Clock", "World clock", ""));
+
+        assert!(index.search("timer").is_empty());
+        assert_eq!(index.search("clock").len(), 1);
+
+        index.remove(id);
+        assert!(index.postings.is_empty());
+        assert!(index.search("clock").is_empty());
+    }
+}
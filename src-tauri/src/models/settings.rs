@@ -0,0 +1,368 @@
+use crate::models::{
+    validate_language, ModelProvider, OpenMode, PromptTemplate, APP_PROMPT_MAX_LENGTH,
+    OLLAMA_DEFAULT_BASE_URL, OLLAMA_DEFAULT_MODEL, OPENAI_DEFAULT_BASE_URL, OPENAI_DEFAULT_MODEL,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Hard ceiling for the configurable prompt limit. Prompts are passed to the
+/// sidecar as a process argument, so this stays well below platform ARG_MAX.
+pub const APP_PROMPT_MAX_LENGTH_CEILING: usize = 32_000;
+
+/// Highest configurable `max_concurrent_generations`; each generation runs
+/// its own sidecar process.
+pub const MAX_CONCURRENT_GENERATIONS_CEILING: usize = 8;
+
+/// Longest configurable retention, in days, for logs and drafts.
+pub const RETENTION_DAYS_CEILING: u32 = 3650;
+
+/// Highest configurable `max_log_size_kb`.
+pub const MAX_LOG_SIZE_KB_CEILING: u64 = 64 * 1024;
+
+/// Highest configurable `html_cache_mb`; the cache lives in memory.
+pub const HTML_CACHE_MB_CEILING: u64 = 4096;
+
+pub const SETTINGS_PROFILE_FORMAT_VERSION: u32 = 1;
+
+pub const USER_PROFILE_NAME_MAX_LENGTH: usize = 32;
+
+/// A separate library of apps, e.g. for handing the laptop to a child. Each
+/// profile has its own apps directory, index and storage; the main
+/// profile's apps listed in `app_ids` are shared into it read-only through
+/// the gallery, with storage kept in the profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub name: String,
+    pub app_ids: Vec<Uuid>,
+}
+
+/// Profile names double as storage directory names, so they are limited to
+/// ASCII letters, digits, `-` and `_`.
+pub fn validate_user_profile_name(name: &str) -> Result<(), String> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || name.len() > USER_PROFILE_NAME_MAX_LENGTH || !valid_chars {
+        return Err(format!(
+            "Profile names must be 1 to {} letters, digits, '-' or '_'",
+            USER_PROFILE_NAME_MAX_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub prompt_max_length: usize,
+    /// Hold back edits whose output fails validation or shrinks drastically.
+    pub guard_suspicious_edits: bool,
+    /// Version snapshots kept per app; older snapshots are pruned by cleanup.
+    pub max_versions_per_app: usize,
+    /// Audit log entries older than this are pruned by cleanup.
+    pub max_log_age_days: u32,
+    /// Cleanup drops each app's oldest audit log entries past this size.
+    pub max_log_size_kb: u64,
+    /// Generations and saves fail early when free space drops below this.
+    pub min_free_disk_mb: u64,
+    /// On macOS, run the sidecar under `sandbox-exec` so it can only write
+    /// to its workspace, temp dirs, and Claude Code's config.
+    pub sandbox_sidecar: bool,
+    /// CPU-time rlimit for the sidecar on Unix; no limit when unset.
+    pub sidecar_max_cpu_seconds: Option<u64>,
+    /// Generations whose sidecar process tree grows past this much resident
+    /// memory are killed; no limit when unset.
+    pub sidecar_max_memory_mb: Option<u64>,
+    /// Generations that may run at once, each in its own sidecar; more wait
+    /// in the queue.
+    pub max_concurrent_generations: usize,
+    /// Generated drafts untouched for this long are deleted by cleanup;
+    /// imported drafts are kept until reviewed.
+    pub max_draft_age_days: u32,
+    /// Language for new apps' UI text when generation doesn't name one.
+    /// Unset leaves the choice to the model, which follows the prompt.
+    pub default_language: Option<String>,
+    /// How `open_app` shows apps without their own override.
+    pub default_open_mode: OpenMode,
+    /// After a generation, ask the agent to suggest tags and a category.
+    pub suggest_tags: bool,
+    /// Before generating a new app, let the agent ask one clarifying
+    /// question when the prompt is too vague to build well.
+    pub ask_clarifying_questions: bool,
+    /// Unlocked password-protected apps lock again after this many minutes
+    /// without activity; they stay unlocked until quit when unset.
+    pub app_lock_idle_minutes: Option<u32>,
+    /// Memory for keeping frequently opened apps' HTML ready to serve;
+    /// nothing is cached when unset.
+    pub html_cache_mb: Option<u64>,
+    /// Percentages of the HTML size limit at which a generation emits
+    /// `generation-warning` and carries on; empty for no warnings.
+    pub html_size_warning_percents: Vec<u8>,
+    /// Compile a weekly summary of app usage, generations and storage
+    /// growth; see `get_latest_digest`.
+    pub weekly_digest: bool,
+    /// Pause background work such as maintenance and digests while running
+    /// on battery; see `set_low_power_mode`.
+    pub low_power_mode: bool,
+    /// Backend for generations that don't pick one themselves.
+    pub provider: ModelProvider,
+    /// Chat completions endpoint root for the OpenAI provider; any
+    /// compatible server works. Keys are stored per base URL outside
+    /// settings (see `set_provider_api_key`); `OPENAI_API_KEY` is only sent
+    /// to api.openai.com.
+    pub openai_base_url: String,
+    pub openai_model: String,
+    pub ollama_base_url: String,
+    pub ollama_model: String,
+    /// Restricted profiles; the main profile isn't listed and sees every app.
+    pub profiles: Vec<UserProfile>,
+    /// The profile in use, or `None` for the main profile.
+    pub current_profile: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            prompt_max_length: APP_PROMPT_MAX_LENGTH,
+            guard_suspicious_edits: true,
+            max_versions_per_app: 50,
+            max_log_age_days: 90,
+            max_log_size_kb: 512,
+            min_free_disk_mb: 200,
+            sandbox_sidecar: false,
+            sidecar_max_cpu_seconds: None,
+            sidecar_max_memory_mb: Some(4096),
+            max_concurrent_generations: 2,
+            max_draft_age_days: 14,
+            default_language: None,
+            default_open_mode: OpenMode::Panel,
+            suggest_tags: false,
+            ask_clarifying_questions: false,
+            app_lock_idle_minutes: Some(15),
+            html_cache_mb: Some(64),
+            html_size_warning_percents: vec![80, 95],
+            weekly_digest: false,
+            low_power_mode: false,
+            provider: ModelProvider::ClaudeCode,
+            openai_base_url: OPENAI_DEFAULT_BASE_URL.to_string(),
+            openai_model: OPENAI_DEFAULT_MODEL.to_string(),
+            ollama_base_url: OLLAMA_DEFAULT_BASE_URL.to_string(),
+            ollama_model: OLLAMA_DEFAULT_MODEL.to_string(),
+            profiles: Vec::new(),
+            current_profile: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.prompt_max_length == 0 || self.prompt_max_length > APP_PROMPT_MAX_LENGTH_CEILING {
+            return Err(format!(
+                "Prompt limit must be between 1 and {} characters",
+                APP_PROMPT_MAX_LENGTH_CEILING
+            ));
+        }
+        if self.max_versions_per_app == 0 {
+            return Err("At least one version must be kept per app".to_string());
+        }
+        if !(1..=RETENTION_DAYS_CEILING).contains(&self.max_log_age_days) {
+            return Err(format!(
+                "Log retention must be between 1 and {} days",
+                RETENTION_DAYS_CEILING
+            ));
+        }
+        if !(1..=MAX_LOG_SIZE_KB_CEILING).contains(&self.max_log_size_kb) {
+            return Err(format!(
+                "Log size limit must be between 1 and {} KB",
+                MAX_LOG_SIZE_KB_CEILING
+            ));
+        }
+        if !(1..=RETENTION_DAYS_CEILING).contains(&self.max_draft_age_days) {
+            return Err(format!(
+                "Drafts must be kept for between 1 and {} days",
+                RETENTION_DAYS_CEILING
+            ));
+        }
+        if self.sidecar_max_cpu_seconds == Some(0) || self.sidecar_max_memory_mb == Some(0) {
+            return Err("Sidecar limits must be greater than zero".to_string());
+        }
+        if !(1..=MAX_CONCURRENT_GENERATIONS_CEILING).contains(&self.max_concurrent_generations) {
+            return Err(format!(
+                "Concurrent generations must be between 1 and {}",
+                MAX_CONCURRENT_GENERATIONS_CEILING
+            ));
+        }
+        if self.app_lock_idle_minutes == Some(0) {
+            return Err("App lock timeout must be at least one minute".to_string());
+        }
+        if let Some(megabytes) = self.html_cache_mb {
+            if !(1..=HTML_CACHE_MB_CEILING).contains(&megabytes) {
+                return Err(format!(
+                    "HTML cache must be between 1 and {} MB",
+                    HTML_CACHE_MB_CEILING
+                ));
+            }
+        }
+        if self
+            .html_size_warning_percents
+            .iter()
+            .any(|percent| !(1..=99).contains(percent))
+        {
+            return Err("Size warnings must be between 1% and 99% of the limit".to_string());
+        }
+        if let Some(language) = &self.default_language {
+            validate_language(language)?;
+        }
+        for base_url in [&self.openai_base_url, &self.ollama_base_url] {
+            let scheme = reqwest::Url::parse(base_url).map(|url| url.scheme().to_string());
+            if !matches!(scheme.as_deref(), Ok("http" | "https")) {
+                return Err(format!("Invalid provider URL: {}", base_url));
+            }
+        }
+        if self.openai_model.trim().is_empty() || self.ollama_model.trim().is_empty() {
+            return Err("Provider models cannot be empty".to_string());
+        }
+        for (index, profile) in self.profiles.iter().enumerate() {
+            validate_user_profile_name(&profile.name)?;
+            if self.profiles[..index].iter().any(|other| other.name == profile.name) {
+                return Err(format!("Duplicate profile: {}", profile.name));
+            }
+        }
+        if let Some(name) = &self.current_profile {
+            if self.active_profile().is_none() {
+                return Err(format!("Profile not found: {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn active_profile(&self) -> Option<&UserProfile> {
+        let name = self.current_profile.as_ref()?;
+        self.profiles.iter().find(|profile| &profile.name == name)
+    }
+
+    /// These settings, except for the ones that never travel between
+    /// machines, which are taken from `local`: user profiles, provider
+    /// endpoints (they receive the API key), edit guarding, and the
+    /// sidecar's sandbox and limits.
+    pub fn keeping_machine_settings(self, local: &Settings) -> Settings {
+        Settings {
+            guard_suspicious_edits: local.guard_suspicious_edits,
+            sandbox_sidecar: local.sandbox_sidecar,
+            sidecar_max_cpu_seconds: local.sidecar_max_cpu_seconds,
+            sidecar_max_memory_mb: local.sidecar_max_memory_mb,
+            openai_base_url: local.openai_base_url.clone(),
+            ollama_base_url: local.ollama_base_url.clone(),
+            profiles: local.profiles.clone(),
+            current_profile: local.current_profile.clone(),
+            ..self
+        }
+    }
+
+    /// Whether the active profile may see a main profile app. The main
+    /// profile sees every app; a profile missing from the list sees none.
+    pub fn is_app_visible(&self, id: Uuid) -> bool {
+        match &self.current_profile {
+            None => true,
+            Some(_) => self
+                .active_profile()
+                .is_some_and(|profile| profile.app_ids.contains(&id)),
+        }
+    }
+}
+
+/// A portable copy of the user's preferences for setting up another machine.
+/// Only user-facing preferences belong here; nothing secret or tied to this
+/// machine's apps, endpoints or security.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsProfile {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub settings: Settings,
+    #[serde(default)]
+    pub templates: Vec<PromptTemplate>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Settings, UserProfile, HTML_CACHE_MB_CEILING, RETENTION_DAYS_CEILING};
+    use uuid::Uuid;
+
+    #[test]
+    fn imported_settings_keep_machine_settings() {
+        let local = Settings {
+            openai_base_url: "https://api.openai.com/v1".to_string(),
+            sandbox_sidecar: true,
+            ..Settings::default()
+        };
+        let imported = Settings {
+            openai_base_url: "https://collector.example.com/v1".to_string(),
+            sandbox_sidecar: false,
+            sidecar_max_memory_mb: None,
+            max_versions_per_app: 7,
+            ..Settings::default()
+        };
+
+        let merged = imported.keeping_machine_settings(&local);
+        assert_eq!(merged.openai_base_url, "https://api.openai.com/v1");
+        assert!(merged.sandbox_sidecar);
+        assert_eq!(merged.sidecar_max_memory_mb, local.sidecar_max_memory_mb);
+        assert_eq!(merged.max_versions_per_app, 7);
+    }
+
+    #[test]
+    fn html_cache_size_is_bounded() {
+        let mut settings = Settings {
+            html_cache_mb: Some(HTML_CACHE_MB_CEILING),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.html_cache_mb = Some(u64::MAX);
+        assert!(settings.validate().is_err());
+        settings.html_cache_mb = Some(0);
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn retention_is_bounded() {
+        let mut settings = Settings {
+            max_log_age_days: RETENTION_DAYS_CEILING,
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        settings.max_log_age_days = u32::MAX;
+        assert!(settings.validate().is_err());
+        settings.max_log_age_days = 90;
+        settings.max_draft_age_days = u32::MAX;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn profiles_restrict_visible_apps() {
+        let (game, budget) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut settings = Settings {
+            profiles: vec![UserProfile {
+                name: "kids".to_string(),
+                app_ids: vec![game],
+            }],
+            ..Settings::default()
+        };
+        assert!(settings.is_app_visible(budget));
+
+        settings.current_profile = Some("kids".to_string());
+        assert!(settings.validate().is_ok());
+        assert!(settings.is_app_visible(game));
+        assert!(!settings.is_app_visible(budget));
+
+        settings.current_profile = Some("guests".to_string());
+        assert!(settings.validate().is_err());
+        assert!(!settings.is_app_visible(game));
+
+        settings.current_profile = None;
+        settings.profiles[0].name = "../kids".to_string();
+        assert!(settings.validate().is_err());
+    }
+}
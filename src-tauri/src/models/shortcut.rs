@@ -0,0 +1,147 @@
+use std::fmt;
+
+pub const MAX_APP_SHORTCUTS: usize = 20;
+pub const SHORTCUT_ID_MAX_LENGTH: usize = 64;
+
+/// Combinations Trove or the system already use, which apps can't take.
+const RESERVED_PRIMARY_KEYS: &[&str] = &[
+    "A",
+    "C",
+    "N",
+    "Q",
+    "V",
+    "W",
+    "X",
+    "Z",
+    "Backspace",
+    "Delete",
+];
+
+const NAMED_KEYS: &[&str] = &[
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+    "Enter",
+    "Space",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+];
+
+/// A key combination an app registers through `TroveShortcuts.register`,
+/// written like "CmdOrCtrl+Shift+K". Cmd and Ctrl are the same modifier, so
+/// a shortcut works on every platform. The runtime builds the same text
+/// from key events, so `Display` output is what it matches on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accelerator {
+    pub primary: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub key: String,
+}
+
+impl Accelerator {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid shortcut: {}", text);
+        let mut accelerator = Accelerator {
+            primary: false,
+            alt: false,
+            shift: false,
+            key: String::new(),
+        };
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let (key, modifiers) = parts.split_last().ok_or_else(invalid)?;
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "cmdorctrl" | "commandorcontrol" | "cmd" | "command" | "ctrl" | "control"
+                | "meta" => accelerator.primary = true,
+                "alt" | "option" => accelerator.alt = true,
+                "shift" => accelerator.shift = true,
+                _ => return Err(invalid()),
+            }
+        }
+        accelerator.key = normalize_key(key).ok_or_else(invalid)?;
+
+        let function_key = accelerator.key.starts_with('F') && accelerator.key.len() > 1;
+        if !accelerator.primary && !accelerator.alt && !function_key {
+            return Err(format!(
+                "Shortcut {} needs CmdOrCtrl or Alt; handle plain keys in the app",
+                text
+            ));
+        }
+        if accelerator.primary && RESERVED_PRIMARY_KEYS.contains(&accelerator.key.as_str()) {
+            return Err(format!("Shortcut {} is reserved", accelerator));
+        }
+        Ok(accelerator)
+    }
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return ch
+            .is_ascii_alphanumeric()
+            .then(|| ch.to_ascii_uppercase().to_string());
+    }
+    if let Some(number) = key
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u8>().ok())
+    {
+        return (1..=12).contains(&number).then(|| format!("F{}", number));
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|named| named.eq_ignore_ascii_case(key))
+        .map(|named| named.to_string())
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.primary {
+            write!(f, "CmdOrCtrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// A shortcut an app registered on the window showing it.
+#[derive(Debug, Clone)]
+pub struct AppShortcut {
+    pub id: String,
+    pub accelerator: Accelerator,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Accelerator;
+
+    #[test]
+    fn parses_and_normalizes_accelerators() {
+        let parsed = Accelerator::parse("shift + ctrl + k").unwrap();
+        assert_eq!(parsed.to_string(), "CmdOrCtrl+Shift+K");
+        assert_eq!(
+            Accelerator::parse("Option+arrowup").unwrap().to_string(),
+            "Alt+ArrowUp"
+        );
+        assert_eq!(Accelerator::parse("f5").unwrap().to_string(), "F5");
+        assert_eq!(
+            Accelerator::parse("CmdOrCtrl+Shift+N").unwrap_err(),
+            "Shortcut CmdOrCtrl+Shift+N is reserved"
+        );
+        assert!(Accelerator::parse("K").is_err());
+        assert!(Accelerator::parse("Ctrl+F13").is_err());
+        assert!(Accelerator::parse("Hyper+K").is_err());
+        assert!(Accelerator::parse("Ctrl+").is_err());
+    }
+}
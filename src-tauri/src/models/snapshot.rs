@@ -0,0 +1,108 @@
+use crate::utils::ContentHasher;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// A stored copy of some HTML, shared by every snapshot with that content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotBlob {
+    pub bytes: u64,
+    pub refs: u32,
+}
+
+/// Maps an app's version and restore point ids to content-addressed blobs,
+/// so saving the same HTML again only adds a reference instead of a copy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotStore {
+    pub blobs: BTreeMap<String, SnapshotBlob>,
+    pub snapshots: HashMap<Uuid, String>,
+}
+
+/// The blob name for `content`. `attempt` disambiguates contents that
+/// happen to share a hash; the caller compares contents to detect that.
+pub fn content_key(content: &str, attempt: u32) -> String {
+    let mut hasher = ContentHasher::new();
+    hasher.update(content.as_bytes());
+    let key = format!("{}-{:x}", hasher.finish(), content.len());
+    if attempt == 0 {
+        key
+    } else {
+        format!("{}-{}", key, attempt)
+    }
+}
+
+impl SnapshotStore {
+    pub fn key_for(&self, id: Uuid) -> Option<&str> {
+        self.snapshots.get(&id).map(String::as_str)
+    }
+
+    /// Points `id` at the blob `key`, creating it with `bytes` if new.
+    pub fn add(&mut self, id: Uuid, key: String, bytes: u64) {
+        if let Some(previous) = self.snapshots.get(&id).cloned() {
+            if previous == key {
+                return;
+            }
+            self.release(id);
+        }
+        self.blobs
+            .entry(key.clone())
+            .or_insert(SnapshotBlob { bytes, refs: 0 })
+            .refs += 1;
+        self.snapshots.insert(id, key);
+    }
+
+    /// Drops `id`'s reference. Returns the blob's key once nothing else
+    /// refers to it, meaning its file can be deleted.
+    pub fn release(&mut self, id: Uuid) -> Option<String> {
+        let key = self.snapshots.remove(&id)?;
+        let blob = self.blobs.get_mut(&key)?;
+        blob.refs = blob.refs.saturating_sub(1);
+        if blob.refs > 0 {
+            return None;
+        }
+        self.blobs.remove(&key);
+        Some(key)
+    }
+
+    /// Bytes that would be on disk if every snapshot had its own copy,
+    /// minus what is actually stored.
+    pub fn saved_bytes(&self) -> u64 {
+        self.blobs
+            .values()
+            .map(|blob| blob.bytes * u64::from(blob.refs.saturating_sub(1)))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{content_key, SnapshotStore};
+    use uuid::Uuid;
+
+    #[test]
+    fn shares_blobs_until_last_reference_is_released() {
+        let mut store = SnapshotStore::default();
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let same = content_key("<p>same</p>", 0);
+        let other = content_key("<p>other</p>", 0);
+        store.add(first, same.clone(), 11);
+        store.add(second, same.clone(), 11);
+        store.add(third, other.clone(), 12);
+
+        assert_eq!(store.blobs.len(), 2);
+        assert_eq!(store.saved_bytes(), 11);
+
+        assert_eq!(store.release(first), None);
+        assert_eq!(store.release(second), Some(same));
+        assert_eq!(store.release(second), None);
+        assert_eq!(store.saved_bytes(), 0);
+        assert_eq!(store.key_for(third), Some(other.as_str()));
+    }
+
+    #[test]
+    fn content_keys_are_stable_and_disambiguated() {
+        assert_eq!(content_key("abc", 0), "e71fa2190541574b-3");
+        assert_eq!(content_key("abc", 2), "e71fa2190541574b-3-2");
+        assert_ne!(content_key("abc", 0), content_key("abd", 0));
+    }
+}
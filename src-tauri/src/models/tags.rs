@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const APP_TAGS_MAX: usize = 10;
+pub const SUGGESTED_TAGS_MAX: usize = 5;
+const TAG_MAX_CHARS: usize = 24;
+const CATEGORY_MAX_CHARS: usize = 32;
+
+/// Tags and a category proposed by the classification pass after
+/// generation. Nothing is applied until the user accepts it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagSuggestion {
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub suggested_at: DateTime<Utc>,
+}
+
+/// Lowercases a tag and collapses whitespace to single dashes. Returns
+/// `None` for tags that are empty or too long once normalized.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= TAG_MAX_CHARS
+        && tag.chars().all(|c| c.is_alphanumeric() || c == '-');
+    valid.then_some(tag)
+}
+
+/// Adds tags not already present, keeping at most `APP_TAGS_MAX`.
+pub fn merge_tags(existing: &mut Vec<String>, tags: &[String]) {
+    for tag in tags {
+        if existing.len() >= APP_TAGS_MAX {
+            break;
+        }
+        if !existing.contains(tag) {
+            existing.push(tag.clone());
+        }
+    }
+}
+
+/// Normalizes untrusted tags, such as an imported bundle's, dropping
+/// invalid and duplicate ones and keeping at most `APP_TAGS_MAX`.
+pub fn normalize_tags(raw: &[String]) -> Vec<String> {
+    let normalized: Vec<String> = raw.iter().filter_map(|tag| normalize_tag(tag)).collect();
+    let mut tags = Vec::new();
+    merge_tags(&mut tags, &normalized);
+    tags
+}
+
+/// Trims a category, returning `None` if it's empty or too long.
+pub fn normalize_category(category: &str) -> Option<String> {
+    let category = category.trim();
+    (!category.is_empty() && category.chars().count() <= CATEGORY_MAX_CHARS)
+        .then(|| category.to_string())
+}
+
+#[derive(Deserialize)]
+struct RawSuggestion {
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl TagSuggestion {
+    /// Parses the classifier's JSON output. Model output is untrusted, so
+    /// malformed tags are dropped rather than failing the whole suggestion.
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let raw: RawSuggestion = serde_json::from_str(raw)
+            .map_err(|e| format!("Failed to parse tag suggestion: {}", e))?;
+
+        let mut tags = Vec::new();
+        for tag in raw.tags.iter().filter_map(|tag| normalize_tag(tag)) {
+            if !tags.contains(&tag) && tags.len() < SUGGESTED_TAGS_MAX {
+                tags.push(tag);
+            }
+        }
+        let category = raw.category.as_deref().and_then(normalize_category);
+        if tags.is_empty() && category.is_none() {
+            return Err("Tag suggestion was empty".to_string());
+        }
+
+        Ok(Self {
+            category,
+            tags,
+            suggested_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_tags, normalize_category, normalize_tags, TagSuggestion, APP_TAGS_MAX};
+
+    #[test]
+    fn parse_normalizes_and_drops_bad_tags() {
+        let suggestion = TagSuggestion::parse(
+            r#"{"category":" Productivity ","tags":["To Do","to-do","<script>","Habits","x","y","z"]}"#,
+        )
+        .expect("suggestion should parse");
+
+        assert_eq!(suggestion.category.as_deref(), Some("Productivity"));
+        assert_eq!(suggestion.tags, vec!["to-do", "habits", "x", "y", "z"]);
+        assert!(TagSuggestion::parse(r#"{"tags":["!!"]}"#).is_err());
+    }
+
+    #[test]
+    fn normalizes_untrusted_tags_and_category() {
+        let raw: Vec<String> = ["Deep Work", "deep-work", "<b>"]
+            .into_iter()
+            .map(String::from)
+            .chain((0..APP_TAGS_MAX).map(|i| format!("t{}", i)))
+            .collect();
+        let tags = normalize_tags(&raw);
+        assert_eq!(tags.len(), APP_TAGS_MAX);
+        assert_eq!(tags[..2], ["deep-work", "t0"]);
+
+        assert_eq!(normalize_category(" Health ").as_deref(), Some("Health"));
+        assert_eq!(normalize_category("  "), None);
+        assert_eq!(normalize_category(&"x".repeat(100)), None);
+    }
+
+    #[test]
+    fn merge_skips_duplicates_and_caps_count() {
+        let mut tags = vec!["finance".to_string()];
+        let incoming: Vec<String> = (0..APP_TAGS_MAX + 3).map(|i| format!("t{}", i)).collect();
+        merge_tags(&mut tags, &["finance".to_string()]);
+        assert_eq!(tags.len(), 1);
+
+        merge_tags(&mut tags, &incoming);
+        assert_eq!(tags.len(), APP_TAGS_MAX);
+        assert_eq!(tags[0], "finance");
+    }
+}
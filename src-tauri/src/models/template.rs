@@ -0,0 +1,197 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const MAX_PROMPT_TEMPLATES: usize = 100;
+pub const TEMPLATE_VARIABLE_MAX_LENGTH: usize = 32;
+pub const TEMPLATE_VALUE_MAX_LENGTH: usize = 200;
+
+/// A reusable prompt with `{variable}` placeholders, e.g. "A {sport} score
+/// tracker for {n} players". `{{` and `}}` stand for literal braces.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub prompt: String,
+    /// Placeholder names in order of first use, derived from `prompt`.
+    pub variables: Vec<String>,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplatesIndex {
+    pub templates: Vec<PromptTemplate>,
+}
+
+impl TemplatesIndex {
+    pub fn get(&self, id: Uuid) -> Option<&PromptTemplate> {
+        self.templates.iter().find(|t| t.id == id)
+    }
+
+    /// Inserts or replaces a template, keeping the most recently saved first.
+    pub fn upsert(&mut self, template: PromptTemplate) -> Result<(), String> {
+        self.templates.retain(|t| t.id != template.id);
+        if self.templates.len() >= MAX_PROMPT_TEMPLATES {
+            return Err(format!(
+                "At most {} templates can be saved",
+                MAX_PROMPT_TEMPLATES
+            ));
+        }
+        self.templates.insert(0, template);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: Uuid) -> Option<PromptTemplate> {
+        let pos = self.templates.iter().position(|t| t.id == id)?;
+        Some(self.templates.remove(pos))
+    }
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Brace(char),
+    Variable(&'a str),
+}
+
+fn segments(prompt: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = prompt;
+    while let Some(pos) = rest.find(['{', '}']) {
+        if pos > 0 {
+            segments.push(Segment::Text(&rest[..pos]));
+        }
+        rest = &rest[pos..];
+        if let Some(after) = rest.strip_prefix("{{") {
+            segments.push(Segment::Brace('{'));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("}}") {
+            segments.push(Segment::Brace('}'));
+            rest = after;
+        } else if rest.starts_with('}') {
+            return Err("Template has an unmatched '}'; use '}}' for a literal brace".to_string());
+        } else {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| "Template has an unclosed '{'".to_string())?;
+            let name = &rest[1..end];
+            validate_variable_name(name)?;
+            segments.push(Segment::Variable(name));
+            rest = &rest[end + 1..];
+        }
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+fn validate_variable_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= TEMPLATE_VARIABLE_MAX_LENGTH
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!(
+            "Invalid template variable {{{}}}: use up to {} letters, digits or '_'",
+            name, TEMPLATE_VARIABLE_MAX_LENGTH
+        ));
+    }
+    Ok(())
+}
+
+/// The placeholder names in `prompt`, in order of first use.
+pub fn parse_template_variables(prompt: &str) -> Result<Vec<String>, String> {
+    let mut variables: Vec<String> = Vec::new();
+    for segment in segments(prompt)? {
+        if let Segment::Variable(name) = segment {
+            if !variables.iter().any(|v| v == name) {
+                variables.push(name.to_string());
+            }
+        }
+    }
+    Ok(variables)
+}
+
+impl PromptTemplate {
+    /// Substitutes `values` into the prompt. Every variable needs a
+    /// non-empty single-line value and unknown names are rejected, so a typo
+    /// can't silently leave a placeholder in the generated prompt.
+    pub fn instantiate(&self, values: &HashMap<String, String>) -> Result<String, String> {
+        if let Some(unknown) = values.keys().find(|name| !self.variables.contains(name)) {
+            return Err(format!("Template has no variable named {}", unknown));
+        }
+        for name in &self.variables {
+            let value = values
+                .get(name)
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("Missing value for {{{}}}", name))?;
+            if value.chars().count() > TEMPLATE_VALUE_MAX_LENGTH {
+                return Err(format!(
+                    "Value for {{{}}} must be at most {} characters",
+                    name, TEMPLATE_VALUE_MAX_LENGTH
+                ));
+            }
+            if value.chars().any(|c| c.is_control()) {
+                return Err(format!("Value for {{{}}} contains invalid characters", name));
+            }
+        }
+
+        let mut prompt = String::with_capacity(self.prompt.len());
+        for segment in segments(&self.prompt)? {
+            match segment {
+                Segment::Text(text) => prompt.push_str(text),
+                Segment::Brace(brace) => prompt.push(brace),
+                Segment::Variable(name) => prompt.push_str(values[name].trim()),
+            }
+        }
+        Ok(prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_template_variables, PromptTemplate};
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    #[test]
+    fn parses_variables_and_rejects_malformed_braces() {
+        assert_eq!(
+            parse_template_variables("A {sport} tracker for {n} players, {{fun}} {sport}").unwrap(),
+            vec!["sport", "n"]
+        );
+        assert!(parse_template_variables("A {sport tracker").is_err());
+        assert!(parse_template_variables("A sport} tracker").is_err());
+        assert!(parse_template_variables("A {1st} tracker").is_err());
+        assert!(parse_template_variables("A {} tracker").is_err());
+    }
+
+    #[test]
+    fn instantiates_only_with_every_variable() {
+        let prompt = "A {sport} score tracker for {n} players {{live}}".to_string();
+        let template = PromptTemplate {
+            id: Uuid::new_v4(),
+            name: "Scores".to_string(),
+            variables: parse_template_variables(&prompt).unwrap(),
+            prompt,
+            saved_at: Utc::now(),
+        };
+        let mut values = HashMap::from([
+            ("sport".to_string(), " tennis ".to_string()),
+            ("n".to_string(), "4".to_string()),
+        ]);
+        assert_eq!(
+            template.instantiate(&values).unwrap(),
+            "A tennis score tracker for 4 players {live}"
+        );
+
+        values.insert("extra".to_string(), "x".to_string());
+        assert!(template.instantiate(&values).is_err());
+        values.remove("extra");
+        values.insert("n".to_string(), " ".to_string());
+        assert!(template.instantiate(&values).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const GENERATION_TIMELINES_MAX: usize = 20;
+const GENERATED_WITH_FIELD_MAX_CHARS: usize = 64;
+
+/// The sidecar build and model behind a generation, as the sidecar reports
+/// them, so quality changes can be matched up with tool updates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratedWith {
+    #[serde(default)]
+    pub sidecar_version: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl GeneratedWith {
+    /// Parses the sidecar's `TOOL_INFO:` JSON. Fields that are blank, too
+    /// long, or contain control characters are dropped.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let parsed: Self = serde_json::from_str(raw).ok()?;
+        let clean = |value: Option<String>| {
+            value.map(|value| value.trim().to_string()).filter(|value| {
+                !value.is_empty()
+                    && value.chars().count() <= GENERATED_WITH_FIELD_MAX_CHARS
+                    && !value.chars().any(|ch| ch.is_control())
+            })
+        };
+        let generated_with = Self {
+            sidecar_version: clean(parsed.sidecar_version),
+            model: clean(parsed.model),
+        };
+        (generated_with.sidecar_version.is_some() || generated_with.model.is_some())
+            .then_some(generated_with)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationKind {
+    Generate,
+    Edit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEvent {
+    Spawned,
+    FirstProgress,
+    HtmlStart,
+    HtmlEnd,
+    Terminated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineMark {
+    pub event: TimelineEvent,
+    pub at: DateTime<Utc>,
+    pub elapsed_ms: i64,
+}
+
+/// When each stage of one sidecar run happened, for diagnosing slow
+/// generations from real runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationTimeline {
+    pub kind: GenerationKind,
+    pub started_at: DateTime<Utc>,
+    pub marks: Vec<TimelineMark>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub generated_with: Option<GeneratedWith>,
+}
+
+impl GenerationTimeline {
+    pub fn new(kind: GenerationKind) -> Self {
+        Self {
+            kind,
+            started_at: Utc::now(),
+            marks: Vec::new(),
+            error: None,
+            generated_with: None,
+        }
+    }
+
+    /// Records an event the first time it happens; repeats are ignored.
+    pub fn mark(&mut self, event: TimelineEvent) {
+        if self.marks.iter().any(|mark| mark.event == event) {
+            return;
+        }
+        let at = Utc::now();
+        self.marks.push(TimelineMark {
+            event,
+            at,
+            elapsed_ms: (at - self.started_at).num_milliseconds(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationTimelines {
+    pub runs: Vec<GenerationTimeline>,
+}
+
+impl GenerationTimelines {
+    /// Appends a run, keeping only the most recent ones.
+    pub fn push(&mut self, timeline: GenerationTimeline) {
+        self.runs.push(timeline);
+        if self.runs.len() > GENERATION_TIMELINES_MAX {
+            let overflow = self.runs.len() - GENERATION_TIMELINES_MAX;
+            self.runs.drain(..overflow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeneratedWith;
+
+    #[test]
+    fn parses_reported_tool_info() {
+        let generated_with =
+            GeneratedWith::parse(r#"{"sidecar_version":" 0.1.1 ","model":"claude-sonnet-4-5"}"#)
+                .expect("tool info should parse");
+        assert_eq!(generated_with.sidecar_version.as_deref(), Some("0.1.1"));
+        assert_eq!(generated_with.model.as_deref(), Some("claude-sonnet-4-5"));
+
+        let model_only = GeneratedWith::parse(r#"{"sidecar_version":"\n","model":"haiku"}"#);
+        assert_eq!(model_only.and_then(|g| g.sidecar_version), None);
+        assert!(GeneratedWith::parse(r#"{"model":""}"#).is_none());
+        assert!(GeneratedWith::parse("not json").is_none());
+    }
+}
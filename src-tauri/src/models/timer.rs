@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const MAX_TIMERS_PER_APP: usize = 20;
+pub const TIMER_MAX_DURATION_SECS: u64 = 7 * 24 * 60 * 60;
+pub const TIMER_ID_MAX_LENGTH: usize = 64;
+pub const TIMER_LABEL_MAX_LENGTH: usize = 100;
+
+/// A countdown an app started with `TroveTimers.start`. Trove keeps it
+/// running after the app's window closes and across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppTimer {
+    pub app_id: Uuid,
+    pub id: String,
+    /// Shown in the notification when the timer finishes.
+    pub label: Option<String>,
+    pub duration_secs: u64,
+    pub started_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimersIndex {
+    pub timers: Vec<AppTimer>,
+}
+
+impl TimersIndex {
+    /// Adds a timer, restarting any the app already has with the same id.
+    pub fn start(&mut self, timer: AppTimer) -> Result<(), String> {
+        self.cancel(timer.app_id, &timer.id);
+        if self.for_app(timer.app_id).len() >= MAX_TIMERS_PER_APP {
+            return Err(format!(
+                "At most {} timers can run per app",
+                MAX_TIMERS_PER_APP
+            ));
+        }
+        self.timers.push(timer);
+        Ok(())
+    }
+
+    pub fn cancel(&mut self, app_id: Uuid, id: &str) -> Option<AppTimer> {
+        let pos = self
+            .timers
+            .iter()
+            .position(|t| t.app_id == app_id && t.id == id)?;
+        Some(self.timers.remove(pos))
+    }
+
+    /// The app's running timers, soonest first.
+    pub fn for_app(&self, app_id: Uuid) -> Vec<AppTimer> {
+        let mut timers: Vec<AppTimer> = self
+            .timers
+            .iter()
+            .filter(|t| t.app_id == app_id)
+            .cloned()
+            .collect();
+        timers.sort_by_key(|t| t.ends_at);
+        timers
+    }
+
+    /// Removes and returns the timers that have finished by `now`.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<AppTimer> {
+        let (due, running) = self.timers.drain(..).partition(|t| t.ends_at <= now);
+        self.timers = running;
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AppTimer, TimersIndex};
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    fn timer(app_id: Uuid, id: &str, secs: i64) -> AppTimer {
+        let now = Utc::now();
+        AppTimer {
+            app_id,
+            id: id.to_string(),
+            label: None,
+            duration_secs: secs as u64,
+            started_at: now,
+            ends_at: now + Duration::seconds(secs),
+        }
+    }
+
+    #[test]
+    fn restarts_by_id_and_hands_out_due_timers_once() {
+        let (pomodoro, tea) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut index = TimersIndex::default();
+        index.start(timer(pomodoro, "focus", 1500)).unwrap();
+        index.start(timer(pomodoro, "focus", 60)).unwrap();
+        index.start(timer(tea, "steep", 240)).unwrap();
+        assert_eq!(index.for_app(pomodoro).len(), 1);
+        assert_eq!(index.for_app(pomodoro)[0].duration_secs, 60);
+
+        let due = index.take_due(Utc::now() + Duration::seconds(120));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, "focus");
+        assert!(index
+            .take_due(Utc::now() + Duration::seconds(120))
+            .is_empty());
+        assert_eq!(index.timers.len(), 1);
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const MAX_VARIANTS_PER_EXPERIMENT: usize = 5;
+
+/// One candidate output of a named experiment. `id` is the id of the draft
+/// app holding the variant's HTML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationVariant {
+    pub id: Uuid,
+    pub experiment_id: Uuid,
+    pub html_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantsIndex {
+    pub variants: Vec<GenerationVariant>,
+}
+
+impl VariantsIndex {
+    pub fn remove(&mut self, id: Uuid) -> Option<GenerationVariant> {
+        let pos = self.variants.iter().position(|v| v.id == id)?;
+        Some(self.variants.remove(pos))
+    }
+}
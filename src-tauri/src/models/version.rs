@@ -0,0 +1,94 @@
+use crate::models::{AppMetadata, GeneratedWith};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+pub const MAX_RESTORE_POINTS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Edited,
+    Imported,
+    Restored,
+    /// The injected runtime was rewritten by `upgrade_apps_runtime`.
+    RuntimeUpgraded,
+}
+
+/// One step in an app's history: the prompt that produced it and the
+/// version snapshot of the HTML it left behind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub version_id: Uuid,
+    pub kind: ChangeKind,
+    pub name: String,
+    pub prompt: String,
+    pub html_bytes: u64,
+    pub created_at: DateTime<Utc>,
+    /// Set once retention cleanup has deleted this entry's HTML snapshot.
+    #[serde(default)]
+    pub pruned: bool,
+    /// Sidecar version and model that produced a created or edited version.
+    #[serde(default)]
+    pub generated_with: Option<GeneratedWith>,
+    /// Element and script counts of the snapshot; unset for entries
+    /// recorded before they were tracked.
+    #[serde(default)]
+    pub node_count: Option<u64>,
+    #[serde(default)]
+    pub script_count: Option<u64>,
+}
+
+/// Size and complexity of one saved version, for charting an app's growth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionGrowth {
+    pub version_id: Uuid,
+    pub kind: ChangeKind,
+    pub created_at: DateTime<Utc>,
+    pub html_bytes: u64,
+    pub node_count: Option<u64>,
+    pub script_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppGrowth {
+    /// Oldest first.
+    pub versions: Vec<VersionGrowth>,
+    /// The most HTML a generation may produce, so growth can be shown
+    /// against it.
+    pub size_limit_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Changelog {
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// State captured right before an edit overwrites an app. The HTML lives in
+/// the versions directory under the restore point's id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePoint {
+    pub id: Uuid,
+    pub app: AppMetadata,
+    #[serde(default)]
+    pub storage: Option<Map<String, Value>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestorePoints {
+    pub points: Vec<RestorePoint>,
+}
+
+/// An edit whose output looked suspicious and was held back instead of
+/// overwriting the app. The HTML is stored next to it on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingEdit {
+    pub app: AppMetadata,
+    pub reason: String,
+    #[serde(default)]
+    pub context: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,249 @@
+use serde_json::{Map, Number, Value};
+
+/// Nesting deeper than this is rejected when decoding, matching
+/// `serde_json`'s own recursion limit.
+const MAX_DEPTH: usize = 128;
+
+/// Encodes a JSON value as MessagePack. Integers use the smallest encoding
+/// that fits and every other number is a float64, so decoding gives back
+/// the same JSON.
+pub fn to_msgpack(value: &Value) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    write_value(&mut out, value)?;
+    Ok(out)
+}
+
+fn write_len(
+    out: &mut Vec<u8>,
+    len: usize,
+    fix: Option<(u8, usize)>,
+    wide: [u8; 3],
+) -> Result<(), String> {
+    match (fix, len) {
+        (Some((marker, limit)), len) if len < limit => out.push(marker | len as u8),
+        (_, len) if len <= u8::MAX as usize && wide[0] != 0 => out.extend([wide[0], len as u8]),
+        (_, len) if len <= u16::MAX as usize => {
+            out.push(wide[1]);
+            out.extend((len as u16).to_be_bytes());
+        }
+        (_, len) if len <= u32::MAX as usize => {
+            out.push(wide[2]);
+            out.extend((len as u32).to_be_bytes());
+        }
+        _ => return Err("Value is too large to encode".to_string()),
+    }
+    Ok(())
+}
+
+fn write_number(out: &mut Vec<u8>, number: &Number) {
+    if let Some(n) = number.as_u64() {
+        match n {
+            0..=0x7f => out.push(n as u8),
+            0x80..=0xff => out.extend([0xcc, n as u8]),
+            0x100..=0xffff => {
+                out.push(0xcd);
+                out.extend((n as u16).to_be_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                out.push(0xce);
+                out.extend((n as u32).to_be_bytes());
+            }
+            _ => {
+                out.push(0xcf);
+                out.extend(n.to_be_bytes());
+            }
+        }
+    } else if let Some(n) = number.as_i64() {
+        // Only negative values reach here.
+        if n >= -32 {
+            out.push(n as i8 as u8);
+        } else if n >= i8::MIN as i64 {
+            out.extend([0xd0, n as i8 as u8]);
+        } else if n >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend((n as i16).to_be_bytes());
+        } else if n >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend((n as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend(n.to_be_bytes());
+        }
+    } else {
+        out.push(0xcb);
+        out.extend(number.as_f64().unwrap_or_default().to_be_bytes());
+    }
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) -> Result<(), String> {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(number) => write_number(out, number),
+        Value::String(text) => write_str(out, text)?,
+        Value::Array(items) => {
+            write_len(out, items.len(), Some((0x90, 16)), [0, 0xdc, 0xdd])?;
+            for item in items {
+                write_value(out, item)?;
+            }
+        }
+        Value::Object(map) => {
+            write_len(out, map.len(), Some((0x80, 16)), [0, 0xde, 0xdf])?;
+            for (key, item) in map {
+                write_str(out, key)?;
+                write_value(out, item)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_str(out: &mut Vec<u8>, text: &str) -> Result<(), String> {
+    write_len(out, text.len(), Some((0xa0, 32)), [0xd9, 0xda, 0xdb])?;
+    out.extend(text.as_bytes());
+    Ok(())
+}
+
+/// Decodes MessagePack written by `to_msgpack`. Only the types JSON can
+/// represent are accepted, and map keys must be strings.
+pub fn from_msgpack(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.value(0)?;
+    if reader.pos != bytes.len() {
+        return Err("Trailing bytes after MessagePack value".to_string());
+    }
+    Ok(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "Unexpected end of MessagePack data".to_string())?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn len(&mut self, width: usize) -> Result<usize, String> {
+        Ok(match width {
+            1 => self.array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.array()?) as usize,
+            _ => u32::from_be_bytes(self.array()?) as usize,
+        })
+    }
+
+    fn string(&mut self, len: usize) -> Result<String, String> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| "Invalid UTF-8 in MessagePack string".to_string())
+    }
+
+    fn float(f: f64) -> Result<Value, String> {
+        Number::from_f64(f)
+            .map(Value::Number)
+            .ok_or_else(|| "MessagePack float is not a JSON number".to_string())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err("MessagePack data is nested too deeply".to_string());
+        }
+        let marker = self.array::<1>()?[0];
+        Ok(match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize, depth)?,
+            0x90..=0x9f => self.list((marker & 0x0f) as usize, depth)?,
+            0xa0..=0xbf => Value::String(self.string((marker & 0x1f) as usize)?),
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xca => Self::float(f32::from_be_bytes(self.array()?) as f64)?,
+            0xcb => Self::float(f64::from_be_bytes(self.array()?))?,
+            0xcc => Value::from(self.array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(self.array::<1>()?[0] as i8),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd9..=0xdb => {
+                let len = self.len(1 << (marker - 0xd9))?;
+                Value::String(self.string(len)?)
+            }
+            0xdc | 0xdd => {
+                let len = self.len(if marker == 0xdc { 2 } else { 4 })?;
+                self.list(len, depth)?
+            }
+            0xde | 0xdf => {
+                let len = self.len(if marker == 0xde { 2 } else { 4 })?;
+                self.map(len, depth)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            _ => return Err(format!("Unsupported MessagePack type 0x{:02x}", marker)),
+        })
+    }
+
+    fn list(&mut self, len: usize, depth: usize) -> Result<Value, String> {
+        // Every element takes at least a byte, which bounds the allocation.
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.value(depth + 1)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize, depth: usize) -> Result<Value, String> {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let Value::String(key) = self.value(depth + 1)? else {
+                return Err("MessagePack map key is not a string".to_string());
+            };
+            map.insert(key, self.value(depth + 1)?);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_msgpack, to_msgpack};
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_json_values() {
+        let value = json!({
+            "n": [0, 127, 128, 255, 65536, u64::MAX, -1, -32, -33, -129, -40000, i64::MIN],
+            "f": [1.5, -0.25, 1e300],
+            "s": ["", "é", "x".repeat(40), "y".repeat(300), "z".repeat(70000)],
+            "nested": {"a": null, "b": true, "c": false, "d": [[], {}]},
+            "many": (0..20).collect::<Vec<_>>(),
+        });
+        let bytes = to_msgpack(&value).unwrap();
+        assert!(bytes.len() < serde_json::to_vec(&value).unwrap().len());
+        assert_eq!(from_msgpack(&bytes).unwrap(), value);
+
+        assert_eq!(
+            to_msgpack(&json!({"a": 1})).unwrap(),
+            [0x81, 0xa1, b'a', 0x01]
+        );
+        assert!(from_msgpack(&[0x81, 0x01, 0x01]).is_err());
+        assert!(from_msgpack(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(from_msgpack(&[0xc0, 0xc0]).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::commands::{
+    ensure_unlocked, get_app_assets_dir, get_visible_app, validate_asset_path, UnlockedApps,
+};
+use crate::utils::{parse_uuid, percent_decode};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use tauri::http::{header, Method, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager};
+
+/// Serves app assets as `trove-app://localhost/<app-id>/<path>` (on Windows
+/// `http://trove-app.localhost/<app-id>/<path>`), with range support so
+/// media elements can seek.
+pub const APP_ASSET_SCHEME: &str = "trove-app";
+
+/// Responses carry at most this many bytes; media elements ask for the rest
+/// as they play. Larger files requested whole get their first chunk as a
+/// range response.
+const RANGE_RESPONSE_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    Full,
+    /// Inclusive start and end offsets.
+    Partial(u64, u64),
+    Unsatisfiable,
+}
+
+/// What to send for a request with `header` for a file of `len` bytes.
+/// Files too large for one response are never sent whole, so a request
+/// without a usable range gets the first chunk instead.
+fn parse_range(header: Option<&str>, len: u64) -> ByteRange {
+    match parse_range_header(header, len) {
+        ByteRange::Full if len > RANGE_RESPONSE_MAX_BYTES => {
+            ByteRange::Partial(0, RANGE_RESPONSE_MAX_BYTES - 1)
+        }
+        range => range,
+    }
+}
+
+/// Interprets a `Range` header for a file of `len` bytes. Only the first
+/// range of a multi-range request is served, and a header that doesn't
+/// parse is ignored as HTTP allows.
+fn parse_range_header(header: Option<&str>, len: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    let first = spec.split(',').next().unwrap_or("").trim();
+    let Some((start, end)) = first.split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return ByteRange::Full,
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(suffix) => (len.saturating_sub(suffix), len.saturating_sub(1)),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return ByteRange::Full;
+            };
+            let end = match end {
+                "" => len.saturating_sub(1),
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+                    _ => return ByteRange::Full,
+                },
+            };
+            (start, end)
+        }
+    };
+    if len == 0 || start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+    ByteRange::Partial(start, end.min(start + RANGE_RESPONSE_MAX_BYTES - 1))
+}
+
+/// Splits a request path into the app id and the decoded asset path.
+fn parse_asset_uri(path: &str) -> Option<(&str, String)> {
+    let (app_id, asset) = path.trim_start_matches('/').split_once('/')?;
+    // `percent_decode` is form decoding; a `+` in a path is literal.
+    let asset = percent_decode(&asset.replace('+', "%2B")).ok()?;
+    Some((app_id, asset))
+}
+
+fn mime_type(path: &str) -> &'static str {
+    let extension = path
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" | "oga" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(message.as_bytes().to_vec())
+        .unwrap_or_else(|_| Response::new(Vec::new()))
+}
+
+/// Answers one `trove-app` request. Like its HTML, an app's assets are only
+/// served while it is visible in the active profile and unlocked.
+pub fn serve_app_asset(app_handle: &AppHandle, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    read_app_asset(app_handle, request)
+        .unwrap_or_else(|(status, message)| error_response(status, &message))
+}
+
+fn read_app_asset(
+    app_handle: &AppHandle,
+    request: &Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, (StatusCode, String)> {
+    let method = request.method();
+    if *method != Method::GET && *method != Method::HEAD {
+        return Err((StatusCode::METHOD_NOT_ALLOWED, "Only GET and HEAD are supported".into()));
+    }
+    let bad_request = |message: String| (StatusCode::BAD_REQUEST, message);
+    let (app_id, asset) = parse_asset_uri(request.uri().path())
+        .ok_or_else(|| bad_request("Invalid asset URL".to_string()))?;
+    let id = parse_uuid(app_id).map_err(bad_request)?;
+    let relative = validate_asset_path(&asset).map_err(bad_request)?;
+    get_visible_app(app_handle, id).map_err(|e| (StatusCode::NOT_FOUND, e))?;
+    ensure_unlocked(app_handle, &app_handle.state::<UnlockedApps>(), id)
+        .map_err(|e| (StatusCode::FORBIDDEN, e))?;
+
+    let path = get_app_assets_dir(app_handle, id)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .join(relative);
+    let not_found = |_| (StatusCode::NOT_FOUND, format!("Asset not found: {}", asset));
+    let mut file = File::open(&path).map_err(not_found)?;
+    let len = file.metadata().map_err(not_found)?.len();
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type(&asset))
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CACHE_CONTROL, "no-cache");
+    let read_failed = |e: std::io::Error| {
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read asset: {}", e))
+    };
+    let head = *method == Method::HEAD;
+
+    let (builder, body) = match parse_range(range, len) {
+        ByteRange::Full => {
+            let mut body = Vec::new();
+            if !head {
+                file.read_to_end(&mut body).map_err(read_failed)?;
+            }
+            let builder = builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_LENGTH, len.to_string());
+            (builder, body)
+        }
+        ByteRange::Partial(start, end) => {
+            let count = end - start + 1;
+            let mut body = Vec::new();
+            if !head {
+                file.seek(SeekFrom::Start(start)).map_err(read_failed)?;
+                body = vec![0; count as usize];
+                file.read_exact(&mut body).map_err(read_failed)?;
+            }
+            let builder = builder
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                .header(header::CONTENT_LENGTH, count.to_string());
+            (builder, body)
+        }
+        ByteRange::Unsatisfiable => {
+            let builder = builder
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", len));
+            (builder, Vec::new())
+        }
+    };
+    builder
+        .body(body)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mime_type, parse_asset_uri, parse_range, ByteRange, RANGE_RESPONSE_MAX_BYTES};
+
+    #[test]
+    fn parses_byte_ranges() {
+        assert_eq!(parse_range(None, 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), ByteRange::Partial(0, 9));
+        assert_eq!(parse_range(Some("bytes=90-"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=-10"), 100), ByteRange::Partial(90, 99));
+        assert_eq!(parse_range(Some("bytes=95-200"), 100), ByteRange::Partial(95, 99));
+        assert_eq!(parse_range(Some("bytes=0-1, 5-6"), 100), ByteRange::Partial(0, 1));
+        assert_eq!(parse_range(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=9-2"), 100), ByteRange::Full);
+        assert_eq!(parse_range(Some("items=0-9"), 100), ByteRange::Full);
+        assert_eq!(
+            parse_range(Some("bytes=0-"), u64::MAX),
+            ByteRange::Partial(0, RANGE_RESPONSE_MAX_BYTES - 1)
+        );
+        let large = RANGE_RESPONSE_MAX_BYTES + 1;
+        assert_eq!(parse_range(None, RANGE_RESPONSE_MAX_BYTES), ByteRange::Full);
+        assert_eq!(
+            parse_range(None, large),
+            ByteRange::Partial(0, RANGE_RESPONSE_MAX_BYTES - 1)
+        );
+        assert_eq!(
+            parse_range(Some("bytes=9-2"), large),
+            ByteRange::Partial(0, RANGE_RESPONSE_MAX_BYTES - 1)
+        );
+    }
+
+    #[test]
+    fn parses_asset_urls_and_mime_types() {
+        let (id, path) = parse_asset_uri("/abc/music/Side%20A+B.MP3").unwrap();
+        assert_eq!(id, "abc");
+        assert_eq!(path, "music/Side A+B.MP3");
+        assert_eq!(mime_type(&path), "audio/mpeg");
+        assert_eq!(mime_type("clip.webm"), "video/webm");
+        assert_eq!(mime_type("README"), "application/octet-stream");
+        assert!(parse_asset_uri("/abc").is_none());
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::Path;
+
+/// Environment variables passed through to the sidecar as-is. Everything
+/// else from the user's environment is dropped.
+const PASSTHROUGH_ENV_VARS: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+    "TMPDIR",
+    "TZ",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+    "SSL_CERT_FILE",
+    "NODE_EXTRA_CA_CERTS",
+];
+
+/// Prefixes of variables the sidecar and Claude Code read for their own
+/// configuration (API keys, model overrides, CLI location).
+const PASSTHROUGH_ENV_PREFIXES: &[&str] = &["ANTHROPIC_", "CLAUDE_", "TROVE_CLAUDE_"];
+
+/// Filters an environment down to the variables the sidecar needs.
+pub fn scrub_env<I>(vars: I) -> Vec<(String, String)>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    vars.into_iter()
+        .filter(|(key, _)| {
+            PASSTHROUGH_ENV_VARS.contains(&key.as_str())
+                || PASSTHROUGH_ENV_PREFIXES
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix))
+        })
+        .collect()
+}
+
+/// Seatbelt profile for `sandbox-exec`: reads and network stay open (the
+/// agent needs its runtime and the API), but writes are confined to the
+/// generation workspace, temp dirs, and Claude Code's own config.
+pub const SIDECAR_SANDBOX_PROFILE: &str = r#"(version 1)
+(allow default)
+(deny file-write*)
+(allow file-write*
+    (subpath (param "WORKSPACE"))
+    (subpath (param "TMPDIR"))
+    (subpath "/private/tmp")
+    (subpath (param "CLAUDE_DIR"))
+    (regex #"/\.claude\.json[^/]*$")
+    (regex #"^/dev/"))
+"#;
+
+pub const SANDBOX_EXEC_PATH: &str = "/usr/bin/sandbox-exec";
+
+/// Arguments that run `program` under `sandbox-exec` with the sidecar
+/// profile, scoped to `workspace`.
+pub fn sandbox_exec_args(workspace: &Path, home: Option<&Path>, program: &Path) -> Vec<String> {
+    let tmp_dir = std::env::temp_dir();
+    let claude_dir = home
+        .map(|home| home.join(".claude"))
+        .unwrap_or_else(|| workspace.join(".claude"));
+    vec![
+        "-p".to_string(),
+        SIDECAR_SANDBOX_PROFILE.to_string(),
+        "-D".to_string(),
+        format!("WORKSPACE={}", workspace.to_string_lossy()),
+        "-D".to_string(),
+        format!("TMPDIR={}", tmp_dir.to_string_lossy()),
+        "-D".to_string(),
+        format!("CLAUDE_DIR={}", claude_dir.to_string_lossy()),
+        program.to_string_lossy().to_string(),
+    ]
+}
+
+/// Shell used to apply rlimits before exec-ing the sidecar, since the shell
+/// plugin has no pre-exec hook.
+pub const RLIMIT_SHELL_PATH: &str = "/bin/sh";
+
+/// Arguments for `RLIMIT_SHELL_PATH` that cap CPU time at `cpu_seconds` and
+/// then exec `program` with `args`. Memory is deliberately not capped with
+/// `ulimit -v`: V8 reserves far more address space than it uses, and macOS
+/// doesn't enforce `RLIMIT_AS` anyway. Memory is watched by sampling RSS.
+pub fn rlimit_args(cpu_seconds: u64, program: &str, args: Vec<String>) -> Vec<String> {
+    let mut wrapped = vec![
+        "-c".to_string(),
+        r#"ulimit -t "$1" || exit 1; shift; exec "$@""#.to_string(),
+        "trove-sidecar".to_string(),
+        cpu_seconds.to_string(),
+        program.to_string(),
+    ];
+    wrapped.extend(args);
+    wrapped
+}
+
+/// Finds `root` and all of its descendants in `ps -A -o pid=,ppid=,rss=`
+/// output, returning their pids and total resident memory in KB.
+pub fn process_tree_usage(ps_output: &str, root: u32) -> (Vec<u32>, u64) {
+    let processes: Vec<(u32, u32, u64)> = ps_output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let ppid = fields.next()?.parse().ok()?;
+            let rss = fields.next()?.parse().ok()?;
+            Some((pid, ppid, rss))
+        })
+        .collect();
+
+    let mut tree = Vec::new();
+    let mut rss_kb = 0;
+    if let Some((_, _, rss)) = processes.iter().find(|(pid, _, _)| *pid == root) {
+        tree.push(root);
+        rss_kb += rss;
+    }
+    let mut index = 0;
+    while index < tree.len() {
+        let parent = tree[index];
+        for (pid, ppid, rss) in &processes {
+            if *ppid == parent && !tree.contains(pid) {
+                tree.push(*pid);
+                rss_kb += rss;
+            }
+        }
+        index += 1;
+    }
+    (tree, rss_kb)
+}
+
+/// Samples the sidecar's process tree with `ps`. Returns `None` where `ps`
+/// is unavailable or the process is already gone.
+pub async fn sample_process_tree(root: u32) -> Option<(Vec<u32>, u64)> {
+    let output = tokio::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,rss="])
+        .output()
+        .await
+        .ok()?;
+    let (pids, rss_kb) = process_tree_usage(&String::from_utf8_lossy(&output.stdout), root);
+    if pids.is_empty() {
+        None
+    } else {
+        Some((pids, rss_kb))
+    }
+}
+
+/// Kills every process in a sampled tree, so the agent's own children die
+/// with the sidecar.
+pub fn kill_process_tree(pids: &[u32]) {
+    #[cfg(unix)]
+    for pid in pids {
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        unsafe {
+            libc::kill(*pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = pids;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{process_tree_usage, scrub_env};
+
+    #[test]
+    fn process_tree_usage_sums_descendants_only() {
+        let ps = "    1     0  1000\n  100     1  2000\n  101   100  3000\n\
+                  102   101   500\n  200     1  9999\n";
+        let (pids, rss_kb) = process_tree_usage(ps, 100);
+        assert_eq!(pids, [100, 101, 102]);
+        assert_eq!(rss_kb, 5500);
+        assert_eq!(process_tree_usage(ps, 999), (Vec::new(), 0));
+    }
+
+    #[test]
+    fn scrub_env_keeps_only_allowlisted_vars() {
+        let vars = [
+            ("PATH", "/usr/bin"),
+            ("HOME", "/Users/me"),
+            ("ANTHROPIC_API_KEY", "sk-test"),
+            ("CLAUDE_CODE_PATH", "/opt/claude"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("GITHUB_TOKEN", "ghp"),
+            ("DYLD_INSERT_LIBRARIES", "/tmp/evil.dylib"),
+        ]
+        .map(|(key, value)| (key.to_string(), value.to_string()));
+
+        let kept: Vec<String> = scrub_env(vars).into_iter().map(|(key, _)| key).collect();
+        assert_eq!(kept, ["PATH", "HOME", "ANTHROPIC_API_KEY", "CLAUDE_CODE_PATH"]);
+    }
+}
@@ -0,0 +1,183 @@
+// The bridge every app gets, injected by html::inject_runtime when Trove
+// stores generated HTML. Bump RUNTIME_VERSION in html.rs when changing it so
+// upgrade_apps_runtime rewrites older copies.
+(function() {
+  var TIMEOUT_MS = 5000;
+  var FOLDER_PICK_TIMEOUT_MS = 5 * 60 * 1000;
+  var DOWNLOAD_TIMEOUT_MS = 30 * 60 * 1000;
+  var pendingRequests = new Map();
+  var requestIdCounter = 0;
+
+  // Shortcut id -> { accelerator, handler }, registered with TroveShortcuts.
+  var shortcuts = new Map();
+
+  var MAX_ERROR_REPORTS = 20;
+  var errorReports = 0;
+
+  function reportError(message, source, line, column, stack) {
+    if (errorReports >= MAX_ERROR_REPORTS) return;
+    errorReports += 1;
+    window.parent.postMessage({
+      type: 'trove-error',
+      error: {
+        message: String(message || 'Unknown error'),
+        source: source || null,
+        line: typeof line === 'number' ? line : null,
+        column: typeof column === 'number' ? column : null,
+        stack: stack ? String(stack) : null
+      }
+    }, '*');
+  }
+
+  window.addEventListener('error', function(event) {
+    var error = event.error;
+    reportError(event.message, event.filename, event.lineno, event.colno, error && error.stack);
+  });
+
+  window.addEventListener('unhandledrejection', function(event) {
+    var reason = event.reason;
+    var message = reason instanceof Error ? reason.name + ': ' + reason.message : String(reason);
+    reportError('Unhandled rejection: ' + message, null, null, null, reason && reason.stack);
+  });
+
+  var root = document.documentElement;
+  var systemDark = window.matchMedia && window.matchMedia('(prefers-color-scheme: dark)');
+  root.setAttribute('data-prefers-trove-theme', systemDark && systemDark.matches ? 'dark' : 'light');
+
+  window.addEventListener('message', function(event) {
+    var data = event.data;
+    if (data?.type === 'trove-theme' && (data.theme === 'light' || data.theme === 'dark')) {
+      if (root.getAttribute('data-prefers-trove-theme') !== data.theme) {
+        root.setAttribute('data-prefers-trove-theme', data.theme);
+        window.dispatchEvent(new CustomEvent('trove-theme-change', { detail: { theme: data.theme } }));
+      }
+      return;
+    }
+    if (data?.type === 'trove-shortcut') {
+      var shortcut = shortcuts.get(data.id);
+      if (shortcut) shortcut.handler();
+      return;
+    }
+    if (data?.type === 'trove-timer-finished') {
+      window.dispatchEvent(new CustomEvent('trove-timer-finished', {
+        detail: { id: data.id, label: data.label ?? null }
+      }));
+      return;
+    }
+    if (data?.type === 'trove-storage-changed') {
+      // Another window showing this app wrote to its storage.
+      window.dispatchEvent(new CustomEvent('trove-storage-change', { detail: { key: data.key ?? null } }));
+      return;
+    }
+    if (data?.type !== 'trove-storage-response') return;
+
+    var request = pendingRequests.get(data.requestId);
+    if (!request) return;
+
+    clearTimeout(request.timeoutId);
+    pendingRequests.delete(data.requestId);
+    if (data.success) {
+      request.resolve(data.result);
+    } else {
+      request.reject(new Error(data.error));
+    }
+  });
+
+  function sendRequest(action, key, value, timeoutMs) {
+    return new Promise(function(resolve, reject) {
+      var requestId = ++requestIdCounter;
+      var timeoutId = setTimeout(function() {
+        pendingRequests.delete(requestId);
+        reject(new Error('TroveStorage: operation timed out'));
+      }, timeoutMs || TIMEOUT_MS);
+
+      pendingRequests.set(requestId, { resolve: resolve, reject: reject, timeoutId: timeoutId });
+      window.parent.postMessage(
+        { type: 'trove-storage', requestId: requestId, action: action, key: key, value: value },
+        '*'
+      );
+    });
+  }
+
+  // Builds the accelerator text Trove normalizes registrations to, using
+  // physical keys so Shift and Alt don't change the letter.
+  function eventAccelerator(event) {
+    var key = event.key;
+    if (!key || key === 'Control' || key === 'Meta' || key === 'Alt' || key === 'Shift') return null;
+    if (/^Key[A-Z]$/.test(event.code)) key = event.code.slice(3);
+    else if (/^Digit[0-9]$/.test(event.code)) key = event.code.slice(5);
+    else if (key === ' ') key = 'Space';
+    var parts = [];
+    if (event.metaKey || event.ctrlKey) parts.push('CmdOrCtrl');
+    if (event.altKey) parts.push('Alt');
+    if (event.shiftKey) parts.push('Shift');
+    parts.push(key);
+    return parts.join('+');
+  }
+
+  document.addEventListener('keydown', function(event) {
+    if (shortcuts.size === 0) return;
+    var accelerator = eventAccelerator(event);
+    if (!accelerator) return;
+    for (var entry of shortcuts.values()) {
+      if (entry.accelerator === accelerator) {
+        event.preventDefault();
+        sendRequest('shortcutPressed', null, accelerator).catch(function() {});
+        return;
+      }
+    }
+  }, true);
+
+  window.TroveStorage = {
+    get: function(key) { return sendRequest('get', key); },
+    set: function(key, value) { return sendRequest('set', key, value); },
+    delete: function(key) { return sendRequest('delete', key); },
+    clear: function() { return sendRequest('clear'); },
+    getAll: function() { return sendRequest('getAll'); }
+  };
+
+  window.TroveConfig = {
+    get: function(key) { return sendRequest('configGet', key); },
+    getAll: function() { return sendRequest('configGetAll'); }
+  };
+
+  window.TroveApp = {
+    getMetadata: function() { return sendRequest('metadata'); },
+    // The user is asked the first time, so allow as long as a folder pick.
+    rename: function(name) { return sendRequest('rename', name, null, FOLDER_PICK_TIMEOUT_MS); },
+    setBadge: function(count) { return sendRequest('badge', null, count == null ? null : count); },
+    setProgress: function(percent) {
+      return sendRequest('progress', null, percent == null ? null : Math.round(percent));
+    },
+    playSound: function(id, volume) { return sendRequest('sound', id, volume == null ? null : volume); }
+  };
+
+  window.TroveShortcuts = {
+    register: function(id, accelerator, handler) {
+      return sendRequest('registerShortcut', id, accelerator).then(function(normalized) {
+        shortcuts.set(id, { accelerator: normalized, handler: handler });
+        return normalized;
+      });
+    },
+    unregister: function(id) {
+      shortcuts.delete(id);
+      return sendRequest('unregisterShortcut', id);
+    }
+  };
+
+  window.TroveTimers = {
+    start: function(id, seconds, label) {
+      return sendRequest('timerStart', id, { seconds: seconds, label: label || null });
+    },
+    list: function() { return sendRequest('timerList'); },
+    cancel: function(id) { return sendRequest('timerCancel', id); }
+  };
+
+  window.TroveFiles = {
+    requestFolder: function() { return sendRequest('requestFolder', null, null, FOLDER_PICK_TIMEOUT_MS); },
+    read: function(path) { return sendRequest('readFile', path); },
+    download: function(url, suggestedName) {
+      return sendRequest('download', url, suggestedName || '', DOWNLOAD_TIMEOUT_MS);
+    }
+  };
+})();
@@ -1,10 +1,17 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
 use uuid::Uuid;
 
 /// Writes content to a file atomically using a temp file + rename pattern.
 /// This prevents corruption if the process crashes mid-write.
 pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    write_atomic_bytes(path, contents.as_bytes())
+}
+
+/// Byte-oriented version of `write_atomic`. The temp file is fsynced before
+/// the rename so a crash can't leave a renamed-but-empty file behind.
+pub fn write_atomic_bytes(path: &Path, contents: &[u8]) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .map_err(|e| format!("Failed to create parent directory: {}", e))?;
@@ -17,10 +24,11 @@ pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
     let tmp_name = format!("{}.tmp", file_name);
     let tmp_path = path.with_file_name(tmp_name);
 
-    fs::write(&tmp_path, contents)
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    write_synced(&tmp_path, contents)?;
 
-    if path.exists() {
+    // rename() replaces the target atomically on Unix; Windows needs the
+    // target removed first.
+    if cfg!(windows) && path.exists() {
         fs::remove_file(path).map_err(|e| format!("Failed to replace file: {}", e))?;
     }
 
@@ -30,8 +38,271 @@ pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Writes a file and flushes it to disk before returning.
+pub fn write_synced(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let mut file =
+        fs::File::create(path).map_err(|e| format!("Failed to write temp file: {}", e))?;
+    file.write_all(contents)
+        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+    file.sync_all()
+        .map_err(|e| format!("Failed to flush temp file: {}", e))
+}
+
+/// Size of a file, or of everything under a directory. Symlinks are not
+/// followed; unreadable entries count as zero.
+pub fn path_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| path_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// Best-effort fsync of a directory so renames inside it are durable.
+pub fn sync_dir(path: &Path) {
+    if let Ok(dir) = fs::File::open(path) {
+        let _ = dir.sync_all();
+    }
+}
+
+/// Returns the bytes available to unprivileged users on the filesystem
+/// containing `path`.
+#[cfg(unix)]
+pub fn available_disk_space(path: &Path) -> Result<u64, String> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| "Invalid path for disk space check".to_string())?;
+    // SAFETY: statvfs only writes into the zeroed struct we pass it.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 {
+        return Err(format!(
+            "Failed to query disk space: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    // Field widths differ between platforms (u32 on macOS, u64 on Linux).
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+pub fn available_disk_space(_path: &Path) -> Result<u64, String> {
+    Ok(u64::MAX)
+}
+
 /// Parses a string as a UUID, returning an error if invalid.
 /// Use this to validate app IDs and prevent path traversal attacks.
 pub fn parse_uuid(id: &str) -> Result<Uuid, String> {
     Uuid::parse_str(id).map_err(|_| format!("Invalid app id: {}", id))
 }
+
+const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Incremental 64-bit FNV-1a hasher. Used for change detection, so it only
+/// needs to be fast and stable across releases, not collision-resistant.
+pub struct ContentHasher(u64);
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+impl Default for ContentHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const BASE85_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// Encodes bytes as unpadded base85 (the RFC 1924 alphabet, matching
+/// Python's `base64.b85encode`).
+pub fn base85_encode(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len().div_ceil(4) * 5);
+    for chunk in input.chunks(4) {
+        let mut b = [0u8; 4];
+        b[..chunk.len()].copy_from_slice(chunk);
+        let mut n = u32::from_be_bytes(b);
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = BASE85_ALPHABET[(n % 85) as usize];
+            n /= 85;
+        }
+        for digit in &digits[..chunk.len() + 1] {
+            out.push(*digit as char);
+        }
+    }
+    out
+}
+
+/// Encodes bytes as unpadded URL-safe base64.
+pub fn base64_url_encode(input: &[u8]) -> String {
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        let chars = chunk.len() + 1;
+        for i in 0..chars {
+            let idx = (n >> (18 - 6 * i)) & 0x3f;
+            out.push(BASE64_URL_ALPHABET[idx as usize] as char);
+        }
+    }
+    out
+}
+
+/// Decodes unpadded (or padded) URL-safe base64.
+pub fn base64_url_decode(input: &str) -> Result<Vec<u8>, String> {
+    let trimmed = input.trim_end_matches('=');
+    let mut out = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for byte in trimmed.bytes() {
+        let value = BASE64_URL_ALPHABET
+            .iter()
+            .position(|&c| c == byte)
+            .ok_or("Invalid base64 data")?;
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
+
+/// Percent-encodes everything except RFC 3986 unreserved characters.
+pub fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+pub fn percent_decode(input: &str) -> Result<String, String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = input.get(i + 1..i + 3).ok_or("Invalid percent-encoding")?;
+                let value =
+                    u8::from_str_radix(hex, 16).map_err(|_| "Invalid percent-encoding")?;
+                out.push(value);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).map_err(|_| "Invalid UTF-8 in percent-encoding".to_string())
+}
+
+/// Splits `text` into pieces of at most `max_bytes` bytes without breaking
+/// a character. Always yields at least one (possibly empty) piece.
+pub fn chunk_str(text: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while end > 0 && !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            // A single character wider than `max_bytes` goes out whole.
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        base64_url_decode, base64_url_encode, base85_encode, chunk_str, percent_decode,
+        percent_encode, ContentHasher,
+    };
+
+    #[test]
+    fn base85_matches_python_b85encode() {
+        assert_eq!(base85_encode(b""), "");
+        assert_eq!(base85_encode(b"hello"), "Xk~0{Zv");
+        assert_eq!(base85_encode(&[0, 0, 0, 0]), "00000");
+        assert_eq!(base85_encode(b"Trove!"), "RB~^2Wg!");
+    }
+
+    #[test]
+    fn content_hash_matches_fnv1a_reference() {
+        let mut hasher = ContentHasher::new();
+        assert_eq!(hasher.finish(), "cbf29ce484222325");
+        hasher.update(b"a");
+        assert_eq!(hasher.finish(), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn base64_url_round_trips_all_lengths() {
+        let data = "héllo <wörld> ✨".as_bytes();
+        for len in 0..data.len() {
+            let encoded = base64_url_encode(&data[..len]);
+            assert!(!encoded.contains('='));
+            assert_eq!(base64_url_decode(&encoded).unwrap(), &data[..len]);
+        }
+    }
+
+    #[test]
+    fn base64_url_matches_known_vector() {
+        assert_eq!(base64_url_encode(b"foobar?"), "Zm9vYmFyPw");
+    }
+
+    #[test]
+    fn chunks_strings_on_character_boundaries() {
+        assert_eq!(chunk_str("", 4), vec![""]);
+        assert_eq!(chunk_str("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(chunk_str("aé✨b", 3), vec!["aé", "✨", "b"]);
+    }
+
+    #[test]
+    fn percent_encoding_round_trips_unicode() {
+        let encoded = percent_encode("Budget & Bills ✨");
+        assert_eq!(encoded, "Budget%20%26%20Bills%20%E2%9C%A8");
+        assert_eq!(percent_decode(&encoded).unwrap(), "Budget & Bills ✨");
+    }
+}
@@ -1,10 +1,14 @@
-import { useCallback, useEffect, useMemo, useState } from "react";
+import { useCallback, useEffect, useState } from "react";
 import { useApps } from "./hooks/useApps";
+import { useAppSearch } from "./hooks/useAppSearch";
 import { Sidebar } from "./components/Sidebar";
 import { ContentArea } from "./components/ContentArea";
 import { AppFormModal } from "./components/AppFormModal";
 import { ConfirmModal } from "./components/ConfirmModal";
 import { KeyboardShortcutsModal } from "./components/KeyboardShortcutsModal";
+import { DEFAULT_CREATIVITY } from "./constants";
+import type { AppKind, AppRuntimeError, DeletionSummary } from "./types";
+import { buildFixErrorPrompt, describeDeletion } from "./utils/apps";
 import "./App.css";
 
 function formatError(err: unknown, fallback: string): string {
@@ -22,25 +26,36 @@ function App() {
     apps,
     selectedApp,
     setSelectedAppId,
+    openApp,
     isLoading,
     isGenerating,
+    progressMessage,
     error,
     setError,
     errorSource,
     lastGenerationRequest,
     generateApp,
+    generateFromClipboard,
     cancelGeneration,
+    finishGenerationEarly,
+    generationQuestion,
+    answerGenerationQuestion,
+    requestDeleteConfirmation,
     deleteApp,
     editApp,
+    autoFixApp,
     updateAppMetadata,
+    resolveSuggestedTags,
     retryLastGeneration,
     getAppPath,
   } = useApps();
 
   const [isModalOpen, setIsModalOpen] = useState(false);
   const [pendingDeleteId, setPendingDeleteId] = useState<string | null>(null);
+  const [deleteSummary, setDeleteSummary] = useState<DeletionSummary | null>(null);
   const [isShortcutsOpen, setIsShortcutsOpen] = useState(false);
   const [appToEdit, setAppToEdit] = useState<typeof selectedApp>(null);
+  const [editPromptSeed, setEditPromptSeed] = useState<string | null>(null);
   const [editError, setEditError] = useState<string | null>(null);
   const [isEditing, setIsEditing] = useState(false);
   const [searchQuery, setSearchQuery] = useState("");
@@ -51,59 +66,137 @@ function App() {
     }
   }, [isGenerating]);
 
+  const handleGenerateFromClipboard = useCallback(async () => {
+    if (isGenerating) return;
+    try {
+      await generateFromClipboard();
+    } catch (err) {
+      setError(formatError(err, "Failed to generate app from clipboard"), "generation");
+    }
+  }, [generateFromClipboard, isGenerating, setError]);
+
   const handleModalClose = () => setIsModalOpen(false);
   const handleOpenShortcuts = () => setIsShortcutsOpen(true);
   const handleCloseShortcuts = () => setIsShortcutsOpen(false);
 
-  const handleModalSubmit = async (name: string, prompt: string, emoji: string, backgroundColor: string) => {
+  const handleModalSubmit = async (
+    name: string,
+    prompt: string,
+    emoji: string,
+    backgroundColor: string,
+    creativity: number,
+    accessibilityMode: boolean,
+    appKind: AppKind
+  ) => {
     setIsModalOpen(false);
     try {
-      await generateApp(name, prompt, emoji, backgroundColor);
+      await generateApp(
+        name,
+        prompt,
+        emoji,
+        backgroundColor,
+        creativity,
+        accessibilityMode,
+        appKind
+      );
     } catch (err) {
       setError(formatError(err, "Failed to generate app"), "generation");
     }
   };
 
-  const handleDeleteApp = useCallback((id: string) => {
-    setPendingDeleteId(id);
-  }, []);
+  const handleDeleteApp = useCallback(
+    async (id: string) => {
+      setPendingDeleteId(id);
+      setDeleteSummary(null);
+      const summary = await requestDeleteConfirmation(id);
+      if (!summary) {
+        setPendingDeleteId(null);
+        return;
+      }
+      setDeleteSummary(summary);
+    },
+    [requestDeleteConfirmation]
+  );
 
-  const pendingDeleteApp = apps.find((app) => app.id === pendingDeleteId) || null;
+  // A summary that arrives after the dialog moved on to another app is
+  // ignored.
+  const pendingDeleteSummary =
+    deleteSummary?.app_id === pendingDeleteId ? deleteSummary : null;
 
   const handleConfirmDelete = async () => {
-    if (!pendingDeleteId) return;
-    await deleteApp(pendingDeleteId);
+    if (!pendingDeleteId || !pendingDeleteSummary) return;
+    await deleteApp(pendingDeleteId, pendingDeleteSummary.token);
     setPendingDeleteId(null);
+    setDeleteSummary(null);
   };
 
   const handleCancelDelete = useCallback(() => {
     setPendingDeleteId(null);
+    setDeleteSummary(null);
   }, []);
 
   const handleEditOpen = useCallback(() => {
     if (selectedApp) {
       setAppToEdit(selectedApp);
+      setEditPromptSeed(null);
       setEditError(null);
     }
   }, [selectedApp]);
 
+  const handleFixError = useCallback(
+    (runtimeError: AppRuntimeError) => {
+      if (!selectedApp) return;
+      setAppToEdit(selectedApp);
+      setEditPromptSeed(buildFixErrorPrompt(selectedApp.prompt, runtimeError));
+      setEditError(null);
+    },
+    [selectedApp]
+  );
+
+  const handleAutoFix = async () => {
+    if (!selectedApp) return;
+    try {
+      await autoFixApp(selectedApp);
+    } catch (err) {
+      setError(formatError(err, "Failed to fix app"), "generation");
+    }
+  };
+
   const handleEditClose = useCallback(() => {
     setAppToEdit(null);
+    setEditPromptSeed(null);
     setEditError(null);
     setIsEditing(false);
   }, []);
 
-  const handleEditSubmit = async (name: string, prompt: string, emoji: string, backgroundColor: string) => {
+  const handleEditSubmit = async (
+    name: string,
+    prompt: string,
+    emoji: string,
+    backgroundColor: string,
+    creativity: number,
+    accessibilityMode: boolean
+  ) => {
     if (!appToEdit) return;
 
     const promptChanged = prompt.trim() !== appToEdit.prompt.trim();
+    const creativityChanged = creativity !== (appToEdit.creativity ?? DEFAULT_CREATIVITY);
+    const accessibilityChanged = accessibilityMode !== (appToEdit.accessibility_mode ?? false);
     setAppToEdit(null);
     setEditError(null);
 
     try {
-      if (promptChanged) {
-        // Prompt changed - need to regenerate
-        await editApp(appToEdit.id, name, prompt, emoji, backgroundColor);
+      if (promptChanged || creativityChanged || accessibilityChanged) {
+        // Prompt or generation options changed - need to regenerate
+        await editApp(
+          appToEdit.id,
+          name,
+          prompt,
+          emoji,
+          backgroundColor,
+          creativity,
+          accessibilityMode
+        );
       } else {
         // Only metadata changed - quick update
         await updateAppMetadata(appToEdit.id, name, emoji, backgroundColor);
@@ -121,18 +214,7 @@ function App() {
     }
   };
 
-  const visibleApps = useMemo(() => {
-    const query = searchQuery.trim().toLowerCase();
-    const filtered = query
-      ? apps.filter(
-          (app) =>
-            app.name.toLowerCase().includes(query) ||
-            app.prompt.toLowerCase().includes(query)
-        )
-      : apps;
-
-    return filtered;
-  }, [apps, searchQuery]);
+  const { visibleApps, snippets } = useAppSearch(apps, searchQuery);
 
   useEffect(() => {
     const isTypingTarget = (target: EventTarget | null) => {
@@ -174,6 +256,16 @@ function App() {
         return;
       }
 
+      if (
+        (event.metaKey || event.ctrlKey) &&
+        event.shiftKey &&
+        event.key.toLowerCase() === "v"
+      ) {
+        event.preventDefault();
+        void handleGenerateFromClipboard();
+        return;
+      }
+
       if ((event.metaKey || event.ctrlKey) && event.key.toLowerCase() === "n") {
         event.preventDefault();
         handleNewApp();
@@ -212,6 +304,7 @@ function App() {
     return () => window.removeEventListener("keydown", onKeyDown);
   }, [
     handleNewApp,
+    handleGenerateFromClipboard,
     handleModalClose,
     handleCancelDelete,
     handleCloseShortcuts,
@@ -230,8 +323,9 @@ function App() {
     <div className="app-container">
       <Sidebar
         apps={visibleApps}
+        snippets={snippets}
         selectedAppId={selectedApp?.id ?? null}
-        onSelectApp={setSelectedAppId}
+        onSelectApp={openApp}
         onDeleteApp={handleDeleteApp}
         onNewApp={handleNewApp}
         onShowShortcuts={handleOpenShortcuts}
@@ -244,9 +338,18 @@ function App() {
         app={selectedApp}
         getAppPath={getAppPath}
         isGenerating={isGenerating}
+        progressMessage={progressMessage}
         onCancelGeneration={cancelGeneration}
+        onFinishGenerationEarly={finishGenerationEarly}
+        generationQuestion={generationQuestion}
+        onAnswerGenerationQuestion={answerGenerationQuestion}
         onNewApp={handleNewApp}
         onEditApp={handleEditOpen}
+        onFixError={handleFixError}
+        onAutoFix={handleAutoFix}
+        onResolveSuggestedTags={(accept) =>
+          selectedApp && resolveSuggestedTags(selectedApp.id, accept)
+        }
       />
       <AppFormModal
         mode="new"
@@ -258,9 +361,9 @@ function App() {
         isOpen={Boolean(pendingDeleteId)}
         appName="Delete app?"
         message={
-          pendingDeleteApp
-            ? `This will permanently delete "${pendingDeleteApp.name}".`
-            : "This will permanently delete this app."
+          pendingDeleteSummary
+            ? describeDeletion(pendingDeleteSummary)
+            : "Checking what will be deleted..."
         }
         confirmLabel="Delete"
         cancelLabel="Cancel"
@@ -275,6 +378,7 @@ function App() {
         mode="edit"
         isOpen={Boolean(appToEdit)}
         app={appToEdit}
+        initialPrompt={editPromptSeed}
         isSubmitting={isEditing}
         error={editError}
         onClose={handleEditClose}
@@ -1,6 +1,7 @@
 import { useEffect, useRef, useState } from "react";
-import type { AppMetadata } from "../types";
-import { APP_NAME_MAX_LENGTH, APP_PROMPT_MAX_LENGTH } from "../constants";
+import { invoke } from "@tauri-apps/api/core";
+import type { AppKind, AppMetadata, NameSuggestion } from "../types";
+import { APP_NAME_MAX_LENGTH, APP_PROMPT_MAX_LENGTH, DEFAULT_CREATIVITY } from "../constants";
 import { Modal } from "./Modal";
 
 type AppFormMode = "new" | "edit";
@@ -24,16 +25,27 @@ interface AppFormModalProps {
   isOpen: boolean;
   mode: AppFormMode;
   app?: AppMetadata | null;
+  /** Overrides the app's stored prompt when opening in edit mode. */
+  initialPrompt?: string | null;
   isSubmitting?: boolean;
   error?: string | null;
   onClose: () => void;
-  onSubmit: (name: string, prompt: string, emoji: string, backgroundColor: string) => Promise<void> | void;
+  onSubmit: (
+    name: string,
+    prompt: string,
+    emoji: string,
+    backgroundColor: string,
+    creativity: number,
+    accessibilityMode: boolean,
+    appKind: AppKind
+  ) => Promise<void> | void;
 }
 
 export function AppFormModal({
   isOpen,
   mode,
   app = null,
+  initialPrompt = null,
   isSubmitting,
   error,
   onClose,
@@ -42,7 +54,14 @@ export function AppFormModal({
   const [name, setName] = useState("");
   const [prompt, setPrompt] = useState("");
   const [emoji, setEmoji] = useState(DEFAULT_EMOJI);
+  const emojiPickedRef = useRef(false);
   const [backgroundColor, setBackgroundColor] = useState(DEFAULT_COLOR);
+  const [creativity, setCreativity] = useState(DEFAULT_CREATIVITY);
+  const [accessibilityMode, setAccessibilityMode] = useState(false);
+  const [appKind, setAppKind] = useState<AppKind>("standard");
+  const [nameSuggestions, setNameSuggestions] = useState<NameSuggestion[]>([]);
+  const [isSuggestingNames, setIsSuggestingNames] = useState(false);
+  const [suggestionError, setSuggestionError] = useState<string | null>(null);
   const [hasSubmitted, setHasSubmitted] = useState(false);
   const [internalSubmitting, setInternalSubmitting] = useState(false);
   const internalSubmittingRef = useRef(false);
@@ -54,25 +73,68 @@ export function AppFormModal({
   useEffect(() => {
     if (!isOpen) return;
     setHasSubmitted(false);
+    emojiPickedRef.current = isEditMode;
+    setNameSuggestions([]);
+    setSuggestionError(null);
     if (isEditMode && app) {
       setName(app.name);
-      setPrompt(app.prompt);
+      setPrompt(initialPrompt ?? app.prompt);
       setEmoji(app.emoji || DEFAULT_EMOJI);
       setBackgroundColor(app.background_color || DEFAULT_COLOR);
+      setCreativity(app.creativity ?? DEFAULT_CREATIVITY);
+      setAccessibilityMode(app.accessibility_mode ?? false);
     } else if (!isEditMode) {
       setName("");
       setPrompt("");
       setEmoji(DEFAULT_EMOJI);
       setBackgroundColor(DEFAULT_COLOR);
+      setCreativity(DEFAULT_CREATIVITY);
+      setAccessibilityMode(false);
+      setAppKind("standard");
     }
     if (!isControlledSubmitting) {
       setInternalSubmitting(false);
       internalSubmittingRef.current = false;
     }
-  }, [isOpen, isEditMode, app, isControlledSubmitting]);
+  }, [isOpen, isEditMode, app, initialPrompt, isControlledSubmitting]);
 
   if (!isOpen || (isEditMode && !app)) return null;
 
+  const pickEmoji = (value: string) => {
+    setEmoji(value);
+    emojiPickedRef.current = true;
+  };
+
+  /** Pre-fills the icon from the prompt until the user picks one. */
+  const handlePromptBlur = async () => {
+    const trimmedPrompt = prompt.trim();
+    if (isEditMode || emojiPickedRef.current || !trimmedPrompt) return;
+    try {
+      const suggested = await invoke<string>("suggest_emoji", { prompt: trimmedPrompt });
+      if (!emojiPickedRef.current) {
+        setEmoji(suggested);
+      }
+    } catch (err) {
+      console.error("Failed to suggest emoji:", err);
+    }
+  };
+
+  const handleSuggestNames = async () => {
+    const trimmedPrompt = prompt.trim();
+    if (!trimmedPrompt || isSuggestingNames) return;
+    setIsSuggestingNames(true);
+    setSuggestionError(null);
+    try {
+      setNameSuggestions(
+        await invoke<NameSuggestion[]>("suggest_app_name", { prompt: trimmedPrompt })
+      );
+    } catch (err) {
+      setSuggestionError(String(err));
+    } finally {
+      setIsSuggestingNames(false);
+    }
+  };
+
   const handleSubmit = async (event: React.FormEvent) => {
     event.preventDefault();
     if (submitting || internalSubmittingRef.current) return;
@@ -88,7 +150,15 @@ export function AppFormModal({
 
     let success = false;
     try {
-      await onSubmit(trimmedName, trimmedPrompt, emoji, backgroundColor);
+      await onSubmit(
+        trimmedName,
+        trimmedPrompt,
+        emoji,
+        backgroundColor,
+        creativity,
+        accessibilityMode,
+        appKind
+      );
       success = true;
     } finally {
       if (!isControlledSubmitting) {
@@ -102,6 +172,10 @@ export function AppFormModal({
       setPrompt("");
       setEmoji(DEFAULT_EMOJI);
       setBackgroundColor(DEFAULT_COLOR);
+      setCreativity(DEFAULT_CREATIVITY);
+      setAccessibilityMode(false);
+      setAppKind("standard");
+      emojiPickedRef.current = false;
       setHasSubmitted(false);
       onClose();
     }
@@ -112,6 +186,9 @@ export function AppFormModal({
   const submittingLabel = isEditMode ? "Updating..." : "Generating...";
   const nameId = isEditMode ? "edit-app-name" : "app-name";
   const promptId = isEditMode ? "edit-app-prompt" : "app-prompt";
+  const creativityId = isEditMode ? "edit-app-creativity" : "app-creativity";
+  const accessibilityId = isEditMode ? "edit-app-accessibility" : "app-accessibility";
+  const appKindId = "app-kind";
   const promptPlaceholder = isEditMode
     ? "Describe the app in detail."
     : "A todo list app with the ability to add, complete, and delete tasks. Include a dark mode toggle.";
@@ -144,6 +221,32 @@ export function AppFormModal({
           {hasSubmitted && !name.trim() && (
             <div className="form-error">Name is required.</div>
           )}
+          {!isEditMode && (
+            <div className="name-suggestions">
+              <button
+                type="button"
+                className="name-suggestion"
+                onClick={handleSuggestNames}
+                disabled={!prompt.trim() || isSuggestingNames || submitting}
+              >
+                {isSuggestingNames ? "Suggesting..." : "Suggest names"}
+              </button>
+              {nameSuggestions.map((suggestion) => (
+                <button
+                  key={suggestion.name}
+                  type="button"
+                  className="name-suggestion"
+                  onClick={() => {
+                    setName(suggestion.name);
+                    pickEmoji(suggestion.emoji);
+                  }}
+                >
+                  {suggestion.emoji} {suggestion.name}
+                </button>
+              ))}
+            </div>
+          )}
+          {suggestionError && <div className="form-hint">{suggestionError}</div>}
         </div>
 
         <div className="form-group">
@@ -162,7 +265,7 @@ export function AppFormModal({
                     key={e}
                     type="button"
                     className={`emoji-option ${emoji === e ? "selected" : ""}`}
-                    onClick={() => setEmoji(e)}
+                    onClick={() => pickEmoji(e)}
                   >
                     {e}
                   </button>
@@ -195,6 +298,7 @@ export function AppFormModal({
             id={promptId}
             value={prompt}
             onChange={(event) => setPrompt(event.target.value)}
+            onBlur={handlePromptBlur}
             placeholder={promptPlaceholder}
             rows={5}
             maxLength={APP_PROMPT_MAX_LENGTH}
@@ -203,6 +307,60 @@ export function AppFormModal({
             <div className="form-error">Prompt is required.</div>
           )}
         </div>
+
+        <div className="form-group">
+          <div className="form-label-row">
+            <label htmlFor={creativityId}>Creativity</label>
+            <span className="form-char-count">{Math.round(creativity * 100)}%</span>
+          </div>
+          <input
+            id={creativityId}
+            type="range"
+            className="creativity-slider"
+            min={0}
+            max={1}
+            step={0.05}
+            value={creativity}
+            onChange={(event) => setCreativity(Number(event.target.value))}
+          />
+          <div className="creativity-labels">
+            <span>Conventional</span>
+            <span>Inventive</span>
+          </div>
+        </div>
+
+        <div className="form-group">
+          <label className="form-checkbox" htmlFor={accessibilityId}>
+            <input
+              id={accessibilityId}
+              type="checkbox"
+              checked={accessibilityMode}
+              onChange={(event) => setAccessibilityMode(event.target.checked)}
+            />
+            Accessibility pass
+          </label>
+          <div className="form-hint">
+            ARIA labels, full keyboard navigation, and high-contrast support.
+          </div>
+        </div>
+
+        {!isEditMode && (
+          <div className="form-group">
+            <label htmlFor={appKindId}>Layout</label>
+            <select
+              id={appKindId}
+              value={appKind}
+              onChange={(event) => setAppKind(event.target.value as AppKind)}
+            >
+              <option value="standard">Standard</option>
+              <option value="widget">Widget</option>
+              <option value="fullscreen">Fullscreen</option>
+            </select>
+            <div className="form-hint">
+              Widgets open in a small frameless window; fullscreen apps fill the screen.
+            </div>
+          </div>
+        )}
         <div className="form-actions">
           <button
             type="button"
@@ -0,0 +1,60 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import type { AppMetadata } from "../types";
+import { ContentArea } from "./ContentArea";
+
+interface AppWindowProps {
+  appId: string;
+}
+
+/** Renders a single app in a dedicated window opened by `open_app`. */
+export function AppWindow({ appId }: AppWindowProps) {
+  const [app, setApp] = useState<AppMetadata | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    invoke<AppMetadata>("get_app", { id: appId })
+      .then((found) => {
+        if (cancelled) return;
+        setApp(found);
+        void getCurrentWindow().setTitle(found.name);
+      })
+      .catch((err) => {
+        if (cancelled) return;
+        const message = String(err);
+        setError(
+          message.startsWith("App not found") ? "This app no longer exists." : message
+        );
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [appId]);
+
+  const getAppPath = useCallback(
+    (id: string) => invoke<string>("get_app_path", { id }),
+    []
+  );
+
+  if (error) {
+    return (
+      <div className="content-area content-area-center">
+        <div className="loading-app">{error}</div>
+      </div>
+    );
+  }
+
+  return (
+    <ContentArea
+      app={app}
+      getAppPath={getAppPath}
+      isGenerating={false}
+      progressMessage={null}
+      onCancelGeneration={() => undefined}
+      onNewApp={() => undefined}
+      openSource="window"
+    />
+  );
+}
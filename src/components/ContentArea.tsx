@@ -1,7 +1,24 @@
 import { useEffect, useState, useRef, useCallback } from "react";
 import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import type { AppMetadata } from "../types";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { open } from "@tauri-apps/plugin-dialog";
+import type {
+  AppLocked,
+  AppMetadata,
+  AppRuntimeError,
+  AppShortcutPressed,
+  GenerationQuestion,
+  OpenSource,
+  StorageChanged,
+  SystemTheme,
+  SystemThemeChanged,
+  TimerFinished,
+} from "../types";
+import { APP_ACTIVITY_THROTTLE_MS, APP_LOCKED_ERROR } from "../constants";
+import { describeRuntimeError } from "../utils/apps";
+import { readStorageStream } from "../utils/storage";
 import { EmptyState } from "./EmptyState";
 import { LoadingIndicator } from "./LoadingIndicator";
 import "./ContentArea.css";
@@ -21,24 +38,240 @@ interface ContentAreaProps {
   app: AppMetadata | null;
   getAppPath: (id: string) => Promise<string>;
   isGenerating: boolean;
+  progressMessage: string | null;
   onCancelGeneration: () => void;
+  onFinishGenerationEarly?: () => void;
+  generationQuestion?: GenerationQuestion | null;
+  onAnswerGenerationQuestion?: (id: string, answer: string) => Promise<void>;
   onNewApp: () => void;
-  onEditApp: () => void;
+  onEditApp?: () => void;
+  onFixError?: (error: AppRuntimeError) => void;
+  onAutoFix?: () => void;
+  onResolveSuggestedTags?: (accept: boolean) => void;
+  /** Reported with opens; dedicated app windows pass "window". */
+  openSource?: OpenSource;
 }
 
 export function ContentArea({
   app,
   getAppPath,
   isGenerating,
+  progressMessage,
   onCancelGeneration,
+  onFinishGenerationEarly,
+  generationQuestion,
+  onAnswerGenerationQuestion,
   onNewApp,
   onEditApp,
+  onFixError,
+  onAutoFix,
+  onResolveSuggestedTags,
+  openSource = "grid",
 }: ContentAreaProps) {
   const [iframeSrc, setIframeSrc] = useState<string | null>(null);
   const [isLoadingApp, setIsLoadingApp] = useState(false);
   const [loadError, setLoadError] = useState<string | null>(null);
+  const [isLocked, setIsLocked] = useState(false);
+  const [password, setPassword] = useState("");
+  const [unlockError, setUnlockError] = useState<string | null>(null);
   const [reloadToken, setReloadToken] = useState(0);
   const iframeRef = useRef<HTMLIFrameElement>(null);
+  const lastActivityRef = useRef(0);
+  const systemThemeRef = useRef<SystemTheme | null>(null);
+  const [appErrors, setAppErrors] = useState<AppRuntimeError[]>([]);
+
+  const appId = app?.id ?? null;
+  const appUpdatedAt = app?.updated_at ?? null;
+
+  useEffect(() => {
+    setAppErrors([]);
+    if (!appId) return;
+    let cancelled = false;
+    invoke<AppRuntimeError[]>("get_app_errors", { id: appId })
+      .then((errors) => {
+        if (!cancelled) setAppErrors(errors);
+      })
+      .catch((err) => {
+        console.error("Failed to load app errors:", err);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [appId, appUpdatedAt]);
+
+  const handleErrorMessage = useCallback(
+    async (event: MessageEvent) => {
+      if (!appId || event.data?.type !== "trove-error") return;
+      if (event.source !== iframeRef.current?.contentWindow) return;
+      try {
+        await invoke("report_app_error", { appId, error: event.data.error });
+        const errors = await invoke<AppRuntimeError[]>("get_app_errors", { id: appId });
+        setAppErrors(errors);
+      } catch (err) {
+        console.error("Failed to record app error:", err);
+      }
+    },
+    [appId]
+  );
+
+  useEffect(() => {
+    window.addEventListener("message", handleErrorMessage);
+    return () => window.removeEventListener("message", handleErrorMessage);
+  }, [handleErrorMessage]);
+
+  const handleDismissErrors = useCallback(async () => {
+    if (!appId) return;
+    try {
+      await invoke("clear_app_errors", { id: appId });
+      setAppErrors([]);
+    } catch (err) {
+      console.error("Failed to clear app errors:", err);
+    }
+  }, [appId]);
+
+  const postThemeToApp = useCallback(() => {
+    const theme = systemThemeRef.current;
+    if (!theme) return;
+    iframeRef.current?.contentWindow?.postMessage({ type: "trove-theme", theme }, "*");
+  }, []);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    invoke<SystemTheme>("get_system_theme")
+      .then((theme) => {
+        if (disposed || systemThemeRef.current) return;
+        systemThemeRef.current = theme;
+        postThemeToApp();
+      })
+      .catch((err) => {
+        console.error("Failed to read system theme:", err);
+      });
+
+    listen<SystemThemeChanged>("system-theme-changed", (event) => {
+      systemThemeRef.current = event.payload.theme;
+      postThemeToApp();
+    }).then((fn) => {
+      if (disposed) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [postThemeToApp]);
+
+  useEffect(() => {
+    if (!appId) return;
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    listen<AppLocked>("app-locked", (event) => {
+      if (event.payload.app_id !== appId) return;
+      setIframeSrc(null);
+      setIsLocked(true);
+    }).then((fn) => {
+      if (disposed) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [appId]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    // Sent only to the windows showing the app, so listen on this window.
+    getCurrentWebviewWindow()
+      .listen<StorageChanged>("storage-changed", (event) => {
+        if (event.payload.app_id !== appId) return;
+        iframeRef.current?.contentWindow?.postMessage(
+          { type: "trove-storage-changed", key: event.payload.key },
+          "*"
+        );
+      })
+      .then((fn) => {
+        if (disposed) {
+          fn();
+        } else {
+          unlisten = fn;
+        }
+      });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [appId]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    // Rust matches the key press against the window's registrations and
+    // answers only this window.
+    getCurrentWebviewWindow()
+      .listen<AppShortcutPressed>("app-shortcut", (event) => {
+        if (event.payload.app_id !== appId) return;
+        iframeRef.current?.contentWindow?.postMessage(
+          { type: "trove-shortcut", id: event.payload.id },
+          "*"
+        );
+      })
+      .then((fn) => {
+        if (disposed) {
+          fn();
+        } else {
+          unlisten = fn;
+        }
+      });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [appId]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    // Timers outlive the app's window, so this is broadcast to every window.
+    listen<TimerFinished>("timer-finished", (event) => {
+      if (event.payload.app_id !== appId) return;
+      iframeRef.current?.contentWindow?.postMessage(
+        {
+          type: "trove-timer-finished",
+          id: event.payload.id,
+          label: event.payload.label,
+        },
+        "*"
+      );
+    }).then((fn) => {
+      if (disposed) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [appId]);
 
   const handleStorageMessage = useCallback(
     async (event: MessageEvent) => {
@@ -50,12 +283,22 @@ export function ContentArea({
       const { requestId, action, key, value } = data;
       const appId = app.id;
 
+      // Bridge traffic is the app being used, which keeps a locked app's
+      // unlock session from idling out.
+      const now = Date.now();
+      if (app.locked && now - lastActivityRef.current > APP_ACTIVITY_THROTTLE_MS) {
+        lastActivityRef.current = now;
+        invoke("record_app_activity", { id: appId }).catch((err) => {
+          console.error("Failed to record app activity:", err);
+        });
+      }
+
       try {
         let result: unknown = null;
 
         switch (action) {
           case "get":
-            result = await invoke("storage_get", { appId, key });
+            result = await readStorageStream(appId, key);
             break;
           case "set":
             await invoke("storage_set", { appId, key, value });
@@ -69,6 +312,74 @@ export function ContentArea({
           case "getAll":
             result = await invoke("storage_get_all", { appId });
             break;
+          case "configGet": {
+            const config = await invoke<Record<string, string>>("get_app_config", { id: appId });
+            result = config[key] ?? null;
+            break;
+          }
+          case "configGetAll":
+            result = await invoke("get_app_config", { id: appId });
+            break;
+          case "metadata":
+            result = await invoke("get_own_metadata", { appId });
+            break;
+          case "badge":
+            await invoke("set_app_badge", { appId, count: value });
+            break;
+          case "progress":
+            await invoke("set_app_progress", { appId, progress: value });
+            break;
+          case "sound":
+            await invoke("app_play_sound", { appId, soundId: key, volume: value });
+            break;
+          case "registerShortcut":
+            result = await invoke("register_app_shortcut", {
+              appId,
+              id: key,
+              accelerator: value,
+            });
+            break;
+          case "unregisterShortcut":
+            await invoke("unregister_app_shortcut", { appId, id: key });
+            break;
+          case "shortcutPressed":
+            result = await invoke("app_shortcut_pressed", { appId, accelerator: value });
+            break;
+          case "timerStart":
+            result = await invoke("start_timer", {
+              appId,
+              id: key,
+              durationSecs: value?.seconds,
+              label: value?.label ?? null,
+            });
+            break;
+          case "timerList":
+            result = await invoke("get_timers", { appId });
+            break;
+          case "timerCancel":
+            result = await invoke("cancel_timer", { appId, id: key });
+            break;
+          case "rename": {
+            const renamed = await invoke<AppMetadata>("request_rename", { appId, newName: key });
+            result = renamed.name;
+            break;
+          }
+          case "requestFolder": {
+            // The user picks the folder in a host dialog; the app only
+            // learns the path once access has been granted.
+            const folder = await open({ directory: true, multiple: false });
+            if (typeof folder === "string") {
+              await invoke("grant_app_folder", { appId, path: folder });
+              result = folder;
+            }
+            break;
+          }
+          case "readFile":
+            result = await invoke("app_read_file", { appId, path: key });
+            break;
+          case "download":
+            result = await invoke("app_download", { appId, url: key, suggestedName: value });
+            break;
           default:
             throw new Error(`Unknown storage action: ${action}`);
         }
@@ -97,6 +408,7 @@ export function ContentArea({
     if (!app) {
       setIframeSrc(null);
       setLoadError(null);
+      setIsLocked(false);
       setIsLoadingApp(false);
       return;
     }
@@ -105,17 +417,26 @@ export function ContentArea({
     const appId = app.id;
     setIsLoadingApp(true);
     setLoadError(null);
+    setIsLocked(false);
+    setUnlockError(null);
     getAppPath(appId)
       .then((path) => {
         if (cancelled) return;
         const assetUrl = convertFileSrc(path);
         setIframeSrc(assetUrl);
+        invoke("mark_app_opened", { id: appId, source: openSource }).catch((err) => {
+          console.error("Failed to record app open:", err);
+        });
       })
       .catch((err) => {
         if (cancelled) return;
+        setIframeSrc(null);
+        if (String(err) === APP_LOCKED_ERROR) {
+          setIsLocked(true);
+          return;
+        }
         console.error("Failed to get app path:", err);
         setLoadError("Failed to load app preview.");
-        setIframeSrc(null);
       })
       .finally(() => {
         if (cancelled) return;
@@ -125,13 +446,19 @@ export function ContentArea({
     return () => {
       cancelled = true;
     };
-  }, [app, getAppPath, reloadToken]);
+  }, [app, getAppPath, openSource, reloadToken]);
 
   if (isGenerating) {
     return (
       <div className="content-area content-area-center">
         <div className="content-drag-region" onMouseDown={startDrag} />
-        <LoadingIndicator onCancel={onCancelGeneration} />
+        <LoadingIndicator
+          message={progressMessage}
+          onCancel={onCancelGeneration}
+          onFinishEarly={onFinishGenerationEarly}
+          question={generationQuestion}
+          onAnswer={onAnswerGenerationQuestion}
+        />
       </div>
     );
   }
@@ -154,6 +481,41 @@ export function ContentArea({
     );
   }
 
+  const handleUnlock = async (event: React.FormEvent) => {
+    event.preventDefault();
+    try {
+      await invoke("unlock_app", { id: app.id, password });
+      setPassword("");
+      setUnlockError(null);
+      setReloadToken((value) => value + 1);
+    } catch (err) {
+      setUnlockError(String(err));
+    }
+  };
+
+  if (isLocked) {
+    return (
+      <div className="content-area content-area-center">
+        <div className="content-drag-region" onMouseDown={startDrag} />
+        <form className="loading-app" onSubmit={handleUnlock}>
+          {app.name} is locked
+          <input
+            type="password"
+            className="app-unlock-input"
+            value={password}
+            onChange={(event) => setPassword(event.target.value)}
+            placeholder="Password"
+            autoFocus
+          />
+          {unlockError && <span className="app-unlock-error">{unlockError}</span>}
+          <button type="submit" className="content-action-button" disabled={!password}>
+            Unlock
+          </button>
+        </form>
+      </div>
+    );
+  }
+
   if (loadError) {
     return (
       <div className="content-area content-area-center">
@@ -173,6 +535,7 @@ export function ContentArea({
 
   const emoji = app.emoji || DEFAULT_EMOJI;
   const backgroundColor = app.background_color || DEFAULT_COLOR;
+  const latestError = appErrors.length > 0 ? appErrors[appErrors.length - 1] : null;
 
   return (
     <div className="content-area">
@@ -189,19 +552,56 @@ export function ContentArea({
           </div>
         </div>
         <div className="content-area-actions">
-          <button
-            className="content-icon-button"
-            onClick={onEditApp}
-            title="Settings"
-            aria-label="App settings"
-          >
-            <svg width="18" height="18" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
-              <circle cx="12" cy="12" r="3"/>
-              <path d="M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z"/>
-            </svg>
-          </button>
+          {onEditApp && (
+            <button
+              className="content-icon-button"
+              onClick={onEditApp}
+              title="Settings"
+              aria-label="App settings"
+            >
+              <svg width="18" height="18" viewBox="0 0 24 24" fill="none" stroke="currentColor" strokeWidth="2" strokeLinecap="round" strokeLinejoin="round">
+                <circle cx="12" cy="12" r="3"/>
+                <path d="M19.4 15a1.65 1.65 0 0 0 .33 1.82l.06.06a2 2 0 0 1 0 2.83 2 2 0 0 1-2.83 0l-.06-.06a1.65 1.65 0 0 0-1.82-.33 1.65 1.65 0 0 0-1 1.51V21a2 2 0 0 1-2 2 2 2 0 0 1-2-2v-.09A1.65 1.65 0 0 0 9 19.4a1.65 1.65 0 0 0-1.82.33l-.06.06a2 2 0 0 1-2.83 0 2 2 0 0 1 0-2.83l.06-.06a1.65 1.65 0 0 0 .33-1.82 1.65 1.65 0 0 0-1.51-1H3a2 2 0 0 1-2-2 2 2 0 0 1 2-2h.09A1.65 1.65 0 0 0 4.6 9a1.65 1.65 0 0 0-.33-1.82l-.06-.06a2 2 0 0 1 0-2.83 2 2 0 0 1 2.83 0l.06.06a1.65 1.65 0 0 0 1.82.33H9a1.65 1.65 0 0 0 1-1.51V3a2 2 0 0 1 2-2 2 2 0 0 1 2 2v.09a1.65 1.65 0 0 0 1 1.51 1.65 1.65 0 0 0 1.82-.33l.06-.06a2 2 0 0 1 2.83 0 2 2 0 0 1 0 2.83l-.06.06a1.65 1.65 0 0 0-.33 1.82V9a1.65 1.65 0 0 0 1.51 1H21a2 2 0 0 1 2 2 2 2 0 0 1-2 2h-.09a1.65 1.65 0 0 0-1.51 1z"/>
+              </svg>
+            </button>
+          )}
         </div>
       </div>
+      {latestError && (
+        <div className="app-error-banner" role="status">
+          <span className="app-error-message" title={latestError.stack ?? undefined}>
+            {describeRuntimeError(latestError)}
+            {appErrors.length > 1 && ` (+${appErrors.length - 1} more)`}
+          </span>
+          {onAutoFix && (
+            <button className="content-action-button" onClick={onAutoFix}>
+              Auto-fix
+            </button>
+          )}
+          {onFixError && (
+            <button className="content-action-button" onClick={() => onFixError(latestError)}>
+              Fix this error
+            </button>
+          )}
+          <button className="content-action-button" onClick={handleDismissErrors}>
+            Dismiss
+          </button>
+        </div>
+      )}
+      {app.suggested_tags && onResolveSuggestedTags && (
+        <div className="app-suggestion-banner" role="status">
+          <span className="app-error-message">
+            Suggested tags: {app.suggested_tags.tags.join(", ") || "none"}
+            {app.suggested_tags.category && ` · ${app.suggested_tags.category}`}
+          </span>
+          <button className="content-action-button" onClick={() => onResolveSuggestedTags(true)}>
+            Accept
+          </button>
+          <button className="content-action-button" onClick={() => onResolveSuggestedTags(false)}>
+            Dismiss
+          </button>
+        </div>
+      )}
       {iframeSrc && (
         <iframe
           ref={iframeRef}
@@ -210,6 +610,7 @@ export function ContentArea({
           className="app-iframe"
           sandbox="allow-scripts allow-same-origin"
           title={app.name}
+          onLoad={postThemeToApp}
         />
       )}
     </div>
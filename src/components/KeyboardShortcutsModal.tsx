@@ -31,6 +31,22 @@ export function KeyboardShortcutsModal({
             <kbd>N</kbd>
           </div>
         </div>
+        <div className="shortcut-row">
+          <span className="shortcut-label">New app from clipboard</span>
+          <div className="shortcut-keys">
+            <kbd>Cmd</kbd>
+            <span className="shortcut-plus">+</span>
+            <kbd>Shift</kbd>
+            <span className="shortcut-plus">+</span>
+            <kbd>V</kbd>
+            <span className="shortcut-or">or</span>
+            <kbd>Ctrl</kbd>
+            <span className="shortcut-plus">+</span>
+            <kbd>Shift</kbd>
+            <span className="shortcut-plus">+</span>
+            <kbd>V</kbd>
+          </div>
+        </div>
         <div className="shortcut-row">
           <span className="shortcut-label">Delete app</span>
           <div className="shortcut-keys">
@@ -1,7 +1,15 @@
+import { useState, type FormEvent } from "react";
+import type { GenerationQuestion } from "../types";
 import "./LoadingIndicator.css";
 
 interface LoadingIndicatorProps {
+  message?: string | null;
   onCancel: () => void;
+  /** Stops generating but keeps the HTML received so far. */
+  onFinishEarly?: () => void;
+  /** A clarifying question the agent is waiting on. */
+  question?: GenerationQuestion | null;
+  onAnswer?: (id: string, answer: string) => Promise<void>;
 }
 
 const LOADER_CELLS = [
@@ -16,7 +24,28 @@ const LOADER_CELLS = [
   "slot-8",
 ] as const;
 
-export function LoadingIndicator({ onCancel }: LoadingIndicatorProps) {
+export function LoadingIndicator({
+  message,
+  onCancel,
+  onFinishEarly,
+  question,
+  onAnswer,
+}: LoadingIndicatorProps) {
+  const [answer, setAnswer] = useState("");
+  const [answerError, setAnswerError] = useState<string | null>(null);
+
+  const submitAnswer = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!question || !onAnswer || !answer.trim()) return;
+    try {
+      await onAnswer(question.id, answer.trim());
+      setAnswer("");
+      setAnswerError(null);
+    } catch (err) {
+      setAnswerError(String(err));
+    }
+  };
+
   return (
     <div className="loading-indicator">
       <div className="loading-grid-loader" aria-hidden="true">
@@ -25,9 +54,37 @@ export function LoadingIndicator({ onCancel }: LoadingIndicatorProps) {
         ))}
       </div>
 
-      <button className="cancel-button" onClick={onCancel}>
-        Cancel
-      </button>
+      <div className="loading-message" aria-live="polite">
+        {message ?? "Starting..."}
+      </div>
+
+      {question && onAnswer && (
+        <form className="loading-question" onSubmit={submitAnswer}>
+          <label htmlFor="generation-answer">{question.question}</label>
+          <input
+            id="generation-answer"
+            type="text"
+            value={answer}
+            onChange={(event) => setAnswer(event.target.value)}
+            autoFocus
+          />
+          {answerError && <div className="loading-question-error">{answerError}</div>}
+          <button type="submit" className="cancel-button" disabled={!answer.trim()}>
+            Answer
+          </button>
+        </form>
+      )}
+
+      <div className="loading-actions">
+        {onFinishEarly && (
+          <button className="cancel-button" onClick={onFinishEarly}>
+            Keep What You Have
+          </button>
+        )}
+        <button className="cancel-button" onClick={onCancel}>
+          Cancel
+        </button>
+      </div>
     </div>
   );
 }
@@ -15,6 +15,8 @@ const startDrag = (e: React.MouseEvent) => {
 
 interface SidebarProps {
   apps: AppMetadata[];
+  /** Search snippets by app id, shown in place of the prompt. */
+  snippets?: Record<string, string>;
   selectedAppId: string | null;
   onSelectApp: (id: string) => void;
   onDeleteApp: (id: string) => void;
@@ -32,6 +34,7 @@ const DEFAULT_WIDTH = 280;
 
 export function Sidebar({
   apps,
+  snippets = {},
   selectedAppId,
   onSelectApp,
   onDeleteApp,
@@ -117,6 +120,7 @@ export function Sidebar({
               <SidebarItem
                 key={app.id}
                 app={app}
+                snippet={snippets[app.id]}
                 isSelected={app.id === selectedAppId}
                 onSelect={() => onSelectApp(app.id)}
                 onDelete={() => onDeleteApp(app.id)}
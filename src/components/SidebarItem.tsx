@@ -7,6 +7,7 @@ const DEFAULT_COLOR = "#6366F1";
 
 interface SidebarItemProps {
   app: AppMetadata;
+  snippet?: string;
   isSelected: boolean;
   onSelect: () => void;
   onDelete: () => void;
@@ -14,6 +15,7 @@ interface SidebarItemProps {
 
 export function SidebarItem({
   app,
+  snippet,
   isSelected,
   onSelect,
   onDelete,
@@ -22,7 +24,7 @@ export function SidebarItem({
 
   const emoji = app.emoji || DEFAULT_EMOJI;
   const backgroundColor = app.background_color || DEFAULT_COLOR;
-  const promptSnippet = truncate(app.prompt || "", 60);
+  const promptSnippet = snippet ?? truncate(app.prompt || "", 60);
 
   const handleDelete = (e: React.MouseEvent) => {
     e.stopPropagation();
@@ -1,2 +1,6 @@
 export const APP_NAME_MAX_LENGTH = 60;
 export const APP_PROMPT_MAX_LENGTH = 2000;
+export const DEFAULT_CREATIVITY = 0.5;
+export const SEARCH_DEBOUNCE_MS = 150;
+export const APP_LOCKED_ERROR = "App is locked";
+export const APP_ACTIVITY_THROTTLE_MS = 30_000;
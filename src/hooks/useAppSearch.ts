@@ -0,0 +1,63 @@
+import { useEffect, useMemo, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { AppMetadata, SearchResult } from "../types";
+import { SEARCH_DEBOUNCE_MS } from "../constants";
+
+/**
+ * Ranks apps for a search query using the backend index, which also covers
+ * each app's HTML. Results are mapped back onto `apps` so the list always
+ * shows current metadata.
+ */
+export function useAppSearch(apps: AppMetadata[], query: string) {
+  const [results, setResults] = useState<SearchResult[] | null>(null);
+  const trimmed = query.trim();
+
+  useEffect(() => {
+    if (!trimmed) {
+      setResults(null);
+      return;
+    }
+
+    let cancelled = false;
+    const timer = setTimeout(() => {
+      invoke<SearchResult[]>("search_apps", { query: trimmed })
+        .then((found) => {
+          if (!cancelled) setResults(found);
+        })
+        .catch((err) => {
+          console.error("Failed to search apps:", err);
+          if (!cancelled) setResults(null);
+        });
+    }, SEARCH_DEBOUNCE_MS);
+
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [apps, trimmed]);
+
+  return useMemo(() => {
+    if (!trimmed) {
+      return { visibleApps: apps, snippets: {} as Record<string, string> };
+    }
+    if (!results) {
+      // Until the first results arrive, fall back to a plain name match.
+      const lower = trimmed.toLowerCase();
+      return {
+        visibleApps: apps.filter((app) => app.name.toLowerCase().includes(lower)),
+        snippets: {} as Record<string, string>,
+      };
+    }
+
+    const byId = new Map(apps.map((app) => [app.id, app]));
+    const visibleApps: AppMetadata[] = [];
+    const snippets: Record<string, string> = {};
+    for (const result of results) {
+      const app = byId.get(result.app.id);
+      if (!app) continue;
+      visibleApps.push(app);
+      snippets[app.id] = result.snippet;
+    }
+    return { visibleApps, snippets };
+  }, [apps, results, trimmed]);
+}
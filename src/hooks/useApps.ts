@@ -1,12 +1,34 @@
-import { useState, useEffect, useCallback, useRef } from "react";
+import {
+  useState,
+  useEffect,
+  useCallback,
+  useRef,
+  type MutableRefObject,
+} from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { type UnlistenFn } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import type {
+  AppChanged,
+  AppKind,
+  AppLocked,
   AppMetadata,
+  AppRemoved,
+  AppRenamed,
+  AppRuntimeIncompatible,
+  DeletionSummary,
   GenerationComplete,
   GenerationError,
+  GenerationJobEvent,
+  GenerationProgress,
+  GenerationQuestion,
+  GenerationWarning,
+  ModelProvider,
+  OpenAppInPanel,
+  ProfileSwitched,
+  TagsSuggested,
 } from "../types";
+import { APP_LOCKED_ERROR } from "../constants";
 import { upsertApp } from "../utils/apps";
 
 type ErrorSource =
@@ -15,6 +37,7 @@ type ErrorSource =
   | "delete"
   | "listeners"
   | "export"
+  | "open"
   | null;
 
 type GenerationRequest = {
@@ -23,19 +46,35 @@ type GenerationRequest = {
   prompt: string;
   emoji: string;
   backgroundColor: string;
-  mode: "create" | "edit";
+  creativity?: number;
+  accessibilityMode?: boolean;
+  appKind?: AppKind;
+  provider?: ModelProvider;
+  mode: "create" | "edit" | "fix";
 };
 
+/** Picks the id for a new generation, so its events can be told apart. */
+function startJob(activeJobId: MutableRefObject<string | null>) {
+  activeJobId.current = crypto.randomUUID();
+  return activeJobId.current;
+}
+
 export function useApps() {
   const [apps, setApps] = useState<AppMetadata[]>([]);
   const [selectedAppId, setSelectedAppId] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [isGenerating, setIsGenerating] = useState(false);
+  const [progressMessage, setProgressMessage] = useState<string | null>(null);
+  const [generationQuestion, setGenerationQuestion] =
+    useState<GenerationQuestion | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [errorSource, setErrorSource] = useState<ErrorSource>(null);
   const [lastGenerationRequest, setLastGenerationRequest] =
     useState<GenerationRequest | null>(null);
   const appPathCache = useRef<Map<string, string>>(new Map());
+  // Job id of the generation this window is following; events from other
+  // generations running alongside it are ignored.
+  const activeJobId = useRef<string | null>(null);
 
   const setErrorWithSource = useCallback(
     (value: string | null, source: ErrorSource = null) => {
@@ -80,21 +119,127 @@ export function useApps() {
         unlisteners.push(unlisten);
       };
 
+      const registerJobListener = <T extends GenerationJobEvent>(
+        eventName: string,
+        handler: (event: { payload: T }) => void
+      ) =>
+        registerListener<T>(eventName, (event) => {
+          if (event.payload.job_id === activeJobId.current) {
+            handler(event);
+          }
+        });
+
       try {
-        await registerListener<GenerationComplete>(
+        await registerJobListener<GenerationComplete>(
           "generation-complete",
           (event) => {
             setApps((prev) => upsertApp(prev, event.payload.app));
             setSelectedAppId(event.payload.app.id);
             setIsGenerating(false);
+            activeJobId.current = null;
+          }
+        );
+
+        await registerListener<OpenAppInPanel>(
+          "open-app-in-panel",
+          (event) => {
+            setApps((prev) => upsertApp(prev, event.payload.app));
+            setSelectedAppId(event.payload.app.id);
+          }
+        );
+
+        await registerListener<TagsSuggested>(
+          "tags-suggested",
+          (event) => {
+            setApps((prev) => upsertApp(prev, event.payload.app));
           }
         );
 
-        await registerListener<GenerationError>(
+        await registerListener<AppRenamed>(
+          "app-renamed",
+          (event) => {
+            setApps((prev) => upsertApp(prev, event.payload.app));
+          }
+        );
+
+        // Any command that changes the index reports it here, so the list
+        // stays current without reloading it.
+        const applyAppChange = (event: { payload: AppChanged }) => {
+          const { app } = event.payload;
+          setApps((prev) =>
+            app.draft
+              ? prev.filter((existing) => existing.id !== app.id)
+              : upsertApp(prev, app)
+          );
+        };
+        await registerListener<AppChanged>("app-added", applyAppChange);
+        await registerListener<AppChanged>("app-updated", applyAppChange);
+
+        await registerListener<AppRemoved>(
+          "app-removed",
+          (event) => {
+            const { app_id } = event.payload;
+            setApps((prev) => prev.filter((app) => app.id !== app_id));
+            setSelectedAppId((selected) => (selected === app_id ? null : selected));
+            appPathCache.current.delete(app_id);
+          }
+        );
+
+        await registerListener<AppLocked>(
+          "app-locked",
+          (event) => {
+            // Force the next open back through the lock check.
+            appPathCache.current.delete(event.payload.app_id);
+          }
+        );
+
+        await registerListener<AppRuntimeIncompatible>(
+          "app-runtime-incompatible",
+          (event) => {
+            const { runtime_version, current_version } = event.payload;
+            setErrorWithSource(
+              `This app uses an outdated Trove runtime (v${runtime_version}, current v${current_version}) and may not work correctly.`,
+              "open"
+            );
+          }
+        );
+
+        await registerListener<ProfileSwitched>("profile-switched", () => {
+          // Each profile has its own library, so nothing cached carries over.
+          appPathCache.current.clear();
+          setSelectedAppId(null);
+          void loadApps();
+        });
+
+        await registerJobListener<GenerationProgress>(
+          "generation-progress",
+          (event) => {
+            setProgressMessage(event.payload.message);
+          }
+        );
+
+        await registerJobListener<GenerationQuestion>(
+          "generation-question",
+          (event) => {
+            setGenerationQuestion(event.payload);
+          }
+        );
+
+        await registerJobListener<GenerationWarning>(
+          "generation-warning",
+          (event) => {
+            // Generation carries on; surface the warning as its progress.
+            setProgressMessage(event.payload.message);
+          }
+        );
+
+        await registerJobListener<GenerationError>(
           "generation-error",
           (event) => {
             setErrorWithSource(event.payload.message, "generation");
+            setGenerationQuestion(null);
             setIsGenerating(false);
+            activeJobId.current = null;
           }
         );
       } catch (err) {
@@ -117,15 +262,45 @@ export function useApps() {
         }
       });
     };
-  }, []);
+  }, [loadApps]);
 
-  const generateApp = useCallback(async (name: string, prompt: string, emoji: string, backgroundColor: string) => {
+  const generateApp = useCallback(async (
+    name: string,
+    prompt: string,
+    emoji: string,
+    backgroundColor: string,
+    creativity?: number,
+    accessibilityMode?: boolean,
+    appKind?: AppKind,
+    provider?: ModelProvider
+  ) => {
     setIsGenerating(true);
+    setProgressMessage(null);
     setErrorWithSource(null);
-    setLastGenerationRequest({ name, prompt, emoji, backgroundColor, mode: "create" });
+    setLastGenerationRequest({
+      name,
+      prompt,
+      emoji,
+      backgroundColor,
+      creativity,
+      accessibilityMode,
+      appKind,
+      provider,
+      mode: "create",
+    });
 
     try {
-      const app = await invoke<AppMetadata>("generate_app", { name, prompt, emoji, backgroundColor });
+      const app = await invoke<AppMetadata>("generate_app", {
+        jobId: startJob(activeJobId),
+        name,
+        prompt,
+        emoji,
+        backgroundColor,
+        creativity,
+        accessibilityMode,
+        appKind,
+        provider,
+      });
       return app;
     } catch (err) {
       setErrorWithSource(String(err), "generation");
@@ -134,19 +309,106 @@ export function useApps() {
     }
   }, [setErrorWithSource]);
 
+  /** Generates an app from the clipboard text, named by the agent. */
+  const generateFromClipboard = useCallback(async () => {
+    setIsGenerating(true);
+    setProgressMessage(null);
+    setErrorWithSource(null);
+    // The clipboard may have changed by the time a retry is asked for.
+    setLastGenerationRequest(null);
+
+    try {
+      return await invoke<AppMetadata>("generate_from_clipboard", {
+        jobId: startJob(activeJobId),
+      });
+    } catch (err) {
+      setErrorWithSource(String(err), "generation");
+      setIsGenerating(false);
+      throw err;
+    }
+  }, [setErrorWithSource]);
+
   const cancelGeneration = useCallback(async () => {
+    // Without a job id the backend would cancel every generation.
+    if (!activeJobId.current) {
+      setIsGenerating(false);
+      return;
+    }
     try {
-      await invoke("cancel_generation");
+      await invoke("cancel_generation", { jobId: activeJobId.current });
       setIsGenerating(false);
     } catch (err) {
       console.error("Failed to cancel generation:", err);
     }
   }, []);
 
-  const deleteApp = useCallback(
+  /** Asks the agent to wrap up; the generation then completes as usual. */
+  const finishGenerationEarly = useCallback(async () => {
+    try {
+      await invoke("finish_generation_early", { jobId: activeJobId.current });
+    } catch (err) {
+      console.error("Failed to finish generation early:", err);
+    }
+  }, []);
+
+  const pauseGeneration = useCallback(async () => {
+    try {
+      await invoke("pause_generation", { jobId: activeJobId.current });
+    } catch (err) {
+      console.error("Failed to pause generation:", err);
+    }
+  }, []);
+
+  const resumeGeneration = useCallback(async () => {
+    try {
+      await invoke("resume_generation", { jobId: activeJobId.current });
+    } catch (err) {
+      console.error("Failed to resume generation:", err);
+    }
+  }, []);
+
+  /** Sends the answer to the sidecar's pending clarifying question. */
+  const answerGenerationQuestion = useCallback(async (id: string, answer: string) => {
+    await invoke("answer_generation_question", { id, answer });
+    setGenerationQuestion(null);
+  }, []);
+
+  /** Opens an app per its open mode; panel opens arrive as an event. */
+  const openApp = useCallback(
     async (id: string) => {
       try {
-        await invoke("delete_app", { id });
+        await invoke("open_app", { id, source: "grid" });
+      } catch (err) {
+        if (String(err) === APP_LOCKED_ERROR) {
+          // The panel shows the password prompt for locked apps.
+          setSelectedAppId(id);
+          return;
+        }
+        setErrorWithSource(String(err), "open");
+      }
+    },
+    [setErrorWithSource]
+  );
+
+  const requestDeleteConfirmation = useCallback(
+    async (id: string) => {
+      try {
+        return await invoke<DeletionSummary>("request_delete_confirmation", {
+          action: "delete_app",
+          appId: id,
+        });
+      } catch (err) {
+        setErrorWithSource(String(err), "delete");
+        return null;
+      }
+    },
+    [setErrorWithSource]
+  );
+
+  const deleteApp = useCallback(
+    async (id: string, confirmToken: string) => {
+      try {
+        await invoke("delete_app", { id, confirmToken });
         setApps((prev) => prev.filter((app) => app.id !== id));
         if (selectedAppId === id) {
           setSelectedAppId(null);
@@ -160,13 +422,43 @@ export function useApps() {
   );
 
   const editApp = useCallback(
-    async (id: string, name: string, prompt: string, emoji: string, backgroundColor: string) => {
+    async (
+      id: string,
+      name: string,
+      prompt: string,
+      emoji: string,
+      backgroundColor: string,
+      creativity?: number,
+      accessibilityMode?: boolean,
+      provider?: ModelProvider
+    ) => {
       setIsGenerating(true);
+      setProgressMessage(null);
       setErrorWithSource(null);
-      setLastGenerationRequest({ id, name, prompt, emoji, backgroundColor, mode: "edit" });
+      setLastGenerationRequest({
+        id,
+        name,
+        prompt,
+        emoji,
+        backgroundColor,
+        creativity,
+        accessibilityMode,
+        provider,
+        mode: "edit",
+      });
 
       try {
-        const app = await invoke<AppMetadata>("edit_app", { id, name, prompt, emoji, backgroundColor });
+        const app = await invoke<AppMetadata>("edit_app", {
+          jobId: startJob(activeJobId),
+          id,
+          name,
+          prompt,
+          emoji,
+          backgroundColor,
+          creativity,
+          accessibilityMode,
+          provider,
+        });
         return app;
       } catch (err) {
         setErrorWithSource(String(err), "generation");
@@ -177,6 +469,34 @@ export function useApps() {
     [setErrorWithSource]
   );
 
+  const autoFixApp = useCallback(
+    async (app: AppMetadata) => {
+      setIsGenerating(true);
+      setProgressMessage(null);
+      setErrorWithSource(null);
+      setLastGenerationRequest({
+        id: app.id,
+        name: app.name,
+        prompt: app.prompt,
+        emoji: app.emoji,
+        backgroundColor: app.background_color,
+        mode: "fix",
+      });
+
+      try {
+        return await invoke<AppMetadata>("auto_fix_app", {
+          jobId: startJob(activeJobId),
+          id: app.id,
+        });
+      } catch (err) {
+        setErrorWithSource(String(err), "generation");
+        setIsGenerating(false);
+        throw err;
+      }
+    },
+    [setErrorWithSource]
+  );
+
   const getAppPath = useCallback(async (id: string): Promise<string> => {
     const cached = appPathCache.current.get(id);
     if (cached) {
@@ -206,24 +526,49 @@ export function useApps() {
     [setErrorWithSource]
   );
 
+  /** Accepts or dismisses an app's pending tag suggestion. */
+  const resolveSuggestedTags = useCallback(
+    async (id: string, accept: boolean) => {
+      try {
+        const command = accept ? "accept_suggested_tags" : "dismiss_suggested_tags";
+        const app = await invoke<AppMetadata>(command, { id });
+        setApps((prev) => upsertApp(prev, app));
+      } catch (err) {
+        setErrorWithSource(String(err), "generation");
+      }
+    },
+    [setErrorWithSource]
+  );
+
   const retryLastGeneration = useCallback(async () => {
     if (!lastGenerationRequest) return null;
+    if (lastGenerationRequest.mode === "fix") {
+      const app = apps.find((candidate) => candidate.id === lastGenerationRequest.id);
+      return app ? autoFixApp(app) : null;
+    }
     if (lastGenerationRequest.mode === "edit" && lastGenerationRequest.id) {
       return editApp(
         lastGenerationRequest.id,
         lastGenerationRequest.name,
         lastGenerationRequest.prompt,
         lastGenerationRequest.emoji,
-        lastGenerationRequest.backgroundColor
+        lastGenerationRequest.backgroundColor,
+        lastGenerationRequest.creativity,
+        lastGenerationRequest.accessibilityMode,
+        lastGenerationRequest.provider
       );
     }
     return generateApp(
       lastGenerationRequest.name,
       lastGenerationRequest.prompt,
       lastGenerationRequest.emoji,
-      lastGenerationRequest.backgroundColor
+      lastGenerationRequest.backgroundColor,
+      lastGenerationRequest.creativity,
+      lastGenerationRequest.accessibilityMode,
+      lastGenerationRequest.appKind,
+      lastGenerationRequest.provider
     );
-  }, [editApp, generateApp, lastGenerationRequest]);
+  }, [apps, autoFixApp, editApp, generateApp, lastGenerationRequest]);
 
   const selectedApp = apps.find((app) => app.id === selectedAppId) || null;
 
@@ -231,17 +576,28 @@ export function useApps() {
     apps,
     selectedApp,
     setSelectedAppId,
+    openApp,
     isLoading,
     isGenerating,
+    progressMessage,
     error,
     setError: setErrorWithSource,
     errorSource,
     lastGenerationRequest,
     generateApp,
+    generateFromClipboard,
     cancelGeneration,
+    finishGenerationEarly,
+    pauseGeneration,
+    resumeGeneration,
+    generationQuestion,
+    answerGenerationQuestion,
+    requestDeleteConfirmation,
     deleteApp,
     editApp,
+    autoFixApp,
     updateAppMetadata,
+    resolveSuggestedTags,
     retryLastGeneration,
     getAppPath,
   };
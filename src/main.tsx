@@ -1,12 +1,21 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
+import { AppWindow } from "./components/AppWindow";
 import { ErrorBoundary } from "./components/ErrorBoundary";
 
+// Dedicated app windows are labelled `app-<id>` and render only that app.
+const APP_WINDOW_LABEL_PREFIX = "app-";
+const windowLabel = getCurrentWindow().label;
+const appWindowId = windowLabel.startsWith(APP_WINDOW_LABEL_PREFIX)
+  ? windowLabel.slice(APP_WINDOW_LABEL_PREFIX.length)
+  : null;
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
     <ErrorBoundary>
-      <App />
+      {appWindowId ? <AppWindow appId={appWindowId} /> : <App />}
     </ErrorBoundary>
   </React.StrictMode>,
 );
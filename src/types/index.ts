@@ -1,3 +1,10 @@
+export type AppOrigin =
+  | { type: "generated" }
+  | { type: "imported"; source: string }
+  | { type: "gallery"; id: string }
+  | { type: "remix"; parent_id: string }
+  | { type: "duplicate"; parent_id: string };
+
 export interface AppMetadata {
   id: string;
   name: string;
@@ -6,12 +13,302 @@ export interface AppMetadata {
   background_color: string;
   created_at: string;
   updated_at: string;
+  quarantined?: boolean;
+  open_count?: number;
+  last_opened_at?: string | null;
+  origin?: AppOrigin;
+  content_hash?: string | null;
+  creativity?: number | null;
+  language?: string | null;
+  accessibility_mode?: boolean;
+  lint_warnings?: LintWarning[];
+  draft?: boolean;
+  open_mode?: OpenMode | null;
+  tags?: string[];
+  category?: string | null;
+  suggested_tags?: TagSuggestion | null;
+  locked?: boolean;
+  runtime_version?: number | null;
+  app_kind?: AppKind;
+  autostart?: boolean;
+  generated_with?: GeneratedWith | null;
+  storage_format?: StorageFormat;
+  storage_read_only?: boolean;
+}
+
+/** `AppMetadata` from `list_apps_light`; fetch the prompt with `get_app`. */
+export type AppListing = Omit<AppMetadata, "prompt"> & {
+  prompt_preview: string;
+};
+
+export type StorageFormat = "json" | "messagepack";
+
+export interface GeneratedWith {
+  sidecar_version: string | null;
+  model: string | null;
+}
+
+export interface TagSuggestion {
+  category: string | null;
+  tags: string[];
+  suggested_at: string;
+}
+
+export interface PromptTemplate {
+  id: string;
+  name: string;
+  prompt: string;
+  variables: string[];
+  saved_at: string;
+}
+
+export interface NameSuggestion {
+  name: string;
+  emoji: string;
+}
+
+export interface TagsSuggested {
+  app: AppMetadata;
+}
+
+export interface AppRenamed {
+  app: AppMetadata;
+}
+
+/** Sent as `app-added` and `app-updated` when the index changes. */
+export interface AppChanged {
+  app: AppMetadata;
+}
+
+export interface AppRemoved {
+  app_id: string;
+}
+
+export interface AppLocked {
+  app_id: string;
+}
+
+export interface StorageChunk {
+  stream_id: string;
+  index: number;
+  data: string;
+  done: boolean;
+}
+
+export interface StorageChanged {
+  app_id: string;
+  /** Null when the app's whole store was cleared. */
+  key: string | null;
+}
+
+export interface AppShortcutPressed {
+  app_id: string;
+  id: string;
+}
+
+export type DestructiveAction = "delete_app" | "storage_clear";
+
+export interface DeletionSummary {
+  token: string;
+  action: DestructiveAction;
+  app_id: string;
+  app_name: string;
+  versions: number;
+  storage_keys: number;
+  storage_bytes: number;
+  total_bytes: number;
+  expires_at: string;
+}
+
+export interface PowerStatus {
+  low_power_mode: boolean;
+  on_battery: boolean | null;
+  background_paused: boolean;
+}
+
+export interface AppUsageSummary {
+  app_id: string;
+  name: string;
+  emoji: string;
+  open_count: number;
+  opened: boolean;
+  opens: number | null;
+  generations: number;
+  edits: number;
+  storage_bytes: number;
+  storage_growth_bytes: number | null;
+}
+
+export interface WeeklyDigest {
+  generated_at: string;
+  period_start: string;
+  apps_used: number;
+  apps_created: number;
+  generations: number;
+  edits: number;
+  failed_runs: number;
+  storage_bytes: number;
+  storage_growth_bytes: number;
+  apps: AppUsageSummary[];
+}
+
+export interface AppTimer {
+  app_id: string;
+  id: string;
+  label: string | null;
+  duration_secs: number;
+  started_at: string;
+  ends_at: string;
+}
+
+export interface TimerFinished {
+  app_id: string;
+  id: string;
+  label: string | null;
+}
+
+export interface AppRuntimeIncompatible {
+  app_id: string;
+  runtime_version: number;
+  current_version: number;
+}
+
+export type OpenMode = "panel" | "window" | "browser";
+
+export interface ExportProgress {
+  app_id: string;
+  app_name: string;
+  completed: number;
+  total: number;
+  percent: number;
+}
+
+export interface ProfileSwitched {
+  profile: string | null;
+}
+
+export interface FocusMode {
+  enabled: boolean;
+  blocked_app_ids: string[];
+  until: string | null;
+}
+
+export type AppKind = "standard" | "widget" | "fullscreen";
+
+/** Backend that writes a generation's HTML; the settings default when unset. */
+export type ModelProvider = "claude_code" | "open_ai" | "ollama";
+
+export type OpenSource = "grid" | "window" | "tray" | "hotkey" | "deep_link";
+
+export interface OpenAppInPanel {
+  app: AppMetadata;
+  source: OpenSource;
+}
+
+export type LintRule = "web_storage" | "remote_fetch" | "missing_viewport";
+
+export interface LintWarning {
+  rule: LintRule;
+  message: string;
+  fixed: boolean;
+}
+
+export type HealthStatus = "healthy" | "warning" | "broken";
+
+export interface AppHealth {
+  app_id: string;
+  status: HealthStatus;
+  html_error: string | null;
+  storage_error: string | null;
+  lint_warnings: LintWarning[];
+  missing_files: string[];
+  recent_errors: AppRuntimeError[];
+}
+
+export interface AppRuntimeError {
+  message: string;
+  source: string | null;
+  line: number | null;
+  column: number | null;
+  stack: string | null;
+  count: number;
+  first_seen_at: string;
+  last_seen_at: string;
 }
 
-export interface GenerationComplete {
+export type SystemTheme = "light" | "dark";
+
+export interface SystemThemeChanged {
+  theme: SystemTheme;
+}
+
+/** Fields shared by every generation event: the job it belongs to. */
+export interface GenerationJobEvent {
+  job_id: string;
+}
+
+export type GenerationJobStatus =
+  | "queued"
+  | "running"
+  | "completed"
+  | "failed"
+  | "cancelled";
+
+/** From `list_generation_jobs` and `generation-job-updated` events. */
+export interface GenerationJobInfo {
+  job_id: string;
+  /** The app being edited; null for new apps. */
+  app_id: string | null;
+  name: string;
+  status: GenerationJobStatus;
+  /** Place in the queue, from zero; null unless queued. */
+  position: number | null;
+  queued_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+}
+
+export interface GenerationProgress extends GenerationJobEvent {
+  message: string;
+}
+
+export interface GenerationComplete extends GenerationJobEvent {
   app: AppMetadata;
 }
 
-export interface GenerationError {
+export interface GenerationError extends GenerationJobEvent {
+  message: string;
+  partial_html_path: string | null;
+}
+
+export interface GenerationQuestion extends GenerationJobEvent {
+  id: string;
+  question: string;
+}
+
+export interface GenerationWarning extends GenerationJobEvent {
   message: string;
+  percent: number;
+  html_bytes: number;
+  limit_bytes: number;
+}
+
+export interface SearchResult {
+  app: AppMetadata;
+  score: number;
+  snippet: string;
+}
+
+export interface VersionGrowth {
+  version_id: string;
+  kind: "created" | "edited" | "imported" | "restored" | "runtime_upgraded";
+  created_at: string;
+  html_bytes: number;
+  node_count: number | null;
+  script_count: number | null;
+}
+
+export interface AppGrowth {
+  versions: VersionGrowth[];
+  size_limit_bytes: number;
 }
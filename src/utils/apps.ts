@@ -1,4 +1,4 @@
-import type { AppMetadata } from "../types";
+import type { AppMetadata, AppRuntimeError, DeletionSummary } from "../types";
 
 export function upsertApp(
   apps: AppMetadata[],
@@ -12,3 +12,31 @@ export function upsertApp(
   next[index] = app;
   return next;
 }
+
+export function describeRuntimeError(error: AppRuntimeError): string {
+  if (error.line === null) return error.message;
+  const column = error.column !== null ? `:${error.column}` : "";
+  return `${error.message} (line ${error.line}${column})`;
+}
+
+/**
+ * Seeds an edit prompt that asks for a captured runtime error to be fixed.
+ */
+export function buildFixErrorPrompt(prompt: string, error: AppRuntimeError): string {
+  return `${prompt.trim()}\n\nFix this runtime error: ${describeRuntimeError(error)}`;
+}
+
+export function formatBytes(bytes: number): string {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+}
+
+/** The delete dialog's description of what `delete_app` will remove. */
+export function describeDeletion(summary: DeletionSummary): string {
+  const versions = `${summary.versions} saved version${summary.versions === 1 ? "" : "s"}`;
+  return (
+    `This will permanently delete "${summary.app_name}", ${versions} ` +
+    `and ${formatBytes(summary.total_bytes)} of data.`
+  );
+}
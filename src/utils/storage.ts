@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import type { StorageChunk } from "../types";
+
+/**
+ * Reads a storage value via `storage_get_stream`, reassembling its JSON from
+ * `storage-chunk` events so large values don't travel as one IPC message.
+ */
+export async function readStorageStream(appId: string, key: string): Promise<unknown> {
+  const streamId = crypto.randomUUID();
+  const chunks: string[] = [];
+  let markDone = () => {};
+  const done = new Promise<void>((resolve) => {
+    markDone = resolve;
+  });
+
+  const unlisten = await getCurrentWebviewWindow().listen<StorageChunk>(
+    "storage-chunk",
+    (event) => {
+      const chunk = event.payload;
+      if (chunk.stream_id !== streamId) return;
+      chunks[chunk.index] = chunk.data;
+      if (chunk.done) markDone();
+    }
+  );
+  try {
+    await invoke("storage_get_stream", { appId, key, streamId });
+    await done;
+  } finally {
+    unlisten();
+  }
+  return JSON.parse(chunks.join(""));
+}